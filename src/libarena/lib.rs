@@ -43,6 +43,7 @@ use std::marker::{PhantomData, Send};
 use std::mem;
 use std::ptr;
 use std::slice;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use alloc::raw_vec::RawVec;
 
@@ -68,9 +69,20 @@ struct TypedArenaChunk<T> {
     storage: RawVec<T>,
 }
 
+/// Bytes of chunk storage allocated by all the arenas of the process.
+static CHUNK_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the number of bytes of chunk storage allocated by all the arenas
+/// of the process so far. Storage released by `clear` or by dropping an
+/// arena is not subtracted, so this only ever grows.
+pub fn total_chunk_bytes() -> usize {
+    CHUNK_BYTES.load(Ordering::Relaxed)
+}
+
 impl<T> TypedArenaChunk<T> {
     #[inline]
     unsafe fn new(capacity: usize) -> TypedArenaChunk<T> {
+        CHUNK_BYTES.fetch_add(capacity * mem::size_of::<T>(), Ordering::Relaxed);
         TypedArenaChunk {
             storage: RawVec::with_capacity(capacity),
         }
@@ -194,7 +206,10 @@ impl<T> TypedArena<T> {
             if let Some(last_chunk) = chunks.last_mut() {
                 let used_bytes = self.ptr.get() as usize - last_chunk.start() as usize;
                 let currently_used_cap = used_bytes / mem::size_of::<T>();
+                let old_cap = last_chunk.storage.cap();
                 if last_chunk.storage.reserve_in_place(currently_used_cap, n) {
+                    let grown_bytes = (last_chunk.storage.cap() - old_cap) * mem::size_of::<T>();
+                    CHUNK_BYTES.fetch_add(grown_bytes, Ordering::Relaxed);
                     self.end.set(last_chunk.end());
                     return;
                 } else {
@@ -329,10 +344,12 @@ impl DroplessArena {
             let (chunk, mut new_capacity);
             if let Some(last_chunk) = chunks.last_mut() {
                 let used_bytes = self.ptr.get() as usize - last_chunk.start() as usize;
+                let old_cap = last_chunk.storage.cap();
                 if last_chunk
                     .storage
                     .reserve_in_place(used_bytes, needed_bytes)
                 {
+                    CHUNK_BYTES.fetch_add(last_chunk.storage.cap() - old_cap, Ordering::Relaxed);
                     self.end.set(last_chunk.end());
                     return;
                 } else {
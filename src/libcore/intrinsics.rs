@@ -925,6 +925,13 @@ extern "rust-intrinsic" {
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn transmute<T, U>(e: T) -> U;
 
+    /// Moves a value out of scope without running its drop glue.
+    ///
+    /// This is the same as [`std::mem::forget`](../../std/mem/fn.forget.html),
+    /// without going through `ManuallyDrop`.
+    #[cfg(not(stage0))]
+    pub fn forget<T>(_: T);
+
     /// Returns `true` if the actual type given as `T` requires drop
     /// glue; returns `false` if the actual type provided for `T`
     /// implements `Copy`.
@@ -1350,6 +1357,22 @@ extern "rust-intrinsic" {
     /// [`std::u32::wrapping_mul`](../../std/primitive.u32.html#method.wrapping_mul)
     pub fn overflowing_mul<T>(a: T, b: T) -> T;
 
+    /// Returns (a + b) mod 2<sup>N</sup>, where N is the width of T in bits.
+    /// This is the same operation as `overflowing_add`, named after the
+    /// `wrapping_add` method of the integer primitives.
+    #[cfg(not(stage0))]
+    pub fn wrapping_add<T>(a: T, b: T) -> T;
+    /// Returns (a - b) mod 2<sup>N</sup>, where N is the width of T in bits.
+    /// This is the same operation as `overflowing_sub`, named after the
+    /// `wrapping_sub` method of the integer primitives.
+    #[cfg(not(stage0))]
+    pub fn wrapping_sub<T>(a: T, b: T) -> T;
+    /// Returns (a * b) mod 2<sup>N</sup>, where N is the width of T in bits.
+    /// This is the same operation as `overflowing_mul`, named after the
+    /// `wrapping_mul` method of the integer primitives.
+    #[cfg(not(stage0))]
+    pub fn wrapping_mul<T>(a: T, b: T) -> T;
+
     /// Returns the value of the discriminant for the variant in 'v',
     /// cast to a `u64`; if `T` has no discriminant, returns 0.
     pub fn discriminant_value<T>(v: &T) -> u64;
@@ -19,7 +19,7 @@ use std::error::Error;
 ///     x & y & z
 ///
 /// where the format-string of the dep-node must contain `x`, `y`, and
-/// `z`.
+/// `z`. An empty filter or `*` accepts all nodes.
 #[derive(Debug)]
 pub struct DepNodeFilter {
     text: String
@@ -34,7 +34,7 @@ impl DepNodeFilter {
 
     /// True if all nodes always pass the filter.
     pub fn accepts_all(&self) -> bool {
-        self.text.is_empty()
+        self.text.is_empty() || self.text == "*"
     }
 
     /// Tests whether `node` meets the filter, returning true if so.
@@ -42,7 +42,7 @@ impl DepNodeFilter {
         let debug_str = format!("{:?}", node);
         self.text.split("&")
                  .map(|s| s.trim())
-                 .all(|f| debug_str.contains(f))
+                 .all(|f| f == "*" || debug_str.contains(f))
     }
 }
 
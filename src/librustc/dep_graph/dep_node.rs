@@ -484,11 +484,13 @@ define_dep_nodes!( <'tcx>
     [] MirConst(DefId),
     [] MirValidated(DefId),
     [] MirOptimized(DefId),
+    [] MirBodyMetrics(DefId),
     [] MirShim { instance_def: InstanceDef<'tcx> },
 
     [] BorrowCheckKrate,
     [] BorrowCheck(DefId),
     [] MirBorrowCheck(DefId),
+    [] MirBorrowckSummary(DefId),
     [] UnsafetyCheckResult(DefId),
     [] UnsafeDeriveOnReprPacked(DefId),
 
@@ -13,7 +13,8 @@ use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::indexed_vec::{Idx, IndexVec};
 use rustc_data_structures::small_vec::SmallVec;
-use rustc_data_structures::sync::{Lrc, Lock};
+use rustc_data_structures::unhash::UnhashMap;
+use rustc_data_structures::sync::{AppendOnlyVec, Lrc, Lock};
 use std::env;
 use std::hash::Hash;
 use ty::{self, TyCtxt};
@@ -78,7 +79,7 @@ struct DepGraphData {
     /// things available to us. If we find that they are not dirty, we
     /// load the path to the file storing those work-products here into
     /// this map. We can later look for and extract that data.
-    previous_work_products: FxHashMap<WorkProductId, WorkProduct>,
+    previous_work_products: UnhashMap<WorkProductId, WorkProduct>,
 
     dep_node_debug: Lock<FxHashMap<DepNode, String>>,
 
@@ -89,7 +90,7 @@ struct DepGraphData {
 impl DepGraph {
 
     pub fn new(prev_graph: PreviousDepGraph,
-               prev_work_products: FxHashMap<WorkProductId, WorkProduct>) -> DepGraph {
+               prev_work_products: UnhashMap<WorkProductId, WorkProduct>) -> DepGraph {
         // Pre-allocate the fingerprints array. We over-allocate a little so
         // that we hopefully don't have to re-allocate during this compilation
         // session.
@@ -128,9 +129,9 @@ impl DepGraph {
         let nodes: Vec<_> = current_dep_graph.nodes.iter().cloned().collect();
         let mut edges = Vec::new();
         for (index, edge_targets) in current_dep_graph.edges.iter_enumerated() {
-            let from = current_dep_graph.nodes[index];
+            let from = current_dep_graph.node(index);
             for &edge_target in edge_targets.iter() {
-                let to = current_dep_graph.nodes[edge_target];
+                let to = current_dep_graph.node(edge_target);
                 edges.push((from, to));
             }
         }
@@ -440,7 +441,7 @@ impl DepGraph {
             Some(&fingerprint) => fingerprint,
             None => {
                 if let Some(ref data) = self.data {
-                    let dep_node = data.current.borrow().nodes[dep_node_index];
+                    let dep_node = data.current.borrow().node(dep_node_index);
                     bug!("Could not find current fingerprint for {:?}", dep_node)
                 } else {
                     bug!("Could not find current fingerprint for {:?}", dep_node_index)
@@ -470,7 +471,7 @@ impl DepGraph {
 
     /// Access the map of work-products created during the cached run. Only
     /// used during saving of the dep-graph.
-    pub fn previous_work_products(&self) -> &FxHashMap<WorkProductId, WorkProduct> {
+    pub fn previous_work_products(&self) -> &UnhashMap<WorkProductId, WorkProduct> {
         &self.data.as_ref().unwrap().previous_work_products
     }
 
@@ -503,7 +504,8 @@ impl DepGraph {
         let current_dep_graph = self.data.as_ref().unwrap().current.borrow();
 
         let fingerprints = self.fingerprints.borrow().clone().convert_index_type();
-        let nodes = current_dep_graph.nodes.clone().convert_index_type();
+        let nodes: IndexVec<SerializedDepNodeIndex, DepNode> =
+            current_dep_graph.nodes.iter().cloned().collect();
 
         let total_edge_count: usize = current_dep_graph.edges.iter()
                                                              .map(|v| v.len())
@@ -818,7 +820,7 @@ impl DepGraph {
 
     pub fn mark_loaded_from_cache(&self, dep_node_index: DepNodeIndex, state: bool) {
         debug!("mark_loaded_from_cache({:?}, {})",
-               self.data.as_ref().unwrap().current.borrow().nodes[dep_node_index],
+               self.data.as_ref().unwrap().current.borrow().node(dep_node_index),
                state);
 
         self.data
@@ -882,9 +884,12 @@ pub enum WorkProductFileKind {
 }
 
 pub(super) struct CurrentDepGraph {
-    nodes: IndexVec<DepNodeIndex, DepNode>,
+    // Indexed by `DepNodeIndex`. Registering a node only appends to this, so
+    // nodes which were allocated before can be read without synchronizing
+    // with the allocation of new ones.
+    nodes: AppendOnlyVec<DepNode>,
     edges: IndexVec<DepNodeIndex, SmallVec<[DepNodeIndex; 8]>>,
-    node_to_node_index: FxHashMap<DepNode, DepNodeIndex>,
+    node_to_node_index: UnhashMap<DepNode, DepNodeIndex>,
     forbidden_edge: Option<EdgeFilter>,
 
     // Anonymous DepNodes are nodes the ID of which we compute from the list of
@@ -929,9 +934,9 @@ impl CurrentDepGraph {
         };
 
         CurrentDepGraph {
-            nodes: IndexVec::new(),
+            nodes: AppendOnlyVec::new(),
             edges: IndexVec::new(),
-            node_to_node_index: FxHashMap(),
+            node_to_node_index: UnhashMap(),
             anon_id_seed: stable_hasher.finish(),
             forbidden_edge,
             total_read_count: 0,
@@ -939,6 +944,11 @@ impl CurrentDepGraph {
         }
     }
 
+    #[inline]
+    fn node(&self, index: DepNodeIndex) -> DepNode {
+        *self.nodes.get(index.index()).unwrap()
+    }
+
     fn complete_task(&mut self, key: DepNode, task: OpenTask) -> DepNodeIndex {
         if let OpenTask::Regular(task) = task {
             let RegularOpenTask {
@@ -960,13 +970,13 @@ impl CurrentDepGraph {
                    //            better in general.
                    node.kind != DepKind::DefSpan &&
                     reads.iter().any(|&i| {
-                        !(self.nodes[i].kind == DepKind::CrateMetadata ||
-                          self.nodes[i].kind == DepKind::Krate)
+                        !(self.node(i).kind == DepKind::CrateMetadata ||
+                          self.node(i).kind == DepKind::Krate)
                     })
                 {
                     bug!("Input node {:?} with unexpected reads: {:?}",
                         node,
-                        reads.iter().map(|&i| self.nodes[i]).collect::<Vec<_>>())
+                        reads.iter().map(|&i| self.node(i)).collect::<Vec<_>>())
                 }
             }
 
@@ -988,7 +998,7 @@ impl CurrentDepGraph {
             let mut hasher = StableHasher::new();
 
             for &read in reads.iter() {
-                let read_dep_node = self.nodes[read];
+                let read_dep_node = self.node(read);
 
                 ::std::mem::discriminant(&read_dep_node.kind).hash(&mut hasher);
 
@@ -1040,7 +1050,7 @@ impl CurrentDepGraph {
                         if cfg!(debug_assertions) {
                             if let Some(ref forbidden_edge) = self.forbidden_edge {
                                 let target = &task.node;
-                                let source = self.nodes[source];
+                                let source = self.node(source);
                                 if forbidden_edge.test(&source, &target) {
                                     bug!("forbidden edge {:?} -> {:?} created",
                                         source,
@@ -1072,8 +1082,7 @@ impl CurrentDepGraph {
         debug_assert_eq!(self.edges.len(), self.nodes.len());
         debug_assert_eq!(self.node_to_node_index.len(), self.nodes.len());
         debug_assert!(!self.node_to_node_index.contains_key(&dep_node));
-        let dep_node_index = DepNodeIndex::new(self.nodes.len());
-        self.nodes.push(dep_node);
+        let dep_node_index = DepNodeIndex::new(self.nodes.push(dep_node));
         self.node_to_node_index.insert(dep_node, dep_node_index);
         self.edges.push(edges);
         dep_node_index
@@ -9,19 +9,19 @@
 // except according to those terms.
 
 use ich::Fingerprint;
-use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::unhash::UnhashMap;
 use super::dep_node::DepNode;
 use super::serialized::{SerializedDepGraph, SerializedDepNodeIndex};
 
 #[derive(Debug, RustcEncodable, RustcDecodable)]
 pub struct PreviousDepGraph {
     data: SerializedDepGraph,
-    index: FxHashMap<DepNode, SerializedDepNodeIndex>,
+    index: UnhashMap<DepNode, SerializedDepNodeIndex>,
 }
 
 impl PreviousDepGraph {
     pub fn new(data: SerializedDepGraph) -> PreviousDepGraph {
-        let index: FxHashMap<_, _> = data.nodes
+        let index: UnhashMap<_, _> = data.nodes
             .iter_enumerated()
             .map(|(idx, &dep_node)| (dep_node, idx))
             .collect();
@@ -21,6 +21,7 @@ use ich::Fingerprint;
 use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::indexed_vec::{IndexVec};
 use rustc_data_structures::stable_hasher::StableHasher;
+use rustc_data_structures::unhash::UnhashMap;
 use serialize::{Encodable, Decodable, Encoder, Decoder};
 use session::CrateDisambiguator;
 use std::borrow::Borrow;
@@ -94,7 +95,7 @@ impl DefPathTable {
 
     pub fn add_def_path_hashes_to(&self,
                                   cnum: CrateNum,
-                                  out: &mut FxHashMap<DefPathHash, DefId>) {
+                                  out: &mut UnhashMap<DefPathHash, DefId>) {
         for &address_space in &[DefIndexAddressSpace::Low, DefIndexAddressSpace::High] {
             out.extend(
                 (&self.def_path_hashes[address_space.index()])
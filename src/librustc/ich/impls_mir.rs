@@ -18,6 +18,13 @@ use rustc_data_structures::stable_hasher::{HashStable, StableHasher,
 use std::mem;
 
 impl_stable_hash_for!(struct mir::GeneratorLayout<'tcx> { fields });
+impl_stable_hash_for!(struct mir::BodyMetrics {
+    basic_blocks,
+    statements,
+    locals,
+    call_sites,
+    loops
+});
 impl_stable_hash_for!(struct mir::SourceInfo { span, scope });
 impl_stable_hash_for!(enum mir::Mutability { Mut, Not });
 impl_stable_hash_for!(enum mir::LocalKind { Var, Temp, Arg, ReturnPointer });
@@ -45,6 +52,7 @@ for mir::BorrowKind {
 
         match *self {
             mir::BorrowKind::Shared |
+            mir::BorrowKind::Shallow |
             mir::BorrowKind::Unique => {}
             mir::BorrowKind::Mut { allow_two_phase_borrow } => {
                 allow_two_phase_borrow.hash_stable(hcx, hasher);
@@ -147,6 +155,19 @@ impl<'a> HashStable<StableHashingContext<'a>> for mir::Promoted {
     }
 }
 
+impl<'a> HashStable<StableHashingContext<'a>> for mir::SwitchTargets {
+    fn hash_stable<W: StableHasherResult>(&self,
+                                          hcx: &mut StableHashingContext<'a>,
+                                          hasher: &mut StableHasher<W>) {
+        self.all_targets().len().hash_stable(hcx, hasher);
+        for (value, target) in self.iter() {
+            value.hash_stable(hcx, hasher);
+            target.hash_stable(hcx, hasher);
+        }
+        self.otherwise().hash_stable(hcx, hasher);
+    }
+}
+
 impl<'a, 'gcx> HashStable<StableHashingContext<'a>>
 for mir::TerminatorKind<'gcx> {
     fn hash_stable<W: StableHasherResult>(&self,
@@ -160,11 +181,9 @@ for mir::TerminatorKind<'gcx> {
             }
             mir::TerminatorKind::SwitchInt { ref discr,
                                              switch_ty,
-                                             ref values,
                                              ref targets } => {
                 discr.hash_stable(hcx, hasher);
                 switch_ty.hash_stable(hcx, hasher);
-                values.hash_stable(hcx, hasher);
                 targets.hash_stable(hcx, hasher);
             }
             mir::TerminatorKind::Resume |
@@ -363,7 +382,7 @@ for mir::ProjectionElem<'gcx, V, T>
     }
 }
 
-impl_stable_hash_for!(struct mir::SourceScopeData { span, parent_scope });
+impl_stable_hash_for!(struct mir::SourceScopeData<'tcx> { span, parent_scope, inlined });
 impl_stable_hash_for!(struct mir::SourceScopeLocalData {
     lint_root, safety
 });
@@ -547,7 +566,33 @@ impl_stable_hash_for!(struct mir::Location { block, statement_index });
 
 impl_stable_hash_for!(struct mir::BorrowCheckResult<'tcx> {
     closure_requirements,
-    used_mut_upvars
+    used_mut_upvars,
+    summary
+});
+
+impl_stable_hash_for!(struct mir::BorrowckSummary {
+    borrows,
+    moves,
+    used_mut_locals,
+    accesses
+});
+
+impl_stable_hash_for!(struct mir::BorrowSummary {
+    kind,
+    span,
+    region,
+    borrowed_place,
+    live_spans
+});
+
+impl_stable_hash_for!(struct mir::VariableAccess { kind, span, var_span });
+
+impl_stable_hash_for!(enum mir::VariableAccessKind {
+    Read,
+    Write,
+    Move,
+    SharedBorrow,
+    MutBorrow
 });
 
 impl_stable_hash_for!(struct mir::ClosureRegionRequirements<'tcx> {
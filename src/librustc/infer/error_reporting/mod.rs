@@ -321,7 +321,12 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
     ) {
         debug!("report_region_errors(): {} errors to start", errors.len());
 
-        if will_later_be_reported_by_nll && self.tcx.use_mir_borrowck() {
+        // In migrate mode, the lexical region errors stay the authoritative
+        // ones, since the NLL errors may be downgraded to warnings.
+        if will_later_be_reported_by_nll
+            && self.tcx.use_mir_borrowck()
+            && !self.tcx.migrate_borrowck()
+        {
             // With `#![feature(nll)]`, we want to present a nice user
             // experience, so don't even mention the errors from the
             // AST checker.
@@ -160,7 +160,7 @@ declare_lint! {
 
 declare_lint! {
     pub SAFE_PACKED_BORROWS,
-    Warn,
+    Deny,
     "safe borrows of fields of packed structs were was erroneously allowed"
 }
 
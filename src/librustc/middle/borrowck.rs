@@ -15,9 +15,22 @@ use util::nodemap::FxHashSet;
 use rustc_data_structures::stable_hasher::{HashStable, StableHasher,
                                            StableHasherResult};
 
+/// Whether the AST borrowck found any errors in a body, even if it didn't emit them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum SignalledError {
+    SawSomeError,
+    NoErrorsSeen,
+}
+
+impl_stable_hash_for!(enum self::SignalledError {
+    SawSomeError,
+    NoErrorsSeen
+});
+
 #[derive(Debug, RustcEncodable, RustcDecodable)]
 pub struct BorrowCheckResult {
     pub used_mut_nodes: FxHashSet<HirId>,
+    pub signalled_any_error: SignalledError,
 }
 
 impl<'a> HashStable<StableHashingContext<'a>> for BorrowCheckResult {
@@ -26,7 +39,9 @@ impl<'a> HashStable<StableHashingContext<'a>> for BorrowCheckResult {
                                           hasher: &mut StableHasher<W>) {
         let BorrowCheckResult {
             ref used_mut_nodes,
+            ref signalled_any_error,
         } = *self;
         used_mut_nodes.hash_stable(hcx, hasher);
+        signalled_any_error.hash_stable(hcx, hasher);
     }
 }
@@ -50,12 +50,16 @@ impl Cache {
         }
     }
 
+    /// Drops everything computed from the CFG. This has to be called whenever
+    /// blocks are added or removed or the successors of a terminator change.
     pub fn invalidate(&self) {
         // FIXME: consider being more fine-grained
         *self.predecessors.borrow_mut() = None;
     }
 
     pub fn predecessors(&self, mir: &Mir) -> ReadGuard<IndexVec<BasicBlock, Vec<BasicBlock>>> {
+        // The cache is dropped by every edit that may change the CFG, see
+        // `Mir::basic_blocks_mut`, so if it is there it is up to date.
         if self.predecessors.borrow().is_none() {
             *self.predecessors.borrow_mut() = Some(calculate_predecessors(mir));
         }
@@ -70,6 +70,39 @@ impl<'tcx> HasLocalDecls<'tcx> for Mir<'tcx> {
     }
 }
 
+/// The various "big phases" that MIR goes through.
+///
+/// Each phase is reached by running one suite of passes over the MIR of the
+/// previous phase, and the queries handing out MIR (`mir_built`, `mir_const`,
+/// `mir_validated` and `optimized_mir`) each return MIR of one phase. Passes
+/// which only make sense in some phase declare it through
+/// `MirPass::required_phase`.
+#[derive(Copy, Clone, RustcEncodable, RustcDecodable, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MirPhase {
+    /// MIR as produced by MIR building, as returned by `mir_built`.
+    Build = 0,
+    /// MIR ready for constant evaluation and qualification, as returned by `mir_const`.
+    Const = 1,
+    /// MIR ready for borrowck, as returned by `mir_validated`.
+    Validated = 2,
+    /// MIR ready for codegen and miri, as returned by `optimized_mir`.
+    Optimized = 3,
+}
+
+impl MirPhase {
+    /// Gets the index of the current MirPhase within the set of all MirPhases.
+    pub fn phase_index(&self) -> usize {
+        *self as usize
+    }
+}
+
+impl_stable_hash_for!(enum self::MirPhase {
+    Build,
+    Const,
+    Validated,
+    Optimized
+});
+
 /// Lowered representation of a single function.
 #[derive(Clone, RustcEncodable, RustcDecodable, Debug)]
 pub struct Mir<'tcx> {
@@ -77,9 +110,13 @@ pub struct Mir<'tcx> {
     /// that indexes into this vector.
     basic_blocks: IndexVec<BasicBlock, BasicBlockData<'tcx>>,
 
+    /// Records how far through the "desugaring and optimization" process this particular
+    /// MIR has traversed.
+    pub phase: MirPhase,
+
     /// List of source scopes; these are referenced by statements
     /// and used for debuginfo. Indexed by a `SourceScope`.
-    pub source_scopes: IndexVec<SourceScope, SourceScopeData>,
+    pub source_scopes: IndexVec<SourceScope, SourceScopeData<'tcx>>,
 
     /// Crate-local information for each source scope, that can't (and
     /// needn't) be tracked across crates.
@@ -118,6 +155,13 @@ pub struct Mir<'tcx> {
     /// the first argument is either the closure or a reference to it.
     pub upvar_decls: Vec<UpvarDecl>,
 
+    /// Debug information pertaining to user variables.
+    ///
+    /// Passes which move or rename locals keep this up to date through
+    /// `MutVisitor::visit_var_debug_info`, so variables stay visible in the
+    /// debugger after optimizations.
+    pub var_debug_info: Vec<VarDebugInfo<'tcx>>,
+
     /// Mark an argument local (which must be a tuple) as getting passed as
     /// its individual components at the LLVM level.
     ///
@@ -137,13 +181,14 @@ pub const START_BLOCK: BasicBlock = BasicBlock(0);
 impl<'tcx> Mir<'tcx> {
     pub fn new(
         basic_blocks: IndexVec<BasicBlock, BasicBlockData<'tcx>>,
-        source_scopes: IndexVec<SourceScope, SourceScopeData>,
+        source_scopes: IndexVec<SourceScope, SourceScopeData<'tcx>>,
         source_scope_local_data: ClearCrossCrate<IndexVec<SourceScope, SourceScopeLocalData>>,
         promoted: IndexVec<Promoted, Mir<'tcx>>,
         yield_ty: Option<Ty<'tcx>>,
         local_decls: IndexVec<Local, LocalDecl<'tcx>>,
         arg_count: usize,
         upvar_decls: Vec<UpvarDecl>,
+        var_debug_info: Vec<VarDebugInfo<'tcx>>,
         span: Span,
     ) -> Self {
         // We need `arg_count` locals, and one for the return place
@@ -155,6 +200,7 @@ impl<'tcx> Mir<'tcx> {
         );
 
         Mir {
+            phase: MirPhase::Build,
             basic_blocks,
             source_scopes,
             source_scope_local_data,
@@ -165,6 +211,7 @@ impl<'tcx> Mir<'tcx> {
             local_decls,
             arg_count,
             upvar_decls,
+            var_debug_info,
             spread_arg: None,
             span,
             cache: cache::Cache::new(),
@@ -182,6 +229,27 @@ impl<'tcx> Mir<'tcx> {
         &mut self.basic_blocks
     }
 
+    /// Returns the basic blocks for modifications which leave the CFG alone,
+    /// e.g. editing statements, so that the cached predecessors stay valid.
+    ///
+    /// No blocks may be added or removed and no terminator may change its
+    /// successors through the returned reference. Code which finds out only
+    /// while editing that it has to change the CFG must call
+    /// `invalidate_cfg_cache` afterwards.
+    #[inline]
+    pub fn basic_blocks_mut_preserves_cfg(
+        &mut self,
+    ) -> &mut IndexVec<BasicBlock, BasicBlockData<'tcx>> {
+        &mut self.basic_blocks
+    }
+
+    /// Drops the cached predecessors after the CFG was changed through
+    /// `basic_blocks_mut_preserves_cfg`.
+    #[inline]
+    pub fn invalidate_cfg_cache(&mut self) {
+        self.cache.invalidate();
+    }
+
     #[inline]
     pub fn basic_blocks_and_local_decls_mut(
         &mut self,
@@ -328,6 +396,55 @@ impl<'tcx> Mir<'tcx> {
     pub fn return_ty(&self) -> Ty<'tcx> {
         self.local_decls[RETURN_PLACE].ty
     }
+
+    /// Computes the size and shape of this body. Prefer the `mir_body_metrics`
+    /// query when asking about another item's optimized MIR, which caches this.
+    pub fn metrics(&self) -> BodyMetrics {
+        let dominators = self.dominators();
+        let mut metrics = BodyMetrics {
+            basic_blocks: self.basic_blocks.len(),
+            statements: 0,
+            locals: self.local_decls.len(),
+            call_sites: 0,
+            loops: 0,
+        };
+
+        for (bb, data) in self.basic_blocks.iter_enumerated() {
+            metrics.statements += data.statements.iter().filter(|stmt| match stmt.kind {
+                StatementKind::StorageLive(_) |
+                StatementKind::StorageDead(_) |
+                StatementKind::Nop => false,
+                _ => true,
+            }).count();
+
+            if let TerminatorKind::Call { .. } = data.terminator().kind {
+                metrics.call_sites += 1;
+            }
+
+            // Every edge into a block dominating its source closes a loop.
+            if dominators.is_reachable(bb) {
+                metrics.loops += data.terminator().successors()
+                    .filter(|&&succ| dominators.is_dominated_by(bb, succ))
+                    .count();
+            }
+        }
+
+        metrics
+    }
+}
+
+/// Size and shape of a MIR body, as used by the heuristics of the MIR passes
+/// (inlining cost, const propagation limits) and `-Z borrowck-stats`.
+#[derive(Copy, Clone, Debug, Default, RustcEncodable, RustcDecodable)]
+pub struct BodyMetrics {
+    pub basic_blocks: usize,
+    /// Statements, not counting storage markers and `Nop`s.
+    pub statements: usize,
+    pub locals: usize,
+    /// Number of `Call` terminators.
+    pub call_sites: usize,
+    /// Number of back edges in the control flow graph.
+    pub loops: usize,
 }
 
 #[derive(Copy, Clone, Debug, RustcEncodable, RustcDecodable)]
@@ -342,6 +459,7 @@ pub enum Safety {
 }
 
 impl_stable_hash_for!(struct Mir<'tcx> {
+    phase,
     basic_blocks,
     source_scopes,
     source_scope_local_data,
@@ -352,6 +470,7 @@ impl_stable_hash_for!(struct Mir<'tcx> {
     local_decls,
     arg_count,
     upvar_decls,
+    var_debug_info,
     spread_arg,
     span,
     cache
@@ -427,6 +546,27 @@ pub enum BorrowKind {
     /// Data must be immutable and is aliasable.
     Shared,
 
+    /// The immediately borrowed place must be immutable, but projections from
+    /// it don't need to be. For example, a shallow borrow of `a.b` doesn't
+    /// conflict with a mutable borrow of `a.b.c`.
+    ///
+    /// This is used when lowering matches: when matching on a place we want to
+    /// ensure that place has the same value from the start of the match until
+    /// an arm is selected. This prevents this code from compiling:
+    ///
+    ///     let mut x = &Some(0);
+    ///     match *x {
+    ///         None => (),
+    ///         Some(_) if { x = &None; false } => (),
+    ///         Some(_) => (),
+    ///     }
+    ///
+    /// This can't be a shared borrow because mutably borrowing `(*x as Some).0`
+    /// should not prevent `if let None = x { ... }`, for example, because
+    /// mutating `(*x as Some).0` can't affect the discriminant of `x`.
+    /// These borrows are removed once borrowck is done with them.
+    Shallow,
+
     /// Data must be immutable but not aliasable.  This kind of borrow
     /// cannot currently be expressed by the user and is used only in
     /// implicit closure bindings. It is needed when you the closure
@@ -475,7 +615,7 @@ pub enum BorrowKind {
 impl BorrowKind {
     pub fn allows_two_phase_borrow(&self) -> bool {
         match *self {
-            BorrowKind::Shared | BorrowKind::Unique => false,
+            BorrowKind::Shared | BorrowKind::Shallow | BorrowKind::Unique => false,
             BorrowKind::Mut {
                 allow_two_phase_borrow,
             } => allow_two_phase_borrow,
@@ -602,10 +742,10 @@ pub struct LocalDecl<'tcx> {
     /// Type of this local.
     pub ty: Ty<'tcx>,
 
-    /// Name of the local, used in debuginfo and pretty-printing.
+    /// Name of the local, used in pretty-printing and diagnostics.
     ///
-    /// Note that function arguments can also have this set to `Some(_)`
-    /// to generate better debuginfo.
+    /// Note that function arguments can also have this set to `Some(_)`.
+    /// Debuginfo does not use this, see `Mir::var_debug_info` instead.
     pub name: Option<Name>,
 
     /// The *syntactic* (i.e. not visibility) source scope the local is defined
@@ -802,6 +942,29 @@ pub struct UpvarDecl {
     pub mutability: Mutability,
 }
 
+/// Debug information pertaining to a user variable.
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+pub struct VarDebugInfo<'tcx> {
+    pub name: Name,
+
+    /// Source info of the user variable, including the scope
+    /// within which the variable is visible (to debuginfo).
+    pub source_info: SourceInfo,
+
+    /// Where the data for this user variable is to be found.
+    ///
+    /// This is always based on a `Local`, never on a `Static`, and it only
+    /// contains `Deref`, `Field` and `Downcast` projections, which
+    /// debuginfo can express as address operations.
+    pub place: Place<'tcx>,
+}
+
+impl_stable_hash_for!(struct VarDebugInfo<'tcx> {
+    name,
+    source_info,
+    place
+});
+
 ///////////////////////////////////////////////////////////////////////////
 // BasicBlock
 
@@ -861,22 +1024,9 @@ pub enum TerminatorKind<'tcx> {
         /// type of value being tested
         switch_ty: Ty<'tcx>,
 
-        /// Possible values. The locations to branch to in each case
-        /// are found in the corresponding indices from the `targets` vector.
-        values: Cow<'tcx, [u128]>,
-
-        /// Possible branch sites. The last element of this vector is used
-        /// for the otherwise branch, so targets.len() == values.len() + 1
-        /// should hold.
-        // This invariant is quite non-obvious and also could be improved.
-        // One way to make this invariant is to have something like this instead:
-        //
-        // branches: Vec<(ConstInt, BasicBlock)>,
-        // otherwise: Option<BasicBlock> // exhaustive if None
-        //
-        // However we’ve decided to keep this as-is until we figure a case
-        // where some other approach seems to be strictly better than other.
-        targets: Vec<BasicBlock>,
+        /// the block to jump to for each tested value, and the `otherwise`
+        /// block for all other values
+        targets: SwitchTargets,
     },
 
     /// Indicates that the landing pad is finished and unwinding should
@@ -997,6 +1147,73 @@ pub enum TerminatorKind<'tcx> {
     },
 }
 
+/// The targets of a `SwitchInt` terminator: a block for each of some values
+/// of the discriminant, and an `otherwise` block for all other values.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
+pub struct SwitchTargets {
+    /// The tested values; no value appears twice.
+    values: Vec<u128>,
+    /// The block for each of `values`, followed by the `otherwise` block.
+    /// They are kept in one vector so that `successors` is a single slice.
+    targets: Vec<BasicBlock>,
+}
+
+impl SwitchTargets {
+    /// Creates switch targets jumping to the block paired with the value of
+    /// the discriminant, or to `otherwise` if no value matches.
+    pub fn new<I>(cases: I, otherwise: BasicBlock) -> Self
+        where I: IntoIterator<Item = (u128, BasicBlock)>
+    {
+        let (values, mut targets): (Vec<_>, Vec<_>) = cases.into_iter().unzip();
+        targets.push(otherwise);
+        SwitchTargets { values, targets }
+    }
+
+    /// Creates switch targets jumping to `then` if the discriminant is
+    /// `value`, and to `else_` otherwise.
+    pub fn static_if(value: u128, then: BasicBlock, else_: BasicBlock) -> Self {
+        SwitchTargets {
+            values: vec![value],
+            targets: vec![then, else_],
+        }
+    }
+
+    /// If these targets test a single value, returns that value, the block
+    /// for it and the `otherwise` block.
+    pub fn as_static_if(&self) -> Option<(u128, BasicBlock, BasicBlock)> {
+        match (&self.values[..], &self.targets[..]) {
+            (&[value], &[then, else_]) => Some((value, then, else_)),
+            _ => None,
+        }
+    }
+
+    /// The block taken when no tested value matches.
+    pub fn otherwise(&self) -> BasicBlock {
+        *self.targets.last().unwrap()
+    }
+
+    /// The tested values, each with the block taken for it.
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = (u128, BasicBlock)> + 'a {
+        self.values.iter().cloned().zip(self.targets.iter().cloned())
+    }
+
+    /// The block taken when the discriminant is `value`.
+    pub fn target_for_value(&self, value: u128) -> BasicBlock {
+        self.iter()
+            .find(|&(v, _)| v == value)
+            .map_or_else(|| self.otherwise(), |(_, target)| target)
+    }
+
+    /// All the blocks, in the order of `iter` followed by `otherwise`.
+    pub fn all_targets(&self) -> &[BasicBlock] {
+        &self.targets
+    }
+
+    pub fn all_targets_mut(&mut self) -> &mut [BasicBlock] {
+        &mut self.targets
+    }
+}
+
 pub type Successors<'a> =
     iter::Chain<option::IntoIter<&'a BasicBlock>, slice::Iter<'a, BasicBlock>>;
 pub type SuccessorsMut<'a> =
@@ -1027,12 +1244,10 @@ impl<'tcx> TerminatorKind<'tcx> {
         t: BasicBlock,
         f: BasicBlock,
     ) -> TerminatorKind<'tcx> {
-        static BOOL_SWITCH_FALSE: &'static [u128] = &[0];
         TerminatorKind::SwitchInt {
             discr: cond,
             switch_ty: tcx.types.bool,
-            values: From::from(BOOL_SWITCH_FALSE),
-            targets: vec![f, t],
+            targets: SwitchTargets::static_if(0, f, t),
         }
     }
 
@@ -1113,7 +1328,7 @@ impl<'tcx> TerminatorKind<'tcx> {
                 real_target: ref t,
                 unwind: Some(ref u),
             } => Some(t).into_iter().chain(slice::from_ref(u)),
-            SwitchInt { ref targets, .. } => None.into_iter().chain(&targets[..]),
+            SwitchInt { ref targets, .. } => None.into_iter().chain(targets.all_targets()),
             FalseEdges {
                 ref real_target,
                 ref imaginary_targets,
@@ -1200,7 +1415,7 @@ impl<'tcx> TerminatorKind<'tcx> {
             } => Some(t).into_iter().chain(slice::from_mut(u)),
             SwitchInt {
                 ref mut targets, ..
-            } => None.into_iter().chain(&mut targets[..]),
+            } => None.into_iter().chain(targets.all_targets_mut()),
             FalseEdges {
                 ref mut real_target,
                 ref mut imaginary_targets,
@@ -1448,7 +1663,7 @@ impl<'tcx> TerminatorKind<'tcx> {
             Return | Resume | Abort | Unreachable | GeneratorDrop => vec![],
             Goto { .. } => vec!["".into()],
             SwitchInt {
-                ref values,
+                ref targets,
                 switch_ty,
                 ..
             } => {
@@ -1457,9 +1672,9 @@ impl<'tcx> TerminatorKind<'tcx> {
                     let switch_ty = tcx.lift_to_global(&switch_ty).unwrap();
                     tcx.layout_of(param_env.and(switch_ty)).unwrap().size
                 });
-                values
+                targets
                     .iter()
-                    .map(|&u| {
+                    .map(|(u, _)| {
                         let mut s = String::new();
                         print_miri_value(
                             Value::Scalar(Scalar::Bits {
@@ -1725,7 +1940,7 @@ pub struct Projection<'tcx, B, V, T> {
     pub elem: ProjectionElem<'tcx, V, T>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
 pub enum ProjectionElem<'tcx, V, T> {
     Deref,
     Field(Field, T),
@@ -1793,8 +2008,127 @@ impl<'tcx> Place<'tcx> {
     pub fn elem(self, elem: PlaceElem<'tcx>) -> Place<'tcx> {
         Place::Projection(Box::new(PlaceProjection { base: self, elem }))
     }
+
+    /// Builds the place `base` projected by each of `elems` in turn.
+    pub fn from_projection(base: Place<'tcx>, elems: &[PlaceElem<'tcx>]) -> Place<'tcx> {
+        elems.iter().fold(base, |place, &elem| place.elem(elem))
+    }
+
+    /// Returns the outermost projection of this place, if any, e.g. `.c`
+    /// in `a.b.c`.
+    pub fn last_projection(&self) -> Option<&PlaceProjection<'tcx>> {
+        match *self {
+            Place::Projection(ref proj) => Some(proj),
+            Place::Local(_) | Place::Static(_) => None,
+        }
+    }
+
+    /// Returns the place all projections of this place are applied to,
+    /// which is always either a `Place::Local` or a `Place::Static`.
+    pub fn base_place(&self) -> &Place<'tcx> {
+        let mut place = self;
+        while let Place::Projection(ref proj) = *place {
+            place = &proj.base;
+        }
+        place
+    }
+
+    /// Returns the projections of this place, innermost first, as an
+    /// interned list. Two places with the same base are equal exactly if
+    /// their projection lists are the same pointer.
+    pub fn projection<'a, 'gcx>(&self, tcx: TyCtxt<'a, 'gcx, 'tcx>) -> PlaceElems<'tcx> {
+        let mut elems = vec![];
+        let mut place = self;
+        while let Place::Projection(ref proj) = *place {
+            elems.push(proj.elem);
+            place = &proj.base;
+        }
+        elems.reverse();
+        tcx.intern_place_elems(&elems)
+    }
+
+    /// Calls `op` with the base of this place and an iterator over its
+    /// projections, innermost first. So given `(*a.b).c`, `op` is called
+    /// with `a` and an iterator yielding `a.b`, `*a.b` and `(*a.b).c`.
+    ///
+    /// This recurses once per projection, to link the projections up on
+    /// the stack, but it does not allocate.
+    pub fn iterate<R>(
+        &self,
+        op: impl FnOnce(&Place<'tcx>, ProjectionsIter<'_, 'tcx>) -> R,
+    ) -> R {
+        self.iterate2(&Projections::Empty, op)
+    }
+
+    fn iterate2<R>(
+        &self,
+        next: &Projections<'_, 'tcx>,
+        op: impl FnOnce(&Place<'tcx>, ProjectionsIter<'_, 'tcx>) -> R,
+    ) -> R {
+        match *self {
+            Place::Projection(ref interior) => interior.base.iterate2(
+                &Projections::List {
+                    projection: interior,
+                    next,
+                },
+                op,
+            ),
+
+            Place::Local(_) | Place::Static(_) => op(self, next.iter()),
+        }
+    }
 }
 
+/// An interned list of place projections; see `Place::projection`.
+pub type PlaceElems<'tcx> = &'tcx ty::Slice<PlaceElem<'tcx>>;
+
+/// A linked list of projections running up the stack; begins with the
+/// innermost projection and extends outwards (e.g., `a.b.c` would have the
+/// projection `a.b` with a "next" pointer to `a.b.c`). Created by
+/// `Place::iterate`.
+///
+/// This particular representation was chosen because it makes a
+/// measurable difference to NLL performance, as `places_conflict` is
+/// somewhat hot.
+pub enum Projections<'p, 'tcx: 'p> {
+    Empty,
+
+    List {
+        projection: &'p PlaceProjection<'tcx>,
+        next: &'p Projections<'p, 'tcx>,
+    },
+}
+
+impl<'p, 'tcx> Projections<'p, 'tcx> {
+    fn iter(&self) -> ProjectionsIter<'_, 'tcx> {
+        ProjectionsIter { value: self }
+    }
+}
+
+/// Iterator over the projections of a place; see `Place::iterate`.
+///
+/// Note that this iterator is fused: once it has returned `None`, it keeps
+/// returning `None`. `places_conflict` relies on being able to keep calling
+/// `next` after that.
+pub struct ProjectionsIter<'p, 'tcx: 'p> {
+    value: &'p Projections<'p, 'tcx>,
+}
+
+impl<'p, 'tcx> Iterator for ProjectionsIter<'p, 'tcx> {
+    type Item = &'p PlaceProjection<'tcx>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let &Projections::List { projection, next } = self.value {
+            self.value = next;
+            Some(projection)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'p, 'tcx> iter::FusedIterator for ProjectionsIter<'p, 'tcx> {}
+
 impl<'tcx> Debug for Place<'tcx> {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         use self::Place::*;
@@ -1850,9 +2184,14 @@ newtype_index!(SourceScope
     });
 
 #[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
-pub struct SourceScopeData {
+pub struct SourceScopeData<'tcx> {
     pub span: Span,
     pub parent_scope: Option<SourceScope>,
+
+    /// Whether this scope is the root of a scope tree of another body,
+    /// inlined into this body by the MIR inliner.
+    /// `ty::Instance` is the callee, and the `Span` is the call site.
+    pub inlined: Option<(ty::Instance<'tcx>, Span)>,
 }
 
 #[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
@@ -2083,6 +2422,7 @@ impl<'tcx> Debug for Rvalue<'tcx> {
             Ref(region, borrow_kind, ref place) => {
                 let kind_str = match borrow_kind {
                     BorrowKind::Shared => "",
+                    BorrowKind::Shallow => "shallow ",
                     BorrowKind::Mut { .. } | BorrowKind::Unique => "mut ",
                 };
 
@@ -2408,6 +2748,58 @@ pub struct GeneratorLayout<'tcx> {
 pub struct BorrowCheckResult<'gcx> {
     pub closure_requirements: Option<ClosureRegionRequirements<'gcx>>,
     pub used_mut_upvars: SmallVec<[Field; 8]>,
+    /// Only computed under `-Z borrowck-summary`, when MIR borrowck runs.
+    pub summary: Option<BorrowckSummary>,
+}
+
+/// What MIR borrowck computed for a body, in a form that tools such as
+/// the RLS can show to the user without re-deriving it from diagnostics.
+/// See the `mir_borrowck_summary` query.
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+pub struct BorrowckSummary {
+    pub borrows: Vec<BorrowSummary>,
+    /// The span of each move out of a place.
+    pub moves: Vec<Span>,
+    /// The declarations of the `mut` user variables that are actually mutated.
+    pub used_mut_locals: Vec<Span>,
+    /// The reads, writes, moves and borrows of user variables, in the order
+    /// borrowck checked them.
+    pub accesses: Vec<VariableAccess>,
+}
+
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+pub struct BorrowSummary {
+    pub kind: BorrowKind,
+    /// The span of the borrow expression.
+    pub span: Span,
+    /// The index of the borrow's region variable in NLL region inference. The
+    /// points the region contains are summarized by `live_spans`.
+    pub region: u32,
+    /// The borrowed place as it would be written in source, if it has a name.
+    pub borrowed_place: Option<String>,
+    /// The statements and terminators at which the borrow is live.
+    pub live_spans: Vec<Span>,
+}
+
+/// An access to a user variable, as borrowck sees it. An access to a field of
+/// a variable or to the contents of a `Box` it owns is an access to the
+/// variable, while going through any other pointer only reads it.
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+pub struct VariableAccess {
+    pub kind: VariableAccessKind,
+    /// The span of the access.
+    pub span: Span,
+    /// The span of the variable's declaration.
+    pub var_span: Span,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum VariableAccessKind {
+    Read,
+    Write,
+    Move,
+    SharedBorrow,
+    MutBorrow,
 }
 
 /// After we borrow check a closure, we are left with various
@@ -2510,17 +2902,18 @@ pub enum ClosureOutlivesSubject<'tcx> {
  */
 
 CloneTypeFoldableAndLiftImpls! {
+    MirPhase,
     Mutability,
     SourceInfo,
     UpvarDecl,
     ValidationOp,
     SourceScope,
-    SourceScopeData,
     SourceScopeLocalData,
 }
 
 BraceStructTypeFoldableImpl! {
     impl<'tcx> TypeFoldable<'tcx> for Mir<'tcx> {
+        phase,
         basic_blocks,
         source_scopes,
         source_scope_local_data,
@@ -2531,12 +2924,29 @@ BraceStructTypeFoldableImpl! {
         local_decls,
         arg_count,
         upvar_decls,
+        var_debug_info,
         spread_arg,
         span,
         cache,
     }
 }
 
+BraceStructTypeFoldableImpl! {
+    impl<'tcx> TypeFoldable<'tcx> for SourceScopeData<'tcx> {
+        span,
+        parent_scope,
+        inlined,
+    }
+}
+
+BraceStructTypeFoldableImpl! {
+    impl<'tcx> TypeFoldable<'tcx> for VarDebugInfo<'tcx> {
+        name,
+        source_info,
+        place,
+    }
+}
+
 BraceStructTypeFoldableImpl! {
     impl<'tcx> TypeFoldable<'tcx> for GeneratorLayout<'tcx> {
         fields
@@ -2606,12 +3016,10 @@ impl<'tcx> TypeFoldable<'tcx> for Terminator<'tcx> {
             SwitchInt {
                 ref discr,
                 switch_ty,
-                ref values,
                 ref targets,
             } => SwitchInt {
                 discr: discr.fold_with(folder),
                 switch_ty: switch_ty.fold_with(folder),
-                values: values.clone(),
                 targets: targets.clone(),
             },
             Drop {
@@ -2868,25 +3276,39 @@ where
     B: TypeFoldable<'tcx>,
     V: TypeFoldable<'tcx>,
     T: TypeFoldable<'tcx>,
+{
+    fn super_fold_with<'gcx: 'tcx, F: TypeFolder<'gcx, 'tcx>>(&self, folder: &mut F) -> Self {
+        let base = self.base.fold_with(folder);
+        let elem = self.elem.fold_with(folder);
+
+        Projection { base, elem }
+    }
+
+    fn super_visit_with<Vs: TypeVisitor<'tcx>>(&self, visitor: &mut Vs) -> bool {
+        self.base.visit_with(visitor) || self.elem.visit_with(visitor)
+    }
+}
+
+impl<'tcx, V, T> TypeFoldable<'tcx> for ProjectionElem<'tcx, V, T>
+where
+    V: TypeFoldable<'tcx>,
+    T: TypeFoldable<'tcx>,
 {
     fn super_fold_with<'gcx: 'tcx, F: TypeFolder<'gcx, 'tcx>>(&self, folder: &mut F) -> Self {
         use mir::ProjectionElem::*;
 
-        let base = self.base.fold_with(folder);
-        let elem = match self.elem {
+        match *self {
             Deref => Deref,
             Field(f, ref ty) => Field(f, ty.fold_with(folder)),
             Index(ref v) => Index(v.fold_with(folder)),
             ref elem => elem.clone(),
-        };
-
-        Projection { base, elem }
+        }
     }
 
     fn super_visit_with<Vs: TypeVisitor<'tcx>>(&self, visitor: &mut Vs) -> bool {
         use mir::ProjectionElem::*;
 
-        self.base.visit_with(visitor) || match self.elem {
+        match *self {
             Field(_, ref ty) => ty.visit_with(visitor),
             Index(ref v) => v.visit_with(visitor),
             _ => false,
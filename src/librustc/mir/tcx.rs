@@ -245,7 +245,7 @@ impl BorrowKind {
     pub fn to_mutbl_lossy(self) -> hir::Mutability {
         match self {
             BorrowKind::Mut { .. } => hir::MutMutable,
-            BorrowKind::Shared => hir::MutImmutable,
+            BorrowKind::Shared | BorrowKind::Shallow => hir::MutImmutable,
 
             // We have no type corresponding to a unique imm borrow, so
             // use `&mut`. It gives all the capabilities of an `&uniq`
@@ -93,7 +93,7 @@ macro_rules! make_mir_visitor {
             }
 
             fn visit_source_scope_data(&mut self,
-                                           scope_data: & $($mutability)* SourceScopeData) {
+                                           scope_data: & $($mutability)* SourceScopeData<'tcx>) {
                 self.super_source_scope_data(scope_data);
             }
 
@@ -255,6 +255,11 @@ macro_rules! make_mir_visitor {
                 self.super_local_decl(local, local_decl);
             }
 
+            fn visit_var_debug_info(&mut self,
+                                    var_debug_info: & $($mutability)* VarDebugInfo<'tcx>) {
+                self.super_var_debug_info(var_debug_info);
+            }
+
             fn visit_local(&mut self,
                             _local: & $($mutability)* Local,
                             _context: PlaceContext<'tcx>,
@@ -302,6 +307,10 @@ macro_rules! make_mir_visitor {
                     self.visit_local_decl(local, & $($mutability)* mir.local_decls[local]);
                 }
 
+                for var_debug_info in & $($mutability)* mir.var_debug_info {
+                    self.visit_var_debug_info(var_debug_info);
+                }
+
                 self.visit_span(&$($mutability)* mir.span);
             }
 
@@ -328,16 +337,20 @@ macro_rules! make_mir_visitor {
             }
 
             fn super_source_scope_data(&mut self,
-                                           scope_data: & $($mutability)* SourceScopeData) {
+                                           scope_data: & $($mutability)* SourceScopeData<'tcx>) {
                 let SourceScopeData {
                     ref $($mutability)* span,
                     ref $($mutability)* parent_scope,
+                    ref $($mutability)* inlined,
                 } = *scope_data;
 
                 self.visit_span(span);
                 if let Some(ref $($mutability)* parent_scope) = *parent_scope {
                     self.visit_source_scope(parent_scope);
                 }
+                if let Some((_, ref $($mutability)* callsite_span)) = *inlined {
+                    self.visit_span(callsite_span);
+                }
             }
 
             fn super_statement(&mut self,
@@ -429,11 +442,10 @@ macro_rules! make_mir_visitor {
 
                     TerminatorKind::SwitchInt { ref $($mutability)* discr,
                                                 ref $($mutability)* switch_ty,
-                                                values: _,
                                                 ref targets } => {
                         self.visit_operand(discr, source_location);
                         self.visit_ty(switch_ty, TyContext::Location(source_location));
-                        for &target in targets {
+                        for &target in targets.all_targets() {
                             self.visit_branch(block, target);
                         }
                     }
@@ -674,10 +686,11 @@ macro_rules! make_mir_visitor {
                     ref $($mutability)* base,
                     ref $($mutability)* elem,
                 } = *proj;
-                let context = if context.is_mutating_use() {
-                    PlaceContext::Projection(Mutability::Mut)
-                } else {
-                    PlaceContext::Projection(Mutability::Not)
+                let context = match context {
+                    // Debuginfo doesn't use the base of the place either.
+                    PlaceContext::VarDebugInfo => PlaceContext::VarDebugInfo,
+                    _ if context.is_mutating_use() => PlaceContext::Projection(Mutability::Mut),
+                    _ => PlaceContext::Projection(Mutability::Not),
                 };
                 self.visit_place(base, context, location);
                 self.visit_projection_elem(elem, context, location);
@@ -728,6 +741,20 @@ macro_rules! make_mir_visitor {
                 self.visit_source_scope(visibility_scope);
             }
 
+            fn super_var_debug_info(&mut self,
+                                    var_debug_info: & $($mutability)* VarDebugInfo<'tcx>) {
+                let VarDebugInfo {
+                    name: _,
+                    ref $($mutability)* source_info,
+                    ref $($mutability)* place,
+                } = *var_debug_info;
+
+                // Debuginfo is not attached to any particular location.
+                let location = START_BLOCK.start_location();
+                self.visit_source_info(source_info);
+                self.visit_place(place, PlaceContext::VarDebugInfo, location);
+            }
+
             fn super_source_scope(&mut self,
                                       _scope: & $($mutability)* SourceScope) {
             }
@@ -912,6 +939,9 @@ pub enum PlaceContext<'tcx> {
 
     // Validation command
     Validate,
+
+    // Referenced by the debuginfo of a user variable; this is not a use
+    VarDebugInfo,
 }
 
 impl<'tcx> PlaceContext<'tcx> {
@@ -957,11 +987,12 @@ impl<'tcx> PlaceContext<'tcx> {
 
             PlaceContext::Inspect |
             PlaceContext::Borrow { kind: BorrowKind::Shared, .. } |
+            PlaceContext::Borrow { kind: BorrowKind::Shallow, .. } |
             PlaceContext::Borrow { kind: BorrowKind::Unique, .. } |
             PlaceContext::Projection(Mutability::Not) |
             PlaceContext::Copy | PlaceContext::Move |
             PlaceContext::StorageLive | PlaceContext::StorageDead |
-            PlaceContext::Validate => false,
+            PlaceContext::Validate | PlaceContext::VarDebugInfo => false,
         }
     }
 
@@ -969,6 +1000,7 @@ impl<'tcx> PlaceContext<'tcx> {
     pub fn is_nonmutating_use(&self) -> bool {
         match *self {
             PlaceContext::Inspect | PlaceContext::Borrow { kind: BorrowKind::Shared, .. } |
+            PlaceContext::Borrow { kind: BorrowKind::Shallow, .. } |
             PlaceContext::Borrow { kind: BorrowKind::Unique, .. } |
             PlaceContext::Projection(Mutability::Not) |
             PlaceContext::Copy | PlaceContext::Move => true,
@@ -977,7 +1009,7 @@ impl<'tcx> PlaceContext<'tcx> {
             PlaceContext::AsmOutput |
             PlaceContext::Call | PlaceContext::Projection(Mutability::Mut) |
             PlaceContext::Drop | PlaceContext::StorageLive | PlaceContext::StorageDead |
-            PlaceContext::Validate => false,
+            PlaceContext::Validate | PlaceContext::VarDebugInfo => false,
         }
     }
 
@@ -65,6 +65,17 @@ pub enum Sanitizer {
     Thread,
 }
 
+/// The granularity at which `-Z dump-mir-spanview` highlights source spans.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum MirSpanview {
+    /// Highlight the span of every statement and terminator.
+    Statement,
+    /// Highlight only the span of every terminator.
+    Terminator,
+    /// Highlight the combined span of each basic block.
+    Block,
+}
+
 #[derive(Clone, Copy, PartialEq, Hash)]
 pub enum OptLevel {
     No,         // -O0
@@ -455,6 +466,9 @@ pub enum BorrowckMode {
     Ast,
     Mir,
     Compare,
+    /// Use the MIR-based borrow checker, but only warn about errors in bodies
+    /// the AST-based borrow checker accepts.
+    Migrate,
 }
 
 impl BorrowckMode {
@@ -464,6 +478,7 @@ impl BorrowckMode {
             BorrowckMode::Ast => true,
             BorrowckMode::Compare => true,
             BorrowckMode::Mir => false,
+            BorrowckMode::Migrate => false,
         }
     }
     /// Should we emit the MIR-based borrow checker errors?
@@ -472,6 +487,17 @@ impl BorrowckMode {
             BorrowckMode::Ast => false,
             BorrowckMode::Compare => true,
             BorrowckMode::Mir => true,
+            BorrowckMode::Migrate => true,
+        }
+    }
+    /// Should we downgrade the MIR-based borrow checker errors to warnings
+    /// in bodies without AST-based borrow checker errors?
+    pub fn migrate(self) -> bool {
+        match self {
+            BorrowckMode::Ast => false,
+            BorrowckMode::Compare => false,
+            BorrowckMode::Mir => false,
+            BorrowckMode::Migrate => true,
         }
     }
 }
@@ -624,10 +650,18 @@ pub fn basic_options() -> Options {
 impl Options {
     /// True if there is a reason to build the dep graph.
     pub fn build_dep_graph(&self) -> bool {
-        self.incremental.is_some() || self.debugging_opts.dump_dep_graph
+        self.incremental.is_some() || self.dump_dep_graph()
             || self.debugging_opts.query_dep_graph
     }
 
+    /// True if the dep graph should be dumped, which setting the path or
+    /// the filter of the dump also requests.
+    pub fn dump_dep_graph(&self) -> bool {
+        self.debugging_opts.dump_dep_graph
+            || self.debugging_opts.dump_dep_graph_path.is_some()
+            || self.debugging_opts.dump_dep_graph_filter.is_some()
+    }
+
     #[inline(always)]
     pub fn enable_dep_node_debug_strs(&self) -> bool {
         cfg!(debug_assertions)
@@ -783,17 +817,23 @@ macro_rules! options {
         pub const parse_opt_pathbuf: Option<&'static str> = Some("a path");
         pub const parse_list: Option<&'static str> = Some("a space-separated list of strings");
         pub const parse_opt_list: Option<&'static str> = Some("a space-separated list of strings");
+        pub const parse_list_with_polarity: Option<&'static str> =
+            Some("a comma-separated list of strings, with elements beginning with + or -");
         pub const parse_uint: Option<&'static str> = Some("a number");
         pub const parse_passes: Option<&'static str> =
             Some("a space-separated list of passes, or `all`");
         pub const parse_opt_uint: Option<&'static str> =
             Some("a number");
+        pub const parse_treat_err_as_bug: Option<&'static str> =
+            Some("either no value or a number bigger than 0");
         pub const parse_panic_strategy: Option<&'static str> =
             Some("either `panic` or `abort`");
         pub const parse_relro_level: Option<&'static str> =
             Some("one of: `full`, `partial`, or `off`");
         pub const parse_sanitizer: Option<&'static str> =
             Some("one of: `address`, `leak`, `memory` or `thread`");
+        pub const parse_mir_spanview: Option<&'static str> =
+            Some("one of `statement`, `terminator`, `block`, or omitted");
         pub const parse_linker_flavor: Option<&'static str> =
             Some(::rustc_target::spec::LinkerFlavor::one_of());
         pub const parse_optimization_fuel: Option<&'static str> =
@@ -810,7 +850,7 @@ macro_rules! options {
     #[allow(dead_code)]
     mod $mod_set {
         use super::{$struct_name, Passes, SomePasses, AllPasses, Sanitizer, Lto,
-                    CrossLangLto};
+                    CrossLangLto, MirSpanview};
         use rustc_target::spec::{LinkerFlavor, PanicStrategy, RelroLevel};
         use std::path::PathBuf;
 
@@ -906,6 +946,26 @@ macro_rules! options {
             }
         }
 
+        fn parse_list_with_polarity(slot: &mut Vec<(String, bool)>, v: Option<&str>) -> bool {
+            match v {
+                Some(s) => {
+                    for s in s.split(',') {
+                        let (name, enabled) = match s.chars().next() {
+                            Some('+') => (&s[1..], true),
+                            Some('-') => (&s[1..], false),
+                            _ => return false,
+                        };
+                        // Only the last mention of a name counts, so that the
+                        // order of the remaining elements does not matter.
+                        slot.retain(|&(ref other, _)| other != name);
+                        slot.push((name.to_string(), enabled));
+                    }
+                    true
+                },
+                None => false,
+            }
+        }
+
         fn parse_uint(slot: &mut usize, v: Option<&str>) -> bool {
             match v.and_then(|s| s.parse().ok()) {
                 Some(i) => { *slot = i; true },
@@ -920,6 +980,16 @@ macro_rules! options {
             }
         }
 
+        fn parse_treat_err_as_bug(slot: &mut Option<usize>, v: Option<&str>) -> bool {
+            match v {
+                Some(s) => match s.parse() {
+                    Ok(i) if i > 0 => { *slot = Some(i); true }
+                    _ => false,
+                },
+                None => { *slot = Some(1); true }
+            }
+        }
+
         fn parse_passes(slot: &mut Passes, v: Option<&str>) -> bool {
             match v {
                 Some("all") => {
@@ -971,6 +1041,16 @@ macro_rules! options {
             true
         }
 
+        fn parse_mir_spanview(slot: &mut Option<MirSpanview>, v: Option<&str>) -> bool {
+            *slot = match v {
+                None | Some("statement") => Some(MirSpanview::Statement),
+                Some("terminator") => Some(MirSpanview::Terminator),
+                Some("block") => Some(MirSpanview::Block),
+                _ => return false,
+            };
+            true
+        }
+
         fn parse_linker_flavor(slote: &mut Option<LinkerFlavor>, v: Option<&str>) -> bool {
             match v.and_then(LinkerFlavor::from_str) {
                 Some(lf) => *slote = Some(lf),
@@ -1127,9 +1207,10 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
     emit_end_regions: bool = (false, parse_bool, [UNTRACKED],
         "emit EndRegion as part of MIR; enable transforms that solely process EndRegion"),
     borrowck: Option<String> = (None, parse_opt_string, [UNTRACKED],
-        "select which borrowck is used (`ast`, `mir`, or `compare`)"),
+        "select which borrowck is used (`ast`, `mir`, `migrate`, or `compare`)"),
     two_phase_borrows: bool = (false, parse_bool, [UNTRACKED],
-        "use two-phase reserved/active distinction for `&mut` borrows in MIR borrowck"),
+        "use two-phase reserved/active distinction for `&mut` borrows in MIR borrowck \
+         (implied by `-Z borrowck=mir`)"),
     two_phase_beyond_autoref: bool = (false, parse_bool, [UNTRACKED],
         "when using two-phase-borrows, allow two phases even for non-autoref `&mut` borrows"),
     time_passes: bool = (false, parse_bool, [UNTRACKED],
@@ -1154,7 +1235,10 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
     verify_llvm_ir: bool = (false, parse_bool, [TRACKED],
         "verify LLVM IR"),
     borrowck_stats: bool = (false, parse_bool, [UNTRACKED],
-        "gather borrowck statistics"),
+        "gather borrowck statistics (and count disagreements under `-Z borrowck=compare`)"),
+    borrowck_summary: bool = (false, parse_bool, [TRACKED],
+        "record the borrows, moves, variable accesses and used `mut` locals of each body \
+         for tools (implied by `-Z save-analysis`)"),
     no_landing_pads: bool = (false, parse_bool, [TRACKED],
         "omit landing pads for unwinding"),
     fewer_names: bool = (false, parse_bool, [TRACKED],
@@ -1191,8 +1275,8 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "parse only; do not compile, assemble, or link"),
     no_codegen: bool = (false, parse_bool, [TRACKED],
           "run all passes except codegen; no output"),
-    treat_err_as_bug: bool = (false, parse_bool, [TRACKED],
-          "treat all errors that occur as bugs"),
+    treat_err_as_bug: Option<usize> = (None, parse_treat_err_as_bug, [TRACKED],
+          "treat the `val`th error that occurs as a bug (default: the first one)"),
     external_macro_backtrace: bool = (false, parse_bool, [UNTRACKED],
           "show macro backtraces even for non-local macros"),
     teach: bool = (false, parse_bool, [TRACKED],
@@ -1212,13 +1296,25 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
     incremental_ignore_spans: bool = (false, parse_bool, [UNTRACKED],
         "ignore spans during ICH computation -- used for testing"),
     dump_dep_graph: bool = (false, parse_bool, [UNTRACKED],
-          "dump the dependency graph to $RUST_DEP_GRAPH (default: /tmp/dep_graph.gv)"),
+          "dump the dependency graph to `<path>.txt` and `<path>.dot` \
+           (see `-Z dump-dep-graph-path`)"),
+    dump_dep_graph_path: Option<String> = (None, parse_opt_string, [UNTRACKED],
+          "base path of the dependency graph dump, which is written to `<path>.txt` and \
+           `<path>.dot` (default: $RUST_DEP_GRAPH, or `dep_graph`)"),
+    dump_dep_graph_filter: Option<String> = (None, parse_opt_string, [UNTRACKED],
+          "only dump the nodes of the dependency graph on a path between nodes matching \
+           `source -> target`, like `TypeckTables & foo -> *` (default: $RUST_DEP_GRAPH_FILTER)"),
     query_dep_graph: bool = (false, parse_bool, [UNTRACKED],
           "enable queries of the dependency graph for regression testing"),
     profile_queries: bool = (false, parse_bool, [UNTRACKED],
           "trace and profile the queries of the incremental compilation framework"),
     profile_queries_and_keys: bool = (false, parse_bool, [UNTRACKED],
           "trace and profile the queries and keys of the incremental compilation framework"),
+    self_profile: bool = (false, parse_bool, [UNTRACKED],
+          "record the time spent in each query and pass, and write it as a trace \
+           to `<crate>.self_profile.json`"),
+    self_profile_summary: bool = (false, parse_bool, [UNTRACKED],
+          "record the time spent in each query and pass, and print a summary of it"),
     no_analysis: bool = (false, parse_bool, [UNTRACKED],
           "parse and expand the source, but run no analysis"),
     extra_plugins: Vec<String> = (Vec::new(), parse_list, [TRACKED],
@@ -1244,30 +1340,60 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
     print_mono_items: Option<String> = (None, parse_opt_string, [UNTRACKED],
           "print the result of the monomorphization collection pass"),
     mir_opt_level: usize = (1, parse_uint, [TRACKED],
-          "set the MIR optimization level (0-3, default: 1)"),
+          "set the MIR optimization level (0: only required passes, 1: default optimizations, \
+           2 and up: also experimental ones like inlining; default: 1)"),
+    inline_mir: Option<bool> = (None, parse_opt_bool, [TRACKED],
+          "enable or disable the MIR inliner (default: enabled at `-Z mir-opt-level=2` and up)"),
+    inline_mir_threshold: Option<usize> = (None, parse_opt_uint, [TRACKED],
+          "the cost above which the MIR inliner won't inline a callee (default: 50)"),
+    inline_mir_hint_threshold: Option<usize> = (None, parse_opt_uint, [TRACKED],
+          "the cost above which the MIR inliner won't inline an `#[inline]` callee \
+           (default: 100)"),
     mutable_noalias: Option<bool> = (None, parse_opt_bool, [TRACKED],
           "emit noalias metadata for mutable references (default: yes on LLVM >= 6)"),
     arg_align_attributes: bool = (false, parse_bool, [TRACKED],
           "emit align metadata for reference arguments"),
     dump_mir: Option<String> = (None, parse_opt_string, [UNTRACKED],
           "dump MIR state at various points in transforms"),
+    dump_mir_exclude: Option<String> = (None, parse_opt_string, [UNTRACKED],
+          "do not dump MIR for passes and functions matching this filter, even if they \
+           match `-Z dump-mir`"),
     dump_mir_dir: String = (String::from("mir_dump"), parse_string, [UNTRACKED],
           "the directory the MIR is dumped into"),
     dump_mir_graphviz: bool = (false, parse_bool, [UNTRACKED],
           "in addition to `.mir` files, create graphviz `.dot` files"),
+    dump_mir_dataflow: bool = (false, parse_bool, [UNTRACKED],
+          "with `-Z dump-mir-graphviz`, annotate borrowck's `.dot` file with the borrows in \
+           scope and the maybe-initialized places at each statement"),
+    dump_mir_spanview: Option<MirSpanview> = (None, parse_mir_spanview, [UNTRACKED],
+          "in addition to `.mir` files, create `.html` files which show the source with \
+           the span of each `statement` (default), `terminator` or `block` highlighted"),
+    dump_mir_json: bool = (false, parse_bool, [UNTRACKED],
+          "in addition to `.mir` files, create `.json` files (see `rustc_mir::util::json` \
+           for the format)"),
     dump_mir_exclude_pass_number: bool = (false, parse_bool, [UNTRACKED],
           "if set, exclude the pass number when dumping MIR (used in tests)"),
+    validate_mir: bool = (false, parse_bool, [UNTRACKED],
+          "validate the structure of MIR after each MIR pass, reporting broken MIR as a bug"),
+    mir_enable_passes: Vec<(String, bool)> = (Vec::new(), parse_list_with_polarity, [TRACKED],
+          "force-enable (`+Name`) or disable (`-Name`) the MIR passes with these names, \
+           overriding whether they would run by default (e.g. `-Z mir-enable-passes=-Inline`)"),
     mir_emit_validate: usize = (0, parse_uint, [TRACKED],
           "emit Validate MIR statements, interpreted e.g. by miri (0: do not emit; 1: if function \
            contains unsafe block, only validate arguments; 2: always emit full validation)"),
     perf_stats: bool = (false, parse_bool, [UNTRACKED],
           "print some performance-related statistics"),
+    emit_artifact_notifications: bool = (false, parse_bool, [UNTRACKED],
+          "print a JSON notification to stderr as soon as the `.rmeta` file is written"),
     hir_stats: bool = (false, parse_bool, [UNTRACKED],
           "print some statistics about AST and HIR"),
     mir_stats: bool = (false, parse_bool, [UNTRACKED],
           "print some statistics about MIR"),
     always_encode_mir: bool = (false, parse_bool, [TRACKED],
           "encode MIR of all functions into the crate metadata"),
+    cross_crate_inline_threshold: usize = (0, parse_uint, [TRACKED],
+          "encode MIR of non-generic functions with at most this many statements into the \
+           crate metadata, so other crates can inline them (default: 0, never)"),
     osx_rpath_install_name: bool = (false, parse_bool, [TRACKED],
           "pass `-install_name @rpath/...` to the macOS linker"),
     sanitizer: Option<Sanitizer> = (None, parse_sanitizer, [TRACKED],
@@ -1861,6 +1987,11 @@ pub fn build_session_options_and_crate_config(
         );
     }
 
+    // Save-analysis reports the variable accesses that MIR borrowck records.
+    if debugging_opts.save_analysis {
+        debugging_opts.borrowck_summary = true;
+    }
+
     if debugging_opts.pgo_gen.is_some() && !debugging_opts.pgo_use.is_empty() {
         early_error(
             error_format,
@@ -2164,6 +2295,7 @@ pub fn build_session_options_and_crate_config(
         None | Some("ast") => BorrowckMode::Ast,
         Some("mir") => BorrowckMode::Mir,
         Some("compare") => BorrowckMode::Compare,
+        Some("migrate") => BorrowckMode::Migrate,
         Some(m) => early_error(error_format, &format!("unknown borrowck mode `{}`", m)),
     };
 
@@ -2451,6 +2583,7 @@ mod dep_tracking {
         Option<cstore::NativeLibraryKind>
     ));
     impl_dep_tracking_hash_for_sortable_vec_of!((String, u64));
+    impl_dep_tracking_hash_for_sortable_vec_of!((String, bool));
 
     impl<T1, T2> DepTrackingHash for (T1, T2)
     where
@@ -2504,7 +2637,7 @@ mod tests {
     use lint;
     use middle::cstore;
     use session::config::{build_configuration, build_session_options_and_crate_config};
-    use session::config::{Lto, CrossLangLto};
+    use session::config::{Lto, CrossLangLto, MirSpanview};
     use session::build_session;
     use std::collections::{BTreeMap, BTreeSet};
     use std::iter::FromIterator;
@@ -3057,6 +3190,10 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.borrowck_stats = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.self_profile = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.self_profile_summary = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.meta_stats = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.print_link_args = true;
@@ -3087,6 +3224,10 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.dump_dep_graph = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.dump_dep_graph_path = Some(String::from("graph"));
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.dump_dep_graph_filter = Some(String::from("Hir -> *"));
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.query_dep_graph = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.no_analysis = true;
@@ -3103,10 +3244,18 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.dump_mir = Some(String::from("abc"));
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.dump_mir_exclude = Some(String::from("abc"));
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.dump_mir_dir = String::from("abc");
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.dump_mir_graphviz = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.dump_mir_dataflow = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.dump_mir_json = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.dump_mir_spanview = Some(MirSpanview::Block);
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
 
         // Make sure changing a [TRACKED] option changes the hash
         opts = reference.clone();
@@ -3130,7 +3279,7 @@ mod tests {
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
 
         opts = reference.clone();
-        opts.debugging_opts.treat_err_as_bug = true;
+        opts.debugging_opts.treat_err_as_bug = Some(1);
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
 
         opts = reference.clone();
@@ -3157,6 +3306,26 @@ mod tests {
         opts.debugging_opts.mir_opt_level = 3;
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
 
+        opts = reference.clone();
+        opts.debugging_opts.mir_enable_passes = vec![(String::from("Inline"), false)];
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.inline_mir = Some(true);
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.inline_mir_threshold = Some(10);
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.cross_crate_inline_threshold = 5;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.borrowck_summary = true;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
         opts = reference.clone();
         opts.debugging_opts.relro_level = Some(RelroLevel::Full);
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
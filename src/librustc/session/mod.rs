@@ -25,6 +25,7 @@ use ty::tls;
 use util::nodemap::{FxHashMap, FxHashSet};
 use util::common::{duration_to_secs_str, ErrorReported};
 use util::common::ProfileQueriesMsg;
+use util::profiling::{ProfileCategory, ProfilerActivity, SelfProfiler};
 
 use rustc_data_structures::sync::{self, Lrc, Lock, LockCell, OneThread, Once, RwLock};
 
@@ -44,7 +45,7 @@ use syntax_pos::{MultiSpan, Span};
 use rustc_target::spec::{LinkerFlavor, PanicStrategy};
 use rustc_target::spec::{Target, TargetTriple};
 use rustc_data_structures::flock;
-use jobserver::Client;
+use jobserver::{Client, Acquired};
 
 use std;
 use std::cell::{self, Cell, RefCell};
@@ -133,9 +134,15 @@ pub struct Session {
     /// Used by -Z profile-queries in util::common
     pub profile_channel: Lock<Option<mpsc::Sender<ProfileQueriesMsg>>>,
 
+    /// Used by -Z self-profile and -Z self-profile-summary
+    pub self_profiling: Lock<SelfProfiler>,
+
     /// Some measurements that are being gathered during compilation.
     pub perf_stats: PerfStats,
 
+    /// Divergences between the borrow checkers under `-Z borrowck=compare`.
+    pub borrowck_compare_stats: BorrowckCompareStats,
+
     /// Data about code being compiled, gathered during compilation.
     pub code_stats: Lock<CodeStats>,
 
@@ -176,6 +183,29 @@ pub struct PerfStats {
     pub normalize_ty_after_erasing_regions: AtomicUsize,
     /// Number of times this query is invoked.
     pub normalize_projection_ty: AtomicUsize,
+    /// Total number of outlives constraints handed to NLL region inference.
+    pub nll_outlives_constraints: AtomicUsize,
+    /// Total number of SCCs in the NLL region constraint graphs.
+    pub nll_constraint_sccs: AtomicUsize,
+    /// Total number of basic blocks processed by the NLL liveness computations.
+    pub nll_liveness_iterations: AtomicUsize,
+    /// Number of trait selections answered by the selection cache.
+    pub selection_cache_hits: AtomicUsize,
+    /// Number of trait selections that missed the selection cache.
+    pub selection_cache_misses: AtomicUsize,
+}
+
+/// How the verdicts of the AST and MIR borrow checkers compare, gathered
+/// under `-Z borrowck=compare`.
+pub struct BorrowckCompareStats {
+    /// Number of bodies checked by both borrow checkers.
+    pub bodies: AtomicUsize,
+    /// Number of bodies rejected by both borrow checkers.
+    pub rejected_by_both: AtomicUsize,
+    /// Number of bodies rejected only by the AST borrow checker.
+    pub rejected_by_ast_only: AtomicUsize,
+    /// Number of bodies rejected only by the MIR borrow checker.
+    pub rejected_by_mir_only: AtomicUsize,
 }
 
 /// Enum to support dispatch of one-time diagnostics (in Session.diag_once)
@@ -501,6 +531,54 @@ impl Session {
     pub fn profile_queries_and_keys(&self) -> bool {
         self.opts.debugging_opts.profile_queries_and_keys
     }
+    pub fn self_profiling_active(&self) -> bool {
+        self.opts.debugging_opts.self_profile || self.opts.debugging_opts.self_profile_summary
+    }
+
+    /// Calls `f` with the self profiler, if it is enabled.
+    #[inline(always)]
+    pub fn profiler<F: FnOnce(&mut SelfProfiler)>(&self, f: F) {
+        if self.self_profiling_active() {
+            let mut profiler = self.self_profiling.borrow_mut();
+            f(&mut profiler);
+        }
+    }
+
+    fn active_self_profiler(&self) -> Option<&Lock<SelfProfiler>> {
+        if self.self_profiling_active() {
+            Some(&self.self_profiling)
+        } else {
+            None
+        }
+    }
+
+    /// Records the query `query_name` in the self profile, until the returned
+    /// guard is dropped.
+    pub fn profile_query(&self, query_name: &'static str) -> ProfilerActivity {
+        ProfilerActivity::start(self.active_self_profiler(), ProfileCategory::Query, query_name)
+    }
+
+    /// Runs `f`, recording it as the pass `what` in the self profile.
+    pub fn profile_activity<T, F: FnOnce() -> T>(&self, what: &str, f: F) -> T {
+        let _activity =
+            ProfilerActivity::start(self.active_self_profiler(), ProfileCategory::Pass, what);
+        f()
+    }
+
+    /// Writes and prints the self profile, as requested on the command line.
+    pub fn finish_self_profile(&self, trace_path: &Path) {
+        let profiler = self.self_profiling.borrow();
+        if self.opts.debugging_opts.self_profile {
+            if let Err(e) = profiler.dump_raw_events(trace_path) {
+                self.err(&format!("could not write self profile to `{}`: {}",
+                                  trace_path.display(), e));
+            }
+        }
+        if self.opts.debugging_opts.self_profile_summary {
+            profiler.print_summary();
+        }
+    }
+
     pub fn count_llvm_insns(&self) -> bool {
         self.opts.debugging_opts.count_llvm_insns
     }
@@ -522,6 +600,9 @@ impl Session {
     pub fn borrowck_stats(&self) -> bool {
         self.opts.debugging_opts.borrowck_stats
     }
+    pub fn borrowck_summary(&self) -> bool {
+        self.opts.debugging_opts.borrowck_summary
+    }
     pub fn print_llvm_passes(&self) -> bool {
         self.opts.debugging_opts.print_llvm_passes
     }
@@ -828,7 +909,8 @@ impl Session {
         }
     }
 
-    pub fn print_perf_stats(&self) {
+    pub fn print_perf_stats(&self, crate_name: &str) {
+        println!("--- perf stats for crate `{}` ---", crate_name);
         println!(
             "Total time spent computing symbol hashes:      {}",
             duration_to_secs_str(*self.perf_stats.symbol_hash_time.lock())
@@ -843,6 +925,29 @@ impl Session {
                  self.perf_stats.normalize_ty_after_erasing_regions.load(Ordering::Relaxed));
         println!("normalize_projection_ty:                       {}",
                  self.perf_stats.normalize_projection_ty.load(Ordering::Relaxed));
+        println!("NLL outlives constraints:                      {}",
+                 self.perf_stats.nll_outlives_constraints.load(Ordering::Relaxed));
+        println!("NLL constraint SCCs:                           {}",
+                 self.perf_stats.nll_constraint_sccs.load(Ordering::Relaxed));
+        println!("NLL liveness iterations:                       {}",
+                 self.perf_stats.nll_liveness_iterations.load(Ordering::Relaxed));
+        println!("selection cache hits:                          {}",
+                 self.perf_stats.selection_cache_hits.load(Ordering::Relaxed));
+        println!("selection cache misses:                        {}",
+                 self.perf_stats.selection_cache_misses.load(Ordering::Relaxed));
+    }
+
+    pub fn print_borrowck_compare_stats(&self) {
+        let stats = &self.borrowck_compare_stats;
+        println!("--- borrowck comparison for this crate ---");
+        println!("bodies checked:                                {}",
+                 stats.bodies.load(Ordering::Relaxed));
+        println!("rejected by both:                              {}",
+                 stats.rejected_by_both.load(Ordering::Relaxed));
+        println!("rejected by AST borrowck only:                 {}",
+                 stats.rejected_by_ast_only.load(Ordering::Relaxed));
+        println!("rejected by MIR borrowck only:                 {}",
+                 stats.rejected_by_mir_only.load(Ordering::Relaxed));
     }
 
     /// We want to know if we're allowed to do an optimization for crate foo from -z fuel=foo=n.
@@ -1127,12 +1232,24 @@ pub fn build_session_(
         incr_comp_session: OneThread::new(RefCell::new(IncrCompSession::NotInitialized)),
         ignored_attr_names: ich::compute_ignored_attr_names(),
         profile_channel: Lock::new(None),
+        self_profiling: Lock::new(SelfProfiler::new()),
         perf_stats: PerfStats {
             symbol_hash_time: Lock::new(Duration::from_secs(0)),
             decode_def_path_tables_time: Lock::new(Duration::from_secs(0)),
             queries_canonicalized: AtomicUsize::new(0),
             normalize_ty_after_erasing_regions: AtomicUsize::new(0),
             normalize_projection_ty: AtomicUsize::new(0),
+            nll_outlives_constraints: AtomicUsize::new(0),
+            nll_constraint_sccs: AtomicUsize::new(0),
+            nll_liveness_iterations: AtomicUsize::new(0),
+            selection_cache_hits: AtomicUsize::new(0),
+            selection_cache_misses: AtomicUsize::new(0),
+        },
+        borrowck_compare_stats: BorrowckCompareStats {
+            bodies: AtomicUsize::new(0),
+            rejected_by_both: AtomicUsize::new(0),
+            rejected_by_ast_only: AtomicUsize::new(0),
+            rejected_by_mir_only: AtomicUsize::new(0),
         },
         code_stats: Lock::new(CodeStats::new()),
         optimization_fuel_crate,
@@ -1140,32 +1257,7 @@ pub fn build_session_(
         print_fuel_crate,
         print_fuel,
         out_of_fuel: LockCell::new(false),
-        // Note that this is unsafe because it may misinterpret file descriptors
-        // on Unix as jobserver file descriptors. We hopefully execute this near
-        // the beginning of the process though to ensure we don't get false
-        // positives, or in other words we try to execute this before we open
-        // any file descriptors ourselves.
-        //
-        // Pick a "reasonable maximum" if we don't otherwise have
-        // a jobserver in our environment, capping out at 32 so we
-        // don't take everything down by hogging the process run queue.
-        // The fixed number is used to have deterministic compilation
-        // across machines.
-        //
-        // Also note that we stick this in a global because there could be
-        // multiple `Session` instances in this process, and the jobserver is
-        // per-process.
-        jobserver: unsafe {
-            static mut GLOBAL_JOBSERVER: *mut Client = 0 as *mut _;
-            static INIT: std::sync::Once = std::sync::ONCE_INIT;
-            INIT.call_once(|| {
-                let client = Client::from_env().unwrap_or_else(|| {
-                    Client::new(32).expect("failed to create jobserver")
-                });
-                GLOBAL_JOBSERVER = Box::into_raw(Box::new(client));
-            });
-            (*GLOBAL_JOBSERVER).clone()
-        },
+        jobserver: global_jobserver(),
         has_global_allocator: Once::new(),
         driver_lint_caps: FxHashMap(),
     };
@@ -1215,6 +1307,109 @@ pub enum IncrCompSession {
     InvalidBecauseOfErrors { session_directory: PathBuf },
 }
 
+/// Returns the jobserver of this process, which limits the number of threads
+/// doing work across the whole build, both in this process and in the other
+/// processes sharing the jobserver (e.g. other rustc instances run by cargo).
+pub fn global_jobserver() -> Client {
+    // Note that this is unsafe because it may misinterpret file descriptors
+    // on Unix as jobserver file descriptors. We hopefully execute this near
+    // the beginning of the process though to ensure we don't get false
+    // positives, or in other words we try to execute this before we open
+    // any file descriptors ourselves.
+    //
+    // Pick a "reasonable maximum" if we don't otherwise have
+    // a jobserver in our environment, capping out at 32 so we
+    // don't take everything down by hogging the process run queue.
+    // The fixed number is used to have deterministic compilation
+    // across machines.
+    //
+    // Also note that we stick this in a global because there could be
+    // multiple `Session` instances in this process, and the jobserver is
+    // per-process.
+    unsafe {
+        static mut GLOBAL_JOBSERVER: *mut Client = 0 as *mut _;
+        static INIT: std::sync::Once = std::sync::ONCE_INIT;
+        INIT.call_once(|| {
+            let client = Client::from_env().unwrap_or_else(|| {
+                Client::new(32).expect("failed to create jobserver")
+            });
+            GLOBAL_JOBSERVER = Box::into_raw(Box::new(client));
+        });
+        (*GLOBAL_JOBSERVER).clone()
+    }
+}
+
+/// The jobserver tokens held for the worker threads of a query thread pool, see
+/// `rustc_driver::driver::spawn_thread_pool`.
+///
+/// The pool holds one token less than it has threads, since the compiler itself runs
+/// on the token this process implicitly owns, which codegen also counts on. The tokens
+/// are not tied to the threads that acquired them. They are released before codegen,
+/// which acquires tokens for its own threads, so that the query threads don't keep them
+/// while they sit idle until the process exits.
+pub struct QueryThreadTokens {
+    jobserver: Client,
+    state: Lock<QueryThreadTokensState>,
+}
+
+struct QueryThreadTokensState {
+    /// How many more tokens the pool may acquire.
+    wanted: usize,
+    tokens: Vec<Acquired>,
+    released: bool,
+}
+
+impl QueryThreadTokens {
+    pub fn new(jobserver: Client, threads: usize) -> QueryThreadTokens {
+        QueryThreadTokens {
+            jobserver,
+            state: Lock::new(QueryThreadTokensState {
+                wanted: threads.saturating_sub(1),
+                tokens: Vec::new(),
+                released: false,
+            }),
+        }
+    }
+
+    /// Acquires a token for a worker thread that is starting, unless the pool already
+    /// has all of its tokens or released them. This blocks until a token is available.
+    /// If no token can be acquired the thread runs anyway.
+    pub fn acquire(&self) {
+        {
+            let mut state = self.state.lock();
+            if state.released || state.wanted == 0 {
+                return;
+            }
+            state.wanted -= 1;
+        }
+        if let Ok(token) = self.jobserver.acquire() {
+            let mut state = self.state.lock();
+            // A token we got after the release is handed back right away.
+            if !state.released {
+                state.tokens.push(token);
+            }
+        }
+    }
+
+    /// Hands all tokens of the pool back to the jobserver. Threads starting after this
+    /// don't acquire any.
+    pub fn release(&self) {
+        let mut state = self.state.lock();
+        state.released = true;
+        state.tokens.clear();
+    }
+}
+
+scoped_thread_local!(pub static QUERY_THREAD_TOKENS: QueryThreadTokens);
+
+/// Releases the jobserver tokens of the query thread pool the current thread belongs
+/// to, if any.
+pub fn release_query_thread_tokens() {
+    if QUERY_THREAD_TOKENS.is_set() {
+        QUERY_THREAD_TOKENS.with(|tokens| tokens.release());
+    }
+}
+
 pub fn early_error(output: config::ErrorOutputType, msg: &str) -> ! {
     let emitter: Box<dyn Emitter + sync::Send> = match output {
         config::ErrorOutputType::HumanReadable(color_config) => {
@@ -1225,7 +1420,7 @@ pub fn early_error(output: config::ErrorOutputType, msg: &str) -> ! {
             Box::new(EmitterWriter::stderr(color_config, None, true, false))
         }
     };
-    let handler = errors::Handler::with_emitter(true, false, emitter);
+    let handler = errors::Handler::with_emitter(true, None, emitter);
     handler.emit(&MultiSpan::new(), msg, errors::Level::Fatal);
     errors::FatalError.raise();
 }
@@ -1240,7 +1435,7 @@ pub fn early_warn(output: config::ErrorOutputType, msg: &str) {
             Box::new(EmitterWriter::stderr(color_config, None, true, false))
         }
     };
-    let handler = errors::Handler::with_emitter(true, false, emitter);
+    let handler = errors::Handler::with_emitter(true, None, emitter);
     handler.emit(&MultiSpan::new(), msg, errors::Level::Warning);
 }
 
@@ -1299,3 +1494,39 @@ fn opt_span_bug_fmt<S: Into<MultiSpan>>(
     });
     unreachable!();
 }
+
+#[cfg(test)]
+mod tests {
+    use jobserver::Client;
+    use super::QueryThreadTokens;
+
+    #[test]
+    fn test_query_thread_tokens() {
+        let jobserver = Client::new(2).unwrap();
+        let tokens = QueryThreadTokens::new(jobserver.clone(), 3);
+
+        // The third thread runs on the implicit token.
+        for _ in 0..3 {
+            tokens.acquire();
+        }
+        assert_eq!(tokens.state.lock().tokens.len(), 2);
+
+        tokens.release();
+        assert_eq!(tokens.state.lock().tokens.len(), 0);
+        tokens.acquire();
+        assert_eq!(tokens.state.lock().tokens.len(), 0);
+
+        // Both tokens are back in the jobserver, otherwise this blocks.
+        let _a = jobserver.acquire().unwrap();
+        let _b = jobserver.acquire().unwrap();
+    }
+
+    #[test]
+    fn test_query_thread_tokens_single_thread() {
+        let jobserver = Client::new(1).unwrap();
+        let tokens = QueryThreadTokens::new(jobserver.clone(), 1);
+        tokens.acquire();
+        assert_eq!(tokens.state.lock().tokens.len(), 0);
+        let _a = jobserver.acquire().unwrap();
+    }
+}
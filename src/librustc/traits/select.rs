@@ -51,6 +51,7 @@ use std::cmp;
 use std::fmt;
 use std::mem;
 use std::rc::Rc;
+use std::sync::atomic::Ordering;
 use rustc_target::spec::abi::Abi;
 use hir;
 use util::nodemap::{FxHashMap, FxHashSet};
@@ -1078,13 +1079,16 @@ impl<'cx, 'gcx, 'tcx> SelectionContext<'cx, 'gcx, 'tcx> {
                stack);
         assert!(!stack.obligation.predicate.has_escaping_regions());
 
+        let perf_stats = &self.tcx().sess.perf_stats;
         if let Some(c) = self.check_candidate_cache(stack.obligation.param_env,
                                                     &cache_fresh_trait_pred) {
             debug!("CACHE HIT: SELECT({:?})={:?}",
                    cache_fresh_trait_pred,
                    c);
+            perf_stats.selection_cache_hits.fetch_add(1, Ordering::Relaxed);
             return c;
         }
+        perf_stats.selection_cache_misses.fetch_add(1, Ordering::Relaxed);
 
         // If no match, compute result and insert into cache.
         let (candidate, dep_node) = self.in_task(|this| {
@@ -31,7 +31,7 @@ use middle::cstore::EncodedMetadata;
 use middle::lang_items;
 use middle::resolve_lifetime::{self, ObjectLifetimeDefault};
 use middle::stability;
-use mir::{self, Mir, interpret};
+use mir::{self, Mir, PlaceElem, PlaceElems, interpret};
 use mir::interpret::Allocation;
 use ty::subst::{Kind, Substs, Subst};
 use ty::ReprOptions;
@@ -59,6 +59,7 @@ use rustc_data_structures::stable_hasher::{HashStable, hash_stable_hashmap,
 use arena::{TypedArena, SyncDroplessArena};
 use rustc_data_structures::indexed_vec::IndexVec;
 use rustc_data_structures::sync::{self, Lrc, Lock, WorkerLocal};
+use rustc_data_structures::unhash::UnhashMap;
 use std::any::Any;
 use std::borrow::Borrow;
 use std::cmp::Ordering;
@@ -143,6 +144,7 @@ pub struct CtxtInterners<'tcx> {
     const_: InternedSet<'tcx, Const<'tcx>>,
     clauses: InternedSet<'tcx, Slice<Clause<'tcx>>>,
     goals: InternedSet<'tcx, Slice<Goal<'tcx>>>,
+    place_elems: InternedSet<'tcx, Slice<PlaceElem<'tcx>>>,
 }
 
 impl<'gcx: 'tcx, 'tcx> CtxtInterners<'tcx> {
@@ -159,6 +161,7 @@ impl<'gcx: 'tcx, 'tcx> CtxtInterners<'tcx> {
             const_: Default::default(),
             clauses: Default::default(),
             goals: Default::default(),
+            place_elems: Default::default(),
         }
     }
 
@@ -887,7 +890,7 @@ pub struct GlobalCtxt<'tcx> {
 
     /// A map from DefPathHash -> DefId. Includes DefIds from the local crate
     /// as well as all upstream crates. Only populated in incremental mode.
-    pub def_path_hash_to_def_id: Option<FxHashMap<DefPathHash, DefId>>,
+    pub def_path_hash_to_def_id: Option<UnhashMap<DefPathHash, DefId>>,
 
     pub(crate) queries: query::Queries<'tcx>,
 
@@ -1117,7 +1120,7 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
             // re-allocate when populating it.
             let capacity = def_path_tables().map(|(_, t)| t.size()).sum::<usize>();
 
-            let mut map: FxHashMap<_, _> = FxHashMap::with_capacity_and_hasher(
+            let mut map: UnhashMap<_, _> = UnhashMap::with_capacity_and_hasher(
                 capacity,
                 ::std::default::Default::default()
             );
@@ -1366,8 +1369,14 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
         self.borrowck_mode().use_mir()
     }
 
-    /// If true, make MIR codegen for `match` emit a temp that holds a
-    /// borrow of the input to the match expression.
+    /// If true, we should use the AST-based borrowck only to decide whether
+    /// errors of the MIR-based borrowck are downgraded to warnings.
+    pub fn migrate_borrowck(self) -> bool {
+        self.borrowck_mode().migrate()
+    }
+
+    /// If true, make MIR codegen for `match` emit fake borrows of the
+    /// places inspected by the match, which are kept alive across guards.
     pub fn generate_borrow_of_any_match_input(&self) -> bool {
         self.emit_read_for_match()
     }
@@ -1388,10 +1397,11 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
     }
 
     /// If true, we should enable two-phase borrows checks. This is
-    /// done with either `-Ztwo-phase-borrows` or with
-    /// `#![feature(nll)]`.
+    /// done whenever the MIR borrowck is used, i.e. with `-Zborrowck=mir`
+    /// or `#![feature(nll)]`, and can also be requested with
+    /// `-Ztwo-phase-borrows`.
     pub fn two_phase_borrows(self) -> bool {
-        self.features().nll || self.sess.opts.debugging_opts.two_phase_borrows
+        self.use_mir_borrowck() || self.sess.opts.debugging_opts.two_phase_borrows
     }
 
     /// What mode(s) of borrowck should we run? AST? MIR? both?
@@ -1399,7 +1409,8 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
     pub fn borrowck_mode(&self) -> BorrowckMode {
         match self.sess.opts.borrowck_mode {
             mode @ BorrowckMode::Mir |
-            mode @ BorrowckMode::Compare => mode,
+            mode @ BorrowckMode::Compare |
+            mode @ BorrowckMode::Migrate => mode,
 
             mode @ BorrowckMode::Ast => {
                 if self.features().nll {
@@ -1796,7 +1807,7 @@ pub mod tls {
         with_context_opt(|icx| {
             if let Some(icx) = icx {
                 if let Some(ref query) = icx.query {
-                    query.diagnostics.lock().push(diagnostic.clone());
+                    query.diagnostics.push(diagnostic.clone());
                 }
             }
         })
@@ -2049,6 +2060,7 @@ impl<'a, 'tcx> TyCtxt<'a, 'tcx, 'tcx> {
 
         println!("Substs interner: #{}", self.interners.substs.borrow().len());
         println!("Region interner: #{}", self.interners.region.borrow().len());
+        println!("Place elems interner: #{}", self.interners.place_elems.borrow().len());
         println!("Stability interner: #{}", self.stability_interner.borrow().len());
         println!("Allocation interner: #{}", self.allocation_interner.borrow().len());
         println!("Layout interner: #{}", self.layout_interner.borrow().len());
@@ -2153,6 +2165,13 @@ for Interned<'tcx, Slice<Goal<'tcx>>> {
     }
 }
 
+impl<'tcx: 'lcx, 'lcx> Borrow<[PlaceElem<'lcx>]>
+for Interned<'tcx, Slice<PlaceElem<'tcx>>> {
+    fn borrow<'a>(&'a self) -> &'a [PlaceElem<'lcx>] {
+        &self.0[..]
+    }
+}
+
 macro_rules! intern_method {
     ($lt_tcx:tt, $name:ident: $method:ident($alloc:ty,
                                             $alloc_method:expr,
@@ -2253,7 +2272,8 @@ slice_interners!(
     type_list: _intern_type_list(Ty),
     substs: _intern_substs(Kind),
     clauses: _intern_clauses(Clause),
-    goals: _intern_goals(Goal)
+    goals: _intern_goals(Goal),
+    place_elems: _intern_place_elems(PlaceElem)
 );
 
 // This isn't a perfect fit: CanonicalVarInfo slices are always
@@ -2579,6 +2599,14 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
         }
     }
 
+    pub fn intern_place_elems(self, ts: &[PlaceElem<'tcx>]) -> PlaceElems<'tcx> {
+        if ts.len() == 0 {
+            Slice::empty()
+        } else {
+            self._intern_place_elems(ts)
+        }
+    }
+
     pub fn mk_fn_sig<I>(self,
                         inputs: I,
                         output: I::Item,
@@ -2633,6 +2661,11 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
         iter.intern_with(|xs| self.intern_goals(xs))
     }
 
+    pub fn mk_place_elems<I: InternAs<[PlaceElem<'tcx>], PlaceElems<'tcx>>>(self, iter: I)
+                                                                        -> I::Output {
+        iter.intern_with(|xs| self.intern_place_elems(xs))
+    }
+
     pub fn mk_goal(self, goal: Goal<'tcx>) -> &'tcx Goal {
         &self.intern_goals(&[goal])[0]
     }
@@ -44,7 +44,8 @@ use std::cmp::{self, Ordering};
 use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::ops::Deref;
-use rustc_data_structures::sync::{self, Lrc, ParallelIterator, par_iter};
+use rustc_data_structures::sync::{self, Lock, Lrc, ParallelIterator, par_iter};
+use rustc_data_structures::OnDrop;
 use std::slice;
 use std::vec::IntoIter;
 use std::mem;
@@ -2427,9 +2428,33 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
                 .map(move |&body_id| self.hir.body_owner_def_id(body_id))
     }
 
+    /// Calls `f` on the owner of each body, on several threads if there is more
+    /// than one query thread. The diagnostics emitted while `f` runs on a body are
+    /// then held back and printed in the order of the bodies, so that the output
+    /// of the compiler does not depend on how the bodies were scheduled.
     pub fn par_body_owners<F: Fn(DefId) + sync::Sync + sync::Send>(self, f: F) {
-        par_iter(&self.hir.krate().body_ids).for_each(|&body_id| {
-            f(self.hir.body_owner_def_id(body_id))
+        let body_ids = &self.hir.krate().body_ids;
+        if self.sess.query_threads() == 1 {
+            for &body_id in body_ids {
+                f(self.hir.body_owner_def_id(body_id));
+            }
+            return;
+        }
+
+        let handler = self.sess.diagnostic();
+        let diagnostics: Vec<_> = body_ids.iter().map(|_| Lock::new(Vec::new())).collect();
+        // This also prints the diagnostics of the bodies that are done if `f`
+        // panics on another body, for instance because of a fatal error.
+        let _print_diagnostics = OnDrop(|| {
+            for body_diagnostics in &diagnostics {
+                handler.emit_deferred(mem::replace(&mut *body_diagnostics.lock(), Vec::new()));
+            }
+        });
+        par_iter(0..body_ids.len()).for_each(|i| {
+            let ((), emitted) = handler.with_deferred_emission(|| {
+                f(self.hir.body_owner_def_id(body_ids[i]))
+            });
+            *diagnostics[i].lock() = emitted;
         });
     }
 
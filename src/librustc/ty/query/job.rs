@@ -11,7 +11,7 @@
 #![allow(warnings)]
 
 use std::mem;
-use rustc_data_structures::sync::{Lock, LockGuard, Lrc, Weak};
+use rustc_data_structures::sync::{AppendOnlyVec, Lock, LockGuard, Lrc, Weak};
 use rustc_data_structures::OnDrop;
 use syntax_pos::Span;
 use ty::tls;
@@ -58,8 +58,9 @@ pub struct QueryJob<'tcx> {
     /// The parent query job which created this job and is implicitly waiting on it.
     pub parent: Option<Lrc<QueryJob<'tcx>>>,
 
-    /// Diagnostic messages which are emitted while the query executes
-    pub diagnostics: Lock<Vec<Diagnostic>>,
+    /// Diagnostic messages which are emitted while the query executes.
+    /// These are only ever appended to, so emitting one does not need a lock.
+    pub diagnostics: AppendOnlyVec<Diagnostic>,
 
     /// The latch which is used to wait on this job
     #[cfg(parallel_queries)]
@@ -70,7 +71,7 @@ impl<'tcx> QueryJob<'tcx> {
     /// Creates a new query job
     pub fn new(info: QueryInfo<'tcx>, parent: Option<Lrc<QueryJob<'tcx>>>) -> Self {
         QueryJob {
-            diagnostics: Lock::new(Vec::new()),
+            diagnostics: AppendOnlyVec::new(),
             info,
             parent,
             #[cfg(parallel_queries)]
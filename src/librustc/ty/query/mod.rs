@@ -213,6 +213,10 @@ define_queries! { <'tcx>
     /// for codegen. This is also the only query that can fetch non-local MIR, at present.
     [] fn optimized_mir: MirOptimized(DefId) -> &'tcx mir::Mir<'tcx>,
 
+    /// Block, statement, local, call site and loop counts of the optimized
+    /// MIR for a given def-id, for use in the heuristics of other passes.
+    [] fn mir_body_metrics: MirBodyMetrics(DefId) -> mir::BodyMetrics,
+
     /// The result of unsafety-checking this def-id.
     [] fn unsafety_check_result: UnsafetyCheckResult(DefId) -> mir::UnsafetyCheckResult,
 
@@ -242,6 +246,13 @@ define_queries! { <'tcx>
     /// additional requirements that the closure's creator must verify.
     [] fn mir_borrowck: MirBorrowCheck(DefId) -> mir::BorrowCheckResult<'tcx>,
 
+    /// The borrows, moves and used `mut` locals MIR borrowck found in the
+    /// function body, for tools. `None` unless `-Z borrowck-summary` is set,
+    /// and also whenever MIR borrowck doesn't run on the body, e.g. with the
+    /// AST borrow checker (`-Z borrowck=ast`, without NLL) or for struct
+    /// constructors.
+    [] fn mir_borrowck_summary: MirBorrowckSummary(DefId) -> Option<Lrc<mir::BorrowckSummary>>,
+
     /// Gets a complete map from all types to their inherent impls.
     /// Not meant to be used directly outside of coherence.
     /// (Defined only for LOCAL_CRATE)
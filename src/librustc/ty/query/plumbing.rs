@@ -204,8 +204,9 @@ impl<'a, 'tcx, Q: QueryDescription<'tcx>> JobOwner<'a, 'tcx, Q> {
             })
         });
 
-        // Extract the diagnostic from the job
-        let diagnostics = mem::replace(&mut *self.job.diagnostics.lock(), Vec::new());
+        // Extract the diagnostic from the job. The query has finished, so nothing
+        // refers to the diagnostics the job collected anymore.
+        let diagnostics = unsafe { self.job.diagnostics.take() };
 
         (r, diagnostics)
     }
@@ -259,34 +260,40 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
                                            span,
                                            E0391,
                                            "cycle detected when {}",
-                                           stack[0].query.describe(self));
+                                           stack[0].query.describe_with_name(self));
 
             for i in 1..stack.len() {
                 let query = &stack[i].query;
                 let span = fix_span(stack[(i + 1) % stack.len()].span, query);
-                err.span_note(span, &format!("...which requires {}...", query.describe(self)));
+                err.span_note(span, &format!("...which requires {}...",
+                                             query.describe_with_name(self)));
             }
 
             err.note(&format!("...which again requires {}, completing the cycle",
-                              stack[0].query.describe(self)));
+                              stack[0].query.describe_with_name(self)));
 
             if let Some((span, query)) = usage {
                 err.span_note(fix_span(span, &query),
-                              &format!("cycle used when {}", query.describe(self)));
+                              &format!("cycle used when {}", query.describe_with_name(self)));
             }
 
             return err
         })
     }
 
+    /// Prints the queries that are being computed on this thread, innermost
+    /// first. Prints nothing if no query is active.
     pub fn try_print_query_stack() {
-        eprintln!("query stack during panic:");
-
         tls::with_context_opt(|icx| {
             if let Some(icx) = icx {
                 let mut current_query = icx.query.clone();
+                if current_query.is_none() {
+                    return;
+                }
                 let mut i = 0;
 
+                eprintln!("query stack during panic:");
+
                 while let Some(query) = current_query {
                     let mut db = DiagnosticBuilder::new(icx.tcx.sess.diagnostic(),
                         Level::FailureNote,
@@ -300,10 +307,10 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
                     current_query = query.parent.clone();
                     i += 1;
                 }
+
+                eprintln!("end of query stack");
             }
         });
-
-        eprintln!("end of query stack");
     }
 
     /// Try to read a node index for the node dep_node.
@@ -361,6 +368,7 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
         let job = match JobOwner::try_get(self, span, &key) {
             TryGetJob::NotYetStarted(job) => job,
             TryGetJob::JobCompleted(result) => {
+                self.sess.profiler(|p| p.record_query_hit(Q::NAME));
                 return result.map(|(v, index)| {
                     self.dep_graph.read_index(index);
                     v
@@ -380,11 +388,14 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
         if dep_node.kind.is_anon() {
             profq_msg!(self, ProfileQueriesMsg::ProviderBegin);
 
-            let res = job.start(self, |tcx| {
-                tcx.dep_graph.with_anon_task(dep_node.kind, || {
-                    Q::compute(tcx.global_tcx(), key)
+            let res = {
+                let _activity = self.sess.profile_query(Q::NAME);
+                job.start(self, |tcx| {
+                    tcx.dep_graph.with_anon_task(dep_node.kind, || {
+                        Q::compute(tcx.global_tcx(), key)
+                    })
                 })
-            });
+            };
 
             profq_msg!(self, ProfileQueriesMsg::ProviderEnd);
             let ((result, dep_node_index), diagnostics) = res;
@@ -402,6 +413,7 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
         if !dep_node.kind.is_input() {
             if let Some(dep_node_index) = self.try_mark_green_and_read(&dep_node) {
                 profq_msg!(self, ProfileQueriesMsg::CacheHit);
+                self.sess.profiler(|p| p.record_query_hit(Q::NAME));
                 return self.load_from_disk_and_cache_in_memory::<Q>(key,
                                                                     job,
                                                                     dep_node_index,
@@ -460,6 +472,7 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
             // The diagnostics for this query have already been
             // promoted to the current session during
             // try_mark_green(), so we can ignore them here.
+            let _activity = self.sess.profile_query(Q::NAME);
             let (result, _) = job.start(self, |tcx| {
                 // The dep-graph for this computation is already in
                 // place
@@ -492,8 +505,11 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
 
             let old_hash = self.dep_graph.fingerprint_of(dep_node_index);
 
-            assert!(new_hash == old_hash, "Found unstable fingerprints \
-                for {:?}", dep_node);
+            if new_hash != old_hash {
+                bug!("found unstable fingerprints for {:?}: the result of `{}` hashed to {} \
+                      in the previous session but hashes to {} now",
+                     dep_node, Q::NAME, old_hash, new_hash);
+            }
         }
 
         if self.sess.opts.debugging_opts.query_dep_graph {
@@ -523,19 +539,22 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
                 key, dep_node);
 
         profq_msg!(self, ProfileQueriesMsg::ProviderBegin);
-        let res = job.start(self, |tcx| {
-            if dep_node.kind.is_eval_always() {
-                tcx.dep_graph.with_eval_always_task(dep_node,
-                                                    tcx,
-                                                    key,
-                                                    Q::compute)
-            } else {
-                tcx.dep_graph.with_task(dep_node,
-                                        tcx,
-                                        key,
-                                        Q::compute)
-            }
-        });
+        let res = {
+            let _activity = self.sess.profile_query(Q::NAME);
+            job.start(self, |tcx| {
+                if dep_node.kind.is_eval_always() {
+                    tcx.dep_graph.with_eval_always_task(dep_node,
+                                                        tcx,
+                                                        key,
+                                                        Q::compute)
+                } else {
+                    tcx.dep_graph.with_task(dep_node,
+                                            tcx,
+                                            key,
+                                            Q::compute)
+                }
+            })
+        };
         profq_msg!(self, ProfileQueriesMsg::ProviderEnd);
 
         let ((result, dep_node_index), diagnostics) = res;
@@ -694,15 +713,22 @@ macro_rules! define_queries {
             }
 
             pub fn describe(&self, tcx: TyCtxt) -> String {
-                let (r, name) = match *self {
-                    $(Query::$name(key) => {
-                        (queries::$name::describe(tcx, key), stringify!($name))
-                    })*
-                };
                 if tcx.sess.verbose() {
-                    format!("{} [{}]", r, name)
+                    self.describe_with_name(tcx)
                 } else {
-                    r
+                    self.describe_without_name(tcx)
+                }
+            }
+
+            /// Describes the query and names its kind, like
+            /// "computing type of `Foo` [type_of]".
+            pub fn describe_with_name(&self, tcx: TyCtxt) -> String {
+                format!("{} [{}]", self.describe_without_name(tcx), self.name())
+            }
+
+            fn describe_without_name(&self, tcx: TyCtxt) -> String {
+                match *self {
+                    $(Query::$name(key) => queries::$name::describe(tcx, key),)*
                 }
             }
 
@@ -1062,9 +1088,11 @@ pub fn force_from_dep_node<'a, 'gcx, 'lcx>(tcx: TyCtxt<'a, 'gcx, 'lcx>,
         DepKind::MirConst => { force!(mir_const, def_id!()); }
         DepKind::MirValidated => { force!(mir_validated, def_id!()); }
         DepKind::MirOptimized => { force!(optimized_mir, def_id!()); }
+        DepKind::MirBodyMetrics => { force!(mir_body_metrics, def_id!()); }
 
         DepKind::BorrowCheck => { force!(borrowck, def_id!()); }
         DepKind::MirBorrowCheck => { force!(mir_borrowck, def_id!()); }
+        DepKind::MirBorrowckSummary => { force!(mir_borrowck_summary, def_id!()); }
         DepKind::UnsafetyCheckResult => { force!(unsafety_check_result, def_id!()); }
         DepKind::UnsafeDeriveOnReprPacked => { force!(unsafe_derive_on_repr_packed, def_id!()); }
         DepKind::Reachability => { force!(reachable_set, LOCAL_CRATE); }
@@ -1274,6 +1302,7 @@ impl_load_from_cache!(
     UnsafetyCheckResult => unsafety_check_result,
     BorrowCheck => borrowck,
     MirBorrowCheck => mir_borrowck,
+    MirBorrowckSummary => mir_borrowck_summary,
     MirConstQualif => mir_const_qualif,
     SymbolName => def_symbol_name,
     ConstIsRvaluePromotableToStatic => const_is_rvalue_promotable_to_static,
@@ -10,6 +10,7 @@
 
 #![allow(non_camel_case_types)]
 
+use arena;
 use rustc_data_structures::sync::Lock;
 
 use std::cell::{RefCell, Cell};
@@ -19,7 +20,6 @@ use std::fmt::Debug;
 use std::hash::{Hash, BuildHasher};
 use std::iter::repeat;
 use std::panic;
-use std::env;
 use std::path::Path;
 use std::time::{Duration, Instant};
 
@@ -53,15 +53,13 @@ fn panic_hook(info: &panic::PanicInfo) {
     if !proc_macro::__internal::in_sess() {
         (*DEFAULT_HOOK)(info);
 
-        let backtrace = env::var_os("RUST_BACKTRACE").map(|x| &x != "0").unwrap_or(false);
-
-        if backtrace {
-            TyCtxt::try_print_query_stack();
-        }
+        // Knowing which item the compiler was working on makes a bug report
+        // actionable, so this is printed even without `RUST_BACKTRACE`.
+        TyCtxt::try_print_query_stack();
 
         #[cfg(windows)]
         unsafe {
-            if env::var("RUSTC_BREAK_ON_ICE").is_ok() {
+            if ::std::env::var("RUSTC_BREAK_ON_ICE").is_ok() {
                 extern "system" {
                     fn DebugBreak();
                 }
@@ -157,7 +155,7 @@ pub fn set_time_depth(depth: usize) {
 pub fn time<T, F>(sess: &Session, what: &str, f: F) -> T where
     F: FnOnce() -> T,
 {
-    time_ext(sess.time_passes(), Some(sess), what, f)
+    sess.profile_activity(what, || time_ext(sess.time_passes(), Some(sess), what, f))
 }
 
 pub fn time_ext<T, F>(do_it: bool, sess: Option<&Session>, what: &str, f: F) -> T where
@@ -176,16 +174,18 @@ pub fn time_ext<T, F>(do_it: bool, sess: Option<&Session>, what: &str, f: F) ->
             profq_msg(sess, ProfileQueriesMsg::TimeBegin(what.to_string()))
         }
     }
+    let mem_before = MemorySample::take();
     let start = Instant::now();
     let rv = f();
     let dur = start.elapsed();
+    let mem_after = MemorySample::take();
     if let Some(sess) = sess {
         if cfg!(debug_assertions) {
             profq_msg(sess, ProfileQueriesMsg::TimeEnd)
         }
     }
 
-    print_time_passes_entry_internal(what, dur);
+    print_time_passes_entry_internal(what, dur, Some((mem_before, mem_after)));
 
     TIME_DEPTH.with(|slot| slot.set(old));
 
@@ -203,21 +203,44 @@ pub fn print_time_passes_entry(do_it: bool, what: &str, dur: Duration) {
         r
     });
 
-    print_time_passes_entry_internal(what, dur);
+    print_time_passes_entry_internal(what, dur, None);
 
     TIME_DEPTH.with(|slot| slot.set(old));
 }
 
-fn print_time_passes_entry_internal(what: &str, dur: Duration) {
-    let indentation = TIME_DEPTH.with(|slot| slot.get());
+/// The memory use of the process, sampled around a timed pass to report how
+/// much the pass added to it.
+#[derive(Clone, Copy)]
+struct MemorySample {
+    max_resident: Option<usize>,
+    arena_bytes: usize,
+}
 
-    let mem_string = match get_resident() {
-        Some(n) => {
-            let mb = n as f64 / 1_000_000.0;
-            format!("; rss: {}MB", mb.round() as usize)
+impl MemorySample {
+    fn take() -> MemorySample {
+        MemorySample {
+            max_resident: get_max_resident(),
+            arena_bytes: arena::total_chunk_bytes(),
         }
+    }
+}
+
+fn print_time_passes_entry_internal(what: &str,
+                                    dur: Duration,
+                                    mem_samples: Option<(MemorySample, MemorySample)>) {
+    let indentation = TIME_DEPTH.with(|slot| slot.get());
+
+    let mut mem_string = match get_resident() {
+        Some(n) => format!("; rss: {}", bytes_to_mb_str(n)),
         None => "".to_owned(),
     };
+    if let Some((before, after)) = mem_samples {
+        if let (Some(before), Some(after)) = (before.max_resident, after.max_resident) {
+            mem_string.push_str(&format!("; max rss: +{}", bytes_to_mb_str(after - before)));
+        }
+        mem_string.push_str(&format!("; arenas: +{}",
+                                     bytes_to_mb_str(after.arena_bytes - before.arena_bytes)));
+    }
     println!("{}time: {}{}\t{}",
              repeat("  ").take(indentation).collect::<String>(),
              duration_to_secs_str(dur),
@@ -225,6 +248,11 @@ fn print_time_passes_entry_internal(what: &str, dur: Duration) {
              what);
 }
 
+fn bytes_to_mb_str(bytes: usize) -> String {
+    let mb = bytes as f64 / 1_000_000.0;
+    format!("{}MB", mb.round() as usize)
+}
+
 // Hack up our own formatting for the duration to make it easier for scripts
 // to parse (always use the same number of decimal places and the same unit).
 pub fn duration_to_secs_str(dur: Duration) -> String {
@@ -279,8 +307,38 @@ fn get_resident() -> Option<usize> {
     Some(npages * 4096)
 }
 
+// The peak of the resident set size of the process so far.
+#[cfg(unix)]
+fn get_max_resident() -> Option<usize> {
+    use libc;
+    use std::mem;
+
+    let mut usage: libc::rusage = unsafe { mem::zeroed() };
+    if unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) } != 0 {
+        return None;
+    }
+    // `ru_maxrss` is in bytes on macOS, and in kilobytes elsewhere.
+    let max_rss = usage.ru_maxrss as usize;
+    if cfg!(target_os = "macos") {
+        Some(max_rss)
+    } else {
+        Some(max_rss * 1024)
+    }
+}
+
 #[cfg(windows)]
 fn get_resident() -> Option<usize> {
+    get_memory_counters().map(|(resident, _)| resident)
+}
+
+#[cfg(windows)]
+fn get_max_resident() -> Option<usize> {
+    get_memory_counters().map(|(_, max_resident)| max_resident)
+}
+
+// The current and peak working set sizes of the process.
+#[cfg(windows)]
+fn get_memory_counters() -> Option<(usize, usize)> {
     type BOOL = i32;
     type DWORD = u32;
     type HANDLE = *mut u8;
@@ -312,7 +370,7 @@ fn get_resident() -> Option<usize> {
     pmc.cb = mem::size_of_val(&pmc) as DWORD;
     match unsafe { GetProcessMemoryInfo(GetCurrentProcess(), &mut pmc, pmc.cb) } {
         0 => None,
-        _ => Some(pmc.WorkingSetSize as usize),
+        _ => Some((pmc.WorkingSetSize as usize, pmc.PeakWorkingSetSize as usize)),
     }
 }
 
@@ -0,0 +1,297 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The self profiler of `-Z self-profile` and `-Z self-profile-summary`.
+//!
+//! It records an event when each query provider and each timed pass (see
+//! `util::common::time`) starts and stops. The events can be written out as
+//! a trace in the Chrome trace event format, which `chrome://tracing` and
+//! similar viewers can open, or aggregated into a summary of where the time
+//! of the compilation went.
+//!
+//! Events are recorded separately for each thread, and each query or pass
+//! is ended by dropping the `ProfilerActivity` guard returned when it was
+//! started, so the events of a thread are always properly nested, even when
+//! queries run on several threads.
+
+use rustc_data_structures::sync::Lock;
+use serialize::json::as_json;
+use util::nodemap::FxHashMap;
+
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::process;
+use std::thread::{self, ThreadId};
+use std::time::Instant;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ProfileCategory {
+    Query,
+    Pass,
+}
+
+impl ProfileCategory {
+    fn name(self) -> &'static str {
+        match self {
+            ProfileCategory::Query => "query",
+            ProfileCategory::Pass => "pass",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum ProfilerEvent {
+    Start { category: ProfileCategory, label: String, nanos: u64 },
+    End { nanos: u64 },
+    QueryCacheHit { label: &'static str },
+}
+
+pub struct SelfProfiler {
+    start: Instant,
+    /// The index into `events` of the events of each thread seen so far.
+    threads: FxHashMap<ThreadId, usize>,
+    events: Vec<Vec<ProfilerEvent>>,
+}
+
+/// A query or pass being recorded by the self profiler, which ends when
+/// this is dropped.
+#[must_use]
+pub struct ProfilerActivity<'a> {
+    profiler: Option<&'a Lock<SelfProfiler>>,
+}
+
+impl<'a> ProfilerActivity<'a> {
+    /// Records the start of a query or pass in `profiler`, if there is one.
+    pub fn start(profiler: Option<&'a Lock<SelfProfiler>>,
+                 category: ProfileCategory,
+                 label: &str)
+                 -> ProfilerActivity<'a> {
+        if let Some(profiler) = profiler {
+            profiler.borrow_mut().start(category, label);
+        }
+        ProfilerActivity { profiler }
+    }
+}
+
+impl<'a> Drop for ProfilerActivity<'a> {
+    fn drop(&mut self) {
+        if let Some(profiler) = self.profiler {
+            profiler.borrow_mut().end();
+        }
+    }
+}
+
+/// Time spent in one query or pass, over all of its invocations.
+#[derive(Clone, Debug, Default)]
+struct SummaryEntry {
+    invocations: usize,
+    cache_hits: usize,
+    /// Time spent in the query or pass itself, excluding the nested ones.
+    self_nanos: u64,
+    /// Time spent in the query or pass and all the nested ones.
+    total_nanos: u64,
+}
+
+impl SelfProfiler {
+    pub fn new() -> SelfProfiler {
+        SelfProfiler {
+            start: Instant::now(),
+            threads: FxHashMap(),
+            events: vec![],
+        }
+    }
+
+    /// The events of the current thread.
+    fn thread_events(&mut self) -> &mut Vec<ProfilerEvent> {
+        let events = &mut self.events;
+        let index = *self.threads.entry(thread::current().id()).or_insert_with(|| {
+            events.push(vec![]);
+            events.len() - 1
+        });
+        &mut events[index]
+    }
+
+    fn elapsed_nanos(&self) -> u64 {
+        let elapsed = self.start.elapsed();
+        elapsed.as_secs() * 1_000_000_000 + elapsed.subsec_nanos() as u64
+    }
+
+    fn start(&mut self, category: ProfileCategory, label: &str) {
+        let nanos = self.elapsed_nanos();
+        self.thread_events().push(ProfilerEvent::Start {
+            category,
+            label: label.to_string(),
+            nanos,
+        });
+    }
+
+    /// Ends the innermost query or pass started on the current thread.
+    fn end(&mut self) {
+        let nanos = self.elapsed_nanos();
+        self.thread_events().push(ProfilerEvent::End { nanos });
+    }
+
+    pub fn record_query_hit(&mut self, query_name: &'static str) {
+        self.thread_events().push(ProfilerEvent::QueryCacheHit { label: query_name });
+    }
+
+    /// Writes the recorded events to `path` in the Chrome trace event format.
+    pub fn dump_raw_events(&self, path: &Path) -> io::Result<()> {
+        let mut file = BufWriter::new(fs::File::create(path)?);
+        let pid = process::id();
+
+        write!(file, "[")?;
+        let mut first = true;
+        for (tid, events) in self.events.iter().enumerate() {
+            let mut open = vec![];
+            for event in events {
+                let (phase, category, label, nanos) = match *event {
+                    ProfilerEvent::Start { category, ref label, nanos } => {
+                        open.push((category, &label[..]));
+                        ("B", category, &label[..], nanos)
+                    }
+                    ProfilerEvent::End { nanos } => {
+                        let (category, label) = open.pop().expect("unbalanced profiler events");
+                        ("E", category, label, nanos)
+                    }
+                    ProfilerEvent::QueryCacheHit { .. } => continue,
+                };
+                if !first {
+                    write!(file, ",")?;
+                }
+                first = false;
+                // Timestamps are in microseconds.
+                write!(file,
+                       "\n{{\"name\":{},\"cat\":\"{}\",\"ph\":\"{}\",\"ts\":{}.{:03},\
+                        \"pid\":{},\"tid\":{}}}",
+                       as_json(&label),
+                       category.name(),
+                       phase,
+                       nanos / 1000,
+                       nanos % 1000,
+                       pid,
+                       tid)?;
+            }
+        }
+        write!(file, "\n]\n")?;
+        file.flush()
+    }
+
+    /// Prints, for each query and pass, how often it ran and how much time was
+    /// spent in it, the most expensive first.
+    pub fn print_summary(&self) {
+        let mut entries: FxHashMap<(ProfileCategory, &str), SummaryEntry> = FxHashMap();
+
+        for events in &self.events {
+            // The open queries and passes of this thread, with their start
+            // time and the time spent in the ones nested in them.
+            let mut open: Vec<(ProfileCategory, &str, u64, u64)> = vec![];
+
+            for event in events {
+                match *event {
+                    ProfilerEvent::Start { category, ref label, nanos } => {
+                        open.push((category, &label[..], nanos, 0));
+                    }
+                    ProfilerEvent::End { nanos } => {
+                        let (category, label, start, nested) =
+                            open.pop().expect("unbalanced profiler events");
+                        let total = nanos - start;
+                        if let Some(parent) = open.last_mut() {
+                            parent.3 += total;
+                        }
+                        let entry = entries.entry((category, label))
+                            .or_insert_with(SummaryEntry::default);
+                        entry.invocations += 1;
+                        entry.self_nanos += total - nested;
+                        // Recursive invocations are already included in the outer one.
+                        if !open.iter().any(|&(c, l, ..)| c == category && l == label) {
+                            entry.total_nanos += total;
+                        }
+                    }
+                    ProfilerEvent::QueryCacheHit { label } => {
+                        entries.entry((ProfileCategory::Query, label))
+                            .or_insert_with(SummaryEntry::default)
+                            .cache_hits += 1;
+                    }
+                }
+            }
+        }
+
+        let mut entries: Vec<_> = entries.into_iter().collect();
+        entries.sort_by(|a, b| b.1.self_nanos.cmp(&a.1.self_nanos).then(a.0.cmp(&b.0)));
+
+        println!("--- self profile summary ---");
+        println!("{:<50} {:>6} {:>12} {:>12} {:>12} {:>12}",
+                 "query or pass", "kind", "invocations", "cache hits", "self time", "total time");
+        for ((category, label), entry) in entries {
+            println!("{:<50} {:>6} {:>12} {:>12} {:>12} {:>12}",
+                     label,
+                     category.name(),
+                     entry.invocations,
+                     entry.cache_hits,
+                     nanos_to_secs_str(entry.self_nanos),
+                     nanos_to_secs_str(entry.total_nanos));
+        }
+    }
+}
+
+fn nanos_to_secs_str(nanos: u64) -> String {
+    format!("{:.3}", nanos as f64 / 1_000_000_000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{mpsc, Arc, Mutex};
+
+    fn label(event: &ProfilerEvent) -> &str {
+        match *event {
+            ProfilerEvent::Start { ref label, .. } => label,
+            _ => panic!("expected a start event, found {:?}", event),
+        }
+    }
+
+    #[test]
+    fn events_are_nested_per_thread() {
+        let profiler = Arc::new(Mutex::new(SelfProfiler::new()));
+        profiler.lock().unwrap().start(ProfileCategory::Pass, "pass");
+
+        let (started_tx, started_rx) = mpsc::channel();
+        let (ended_tx, ended_rx) = mpsc::channel();
+        let other = {
+            let profiler = profiler.clone();
+            thread::spawn(move || {
+                profiler.lock().unwrap().start(ProfileCategory::Query, "query");
+                started_tx.send(()).unwrap();
+                ended_rx.recv().unwrap();
+                profiler.lock().unwrap().end();
+            })
+        };
+
+        // End the pass while the query on the other thread is still running.
+        started_rx.recv().unwrap();
+        profiler.lock().unwrap().end();
+        ended_tx.send(()).unwrap();
+        other.join().unwrap();
+
+        let profiler = profiler.lock().unwrap();
+        assert_eq!(profiler.events.len(), 2);
+        assert_eq!(label(&profiler.events[0][0]), "pass");
+        assert_eq!(label(&profiler.events[1][0]), "query");
+        for events in &profiler.events {
+            assert_eq!(events.len(), 2);
+            match events[1] {
+                ProfilerEvent::End { .. } => {}
+                ref event => panic!("expected an end event, found {:?}", event),
+            }
+        }
+    }
+}
@@ -88,7 +88,7 @@ fn report_move_errors<'a, 'tcx>(bccx: &BorrowckCtxt<'a, 'tcx>, errors: &Vec<Move
             _ => {
                 for move_to in &error.move_to_places {
 
-                    err = note_move_destination(err, move_to.span, move_to.name, is_first_note);
+                    err = note_move_destination(bccx, err, move_to, is_first_note);
                     is_first_note = false;
                 }
             }
@@ -168,10 +168,29 @@ fn report_cannot_move_out_of<'a, 'tcx>(bccx: &'a BorrowckCtxt<'a, 'tcx>,
     }
 }
 
-fn note_move_destination(mut err: DiagnosticBuilder,
-                         move_to_span: syntax_pos::Span,
-                         pat_name: ast::Name,
-                         is_first_note: bool) -> DiagnosticBuilder {
+fn note_move_destination<'a, 'tcx>(bccx: &'a BorrowckCtxt<'a, 'tcx>,
+                                   mut err: DiagnosticBuilder<'a>,
+                                   move_to: &MovePlace<'tcx>,
+                                   is_first_note: bool) -> DiagnosticBuilder<'a> {
+    let move_to_span = move_to.span;
+    let pat_name = move_to.name;
+
+    // Plain bindings can be replaced by `ref` bindings outright; bindings
+    // with a subpattern only get the hint.
+    if let Ok(snippet) = bccx.tcx.sess.codemap().span_to_snippet(move_to_span) {
+        let ref_binding = if snippet == &*pat_name.as_str() {
+            Some(format!("ref {}", pat_name))
+        } else if snippet == format!("mut {}", pat_name) {
+            Some(format!("ref mut {}", pat_name))
+        } else {
+            None
+        };
+        if let Some(ref_binding) = ref_binding {
+            err.span_suggestion(move_to_span, "to prevent move, use ref or ref mut", ref_binding);
+            return err;
+        }
+    }
+
     if is_first_note {
         err.span_label(
             move_to_span,
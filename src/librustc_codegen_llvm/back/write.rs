@@ -24,6 +24,7 @@ use rustc::session::config::{self, OutputFilenames, OutputType, Passes, SomePass
                              AllPasses, Sanitizer, Lto};
 use rustc::session::Session;
 use rustc::util::nodemap::FxHashMap;
+use rustc_data_structures::unhash::UnhashMap;
 use time_graph::{self, TimeGraph, Timeline};
 use llvm;
 use llvm::{ModuleRef, TargetMachineRef, PassManagerRef, DiagnosticInfoRef};
@@ -372,7 +373,7 @@ pub struct CodegenContext {
 
 impl CodegenContext {
     pub fn create_diag_handler(&self) -> Handler {
-        Handler::with_emitter(true, false, Box::new(self.diag_emitter.clone()))
+        Handler::with_emitter(true, None, Box::new(self.diag_emitter.clone()))
     }
 
     pub(crate) fn config(&self, kind: ModuleKind) -> &ModuleConfig {
@@ -970,7 +971,9 @@ pub fn start_async_codegen(tcx: TyCtxt,
                 }
             }
             OutputType::Object => { modules_config.emit_obj = true; }
-            OutputType::Metadata => { metadata_config.emit_obj = true; }
+            // The `.rmeta` file is written straight from the encoded metadata,
+            // so no object file is needed for the metadata module here.
+            OutputType::Metadata => {}
             OutputType::Exe => {
                 modules_config.emit_obj = true;
                 metadata_config.emit_obj = true;
@@ -1025,8 +1028,8 @@ pub fn start_async_codegen(tcx: TyCtxt,
 fn copy_all_cgu_workproducts_to_incr_comp_cache_dir(
     sess: &Session,
     compiled_modules: &CompiledModules
-) -> FxHashMap<WorkProductId, WorkProduct> {
-    let mut work_products = FxHashMap::default();
+) -> UnhashMap<WorkProductId, WorkProduct> {
+    let mut work_products = UnhashMap::default();
 
     if sess.opts.incremental.is_none() {
         return work_products;
@@ -2254,7 +2257,7 @@ impl OngoingCodegen {
     pub(crate) fn join(
         self,
         sess: &Session
-    ) -> (CodegenResults, FxHashMap<WorkProductId, WorkProduct>) {
+    ) -> (CodegenResults, UnhashMap<WorkProductId, WorkProduct>) {
         self.shared_emitter_main.check(sess, true);
         let compiled_modules = match self.future.join() {
             Ok(Ok(compiled_modules)) => compiled_modules,
@@ -8,14 +8,16 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use super::{FunctionDebugContext, FunctionDebugContextData};
-use super::metadata::file_metadata;
+use super::{create_inlined_subprogram, FunctionDebugContext, FunctionDebugContextData};
+use super::metadata::{extend_scope_to_file, file_metadata};
 use super::utils::{DIB, span_start};
 
 use llvm;
-use llvm::debuginfo::DIScope;
+use llvm::debuginfo::{DILocation, DIScope};
 use common::CodegenCx;
+use monomorphize::Instance;
 use rustc::mir::{Mir, SourceScope};
+use rustc::ty::ParamEnv;
 
 use libc::c_uint;
 use std::ptr;
@@ -30,6 +32,8 @@ use syntax_pos::BytePos;
 #[derive(Clone, Copy, Debug)]
 pub struct MirDebugScope {
     pub scope_metadata: DIScope,
+    // The location of the call this scope was inlined at by the MIR inliner, or null.
+    pub inlined_at: DILocation,
     // Start and end offsets of the file to which this DIScope belongs.
     // These are used to quickly determine whether some span refers to the same file.
     pub file_start_pos: BytePos,
@@ -42,12 +46,17 @@ impl MirDebugScope {
     }
 }
 
-/// Produce DIScope DIEs for each MIR Scope which has variables defined in it.
+/// Produce DIScope DIEs for each MIR Scope which has variables defined in it,
+/// and for the root scope of each function inlined into `instance`.
 /// If debuginfo is disabled, the returned vector is empty.
-pub fn create_mir_scopes(cx: &CodegenCx, mir: &Mir, debug_context: &FunctionDebugContext)
+pub fn create_mir_scopes<'a, 'tcx>(cx: &CodegenCx<'a, 'tcx>,
+                                   instance: Instance<'tcx>,
+                                   mir: &Mir<'tcx>,
+                                   debug_context: &FunctionDebugContext)
     -> IndexVec<SourceScope, MirDebugScope> {
     let null_scope = MirDebugScope {
         scope_metadata: ptr::null_mut(),
+        inlined_at: ptr::null_mut(),
         file_start_pos: BytePos(0),
         file_end_pos: BytePos(0)
     };
@@ -71,37 +80,74 @@ pub fn create_mir_scopes(cx: &CodegenCx, mir: &Mir, debug_context: &FunctionDebu
     // Instantiate all scopes.
     for idx in 0..mir.source_scopes.len() {
         let scope = SourceScope::new(idx);
-        make_mir_scope(cx, &mir, &has_variables, debug_context, scope, &mut scopes);
+        make_mir_scope(cx, instance, &mir, &has_variables, debug_context, scope, &mut scopes);
     }
 
     scopes
 }
 
-fn make_mir_scope(cx: &CodegenCx,
-                  mir: &Mir,
-                  has_variables: &BitVector,
-                  debug_context: &FunctionDebugContextData,
-                  scope: SourceScope,
-                  scopes: &mut IndexVec<SourceScope, MirDebugScope>) {
+fn make_mir_scope<'a, 'tcx>(cx: &CodegenCx<'a, 'tcx>,
+                            instance: Instance<'tcx>,
+                            mir: &Mir<'tcx>,
+                            has_variables: &BitVector,
+                            debug_context: &FunctionDebugContextData,
+                            scope: SourceScope,
+                            scopes: &mut IndexVec<SourceScope, MirDebugScope>) {
     if scopes[scope].is_valid() {
         return;
     }
 
     let scope_data = &mir.source_scopes[scope];
     let parent_scope = if let Some(parent) = scope_data.parent_scope {
-        make_mir_scope(cx, mir, has_variables, debug_context, parent, scopes);
+        make_mir_scope(cx, instance, mir, has_variables, debug_context, parent, scopes);
         scopes[parent]
     } else {
         // The root is the function itself.
         let loc = span_start(cx, mir.span);
         scopes[scope] = MirDebugScope {
             scope_metadata: debug_context.fn_metadata,
+            inlined_at: ptr::null_mut(),
             file_start_pos: loc.file.start_pos,
             file_end_pos: loc.file.end_pos,
         };
         return;
     };
 
+    if let Some((callee, callsite_span)) = scope_data.inlined {
+        // The root of an inlined function is described by the callee's own
+        // subprogram, and everything in it is marked as inlined at the call.
+        let callee = cx.tcx.subst_and_normalize_erasing_regions(
+            instance.substs,
+            ParamEnv::reveal_all(),
+            &callee,
+        );
+        let callsite_loc = span_start(cx, callsite_span);
+        let callsite_scope = if callsite_span.lo() < parent_scope.file_start_pos ||
+                                callsite_span.lo() >= parent_scope.file_end_pos {
+            extend_scope_to_file(cx,
+                                 parent_scope.scope_metadata,
+                                 &callsite_loc.file,
+                                 debug_context.defining_crate)
+        } else {
+            parent_scope.scope_metadata
+        };
+        let inlined_at = unsafe {
+            llvm::LLVMRustDIBuilderCreateLocation(
+                callsite_loc.line as c_uint,
+                callsite_loc.col.to_usize() as c_uint,
+                callsite_scope,
+                parent_scope.inlined_at)
+        };
+        let loc = span_start(cx, scope_data.span);
+        scopes[scope] = MirDebugScope {
+            scope_metadata: create_inlined_subprogram(cx, callee),
+            inlined_at,
+            file_start_pos: loc.file.start_pos,
+            file_end_pos: loc.file.end_pos,
+        };
+        return;
+    }
+
     if !has_variables.contains(scope.index()) {
         // Do not create a DIScope if there are no variables
         // defined in this MIR Scope, to avoid debuginfo bloat.
@@ -130,6 +176,7 @@ fn make_mir_scope(cx: &CodegenCx,
     };
     scopes[scope] = MirDebugScope {
         scope_metadata,
+        inlined_at: parent_scope.inlined_at,
         file_start_pos: loc.file.start_pos,
         file_end_pos: loc.file.end_pos,
     };
@@ -23,12 +23,13 @@ use self::source_loc::InternalDebugLocation::{self, UnknownLocation};
 use llvm;
 use llvm::{ModuleRef, ContextRef, ValueRef};
 use llvm::debuginfo::{DIFile, DIType, DIScope, DIBuilderRef, DISubprogram, DIArray, DIFlags};
+use llvm::debuginfo::DILocation;
 use rustc::hir::CodegenFnAttrFlags;
 use rustc::hir::def_id::{DefId, CrateNum};
 use rustc::ty::subst::{Substs, UnpackedKind};
 
 use abi::Abi;
-use common::CodegenCx;
+use common::{self, CodegenCx};
 use builder::Builder;
 use monomorphize::Instance;
 use rustc::ty::{self, ParamEnv, Ty, InstanceDef};
@@ -224,6 +225,33 @@ pub fn create_function_debug_context<'a, 'tcx>(cx: &CodegenCx<'a, 'tcx>,
         return FunctionDebugContext::FunctionWithoutDebugInfo;
     }
 
+    let fn_metadata = create_subprogram(cx, instance, sig, span, llfn);
+
+    // Initialize fn debug context (including scope map and namespace map)
+    let fn_debug_context = FunctionDebugContextData {
+        fn_metadata,
+        source_locations_enabled: Cell::new(false),
+        defining_crate: instance.def_id().krate,
+    };
+
+    FunctionDebugContext::RegularContext(fn_debug_context)
+}
+
+/// Creates the `DISubprogram` describing a function which was inlined into
+/// another one by the MIR inliner. It isn't attached to any LLVM function.
+pub fn create_inlined_subprogram<'a, 'tcx>(cx: &CodegenCx<'a, 'tcx>,
+                                           instance: Instance<'tcx>) -> DISubprogram {
+    let sig = common::ty_fn_sig(cx, instance.ty(cx.tcx));
+    let sig = cx.tcx.normalize_erasing_late_bound_regions(ParamEnv::reveal_all(), &sig);
+    let span = cx.tcx.def_span(instance.def_id());
+    create_subprogram(cx, instance, sig, span, ptr::null_mut())
+}
+
+fn create_subprogram<'a, 'tcx>(cx: &CodegenCx<'a, 'tcx>,
+                               instance: Instance<'tcx>,
+                               sig: ty::FnSig<'tcx>,
+                               span: Span,
+                               llfn: ValueRef) -> DISubprogram {
     let def_id = instance.def_id();
     let containing_scope = get_containing_scope(cx, instance);
     let loc = span_start(cx, span);
@@ -274,7 +302,7 @@ pub fn create_function_debug_context<'a, 'tcx>(cx: &CodegenCx<'a, 'tcx>,
         flags = flags | DIFlags::FlagNoReturn;
     }
 
-    let fn_metadata = unsafe {
+    return unsafe {
         llvm::LLVMRustDIBuilderCreateFunction(
             DIB(cx),
             containing_scope,
@@ -293,15 +321,6 @@ pub fn create_function_debug_context<'a, 'tcx>(cx: &CodegenCx<'a, 'tcx>,
             ptr::null_mut())
     };
 
-    // Initialize fn debug context (including scope map and namespace map)
-    let fn_debug_context = FunctionDebugContextData {
-        fn_metadata,
-        source_locations_enabled: Cell::new(false),
-        defining_crate: def_id.krate,
-    };
-
-    return FunctionDebugContext::RegularContext(fn_debug_context);
-
     fn get_function_signature<'a, 'tcx>(cx: &CodegenCx<'a, 'tcx>,
                                         sig: ty::FnSig<'tcx>) -> DIArray {
         if cx.sess().opts.debuginfo == LimitedDebugInfo {
@@ -474,6 +493,7 @@ pub fn declare_local<'a, 'tcx>(bx: &Builder<'a, 'tcx>,
                                variable_name: ast::Name,
                                variable_type: Ty<'tcx>,
                                scope_metadata: DIScope,
+                               inlined_at: DILocation,
                                variable_access: VariableAccess,
                                variable_kind: VariableKind,
                                span: Span) {
@@ -514,7 +534,8 @@ pub fn declare_local<'a, 'tcx>(bx: &Builder<'a, 'tcx>,
                 )
             };
             source_loc::set_debug_location(bx,
-                InternalDebugLocation::new(scope_metadata, loc.line, loc.col.to_usize()));
+                InternalDebugLocation::new(scope_metadata, inlined_at,
+                                           loc.line, loc.col.to_usize()));
             unsafe {
                 let debug_loc = llvm::LLVMGetCurrentDebugLocation(bx.llbuilder);
                 let instr = llvm::LLVMRustDIBuilderInsertDeclareAtEnd(
@@ -15,7 +15,7 @@ use super::metadata::UNKNOWN_COLUMN_NUMBER;
 use super::FunctionDebugContext;
 
 use llvm;
-use llvm::debuginfo::DIScope;
+use llvm::debuginfo::{DILocation, DIScope};
 use builder::Builder;
 
 use libc::c_uint;
@@ -23,10 +23,15 @@ use std::ptr;
 use syntax_pos::{Span, Pos};
 
 /// Sets the current debug location at the beginning of the span.
+/// `inlined_at` is the location of the call `scope` was inlined at, or null.
 ///
 /// Maps to a call to llvm::LLVMSetCurrentDebugLocation(...).
 pub fn set_source_location(
-    debug_context: &FunctionDebugContext, bx: &Builder, scope: DIScope, span: Span
+    debug_context: &FunctionDebugContext,
+    bx: &Builder,
+    scope: DIScope,
+    inlined_at: DILocation,
+    span: Span,
 ) {
     let function_debug_context = match *debug_context {
         FunctionDebugContext::DebugInfoDisabled => return,
@@ -40,7 +45,7 @@ pub fn set_source_location(
     let dbg_loc = if function_debug_context.source_locations_enabled.get() {
         debug!("set_source_location: {}", bx.sess().codemap().span_to_string(span));
         let loc = span_start(bx.cx, span);
-        InternalDebugLocation::new(scope, loc.line, loc.col.to_usize())
+        InternalDebugLocation::new(scope, inlined_at, loc.line, loc.col.to_usize())
     } else {
         UnknownLocation
     };
@@ -65,14 +70,16 @@ pub fn start_emitting_source_locations(dbg_context: &FunctionDebugContext) {
 
 #[derive(Copy, Clone, PartialEq)]
 pub enum InternalDebugLocation {
-    KnownLocation { scope: DIScope, line: usize, col: usize },
+    KnownLocation { scope: DIScope, inlined_at: DILocation, line: usize, col: usize },
     UnknownLocation
 }
 
 impl InternalDebugLocation {
-    pub fn new(scope: DIScope, line: usize, col: usize) -> InternalDebugLocation {
+    pub fn new(scope: DIScope, inlined_at: DILocation, line: usize, col: usize)
+               -> InternalDebugLocation {
         KnownLocation {
             scope,
+            inlined_at,
             line,
             col,
         }
@@ -81,7 +88,7 @@ impl InternalDebugLocation {
 
 pub fn set_debug_location(bx: &Builder, debug_location: InternalDebugLocation) {
     let metadata_node = match debug_location {
-        KnownLocation { scope, line, col } => {
+        KnownLocation { scope, inlined_at, line, col } => {
             // For MSVC, set the column number to zero.
             // Otherwise, emit it. This mimics clang behaviour.
             // See discussion in https://github.com/rust-lang/rust/issues/42921
@@ -98,7 +105,7 @@ pub fn set_debug_location(bx: &Builder, debug_location: InternalDebugLocation) {
                     line as c_uint,
                     col_used,
                     scope,
-                    ptr::null_mut())
+                    inlined_at)
             }
         }
         UnknownLocation => {
@@ -118,6 +118,10 @@ pub fn codegen_intrinsic_call<'a, 'tcx>(bx: &Builder<'a, 'tcx>,
         "unreachable" => {
             return;
         },
+        "forget" => {
+            // The argument was moved into the call, so not dropping it is all there is to do.
+            return;
+        }
         "likely" => {
             let expect = cx.get_intrinsic(&("llvm.expect.i1"));
             bx.call(expect, &[args[0].immediate(), C_bool(cx, true)], None)
@@ -271,6 +275,7 @@ pub fn codegen_intrinsic_call<'a, 'tcx>(bx: &Builder<'a, 'tcx>,
         "ctlz" | "ctlz_nonzero" | "cttz" | "cttz_nonzero" | "ctpop" | "bswap" |
         "bitreverse" | "add_with_overflow" | "sub_with_overflow" |
         "mul_with_overflow" | "overflowing_add" | "overflowing_sub" | "overflowing_mul" |
+        "wrapping_add" | "wrapping_sub" | "wrapping_mul" |
         "unchecked_div" | "unchecked_rem" | "unchecked_shl" | "unchecked_shr" | "exact_div" => {
             let ty = arg_tys[0];
             match int_type_width_signed(ty, cx) {
@@ -322,9 +327,15 @@ pub fn codegen_intrinsic_call<'a, 'tcx>(bx: &Builder<'a, 'tcx>,
 
                             return;
                         },
-                        "overflowing_add" => bx.add(args[0].immediate(), args[1].immediate()),
-                        "overflowing_sub" => bx.sub(args[0].immediate(), args[1].immediate()),
-                        "overflowing_mul" => bx.mul(args[0].immediate(), args[1].immediate()),
+                        "overflowing_add" | "wrapping_add" => {
+                            bx.add(args[0].immediate(), args[1].immediate())
+                        }
+                        "overflowing_sub" | "wrapping_sub" => {
+                            bx.sub(args[0].immediate(), args[1].immediate())
+                        }
+                        "overflowing_mul" | "wrapping_mul" => {
+                            bx.mul(args[0].immediate(), args[1].immediate())
+                        }
                         "exact_div" =>
                             if signed {
                                 bx.exactsdiv(args[0].immediate(), args[1].immediate())
@@ -206,7 +206,8 @@ impl<'mir, 'a, 'tcx> Visitor<'tcx> for LocalAnalyzer<'mir, 'a, 'tcx> {
 
             PlaceContext::StorageLive |
             PlaceContext::StorageDead |
-            PlaceContext::Validate => {}
+            PlaceContext::Validate |
+            PlaceContext::VarDebugInfo => {}
 
             PlaceContext::Copy |
             PlaceContext::Move => {
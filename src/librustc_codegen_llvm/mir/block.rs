@@ -189,34 +189,34 @@ impl<'a, 'tcx> FunctionCx<'a, 'tcx> {
                 funclet_br(self, bx, target);
             }
 
-            mir::TerminatorKind::SwitchInt { ref discr, switch_ty, ref values, ref targets } => {
+            mir::TerminatorKind::SwitchInt { ref discr, switch_ty, ref targets } => {
                 let discr = self.codegen_operand(&bx, discr);
-                if targets.len() == 2 {
-                    // If there are two targets, emit br instead of switch
-                    let lltrue = llblock(self, targets[0]);
-                    let llfalse = llblock(self, targets[1]);
+                if let Some((value, then, else_)) = targets.as_static_if() {
+                    // If a single value is tested, emit br instead of switch
+                    let lltrue = llblock(self, then);
+                    let llfalse = llblock(self, else_);
                     if switch_ty == bx.tcx().types.bool {
                         // Don't generate trivial icmps when switching on bool
-                        if let [0] = values[..] {
+                        if value == 0 {
                             bx.cond_br(discr.immediate(), llfalse, lltrue);
                         } else {
-                            assert_eq!(&values[..], &[1]);
+                            assert_eq!(value, 1);
                             bx.cond_br(discr.immediate(), lltrue, llfalse);
                         }
                     } else {
                         let switch_llty = bx.cx.layout_of(switch_ty).immediate_llvm_type(bx.cx);
-                        let llval = C_uint_big(switch_llty, values[0]);
+                        let llval = C_uint_big(switch_llty, value);
                         let cmp = bx.icmp(llvm::IntEQ, discr.immediate(), llval);
                         bx.cond_br(cmp, lltrue, llfalse);
                     }
                 } else {
-                    let (otherwise, targets) = targets.split_last().unwrap();
+                    let cases = targets.all_targets().len() - 1;
                     let switch = bx.switch(discr.immediate(),
-                                            llblock(self, *otherwise), values.len());
+                                           llblock(self, targets.otherwise()), cases);
                     let switch_llty = bx.cx.layout_of(switch_ty).immediate_llvm_type(bx.cx);
-                    for (&value, target) in values.iter().zip(targets) {
+                    for (value, target) in targets.iter() {
                         let llval = C_uint_big(switch_llty, value);
-                        let llbb = llblock(self, *target);
+                        let llbb = llblock(self, target);
                         bx.add_case(switch, llval, llbb)
                     }
                 }
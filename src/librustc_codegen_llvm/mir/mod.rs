@@ -29,6 +29,7 @@ use syntax_pos::{DUMMY_SP, NO_EXPANSION, BytePos, Span};
 use syntax::symbol::keywords;
 
 use std::iter;
+use std::ptr;
 
 use rustc_data_structures::bitvec::BitVector;
 use rustc_data_structures::indexed_vec::{IndexVec, Idx};
@@ -118,7 +119,8 @@ impl<'a, 'tcx> FunctionCx<'a, 'tcx> {
 
     pub fn set_debug_loc(&mut self, bx: &Builder, source_info: mir::SourceInfo) {
         let (scope, span) = self.debug_loc(source_info);
-        debuginfo::set_source_location(&self.debug_context, bx, scope, span);
+        let inlined_at = self.scopes[source_info.scope].inlined_at;
+        debuginfo::set_source_location(&self.debug_context, bx, scope, inlined_at, span);
     }
 
     pub fn debug_loc(&mut self, source_info: mir::SourceInfo) -> (DIScope, Span) {
@@ -228,7 +230,7 @@ pub fn codegen_mir<'a, 'tcx: 'a>(
         }).collect();
 
     // Compute debuginfo scopes from MIR scopes.
-    let scopes = debuginfo::create_mir_scopes(cx, mir, &debug_context);
+    let scopes = debuginfo::create_mir_scopes(cx, instance, mir, &debug_context);
     let (landing_pads, funclets) = create_funclets(mir, &bx, &cleanup_kinds, &block_bxs);
 
     let mut fx = FunctionCx {
@@ -254,16 +256,24 @@ pub fn codegen_mir<'a, 'tcx: 'a>(
 
     let memory_locals = analyze::non_ssa_locals(&fx);
 
+    // The locals holding user variables, named after the first of them.
+    let mut local_names = IndexVec::from_elem(None, &mir.local_decls);
+    for var in &mir.var_debug_info {
+        if let mir::Place::Local(local) = *var.place.base_place() {
+            local_names[local].get_or_insert(var.name);
+        }
+    }
+
     // Allocate variable and temp allocas
     fx.locals = {
         let args = arg_local_refs(&bx, &fx, &fx.scopes, &memory_locals);
 
-        let mut allocate_local = |local| {
+        let allocate_local = |local| {
             let decl = &mir.local_decls[local];
             let layout = bx.cx.layout_of(fx.monomorphize(&decl.ty));
             assert!(!layout.ty.has_erasable_regions());
 
-            if let Some(name) = decl.name {
+            if let Some(name) = local_names[local] {
                 // User variable
                 let debug_scope = fx.scopes[decl.visibility_scope];
                 let dbg = debug_scope.is_valid() && bx.sess().opts.debuginfo == FullDebugInfo;
@@ -274,17 +284,7 @@ pub fn codegen_mir<'a, 'tcx: 'a>(
                 }
 
                 debug!("alloc: {:?} ({}) -> place", local, name);
-                let place = PlaceRef::alloca(&bx, layout, &name.as_str());
-                if dbg {
-                    let (scope, span) = fx.debug_loc(mir::SourceInfo {
-                        span: decl.source_info.span,
-                        scope: decl.visibility_scope,
-                    });
-                    declare_local(&bx, &fx.debug_context, name, layout.ty, scope,
-                        VariableAccess::DirectVariable { alloca: place.llval },
-                        VariableKind::LocalVariable, span);
-                }
-                LocalRef::Place(place)
+                LocalRef::Place(PlaceRef::alloca(&bx, layout, &name.as_str()))
             } else {
                 // Temporary or return place
                 if local == mir::RETURN_PLACE && fx.fn_ty.ret.is_indirect() {
@@ -311,6 +311,8 @@ pub fn codegen_mir<'a, 'tcx: 'a>(
             .collect()
     };
 
+    declare_vars(&bx, &mut fx);
+
     // Branch to the START block, if it's not the entry block.
     if reentrant_start_block {
         bx.br(fx.blocks[mir::START_BLOCK]);
@@ -343,6 +345,109 @@ pub fn codegen_mir<'a, 'tcx: 'a>(
     }
 }
 
+/// Emits debuginfo for the user variables in `mir.var_debug_info` whose
+/// locals ended up in memory, and for the arguments which are not bound to
+/// a single variable.
+fn declare_vars<'a, 'tcx>(bx: &Builder<'a, 'tcx>, fx: &mut FunctionCx<'a, 'tcx>) {
+    if bx.sess().opts.debuginfo != FullDebugInfo {
+        return;
+    }
+
+    let mir = fx.mir;
+    let mut declared_args = BitVector::new(mir.local_decls.len());
+    for var in &mir.var_debug_info {
+        if !fx.scopes[var.source_info.scope].is_valid() {
+            continue;
+        }
+
+        let (local, elems) = var.place.iterate(|base, projections| {
+            let local = match *base {
+                mir::Place::Local(local) => local,
+                _ => span_bug!(var.source_info.span,
+                               "debuginfo of `{}` is not based on a local", var.name),
+            };
+            (local, projections.map(|proj| proj.elem).collect::<Vec<_>>())
+        });
+        let place = match fx.locals[local] {
+            LocalRef::Place(place) => place,
+            // The variable only exists as an SSA value.
+            LocalRef::Operand(_) => continue,
+        };
+
+        // Find the variable inside the local, e.g. a local which was moved
+        // into a generator is accessed through the generator's fields.
+        let mut layout = place.layout;
+        let mut ops = vec![];
+        for elem in elems {
+            match elem {
+                mir::ProjectionElem::Deref => {
+                    ops.push(unsafe { llvm::LLVMRustDIBuilderCreateOpDeref() });
+                    let pointee = layout.ty.builtin_deref(true).unwrap_or_else(|| {
+                        bug!("deref of non-pointer `{}` in debuginfo", layout.ty)
+                    });
+                    layout = bx.cx.layout_of(pointee.ty);
+                }
+                mir::ProjectionElem::Field(field, _) => {
+                    let offset = layout.fields.offset(field.index()).bytes();
+                    if offset != 0 {
+                        ops.push(unsafe { llvm::LLVMRustDIBuilderCreateOpPlusUconst() });
+                        ops.push(offset as i64);
+                    }
+                    layout = layout.field(bx.cx, field.index());
+                }
+                mir::ProjectionElem::Downcast(_, variant_index) => {
+                    layout = layout.for_variant(bx.cx, variant_index);
+                }
+                ref elem => span_bug!(var.source_info.span,
+                                      "unsupported projection {:?} in debuginfo of `{}`",
+                                      elem, var.name),
+            }
+        }
+
+        // Arguments keep using the argument scope and no line, as before.
+        let is_arg = ops.is_empty() && local.index() > 0 && local.index() <= mir.arg_count;
+        let (scope, span) = fx.debug_loc(var.source_info);
+        let inlined_at = fx.scopes[var.source_info.scope].inlined_at;
+        let (kind, span) = if is_arg {
+            declared_args.insert(local.index());
+            (VariableKind::ArgumentVariable(local.index()), DUMMY_SP)
+        } else {
+            (VariableKind::LocalVariable, span)
+        };
+        let variable_access = if ops.is_empty() {
+            VariableAccess::DirectVariable { alloca: place.llval }
+        } else {
+            VariableAccess::IndirectVariable { alloca: place.llval, address_operations: &ops }
+        };
+        declare_local(bx, &fx.debug_context, var.name, layout.ty, scope, inlined_at,
+                      variable_access, kind, span);
+    }
+
+    // Arguments matched by `_` or by a destructuring pattern still get an
+    // unnamed entry, so that the debugger sees each argument at its position.
+    let arg_scope = fx.scopes[mir::OUTERMOST_SOURCE_SCOPE];
+    if !arg_scope.is_valid() {
+        return;
+    }
+    for local in mir.args_iter() {
+        if declared_args.contains(local.index()) {
+            continue;
+        }
+        // The closure environment is described through its upvars instead.
+        if local.index() == 1 && !mir.upvar_decls.is_empty() {
+            continue;
+        }
+        let place = match fx.locals[local] {
+            LocalRef::Place(place) => place,
+            LocalRef::Operand(_) => continue,
+        };
+        declare_local(bx, &fx.debug_context, keywords::Invalid.name(), place.layout.ty,
+                      arg_scope.scope_metadata, arg_scope.inlined_at,
+                      VariableAccess::DirectVariable { alloca: place.llval },
+                      VariableKind::ArgumentVariable(local.index()), DUMMY_SP);
+    }
+}
+
 fn create_funclets<'a, 'tcx>(
     mir: &'a Mir<'tcx>,
     bx: &Builder<'a, 'tcx>,
@@ -461,23 +566,8 @@ fn arg_local_refs<'a, 'tcx>(bx: &Builder<'a, 'tcx>,
                 arg.store_fn_arg(bx, &mut llarg_idx, place.project_field(bx, i));
             }
 
-            // Now that we have one alloca that contains the aggregate value,
-            // we can create one debuginfo entry for the argument.
-            arg_scope.map(|scope| {
-                let variable_access = VariableAccess::DirectVariable {
-                    alloca: place.llval
-                };
-                declare_local(
-                    bx,
-                    &fx.debug_context,
-                    arg_decl.name.unwrap_or(keywords::Invalid.name()),
-                    arg_ty, scope,
-                    variable_access,
-                    VariableKind::ArgumentVariable(arg_index + 1),
-                    DUMMY_SP
-                );
-            });
-
+            // The debuginfo for the argument, if any, is emitted by
+            // `declare_vars` once all locals are allocated.
             return LocalRef::Place(place);
         }
 
@@ -535,26 +625,8 @@ fn arg_local_refs<'a, 'tcx>(bx: &Builder<'a, 'tcx>,
             tmp
         };
         arg_scope.map(|scope| {
-            // Is this a regular argument?
+            // Regular arguments are described by `declare_vars`.
             if arg_index > 0 || mir.upvar_decls.is_empty() {
-                // The Rust ABI passes indirect variables using a pointer and a manual copy, so we
-                // need to insert a deref here, but the C ABI uses a pointer and a copy using the
-                // byval attribute, for which LLVM always does the deref itself,
-                // so we must not add it.
-                let variable_access = VariableAccess::DirectVariable {
-                    alloca: place.llval
-                };
-
-                declare_local(
-                    bx,
-                    &fx.debug_context,
-                    arg_decl.name.unwrap_or(keywords::Invalid.name()),
-                    arg.layout.ty,
-                    scope,
-                    variable_access,
-                    VariableKind::ArgumentVariable(arg_index + 1),
-                    DUMMY_SP
-                );
                 return;
             }
 
@@ -602,6 +674,7 @@ fn arg_local_refs<'a, 'tcx>(bx: &Builder<'a, 'tcx>,
                     decl.debug_name,
                     ty,
                     scope,
+                    ptr::null_mut(),
                     variable_access,
                     VariableKind::LocalVariable,
                     DUMMY_SP
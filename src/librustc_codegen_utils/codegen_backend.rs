@@ -44,7 +44,7 @@ use rustc::dep_graph::DepGraph;
 use rustc_target::spec::Target;
 use rustc_data_structures::fx::FxHashMap;
 use rustc_mir::monomorphize::collector;
-use link::{build_link_meta, out_filename};
+use link::out_filename;
 
 pub use rustc_data_structures::sync::MetadataRef;
 
@@ -59,9 +59,13 @@ pub trait CodegenBackend {
     fn metadata_loader(&self) -> Box<dyn MetadataLoader + Sync>;
     fn provide(&self, _providers: &mut Providers);
     fn provide_extern(&self, _providers: &mut Providers);
+    /// Starts the codegen of the crate. `metadata` is the metadata of the
+    /// crate, which the driver has already encoded (and written out as an
+    /// `.rmeta` file if requested) before calling this.
     fn codegen_crate<'a, 'tcx>(
         &self,
         tcx: TyCtxt<'a, 'tcx, 'tcx>,
+        metadata: EncodedMetadata,
         rx: mpsc::Receiver<Box<dyn Any + Send>>
     ) -> Box<dyn Any>;
 
@@ -145,6 +149,7 @@ impl CodegenBackend for MetadataOnlyCodegenBackend {
     fn codegen_crate<'a, 'tcx>(
         &self,
         tcx: TyCtxt<'a, 'tcx, 'tcx>,
+        metadata: EncodedMetadata,
         _rx: mpsc::Receiver<Box<dyn Any + Send>>
     ) -> Box<dyn Any> {
         use rustc_mir::monomorphize::item::MonoItem;
@@ -181,9 +186,6 @@ impl CodegenBackend for MetadataOnlyCodegenBackend {
         }
         tcx.sess.abort_if_errors();
 
-        let link_meta = build_link_meta(tcx.crate_hash(LOCAL_CRATE));
-        let metadata = tcx.encode_metadata(&link_meta);
-
         box OngoingCodegen {
             metadata: metadata,
             metadata_version: tcx.metadata_encoding_version().to_vec(),
@@ -49,6 +49,17 @@ fn is_writeable(p: &Path) -> bool {
     }
 }
 
+pub fn filename_for_metadata(sess: &Session,
+                             crate_name: &str,
+                             outputs: &OutputFilenames) -> PathBuf {
+    let out_filename = outputs.single_output_file.clone()
+        .unwrap_or(outputs
+            .out_directory
+            .join(&format!("lib{}{}.rmeta", crate_name, sess.opts.cg.extra_filename)));
+    check_file_is_writeable(&out_filename, sess);
+    out_filename
+}
+
 pub fn build_link_meta(crate_hash: Svh) -> LinkMeta {
     let r = LinkMeta {
         crate_hash,
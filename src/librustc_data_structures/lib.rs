@@ -79,6 +79,7 @@ pub mod owning_ref;
 pub mod tiny_list;
 pub mod sorted_map;
 pub mod work_queue;
+pub mod unhash;
 
 pub struct OnDrop<F: Fn()>(pub F);
 
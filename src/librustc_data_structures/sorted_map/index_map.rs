@@ -0,0 +1,213 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A variant of `SortedMap` that preserves insertion order.
+
+use std::borrow::Borrow;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+
+use indexed_vec::{Idx, IndexVec};
+
+/// An indexed multi-map that preserves insertion order while permitting both `O(log n)` lookup of
+/// an item by key and `O(1)` lookup by index.
+///
+/// This data structure is a hybrid of an [`IndexVec`] and a [`SortedMap`]. Like `IndexVec`,
+/// `SortedIndexMultiMap` assigns a typed index to each item while preserving insertion order.
+/// Like `SortedMap`, `SortedIndexMultiMap` has efficient lookup of items by key. However, this
+/// is accomplished by sorting an array of item indices instead of the items themselves.
+///
+/// Unlike `SortedMap`, this data structure can hold multiple equivalent items at once, so the
+/// `get_by_key` method and its variants return an iterator instead of an `Option`. Equivalent
+/// items will be yielded in insertion order.
+///
+/// Unlike a general-purpose map like `BTreeSet` or `HashSet`, `SortedMap` and
+/// `SortedIndexMultiMap` require `O(n)` time to insert a single item. This is because we may need
+/// to insert into the middle of the sorted array. Users should avoid mutating this data structure
+/// in-place.
+///
+/// [`IndexVec`]: ../../indexed_vec/struct.IndexVec.html
+/// [`SortedMap`]: ../struct.SortedMap.html
+#[derive(Clone, Debug)]
+pub struct SortedIndexMultiMap<I: Idx, K, V> {
+    /// The elements of the map in insertion order.
+    items: IndexVec<I, (K, V)>,
+
+    /// Indices of the items in the set, sorted by the item's key.
+    idx_sorted_by_item_key: Vec<I>,
+}
+
+impl<I: Idx, K: Ord, V> SortedIndexMultiMap<I, K, V> {
+    #[inline]
+    pub fn new() -> Self {
+        SortedIndexMultiMap {
+            items: IndexVec::new(),
+            idx_sorted_by_item_key: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns an iterator over the items in the map in insertion order.
+    #[inline]
+    pub fn into_iter(self) -> impl DoubleEndedIterator<Item = (K, V)> {
+        self.items.into_iter()
+    }
+
+    /// Returns an iterator over the items in the map in insertion order along with their indices.
+    #[inline]
+    pub fn into_iter_enumerated(self) -> impl DoubleEndedIterator<Item = (I, (K, V))> {
+        self.items.into_iter_enumerated()
+    }
+
+    /// Returns an iterator over the items in the map in insertion order.
+    #[inline]
+    pub fn iter(&self) -> impl '_ + DoubleEndedIterator<Item = (&K, &V)> {
+        self.items.iter().map(|&(ref k, ref v)| (k, v))
+    }
+
+    /// Returns an iterator over the items in the map in insertion order along with their indices.
+    #[inline]
+    pub fn iter_enumerated(&self) -> impl '_ + DoubleEndedIterator<Item = (I, (&K, &V))> {
+        self.items.iter_enumerated().map(|(i, &(ref k, ref v))| (i, (k, v)))
+    }
+
+    /// Returns the item in the map with the given index.
+    #[inline]
+    pub fn get(&self, idx: I) -> Option<&(K, V)> {
+        self.items.get(idx)
+    }
+
+    /// Returns an iterator over the items in the map that are equal to `key`.
+    ///
+    /// If there are multiple items that are equivalent to `key`, they will be yielded in
+    /// insertion order.
+    #[inline]
+    pub fn get_by_key<'a, Q>(&'a self, key: &'a Q) -> impl 'a + Iterator<Item = &'a V>
+        where K: Borrow<Q>,
+              Q: Ord + ?Sized,
+    {
+        self.get_by_key_enumerated(key).map(|(_, v)| v)
+    }
+
+    /// Returns an iterator over the items in the map that are equal to `key` along with their
+    /// indices.
+    ///
+    /// If there are multiple items that are equivalent to `key`, they will be yielded in
+    /// insertion order.
+    pub fn get_by_key_enumerated<'a, Q>(&'a self, key: &Q)
+                                        -> impl 'a + Iterator<Item = (I, &'a V)>
+        where K: Borrow<Q>,
+              Q: Ord + ?Sized,
+    {
+        // FIXME: This should be in the standard library as `equal_range`. See rust-lang/rfcs#2184.
+        match self.binary_search_idx(key) {
+            Err(_) => self.idxs_to_items_enumerated(&[]),
+
+            Ok(idx) => {
+                let start = self.idx_sorted_by_item_key[..idx]
+                    .iter()
+                    .rposition(|&i| self.items[i].0.borrow() != key);
+                let start = start.map(|i| i + 1).unwrap_or(0);
+
+                let end = self.idx_sorted_by_item_key[idx..]
+                    .iter()
+                    .position(|&i| self.items[i].0.borrow() != key);
+                let end = end.map(|i| i + idx).unwrap_or(self.idx_sorted_by_item_key.len());
+
+                let items = &self.idx_sorted_by_item_key[start..end];
+                self.idxs_to_items_enumerated(items)
+            }
+        }
+    }
+
+    fn binary_search_idx<Q>(&self, key: &Q) -> Result<usize, usize>
+        where K: Borrow<Q>,
+              Q: Ord + ?Sized,
+    {
+        self.idx_sorted_by_item_key.binary_search_by(|&idx| self.items[idx].0.borrow().cmp(key))
+    }
+
+    fn idxs_to_items_enumerated<'a>(&'a self, idxs: &'a [I])
+                                    -> impl 'a + Iterator<Item = (I, &'a V)>
+    {
+        idxs.iter().map(move |&idx| (idx, &self.items[idx].1))
+    }
+}
+
+impl<I: Idx, K: Eq, V: Eq> Eq for SortedIndexMultiMap<I, K, V> {}
+impl<I: Idx, K: PartialEq, V: PartialEq> PartialEq for SortedIndexMultiMap<I, K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        // No need to compare the sorted index. If the items are the same, the index will be too.
+        self.items == other.items
+    }
+}
+
+impl<I: Idx, K, V> Hash for SortedIndexMultiMap<I, K, V>
+    where K: Hash,
+          V: Hash,
+{
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.items.hash(hasher)
+    }
+}
+
+impl<I: Idx, K: Ord, V> FromIterator<(K, V)> for SortedIndexMultiMap<I, K, V> {
+    fn from_iter<J>(iter: J) -> Self
+        where J: IntoIterator<Item = (K, V)>
+    {
+        let items = IndexVec::from_iter(iter);
+        let mut idx_sorted_by_item_key: Vec<_> = items.indices().collect();
+
+        // `sort_by` is stable, so insertion order is preserved for duplicate items.
+        idx_sorted_by_item_key.sort_by(|&a, &b| items[a].0.cmp(&items[b].0));
+
+        SortedIndexMultiMap { items, idx_sorted_by_item_key }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SortedIndexMultiMap;
+
+    #[test]
+    fn test_sorted_index_multi_map() {
+        let entries: Vec<_> = vec![(2, 0), (1, 0), (2, 1), (3, 0), (2, 2)];
+        let set: SortedIndexMultiMap<usize, _, _> = entries.iter().cloned().collect();
+
+        // Insertion order is preserved.
+        assert!(entries.iter().map(|&(ref k, ref v)| (k, v)).eq(set.iter()));
+
+        // Indexing
+        for (i, expect) in entries.iter().enumerate() {
+            assert_eq!(set.get(i), Some(expect));
+        }
+
+        // `get_by_key` works.
+        assert_eq!(set.get_by_key(&3).cloned().collect::<Vec<_>>(), vec![0]);
+        assert!(set.get_by_key(&4).next().is_none());
+
+        // `get_by_key` returns items in insertion order.
+        let twos: Vec<_> = set.get_by_key_enumerated(&2).collect();
+        let idxs: Vec<usize> = twos.iter().map(|&(i, _)| i).collect();
+        let values: Vec<usize> = twos.iter().map(|&(_, &v)| v).collect();
+
+        assert_eq!(idxs, vec![0, 2, 4]);
+        assert_eq!(values, vec![0, 1, 2]);
+    }
+}
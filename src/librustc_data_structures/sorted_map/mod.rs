@@ -14,6 +14,10 @@ use std::convert::From;
 use std::mem;
 use std::ops::{RangeBounds, Bound, Index, IndexMut};
 
+mod index_map;
+
+pub use self::index_map::SortedIndexMultiMap;
+
 /// `SortedMap` is a data structure with similar characteristics as BTreeMap but
 /// slightly different trade-offs: lookup, inseration, and removal are O(log(N))
 /// and elements can be iterated in order cheaply.
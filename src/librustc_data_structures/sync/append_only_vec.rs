@@ -0,0 +1,238 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+
+/// The number of buckets. Bucket `b` holds `2^b` elements, so this is enough
+/// for every index representable in a `usize`.
+const BUCKETS: usize = mem::size_of::<usize>() * 8;
+
+/// A vector which can only be appended to, through a shared reference.
+///
+/// Elements are stored in a sequence of buckets of doubling size which are
+/// never reallocated, so a reference to an element stays valid for as long
+/// as the vector itself. Pushes are serialized by a lock, but reading never
+/// takes it: readers only observe elements which were fully written before
+/// the length was published.
+///
+/// Unlike the other types in `sync`, this is thread-safe even without
+/// `parallel_queries`, as the elements are only reachable through raw pointers.
+pub struct AppendOnlyVec<T> {
+    buckets: Box<[AtomicPtr<T>]>,
+    len: AtomicUsize,
+    push_lock: Mutex<()>,
+    /// The vector owns its elements.
+    marker: PhantomData<T>,
+}
+
+unsafe impl<T: ::std::marker::Send> ::std::marker::Send for AppendOnlyVec<T> {}
+unsafe impl<T: ::std::marker::Send + ::std::marker::Sync> ::std::marker::Sync
+    for AppendOnlyVec<T> {}
+
+/// Maps an index to the bucket containing it and its offset within that bucket.
+#[inline]
+fn location(index: usize) -> (usize, usize) {
+    let biased = index + 1;
+    let bucket = BUCKETS - 1 - biased.leading_zeros() as usize;
+    (bucket, biased - (1 << bucket))
+}
+
+impl<T> AppendOnlyVec<T> {
+    pub fn new() -> Self {
+        AppendOnlyVec {
+            buckets: (0..BUCKETS).map(|_| AtomicPtr::new(ptr::null_mut())).collect::<Vec<_>>()
+                                 .into_boxed_slice(),
+            len: AtomicUsize::new(0),
+            push_lock: Mutex::new(()),
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends `value` and returns its index. The index stays valid, and keeps
+    /// referring to `value`, for the lifetime of the vector.
+    pub fn push(&self, value: T) -> usize {
+        let _lock = self.push_lock.lock().unwrap();
+
+        let index = self.len.load(Ordering::Relaxed);
+        let (bucket, offset) = location(index);
+
+        let mut data = self.buckets[bucket].load(Ordering::Relaxed);
+        if data.is_null() {
+            let mut storage = Vec::with_capacity(1 << bucket);
+            data = storage.as_mut_ptr();
+            mem::forget(storage);
+            self.buckets[bucket].store(data, Ordering::Release);
+        }
+
+        unsafe {
+            ptr::write(data.add(offset), value);
+        }
+
+        // Publish the element. Readers which observe the new length are
+        // guaranteed to also observe the write above.
+        self.len.store(index + 1, Ordering::Release);
+        index
+    }
+
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let (bucket, offset) = location(index);
+        let data = self.buckets[bucket].load(Ordering::Acquire);
+        unsafe {
+            Some(&*data.add(offset))
+        }
+    }
+
+    /// Iterates over the elements which were pushed before this call.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        (0..self.len()).map(move |i| self.get(i).unwrap())
+    }
+
+    /// Moves all elements out of the vector, leaving it empty.
+    ///
+    /// This is unsafe because the references handed out by `get` and `iter`
+    /// would dangle afterwards. The caller has to ensure none of them are alive.
+    pub unsafe fn take(&self) -> Vec<T> {
+        let _lock = self.push_lock.lock().unwrap();
+
+        let len = self.len.load(Ordering::Relaxed);
+        let mut result = Vec::with_capacity(len);
+        for i in 0..len {
+            let (bucket, offset) = location(i);
+            result.push(ptr::read(self.buckets[bucket].load(Ordering::Relaxed).add(offset)));
+        }
+
+        // The elements have been moved out, only the storage is left.
+        self.len.store(0, Ordering::Release);
+        result
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        // No references into `self` can be alive, since we own it.
+        unsafe { self.take() }
+    }
+}
+
+impl<T> Drop for AppendOnlyVec<T> {
+    fn drop(&mut self) {
+        let mut remaining = *self.len.get_mut();
+        for (bucket, data) in self.buckets.iter_mut().enumerate() {
+            let data = *data.get_mut();
+            if data.is_null() {
+                break;
+            }
+
+            let capacity = 1 << bucket;
+            let len = ::std::cmp::min(remaining, capacity);
+            remaining -= len;
+            unsafe {
+                drop(Vec::from_raw_parts(data, len, capacity));
+            }
+        }
+    }
+}
+
+impl<T> Default for AppendOnlyVec<T> {
+    fn default() -> Self {
+        AppendOnlyVec::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for AppendOnlyVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{location, AppendOnlyVec};
+    use std::rc::Rc;
+
+    #[test]
+    fn test_location() {
+        assert_eq!(location(0), (0, 0));
+        assert_eq!(location(1), (1, 0));
+        assert_eq!(location(2), (1, 1));
+        assert_eq!(location(3), (2, 0));
+        assert_eq!(location(6), (2, 3));
+        assert_eq!(location(7), (3, 0));
+    }
+
+    #[test]
+    fn test_push_and_get() {
+        let vec = AppendOnlyVec::new();
+        let mut refs = Vec::new();
+        for i in 0..100 {
+            assert_eq!(vec.push(i * 2), i);
+            refs.push(vec.get(i).unwrap() as *const usize);
+        }
+
+        assert_eq!(vec.len(), 100);
+        assert_eq!(vec.get(100), None);
+        for (i, r) in refs.into_iter().enumerate() {
+            // Earlier references are not invalidated by later pushes.
+            assert_eq!(unsafe { *r }, i * 2);
+        }
+        assert_eq!(vec.into_vec(), (0..100).map(|i| i * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_take() {
+        let vec = AppendOnlyVec::new();
+        for i in 0..10 {
+            vec.push(i);
+        }
+        assert_eq!(unsafe { vec.take() }, (0..10).collect::<Vec<_>>());
+        assert!(vec.is_empty());
+
+        // The storage is reused by later pushes.
+        assert_eq!(vec.push(42), 0);
+        assert_eq!(vec.into_vec(), vec![42]);
+    }
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<AppendOnlyVec<Vec<u32>>>();
+    }
+
+    #[test]
+    fn test_drop() {
+        let counted = Rc::new(());
+        {
+            let vec = AppendOnlyVec::new();
+            for _ in 0..10 {
+                vec.push(counted.clone());
+            }
+            assert_eq!(Rc::strong_count(&counted), 11);
+        }
+        assert_eq!(Rc::strong_count(&counted), 1);
+    }
+}
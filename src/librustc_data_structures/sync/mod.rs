@@ -30,6 +30,9 @@
 //!
 //! `rustc_erase_owner!` erases a OwningRef owner into Erased or Erased + Send + Sync
 //! depending on the value of cfg!(parallel_queries).
+//!
+//! `AppendOnlyVec` is a vector which can be pushed to through a shared reference,
+//! handing out indices which stay valid for the lifetime of the vector.
 
 use std::collections::HashMap;
 use std::hash::{Hash, BuildHasher};
@@ -41,6 +44,10 @@ use std::fmt;
 use std::ops::{Deref, DerefMut};
 use owning_ref::{Erased, OwningRef};
 
+mod append_only_vec;
+
+pub use self::append_only_vec::AppendOnlyVec;
+
 pub fn serial_join<A, B, RA, RB>(oper_a: A, oper_b: B) -> (RA, RB)
     where A: FnOnce() -> RA,
           B: FnOnce() -> RB
@@ -0,0 +1,83 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Hash maps for keys which already are strong hashes, such as `Fingerprint`
+//! and `DefPathHash`. Running those through `FxHasher` again buys nothing and
+//! is measurable on large incremental caches.
+
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasherDefault, Hasher};
+
+pub type UnhashMap<K, V> = HashMap<K, V, BuildHasherDefault<Unhasher>>;
+pub type UnhashSet<V> = HashSet<V, BuildHasherDefault<Unhasher>>;
+
+#[allow(non_snake_case)]
+pub fn UnhashMap<K: ::std::hash::Hash + Eq, V>() -> UnhashMap<K, V> {
+    HashMap::default()
+}
+
+#[allow(non_snake_case)]
+pub fn UnhashSet<V: ::std::hash::Hash + Eq>() -> UnhashSet<V> {
+    HashSet::default()
+}
+
+/// This no-op hasher expects mostly `write_u64` calls. The written values are
+/// summed up, so that a 128-bit hash written as two `u64` halves still ends
+/// up using all of its bits. Other writes, such as the discriminant of a
+/// `DepKind`, are folded in byte by byte.
+#[derive(Default, Clone, Copy)]
+pub struct Unhasher {
+    value: u64,
+}
+
+impl Hasher for Unhasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.value
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.value = self.value.rotate_left(8) ^ byte as u64;
+        }
+    }
+
+    #[inline]
+    fn write_u64(&mut self, value: u64) {
+        self.value = self.value.wrapping_add(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UnhashMap;
+
+    #[test]
+    fn test_unhash_map() {
+        let mut map = UnhashMap();
+        for i in 0..100u64 {
+            map.insert((i.wrapping_mul(0x9e3779b97f4a7c15), i), i);
+        }
+        for i in 0..100u64 {
+            assert_eq!(map.get(&(i.wrapping_mul(0x9e3779b97f4a7c15), i)), Some(&i));
+        }
+    }
+
+    #[test]
+    fn test_unhash_map_byte_keys() {
+        let mut map = UnhashMap();
+        for i in 0..100u8 {
+            map.insert((i, (i as u64) << 32), i);
+        }
+        for i in 0..100u8 {
+            assert_eq!(map.get(&(i, (i as u64) << 32)), Some(&i));
+        }
+    }
+}
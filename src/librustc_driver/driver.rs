@@ -10,17 +10,18 @@
 
 use rustc::dep_graph::DepGraph;
 use rustc::hir::{self, map as hir_map};
+use rustc::hir::def_id::LOCAL_CRATE;
 use rustc::hir::lowering::lower_crate;
 use rustc::ich::Fingerprint;
 use rustc_data_structures::stable_hasher::StableHasher;
 use rustc_mir as mir;
-use rustc::session::{CompileResult, CrateDisambiguator, Session};
+use rustc::session::{self, CompileResult, CrateDisambiguator, Session};
 use rustc::session::CompileIncomplete;
 use rustc::session::config::{self, Input, OutputFilenames, OutputType};
 use rustc::session::search_paths::PathKind;
 use rustc::lint;
 use rustc::middle::{self, reachable, resolve_lifetime, stability};
-use rustc::middle::cstore::CrateStoreDyn;
+use rustc::middle::cstore::{CrateStoreDyn, EncodedMetadata};
 use rustc::middle::privacy::AccessLevels;
 use rustc::ty::{self, AllArenas, Resolutions, TyCtxt};
 use rustc::traits;
@@ -33,6 +34,7 @@ use rustc_metadata::creader::CrateLoader;
 use rustc_metadata::cstore::{self, CStore};
 use rustc_traits;
 use rustc_codegen_utils::codegen_backend::CodegenBackend;
+use rustc_codegen_utils::link::{build_link_meta, filename_for_metadata};
 use rustc_typeck as typeck;
 use rustc_privacy;
 use rustc_plugin::registry::Registry;
@@ -58,6 +60,7 @@ use syntax::parse::{self, PResult};
 use syntax::util::node_count::NodeCounter;
 use syntax_pos::FileName;
 use syntax_ext;
+use tempfile::Builder as TempFileBuilder;
 
 use derive_registrar;
 use pretty::ReplaceBodyWithLoop;
@@ -82,11 +85,14 @@ pub fn spawn_thread_pool<F: FnOnce(config::Options) -> R + sync::Send, R: sync::
     use syntax;
     use syntax_pos;
     use rayon::{ThreadPoolBuilder, ThreadPool};
+    use rustc::session::QueryThreadTokens;
 
     let gcx_ptr = &Lock::new(0);
+    let threads = Session::query_threads_from_opts(&opts);
+    let tokens = &QueryThreadTokens::new(session::global_jobserver(), threads);
 
     let config = ThreadPoolBuilder::new()
-        .num_threads(Session::query_threads_from_opts(&opts))
+        .num_threads(threads)
         .deadlock_handler(|| unsafe { ty::query::handle_deadlock() })
         .stack_size(16 * 1024 * 1024);
 
@@ -100,12 +106,20 @@ pub fn spawn_thread_pool<F: FnOnce(config::Options) -> R + sync::Send, R: sync::
             // the thread local rustc uses. syntax_globals and syntax_pos_globals are
             // captured and set on the new threads. ty::tls::with_thread_locals sets up
             // thread local callbacks from libsyntax
+            //
+            // Each worker thread also acquires a jobserver token for the pool
+            // when it starts, so that the query threads count against the job
+            // limit shared with codegen and with the other processes of the
+            // build. The tokens are released again before codegen.
             let main_handler = move |worker: &mut FnMut()| {
+                tokens.acquire();
                 syntax::GLOBALS.set(syntax_globals, || {
                     syntax_pos::GLOBALS.set(syntax_pos_globals, || {
                         ty::tls::with_thread_locals(|| {
                             ty::tls::GCX_PTR.set(gcx_ptr, || {
-                                worker()
+                                session::QUERY_THREAD_TOKENS.set(tokens, || {
+                                    worker()
+                                })
                             })
                         })
                     })
@@ -322,7 +336,11 @@ pub fn compile_input(
                     tcx.print_debug_stats();
                 }
 
-                let ongoing_codegen = phase_4_codegen(&*codegen_backend, tcx, rx);
+                // Codegen acquires jobserver tokens for its own threads, while the
+                // query threads are idle from here on.
+                session::release_query_thread_tokens();
+
+                let ongoing_codegen = phase_4_codegen(&*codegen_backend, tcx, &outputs, rx);
 
                 if log_enabled!(::log::Level::Info) {
                     println!("Post-codegen");
@@ -348,7 +366,11 @@ pub fn compile_input(
     codegen_backend.join_codegen_and_link(ongoing_codegen, sess, &dep_graph, &outputs)?;
 
     if sess.opts.debugging_opts.perf_stats {
-        sess.print_perf_stats();
+        sess.print_perf_stats(&crate_name);
+    }
+
+    if sess.self_profiling_active() {
+        sess.finish_self_profile(&outputs.with_extension("self_profile.json"));
     }
 
     controller_entry_point!(
@@ -1212,6 +1234,8 @@ where
 
     time(sess, "loop checking", || loops::check_crate(sess, &hir_map));
 
+    mir::transform::check_mir_enable_passes(sess);
+
     let mut local_providers = ty::query::Providers::default();
     default_provide(&mut local_providers);
     codegen_backend.provide(&mut local_providers);
@@ -1289,14 +1313,18 @@ where
                  "MIR borrow checking",
                  || tcx.par_body_owners(|def_id| { tcx.mir_borrowck(def_id); }));
 
+            if sess.opts.borrowck_mode == config::BorrowckMode::Compare && sess.borrowck_stats() {
+                sess.print_borrowck_compare_stats();
+            }
+
             time(sess, "dumping chalk-like clauses", || {
                 rustc_traits::lowering::dump_program_clauses(tcx);
             });
 
             time(sess, "MIR effect checking", || {
-                for def_id in tcx.body_owners() {
+                tcx.par_body_owners(|def_id| {
                     mir::transform::check_unsafety::check_unsafety(tcx, def_id)
-                }
+                })
             });
             // Avoid overwhelming user with errors if type checking failed.
             // I'm not sure how helpful this is, to be honest, but it avoids
@@ -1316,6 +1344,21 @@ where
 
             time(sess, "lint checking", || lint::check_crate(tcx));
 
+            if sess.query_threads() > 1 && sess.err_count() == 0 &&
+               sess.opts.output_types.should_codegen() {
+                // Optimize the MIR of all the function bodies on the query
+                // threads, rather than on demand as monomorphization reaches
+                // them, once codegen has taken over the jobserver tokens.
+                time(sess, "MIR optimization", || {
+                    tcx.par_body_owners(|def_id| {
+                        let node_id = tcx.hir.as_local_node_id(def_id).unwrap();
+                        if let hir::BodyOwnerKind::Fn = tcx.hir.body_owner_kind(node_id) {
+                            tcx.optimized_mir(def_id);
+                        }
+                    })
+                });
+            }
+
             return Ok(f(tcx, analysis, rx, tcx.sess.compile_status()));
         },
     )
@@ -1326,13 +1369,20 @@ where
 pub fn phase_4_codegen<'a, 'tcx>(
     codegen_backend: &CodegenBackend,
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    outputs: &OutputFilenames,
     rx: mpsc::Receiver<Box<Any + Send>>,
 ) -> Box<Any> {
     time(tcx.sess, "resolving dependency formats", || {
         ::rustc::middle::dependency_format::calculate(tcx)
     });
 
-    let codegen = time(tcx.sess, "codegen", move || codegen_backend.codegen_crate(tcx, rx));
+    let metadata = time(tcx.sess, "metadata encoding and writing", || {
+        encode_and_write_metadata(tcx, outputs)
+    });
+
+    let codegen = time(tcx.sess, "codegen", move || {
+        codegen_backend.codegen_crate(tcx, metadata, rx)
+    });
     if tcx.sess.profile_queries() {
         profile::dump(&tcx.sess, "profile_queries".to_string())
     }
@@ -1340,6 +1390,63 @@ pub fn phase_4_codegen<'a, 'tcx>(
     codegen
 }
 
+/// Encodes the metadata of the crate and, if an `.rmeta` file was requested,
+/// writes it out before codegen starts. This lets a build system start
+/// compiling the crates depending on this one while it is being codegened.
+fn encode_and_write_metadata<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    outputs: &OutputFilenames,
+) -> EncodedMetadata {
+    let sess = tcx.sess;
+    let needs_metadata = sess.crate_types.borrow().iter().any(|ty| {
+        match *ty {
+            config::CrateTypeExecutable |
+            config::CrateTypeStaticlib |
+            config::CrateTypeCdylib => false,
+
+            config::CrateTypeRlib |
+            config::CrateTypeDylib |
+            config::CrateTypeProcMacro => true,
+        }
+    });
+    let metadata = if needs_metadata {
+        tcx.encode_metadata(&build_link_meta(tcx.crate_hash(LOCAL_CRATE)))
+    } else {
+        EncodedMetadata::new()
+    };
+
+    if sess.opts.output_types.contains_key(&OutputType::Metadata) {
+        let crate_name = tcx.crate_name(LOCAL_CRATE).as_str();
+        let out_filename = filename_for_metadata(sess, &crate_name, outputs);
+        // To avoid races with another rustc process scanning the output directory,
+        // we need to write the file somewhere else and atomically move it to its
+        // final destination, with a `fs::rename` call. In order for the rename to
+        // always succeed, the temporary file needs to be on the same filesystem,
+        // which is why we create it inside the output directory specifically.
+        let metadata_tmpdir = match TempFileBuilder::new()
+            .prefix("rmeta")
+            .tempdir_in(out_filename.parent().unwrap())
+        {
+            Ok(tmpdir) => tmpdir,
+            Err(err) => sess.fatal(&format!("couldn't create a temp dir: {}", err)),
+        };
+        let metadata_filename = metadata_tmpdir.path().join(out_filename.file_name().unwrap());
+        if let Err(e) = fs::write(&metadata_filename, &metadata.raw_data) {
+            sess.fatal(&format!("failed to write {}: {}", metadata_filename.display(), e));
+        }
+        if let Err(e) = fs::rename(&metadata_filename, &out_filename) {
+            sess.fatal(&format!("failed to write {}: {}", out_filename.display(), e));
+        }
+
+        if sess.opts.debugging_opts.emit_artifact_notifications {
+            eprintln!("{{\"artifact\":{},\"emit\":\"metadata\"}}",
+                      json::as_json(&out_filename.display().to_string()));
+        }
+    }
+
+    metadata
+}
+
 fn escape_dep_filename(filename: &FileName) -> String {
     // Apparently clang and gcc *only* escape spaces:
     // http://llvm.org/klaus/clang/commit/9d50634cfc268ecc9a7250226dd5ca0e945240d4
@@ -61,6 +61,7 @@ extern crate log;
 extern crate syntax;
 extern crate syntax_ext;
 extern crate syntax_pos;
+extern crate tempfile;
 
 use driver::CompileController;
 use pretty::{PpMode, UserIdentifiedItem};
@@ -190,7 +191,7 @@ pub fn run<F>(run_compiler: F) -> isize
                                                                None,
                                                                true,
                                                                false);
-                    let handler = errors::Handler::with_emitter(true, false, Box::new(emitter));
+                    let handler = errors::Handler::with_emitter(true, None, Box::new(emitter));
                     handler.emit(&MultiSpan::new(),
                                  "aborting due to previous error(s)",
                                  errors::Level::Fatal);
@@ -1644,7 +1645,7 @@ pub fn monitor<F: FnOnce() + Send + 'static>(f: F) {
                                                                 None,
                                                                 false,
                                                                 false));
-            let handler = errors::Handler::with_emitter(true, false, emitter);
+            let handler = errors::Handler::with_emitter(true, None, emitter);
 
             // a .span_bug or .bug call has already printed what
             // it wants to print.
@@ -88,23 +88,15 @@ impl<'a> DiagnosticBuilder<'a> {
         self.cancel();
     }
 
-    pub fn is_error(&self) -> bool {
-        match self.level {
-            Level::Bug |
-            Level::Fatal |
-            Level::PhaseFatal |
-            Level::Error |
-            Level::FailureNote => {
-                true
-            }
-
-            Level::Warning |
-            Level::Note |
-            Level::Help |
-            Level::Cancelled => {
-                false
-            }
+    /// Buffers the diagnostic in `buffered_diagnostics` instead of emitting it, so that
+    /// it can be emitted (or altered) later, e.g. with `new_diagnostic`.
+    pub fn buffer(mut self, buffered_diagnostics: &mut Vec<Diagnostic>) {
+        if self.cancelled() {
+            return;
         }
+
+        buffered_diagnostics.push(self.diagnostic.clone());
+        self.cancel();
     }
 
     /// Convenience function for internal use, clients should use one of the
@@ -205,6 +197,12 @@ impl<'a> DiagnosticBuilder<'a> {
                                                  suggestions: Vec<String>,
                                                  applicability: Applicability)
                                                  -> &mut Self);
+    forward!(pub fn multipart_suggestion_with_applicability(
+        &mut self,
+        msg: &str,
+        suggestion: Vec<(Span, String)>,
+        applicability: Applicability
+    ) -> &mut Self);
     forward!(pub fn span_suggestion_short_with_applicability(&mut self,
                                                              sp: Span,
                                                              msg: &str,
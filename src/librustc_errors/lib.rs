@@ -38,7 +38,7 @@ use rustc_data_structures::fx::FxHashSet;
 use rustc_data_structures::stable_hasher::StableHasher;
 
 use std::borrow::Cow;
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::{error, fmt};
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering::SeqCst;
@@ -293,17 +293,47 @@ fn default_track_diagnostic(_: &Diagnostic) {}
 thread_local!(pub static TRACK_DIAGNOSTICS: Cell<fn(&Diagnostic)> =
                 Cell::new(default_track_diagnostic));
 
+// The diagnostics held back by `Handler::with_deferred_emission` on this thread.
+thread_local!(static DEFERRED_DIAGNOSTICS: RefCell<Option<Vec<Diagnostic>>> =
+                RefCell::new(None));
+
+/// Restores the outer deferral of `Handler::with_deferred_emission`. If the
+/// deferred closure panics, for instance because of a fatal error, the
+/// diagnostics it held back are printed (or handed to the outer deferral)
+/// so that they are not lost.
+struct DeferredEmission<'a> {
+    handler: &'a Handler,
+    outer: Option<Option<Vec<Diagnostic>>>,
+}
+
+impl<'a> DeferredEmission<'a> {
+    fn finish(mut self) -> Vec<Diagnostic> {
+        let outer = self.outer.take().unwrap();
+        DEFERRED_DIAGNOSTICS.with(|deferred| deferred.replace(outer)).unwrap()
+    }
+}
+
+impl<'a> Drop for DeferredEmission<'a> {
+    fn drop(&mut self) {
+        if let Some(outer) = self.outer.take() {
+            let held_back = DEFERRED_DIAGNOSTICS.with(|deferred| deferred.replace(outer));
+            self.handler.emit_deferred(held_back.unwrap());
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct HandlerFlags {
     pub can_emit_warnings: bool,
-    pub treat_err_as_bug: bool,
+    /// If set to `Some(n)`, the `n`th error panics instead of being reported.
+    pub treat_err_as_bug: Option<usize>,
     pub external_macro_backtrace: bool,
 }
 
 impl Handler {
     pub fn with_tty_emitter(color_config: ColorConfig,
                             can_emit_warnings: bool,
-                            treat_err_as_bug: bool,
+                            treat_err_as_bug: Option<usize>,
                             cm: Option<Lrc<CodeMapperDyn>>)
                             -> Handler {
         Handler::with_tty_emitter_and_flags(
@@ -325,7 +355,7 @@ impl Handler {
     }
 
     pub fn with_emitter(can_emit_warnings: bool,
-                        treat_err_as_bug: bool,
+                        treat_err_as_bug: Option<usize>,
                         e: Box<Emitter + sync::Send>)
                         -> Handler {
         Handler::with_emitter_and_flags(
@@ -456,9 +486,17 @@ impl Handler {
         err.cancel();
     }
 
+    /// Whether the next error is the one `-Z treat-err-as-bug` turns into a bug.
+    fn treat_err_as_bug(&self) -> bool {
+        self.flags.treat_err_as_bug.map_or(false, |n| self.err_count() + 1 >= n)
+    }
+
     fn panic_if_treat_err_as_bug(&self) {
-        if self.flags.treat_err_as_bug {
-            panic!("encountered error with `-Z treat_err_as_bug");
+        if self.treat_err_as_bug() {
+            match self.err_count() {
+                0 => panic!("encountered error with `-Z treat_err_as_bug"),
+                count => panic!("encountered error {} with `-Z treat_err_as_bug", count + 1),
+            }
         }
     }
 
@@ -499,7 +537,7 @@ impl Handler {
         panic!(ExplicitBug);
     }
     pub fn delay_span_bug<S: Into<MultiSpan>>(&self, sp: S, msg: &str) {
-        if self.flags.treat_err_as_bug {
+        if self.treat_err_as_bug() {
             self.span_bug(sp, msg);
         }
         let mut diagnostic = Diagnostic::new(Level::Bug, msg);
@@ -527,14 +565,14 @@ impl Handler {
         DiagnosticBuilder::new(self, FailureNote, msg).emit()
     }
     pub fn fatal(&self, msg: &str) -> FatalError {
-        if self.flags.treat_err_as_bug {
+        if self.treat_err_as_bug() {
             self.bug(msg);
         }
         DiagnosticBuilder::new(self, Fatal, msg).emit();
         FatalError
     }
     pub fn err(&self, msg: &str) {
-        if self.flags.treat_err_as_bug {
+        if self.treat_err_as_bug() {
             self.bug(msg);
         }
         let mut db = DiagnosticBuilder::new(self, Error, msg);
@@ -656,6 +694,47 @@ impl Handler {
         db.cancel();
     }
 
+    /// Runs `f`, holding back the diagnostics it emits on this thread instead
+    /// of printing them, and returns them. They are still counted and
+    /// deduplicated as if they were printed. Work running in parallel uses
+    /// this to print its diagnostics in a deterministic order, with
+    /// `emit_deferred`.
+    pub fn with_deferred_emission<F: FnOnce() -> R, R>(&self, f: F) -> (R, Vec<Diagnostic>) {
+        let outer = DEFERRED_DIAGNOSTICS.with(|deferred| deferred.replace(Some(Vec::new())));
+        let deferral = DeferredEmission {
+            handler: self,
+            outer: Some(outer),
+        };
+        let r = f();
+        (r, deferral.finish())
+    }
+
+    /// Prints diagnostics returned by `with_deferred_emission`, or holds them
+    /// back again if an outer `with_deferred_emission` is running.
+    pub fn emit_deferred(&self, diagnostics: Vec<Diagnostic>) {
+        for diagnostic in diagnostics {
+            if !self.defer(&diagnostic) {
+                let mut db = DiagnosticBuilder::new_diagnostic(self, diagnostic);
+                self.emitter.borrow_mut().emit(&db);
+                db.cancel();
+            }
+        }
+    }
+
+    /// Holds back `diagnostic` if `with_deferred_emission` is running on this
+    /// thread, returning whether it did.
+    fn defer(&self, diagnostic: &Diagnostic) -> bool {
+        DEFERRED_DIAGNOSTICS.with(|deferred| {
+            match *deferred.borrow_mut() {
+                Some(ref mut deferred) => {
+                    deferred.push(diagnostic.clone());
+                    true
+                }
+                None => false,
+            }
+        })
+    }
+
     fn emit_db(&self, db: &DiagnosticBuilder) {
         let diagnostic = &**db;
 
@@ -677,7 +756,9 @@ impl Handler {
         // Only emit the diagnostic if we haven't already emitted an equivalent
         // one:
         if self.emitted_diagnostics.borrow_mut().insert(diagnostic_hash) {
-            self.emitter.borrow_mut().emit(db);
+            if !self.defer(diagnostic) {
+                self.emitter.borrow_mut().emit(db);
+            }
             if db.is_error() {
                 self.bump_err_count();
             }
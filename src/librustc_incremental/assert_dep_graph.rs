@@ -64,7 +64,7 @@ use syntax_pos::Span;
 
 pub fn assert_dep_graph<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
     tcx.dep_graph.with_ignore(|| {
-        if tcx.sess.opts.debugging_opts.dump_dep_graph {
+        if tcx.sess.opts.dump_dep_graph() {
             dump_graph(tcx);
         }
 
@@ -229,20 +229,29 @@ fn check_paths<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
 }
 
 fn dump_graph(tcx: TyCtxt) {
-    let path: String = env::var("RUST_DEP_GRAPH").unwrap_or_else(|_| format!("dep_graph"));
+    let opts = &tcx.sess.opts.debugging_opts;
+    let path = opts.dump_dep_graph_path.clone()
+                   .or_else(|| env::var("RUST_DEP_GRAPH").ok())
+                   .unwrap_or_else(|| format!("dep_graph"));
+    let filter = opts.dump_dep_graph_filter.clone()
+                     .or_else(|| env::var("RUST_DEP_GRAPH_FILTER").ok());
     let query = tcx.dep_graph.query();
 
-    let nodes = match env::var("RUST_DEP_GRAPH_FILTER") {
-        Ok(string) => {
+    let nodes = match filter {
+        Some(string) => {
             // Expect one of: "-> target", "source -> target", or "source ->".
-            let edge_filter = EdgeFilter::new(&string).unwrap_or_else(|e| {
-                bug!("invalid filter: {}", e)
-            });
+            let edge_filter = match EdgeFilter::new(&string) {
+                Ok(edge_filter) => edge_filter,
+                Err(e) => {
+                    tcx.sess.err(&format!("invalid dependency graph filter: {}", e));
+                    return;
+                }
+            };
             let sources = node_set(&query, &edge_filter.source);
             let targets = node_set(&query, &edge_filter.target);
             filter_nodes(&query, &sources, &targets)
         }
-        Err(_) => {
+        None => {
             query.nodes()
                  .into_iter()
                  .collect()
@@ -14,6 +14,7 @@ use rustc::ty::TyCtxt;
 use rustc::util::common::time;
 use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::sync::join;
+use rustc_data_structures::unhash::UnhashMap;
 use rustc_serialize::Encodable as RustcEncodable;
 use rustc_serialize::opaque::Encoder;
 use std::fs;
@@ -62,7 +63,7 @@ pub fn save_dep_graph<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
 
 pub fn save_work_product_index(sess: &Session,
                                dep_graph: &DepGraph,
-                               new_work_products: FxHashMap<WorkProductId, WorkProduct>) {
+                               new_work_products: UnhashMap<WorkProductId, WorkProduct>) {
     if sess.opts.incremental.is_none() {
         return;
     }
@@ -228,7 +229,7 @@ fn encode_dep_graph(tcx: TyCtxt,
     });
 }
 
-fn encode_work_product_index(work_products: &FxHashMap<WorkProductId, WorkProduct>,
+fn encode_work_product_index(work_products: &UnhashMap<WorkProductId, WorkProduct>,
                              encoder: &mut Encoder) {
     let serialized_products: Vec<_> = work_products
         .iter()
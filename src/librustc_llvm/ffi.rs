@@ -17,7 +17,7 @@
 use debuginfo::{DIBuilderRef, DIDescriptor, DIFile, DILexicalBlock, DISubprogram, DIType,
                 DIBasicType, DIDerivedType, DICompositeType, DIScope, DIVariable,
                 DIGlobalVariable, DIArray, DISubrange, DITemplateTypeParameter, DIEnumerator,
-                DINameSpace, DIFlags};
+                DINameSpace, DIFlags, DILocation};
 
 use libc::{c_uint, c_int, size_t, c_char};
 use libc::{c_longlong, c_ulonglong, c_void};
@@ -1610,6 +1610,11 @@ extern "C" {
                                                 Scope: DIScope,
                                                 InlinedAt: MetadataRef)
                                                 -> ValueRef;
+    pub fn LLVMRustDIBuilderCreateLocation(Line: c_uint,
+                                           Column: c_uint,
+                                           Scope: DIScope,
+                                           InlinedAt: DILocation)
+                                           -> DILocation;
     pub fn LLVMRustDIBuilderCreateOpDeref() -> i64;
     pub fn LLVMRustDIBuilderCreateOpPlusUconst() -> i64;
 
@@ -24,6 +24,7 @@ use rustc::middle::exported_symbols::{ExportedSymbol, SymbolExportLevel,
                                       metadata_symbol_name};
 use rustc::middle::lang_items;
 use rustc::mir::{self, interpret};
+use rustc::mir::visit::{self as mir_visit, Visitor as MirVisitor};
 use rustc::traits::specialization_graph;
 use rustc::ty::{self, Ty, TyCtxt, ReprOptions, SymbolName};
 use rustc::ty::codec::{self as ty_codec, TyEncoder};
@@ -883,6 +884,27 @@ impl<'a, 'b: 'a, 'tcx: 'b> IsolatedEncoder<'a, 'b, 'tcx> {
         !self.tcx.sess.opts.output_types.should_codegen()
     }
 
+    /// Whether the MIR of a non-generic function is worth encoding so that
+    /// other crates can inline it, see `-Z cross-crate-inline-threshold`.
+    fn is_small_enough_to_export(&self, def_id: DefId) -> bool {
+        let threshold = self.tcx.sess.opts.debugging_opts.cross_crate_inline_threshold;
+        if threshold == 0 || self.metadata_output_only() {
+            return false;
+        }
+
+        let metrics = self.tcx.mir_body_metrics(def_id);
+        if metrics.statements > threshold || metrics.loops > 0 {
+            return false;
+        }
+
+        // Unlike `#[inline]` functions, these do not make what they use
+        // reachable, so their MIR must not refer to other items.
+        let mir = self.tcx.optimized_mir(def_id);
+        let mut finder = ItemMentionFinder { found: !mir.promoted.is_empty() };
+        finder.visit_mir(mir);
+        !finder.found
+    }
+
     fn const_qualif(&self, mir: u8, body_id: hir::BodyId) -> ConstQualif {
         let body_owner_def_id = self.tcx.hir.body_owner_def_id(body_id);
         let ast_promotable = self.tcx.const_is_rvalue_promotable_to_static(body_owner_def_id);
@@ -946,7 +968,8 @@ impl<'a, 'b: 'a, 'tcx: 'b> IsolatedEncoder<'a, 'b, 'tcx> {
                                         !self.metadata_output_only();
                     let is_const_fn = sig.header.constness == hir::Constness::Const;
                     let always_encode_mir = self.tcx.sess.opts.debugging_opts.always_encode_mir;
-                    needs_inline || is_const_fn || always_encode_mir
+                    needs_inline || is_const_fn || always_encode_mir ||
+                        self.is_small_enough_to_export(def_id)
                 },
                 hir::ImplItemKind::Type(..) => false,
             };
@@ -1261,6 +1284,7 @@ impl<'a, 'b: 'a, 'tcx: 'b> IsolatedEncoder<'a, 'b, 'tcx> {
                     if needs_inline
                         || header.constness == hir::Constness::Const
                         || always_encode_mir
+                        || self.is_small_enough_to_export(def_id)
                     {
                         self.encode_optimized_mir(def_id)
                     } else {
@@ -1783,6 +1807,44 @@ impl<'a, 'tcx, 'v> ItemLikeVisitor<'v> for ImplVisitor<'a, 'tcx> {
     }
 }
 
+/// Looks for anything in a MIR body that would make another crate refer
+/// to an item of this one if the body was inlined there: calls, drops,
+/// statics and function items.
+struct ItemMentionFinder {
+    found: bool,
+}
+
+impl<'tcx> MirVisitor<'tcx> for ItemMentionFinder {
+    fn visit_terminator_kind(&mut self,
+                             block: mir::BasicBlock,
+                             kind: &mir::TerminatorKind<'tcx>,
+                             location: mir::Location) {
+        match *kind {
+            mir::TerminatorKind::Call { .. } |
+            mir::TerminatorKind::Drop { .. } |
+            mir::TerminatorKind::DropAndReplace { .. } => self.found = true,
+            _ => self.super_terminator_kind(block, kind, location),
+        }
+    }
+
+    fn visit_place(&mut self,
+                   place: &mir::Place<'tcx>,
+                   context: mir_visit::PlaceContext<'tcx>,
+                   location: mir::Location) {
+        if let mir::Place::Static(..) = *place {
+            self.found = true;
+        }
+        self.super_place(place, context, location);
+    }
+
+    fn visit_constant(&mut self, constant: &mir::Constant<'tcx>, location: mir::Location) {
+        if let ty::TyFnDef(..) = constant.ty.sty {
+            self.found = true;
+        }
+        self.super_constant(constant, location);
+    }
+}
+
 // NOTE(eddyb) The following comment was preserved for posterity, even
 // though it's no longer relevant as EBML (which uses nested & tagged
 // "documents") was replaced with a scheme that can't go out of bounds.
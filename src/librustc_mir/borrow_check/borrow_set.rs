@@ -16,6 +16,7 @@ use rustc::mir::{self, Location, Mir, Place};
 use rustc::ty::{Region, TyCtxt};
 use rustc::util::nodemap::{FxHashMap, FxHashSet};
 use rustc_data_structures::indexed_vec::IndexVec;
+use rustc_data_structures::sorted_map::SortedIndexMultiMap;
 use std::fmt;
 use std::hash::Hash;
 use std::ops::Index;
@@ -42,9 +43,11 @@ crate struct BorrowSet<'tcx> {
     crate region_map: FxHashMap<Region<'tcx>, FxHashSet<BorrowIndex>>,
 
     /// Map from local to all the borrows on that local
-    crate local_map: FxHashMap<mir::Local, FxHashSet<BorrowIndex>>,
+    crate local_map: SortedIndexMultiMap<LocalBorrowIndex, mir::Local, BorrowIndex>,
 }
 
+newtype_index!(LocalBorrowIndex { DEBUG_FORMAT = "LocalBorrowIndex({})" });
+
 impl<'tcx> Index<BorrowIndex> for BorrowSet<'tcx> {
     type Output = BorrowData<'tcx>;
 
@@ -83,6 +86,7 @@ impl<'tcx> fmt::Display for BorrowData<'tcx> {
     fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
         let kind = match self.kind {
             mir::BorrowKind::Shared => "",
+            mir::BorrowKind::Shallow => "shallow ",
             mir::BorrowKind::Unique => "uniq ",
             mir::BorrowKind::Mut { .. } => "mut ",
         };
@@ -105,7 +109,7 @@ impl<'tcx> BorrowSet<'tcx> {
             location_map: FxHashMap(),
             activation_map: FxHashMap(),
             region_map: FxHashMap(),
-            local_map: FxHashMap(),
+            local_borrows: Vec::new(),
             pending_activations: FxHashMap(),
         };
 
@@ -118,7 +122,7 @@ impl<'tcx> BorrowSet<'tcx> {
             location_map: visitor.location_map,
             activation_map: visitor.activation_map,
             region_map: visitor.region_map,
-            local_map: visitor.local_map,
+            local_map: visitor.local_borrows.into_iter().collect(),
         }
     }
 
@@ -137,7 +141,9 @@ struct GatherBorrows<'a, 'gcx: 'tcx, 'tcx: 'a> {
     location_map: FxHashMap<Location, BorrowIndex>,
     activation_map: FxHashMap<Location, Vec<BorrowIndex>>,
     region_map: FxHashMap<Region<'tcx>, FxHashSet<BorrowIndex>>,
-    local_map: FxHashMap<mir::Local, FxHashSet<BorrowIndex>>,
+    /// Each `(local, borrow)` pair in MIR preorder; collected into
+    /// `BorrowSet::local_map` once all borrows have been gathered.
+    local_borrows: Vec<(mir::Local, BorrowIndex)>,
 
     /// When we encounter a 2-phase borrow statement, it will always
     /// be assigning into a temporary TEMP:
@@ -178,7 +184,7 @@ impl<'a, 'gcx, 'tcx> Visitor<'tcx> for GatherBorrows<'a, 'gcx, 'tcx> {
 
             insert(&mut self.region_map, &region, idx);
             if let Some(local) = borrowed_place.root_local() {
-                insert(&mut self.local_map, &local, idx);
+                self.local_borrows.push((local, idx));
             }
         }
 
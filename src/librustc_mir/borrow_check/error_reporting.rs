@@ -9,36 +9,42 @@
 // except according to those terms.
 
 use borrow_check::WriteKind;
+use rustc::hir;
+use rustc::hir::def::Def;
+use rustc::hir::def_id::LocalDefId;
 use rustc::middle::region::ScopeTree;
-use rustc::mir::{BindingForm, BorrowKind, ClearCrossCrate, Field, Local};
-use rustc::mir::{LocalDecl, LocalKind, Location, Operand, Place};
-use rustc::mir::{ProjectionElem, Rvalue, Statement, StatementKind};
+use rustc::mir::{AggregateKind, BasicBlock, BindingForm, BorrowKind, ClearCrossCrate, Constant};
+use rustc::mir::{Field, Local, LocalDecl, LocalKind, Location, Mutability, Operand, Place};
+use rustc::mir::{ProjectionElem, Rvalue, Statement, StatementKind, TerminatorKind};
 use rustc::mir::VarBindingForm;
+use rustc::traits;
 use rustc::ty::{self, RegionKind};
+use rustc::util::nodemap::FxHashSet;
 use rustc_data_structures::indexed_vec::Idx;
 use rustc_data_structures::sync::Lrc;
-use syntax_pos::Span;
+use rustc_errors::{Applicability, DiagnosticBuilder};
+use syntax_pos::{BytePos, CompilerDesugaringKind, Span};
 
 use super::borrow_set::BorrowData;
+use super::flows::Flows;
 use super::{Context, MirBorrowckCtxt};
 use super::{InitializationRequiringAction, PrefixSet};
 
 use dataflow::move_paths::MovePathIndex;
-use dataflow::{FlowAtLocation, MovingOutStatements};
 use util::borrowck_errors::{BorrowckErrors, Origin};
 
 impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
     pub(super) fn report_use_of_moved_or_uninitialized(
         &mut self,
-        _context: Context,
+        context: Context,
         desired_action: InitializationRequiringAction,
         (place, span): (&Place<'tcx>, Span),
         mpi: MovePathIndex,
-        curr_move_out: &FlowAtLocation<MovingOutStatements<'_, 'gcx, 'tcx>>,
+        flow_state: &Flows<'cx, 'gcx, 'tcx>,
     ) {
         let mois = self.move_data.path_map[mpi]
             .iter()
-            .filter(|moi| curr_move_out.contains(moi))
+            .filter(|moi| flow_state.move_outs.contains(moi))
             .collect::<Vec<_>>();
 
         if mois.is_empty() {
@@ -58,17 +64,17 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                 Some(name) => format!("`{}`", name),
                 None => "value".to_owned(),
             };
-            self.tcx
-                .cannot_act_on_uninitialized_variable(
-                    span,
-                    desired_action.as_noun(),
-                    &self
-                        .describe_place_with_options(place, IncludingDowncast(true))
-                        .unwrap_or("_".to_owned()),
-                    Origin::Mir,
-                )
-                .span_label(span, format!("use of possibly uninitialized {}", item_msg))
-                .emit();
+            let mut err = self.tcx.cannot_act_on_uninitialized_variable(
+                span,
+                desired_action.as_noun(),
+                &self
+                    .describe_place_with_options(place, IncludingDowncast(true))
+                    .unwrap_or("_".to_owned()),
+                Origin::Mir,
+            );
+            err.span_label(span, format!("use of possibly uninitialized {}", item_msg));
+            self.note_uninitialized_fields(mpi, flow_state, &mut err);
+            err.buffer(&mut self.errors_buffer);
         } else {
             let msg = ""; //FIXME: add "partially " or "collaterally "
 
@@ -81,18 +87,47 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             );
 
             let mut is_loop_move = false;
+            let mut moved_into = None;
             for moi in &mois {
-                let move_msg = ""; //FIXME: add " (into closure)"
-                let move_span = self
-                    .mir
-                    .source_info(self.move_data.moves[**moi].source)
-                    .span;
-                if span == move_span {
+                let move_out = &self.move_data.moves[**moi];
+                let move_location = move_out.source;
+                let move_span = self.mir.source_info(move_location).span;
+                let capture = match self.move_data.move_paths[move_out.path].place {
+                    Place::Local(local) => self.mir[move_location.block]
+                        .statements
+                        .get(move_location.statement_index)
+                        .and_then(|stmt| self.find_move_into_closure(stmt, local)),
+                    _ => None,
+                };
+                let move_msg = match capture {
+                    Some((_, kind)) => format!(" into {}", kind.description()),
+                    None => String::new(),
+                };
+                if let Some((var_span, kind)) = capture {
+                    err.span_label(
+                        var_span,
+                        format!("variable moved due to use in {}", kind.description()),
+                    );
+                    if moved_into.is_none() {
+                        moved_into = Some((move_out.path, move_location, kind));
+                    }
+                }
+                // The move can only have happened in an earlier iteration if it is the use
+                // itself, or if every path to it goes through the use.
+                let in_previous_iteration = span == move_span
+                    || (self.dominators.is_reachable(move_location.block)
+                        && context.loc.dominates(move_location, &self.dominators));
+                if in_previous_iteration {
                     err.span_label(
-                        span,
-                        format!("value moved{} here in previous iteration of loop", move_msg),
+                        move_span,
+                        format!("value moved{} here, in previous iteration of loop", move_msg),
                     );
-                    is_loop_move = true;
+                    if let Some(loop_span) = self.loop_header_span(context.loc, move_location) {
+                        err.span_label(loop_span, "inside of this loop");
+                    }
+                    if span == move_span {
+                        is_loop_move = true;
+                    }
                 } else {
                     err.span_label(move_span, format!("value moved{} here", move_msg));
                 };
@@ -142,11 +177,102 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                     }
                 }
             }
+            if let Some((mpi, move_location, kind)) = moved_into {
+                self.add_moved_into_closure_hints(mpi, move_location, kind, &mut err);
+            }
+            for moi in &mois {
+                let move_out = &self.move_data.moves[**moi];
+                let place = &self.move_data.move_paths[move_out.path].place;
+                if self.suggest_for_loop_by_reference(move_out.source, place, &mut err) {
+                    break;
+                }
+            }
 
-            err.emit();
+            err.buffer(&mut self.errors_buffer);
         }
     }
 
+    /// Names the fields that are still uninitialized when some, but not all, of the fields of
+    /// the place at `mpi` were assigned.
+    fn note_uninitialized_fields(
+        &self,
+        mpi: MovePathIndex,
+        flow_state: &Flows<'cx, 'gcx, 'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+    ) {
+        let uninit_fields = match self.uninitialized_fields(mpi, flow_state) {
+            Some(uninit_fields) => uninit_fields,
+            None => return,
+        };
+        if uninit_fields.is_empty() {
+            err.help(
+                "add #![feature(partial_init)] to the crate attributes to use a value \
+                 initialized field by field",
+            );
+            return;
+        }
+        // Listing every field of a value none of whose fields were ever
+        // assigned would not say anything the error itself does not.
+        if self.move_data.move_paths[mpi].first_child.is_none() {
+            return;
+        }
+        let mut descriptions = vec![];
+        for (struct_mpi, field) in uninit_fields {
+            let place = &self.move_data.move_paths[struct_mpi].place;
+            match self.describe_place(place) {
+                Some(base) => {
+                    descriptions.push(format!("`{}.{}`", base, self.describe_field(place, field)))
+                }
+                None => return,
+            }
+        }
+        let last = descriptions.pop().unwrap();
+        if descriptions.is_empty() {
+            err.note(&format!("field {} is not initialized", last));
+        } else {
+            err.note(&format!(
+                "fields {} and {} are not initialized",
+                descriptions.join(", "),
+                last,
+            ));
+        }
+    }
+
+    /// Finds the header of the innermost `loop` containing both `use_location`
+    /// and `move_location`, and returns its span up to the loop body.
+    fn loop_header_span(&self, use_location: Location, move_location: Location) -> Option<Span> {
+        if !self.dominators.is_reachable(use_location.block) {
+            return None;
+        }
+        for bb in self.dominators.dominators(use_location.block) {
+            let terminator = self.mir[bb].terminator();
+            if let TerminatorKind::FalseUnwind { .. } = terminator.kind {
+                if self.dominators.is_dominated_by(move_location.block, bb)
+                    && self.block_reaches(move_location.block, bb)
+                {
+                    let codemap = self.tcx.sess.codemap();
+                    return Some(codemap.span_until_char(terminator.source_info.span, '{'));
+                }
+            }
+        }
+        None
+    }
+
+    /// Whether `to` can be reached from `from` in the control-flow graph.
+    fn block_reaches(&self, from: BasicBlock, to: BasicBlock) -> bool {
+        let mut visited = FxHashSet();
+        let mut stack = vec![from];
+        while let Some(bb) = stack.pop() {
+            if bb == to {
+                return true;
+            }
+            if visited.insert(bb) {
+                stack.extend(self.mir[bb].terminator().successors().cloned());
+            }
+        }
+        false
+    }
+
     pub(super) fn report_move_out_while_borrowed(
         &mut self,
         context: Context,
@@ -172,8 +298,8 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             format!("borrow of {} occurs here", borrow_msg),
         );
         err.span_label(span, format!("move out of {} occurs here", value_msg));
-        self.explain_why_borrow_contains_point(context, borrow, None, &mut err);
-        err.emit();
+        self.explain_why_borrow_contains_point(context, borrow, None, "", &mut err);
+        err.buffer(&mut self.errors_buffer);
     }
 
     pub(super) fn report_use_while_mutably_borrowed(
@@ -193,23 +319,20 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             Origin::Mir,
         );
 
-        self.explain_why_borrow_contains_point(context, borrow, None, &mut err);
+        self.explain_why_borrow_contains_point(context, borrow, None, "", &mut err);
 
-        err.emit();
+        err.buffer(&mut self.errors_buffer);
     }
 
     /// Finds the span of arguments of a closure (within `maybe_closure_span`) and its usage of
-    /// the local assigned at `location`.
+    /// the local assigned at `location`, along with how the closure captures that local.
     /// This is done by searching in statements succeeding `location`
     /// and originating from `maybe_closure_span`.
     fn find_closure_span(
         &self,
         maybe_closure_span: Span,
         location: Location,
-    ) -> Option<(Span, Span)> {
-        use rustc::hir::ExprClosure;
-        use rustc::mir::AggregateKind;
-
+    ) -> Option<(Span, Span, &'static str)> {
         let local = match self.mir[location.block]
             .statements
             .get(location.statement_index)
@@ -226,46 +349,315 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                 break;
             }
 
-            if let StatementKind::Assign(_, Rvalue::Aggregate(ref kind, ref places)) = stmt.kind {
-                if let AggregateKind::Closure(def_id, _) = **kind {
-                    debug!("find_closure_span: found closure {:?}", places);
+            if let Some(capture) = self.find_closure_capture(stmt, local) {
+                return Some(capture);
+            }
+        }
 
-                    return if let Some(node_id) = self.tcx.hir.as_local_node_id(def_id) {
-                        let args_span = if let ExprClosure(_, _, _, span, _) =
-                            self.tcx.hir.expect_expr(node_id).node
-                        {
-                            span
-                        } else {
-                            return None;
-                        };
+        None
+    }
 
-                        self.tcx
-                            .with_freevars(node_id, |freevars| {
-                                for (v, place) in freevars.iter().zip(places) {
-                                    match *place {
-                                        Operand::Copy(Place::Local(l))
-                                        | Operand::Move(Place::Local(l)) if local == l =>
-                                        {
-                                            debug!(
-                                                "find_closure_span: found captured local {:?}",
-                                                l
-                                            );
-                                            return Some(v.span);
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                                None
-                            })
-                            .map(|var_span| (args_span, var_span))
-                    } else {
-                        None
-                    };
+    /// If `stmt` builds a closure or an async block that captures `local`, returns the span of
+    /// the captured variable's use in it, and which of the two it is.
+    fn find_move_into_closure(
+        &self,
+        stmt: &Statement<'tcx>,
+        local: Local,
+    ) -> Option<(Span, MovedInto)> {
+        if let Some((_, var_span, _)) = self.find_closure_capture(stmt, local) {
+            let kind = if self.is_spawned_closure(stmt) {
+                MovedInto::SpawnedClosure
+            } else {
+                MovedInto::Closure
+            };
+            return Some((var_span, kind));
+        }
+
+        let (def_id, places) = match stmt.kind {
+            StatementKind::Assign(_, Rvalue::Aggregate(ref kind, ref places)) => match **kind {
+                AggregateKind::Generator(def_id, _, _) => (def_id, places),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let node_id = self.tcx.hir.as_local_node_id(def_id)?;
+
+        // An async block is lowered to a generator passed to
+        // `std::future::from_generator`.
+        let parent = self.tcx.hir.get_parent_node(node_id);
+        match self.tcx.hir.find(parent) {
+            Some(hir::map::NodeExpr(&hir::Expr { node: hir::ExprCall(ref callee, _), .. }))
+                if callee.span.is_compiler_desugaring(CompilerDesugaringKind::Async) => {}
+            _ => return None,
+        }
+
+        self.tcx.with_freevars(node_id, |freevars| {
+            freevars
+                .iter()
+                .zip(places)
+                .filter_map(|(v, place)| match *place {
+                    Operand::Copy(Place::Local(l)) | Operand::Move(Place::Local(l))
+                        if l == local =>
+                    {
+                        Some((v.span, MovedInto::AsyncBlock))
+                    }
+                    _ => None,
+                })
+                .next()
+        })
+    }
+
+    /// Whether `stmt` builds a closure that is passed straight to a function or method named
+    /// `spawn`, such as `std::thread::spawn`.
+    fn is_spawned_closure(&self, stmt: &Statement<'tcx>) -> bool {
+        let def_id = match stmt.kind {
+            StatementKind::Assign(_, Rvalue::Aggregate(ref kind, _)) => match **kind {
+                AggregateKind::Closure(def_id, _) => def_id,
+                _ => return false,
+            },
+            _ => return false,
+        };
+        let node_id = match self.tcx.hir.as_local_node_id(def_id) {
+            Some(node_id) => node_id,
+            None => return false,
+        };
+        let parent = self.tcx.hir.get_parent_node(node_id);
+        let callee_name = match self.tcx.hir.find(parent) {
+            Some(hir::map::NodeExpr(expr)) => match expr.node {
+                hir::ExprCall(ref callee, _) => match callee.node {
+                    hir::ExprPath(hir::QPath::Resolved(_, ref path)) => {
+                        path.segments.last().map(|segment| segment.ident.name)
+                    }
+                    hir::ExprPath(hir::QPath::TypeRelative(_, ref segment)) => {
+                        Some(segment.ident.name)
+                    }
+                    _ => None,
+                },
+                hir::ExprMethodCall(ref segment, ..) => Some(segment.ident.name),
+                _ => None,
+            },
+            _ => None,
+        };
+        callee_name.map_or(false, |name| name == "spawn")
+    }
+
+    /// Suggests ways to keep using a named variable after it was moved into an async block or
+    /// a spawned thread: cloning it beforehand and, for threads, borrowing it from a scoped
+    /// thread instead.
+    fn add_moved_into_closure_hints(
+        &self,
+        mpi: MovePathIndex,
+        move_location: Location,
+        kind: MovedInto,
+        err: &mut DiagnosticBuilder<'_>,
+    ) {
+        if kind == MovedInto::Closure {
+            return;
+        }
+        let place = &self.move_data.move_paths[mpi].place;
+        let name = match self.describe_place(place) {
+            Some(name) => name,
+            None => return,
+        };
+        if kind == MovedInto::AsyncBlock {
+            err.note(&format!("`{}` is moved into this async block", name));
+        }
+
+        let ty = place.ty(self.mir, self.tcx).to_ty(self.tcx);
+        let span = self.mir.source_info(move_location).span;
+        let implements_clone = match self.tcx.lang_items().clone_trait() {
+            Some(clone_trait) => traits::type_known_to_meet_bound(
+                self.infcx, self.param_env, ty, clone_trait, span,
+            ),
+            None => false,
+        };
+        if implements_clone {
+            err.help(&format!(
+                "consider cloning `{}` before the {} and moving the clone into it instead",
+                name,
+                kind.description(),
+            ));
+        }
+        if kind == MovedInto::SpawnedClosure {
+            err.help(&format!(
+                "if the thread does not need to outlive this function, a scoped thread can \
+                 borrow `{}` instead of taking ownership of it",
+                name,
+            ));
+        }
+    }
+
+    /// If the move at `location` hands `place` to the `IntoIterator::into_iter` call of a
+    /// `for` loop, suggests iterating over a reference to it instead. Returns whether a
+    /// suggestion was made.
+    fn suggest_for_loop_by_reference(
+        &self,
+        location: Location,
+        place: &Place<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+    ) -> bool {
+        let local = match *place {
+            Place::Local(local) if self.mir.local_decls[local].is_user_variable.is_some() => local,
+            _ => return false,
+        };
+        // The loop head is moved into a temporary that is passed to `into_iter`.
+        let block = &self.mir[location.block];
+        let (temp, move_span) = match block.statements.get(location.statement_index) {
+            Some(&Statement {
+                kind: StatementKind::Assign(
+                    Place::Local(temp),
+                    Rvalue::Use(Operand::Move(Place::Local(moved))),
+                ),
+                source_info,
+            }) if moved == local => (temp, source_info.span),
+            _ => return false,
+        };
+        let terminator = block.terminator();
+        let into_iter_trait = match terminator.kind {
+            TerminatorKind::Call {
+                func: Operand::Constant(box Constant { ty, .. }),
+                ref args,
+                ..
+            } if args.first() == Some(&Operand::Move(Place::Local(temp))) => match ty.sty {
+                ty::TyFnDef(def_id, _) if self.tcx.item_name(def_id) == "into_iter" => {
+                    self.tcx.trait_of_item(def_id)
                 }
+                _ => None,
+            },
+            _ => None,
+        };
+        let into_iter_trait = match into_iter_trait {
+            Some(trait_def_id) if self.tcx.item_name(trait_def_id) == "IntoIterator" => {
+                trait_def_id
             }
+            _ => return false,
+        };
+        // The call a `for` loop is lowered to has the span of the loop head;
+        // a call written out by hand spans more than its argument.
+        if terminator.source_info.span != move_span {
+            return false;
         }
+        let snippet = match self.tcx.sess.codemap().span_to_snippet(move_span) {
+            Ok(snippet) => snippet,
+            Err(_) => return false,
+        };
 
-        None
+        let local_decl = &self.mir.local_decls[local];
+        let region = self.tcx.types.re_erased;
+        let implements_into_iter = |ty| {
+            traits::type_known_to_meet_bound(
+                self.infcx, self.param_env, ty, into_iter_trait, move_span,
+            )
+        };
+        let mut suggestions = vec![];
+        if implements_into_iter(self.tcx.mk_imm_ref(region, local_decl.ty)) {
+            suggestions.push(format!("&{}", snippet));
+        }
+        if local_decl.mutability == Mutability::Mut
+            && implements_into_iter(self.tcx.mk_mut_ref(region, local_decl.ty))
+        {
+            suggestions.push(format!("&mut {}", snippet));
+        }
+        if suggestions.is_empty() {
+            return false;
+        }
+        err.span_suggestions_with_applicability(
+            move_span,
+            &format!("consider iterating over a reference to `{}` instead", snippet),
+            suggestions,
+            Applicability::MaybeIncorrect,
+        );
+        true
+    }
+
+    /// If `stmt` builds a closure capturing `local`, returns the span of the closure's
+    /// arguments, the span of the captured variable's use in the closure, and a
+    /// description of how it is captured.
+    fn find_closure_capture(
+        &self,
+        stmt: &Statement<'tcx>,
+        local: Local,
+    ) -> Option<(Span, Span, &'static str)> {
+        use rustc::hir::ExprClosure;
+
+        let (def_id, places) = match stmt.kind {
+            StatementKind::Assign(_, Rvalue::Aggregate(ref kind, ref places)) => match **kind {
+                AggregateKind::Closure(def_id, _) => (def_id, places),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        debug!("find_closure_capture: found closure {:?}", places);
+
+        let node_id = self.tcx.hir.as_local_node_id(def_id)?;
+        let args_span = match self.tcx.hir.expect_expr(node_id).node {
+            ExprClosure(_, _, _, span, _) => span,
+            _ => return None,
+        };
+        let tables = self.tcx.typeck_tables_of(def_id);
+
+        self.tcx.with_freevars(node_id, |freevars| {
+            for (v, place) in freevars.iter().zip(places) {
+                match *place {
+                    Operand::Copy(Place::Local(l)) | Operand::Move(Place::Local(l))
+                        if local == l =>
+                    {
+                        debug!("find_closure_capture: found captured local {:?}", l);
+                        let capture = tables.upvar_capture(ty::UpvarId {
+                            var_id: self.tcx.hir.node_to_hir_id(v.var_id()),
+                            closure_expr_id: LocalDefId::from_def_id(def_id),
+                        });
+                        let capture_desc = match capture {
+                            ty::UpvarCapture::ByValue => "value",
+                            ty::UpvarCapture::ByRef(borrow) => match borrow.kind {
+                                ty::BorrowKind::ImmBorrow => "shared reference",
+                                ty::BorrowKind::UniqueImmBorrow => "unique reference",
+                                ty::BorrowKind::MutBorrow => "mutable reference",
+                            },
+                        };
+                        return Some((args_span, v.span, capture_desc));
+                    }
+                    _ => {}
+                }
+            }
+            None
+        })
+    }
+
+    /// If `local` holds a closure that captures a variable by mutable (or
+    /// unique) reference, returns the name of that variable and the span of
+    /// its use in the closure: this capture is why calling the closure
+    /// requires the closure itself to be borrowed mutably.
+    pub(super) fn find_mutable_closure_capture(&self, local: Local) -> Option<(String, Span)> {
+        let def_id = match self.mir.local_decls[local].ty.sty {
+            ty::TyClosure(def_id, _) => def_id,
+            _ => return None,
+        };
+        let node_id = self.tcx.hir.as_local_node_id(def_id)?;
+        let tables = self.tcx.typeck_tables_of(def_id);
+
+        self.tcx.with_freevars(node_id, |freevars| {
+            freevars
+                .iter()
+                .filter_map(|v| {
+                    let capture = tables.upvar_capture(ty::UpvarId {
+                        var_id: self.tcx.hir.node_to_hir_id(v.var_id()),
+                        closure_expr_id: LocalDefId::from_def_id(def_id),
+                    });
+                    match capture {
+                        ty::UpvarCapture::ByRef(ty::UpvarBorrow {
+                            kind: ty::BorrowKind::MutBorrow,
+                            ..
+                        })
+                        | ty::UpvarCapture::ByRef(ty::UpvarBorrow {
+                            kind: ty::BorrowKind::UniqueImmBorrow,
+                            ..
+                        }) => Some((self.tcx.hir.name(v.var_id()).to_string(), v.span)),
+                        _ => None,
+                    }
+                })
+                .next()
+        })
     }
 
     pub(super) fn report_conflicting_borrow(
@@ -278,16 +670,59 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         let issued_span = self.retrieve_borrow_span(issued_borrow);
 
         let new_closure_span = self.find_closure_span(span, context.loc);
-        let span = new_closure_span.map(|(args, _)| args).unwrap_or(span);
+        let span = new_closure_span.map(|(args, _, _)| args).unwrap_or(span);
         let old_closure_span = self.find_closure_span(issued_span, issued_borrow.reserve_location);
         let issued_span = old_closure_span
-            .map(|(args, _)| args)
+            .map(|(args, _, _)| args)
             .unwrap_or(issued_span);
 
         let desc_place = self.describe_place(place).unwrap_or("_".to_owned());
         let tcx = self.tcx;
 
-        // FIXME: supply non-"" `opt_via` when appropriate
+        if issued_borrow.kind == BorrowKind::Shallow {
+            // Fake borrows of the places inspected by a match are only
+            // conflicting with mutable borrows made by its guards.
+            let mut err = tcx.cannot_mutate_in_match_guard(
+                span,
+                issued_span,
+                &desc_place,
+                "mutably borrow",
+                Origin::Mir,
+            );
+            if let Some((_, var_span, capture)) = new_closure_span {
+                err.span_label(
+                    var_span,
+                    format!(
+                        "borrow occurs due to use of `{}` in closure (captured by {})",
+                        desc_place, capture
+                    ),
+                );
+            }
+            err.buffer(&mut self.errors_buffer);
+            return;
+        }
+
+        // When two different fields of the same union are borrowed, describe
+        // the conflict in terms of the union itself, as the AST borrowck does.
+        let union_conflict = self.describe_union_conflict(place, &issued_borrow.borrowed_place);
+        let (desc_conflict, msg_new, msg_old, noun_old) = match union_conflict {
+            Some((ref union_desc, ref new_desc, ref old_desc, _)) => (
+                union_desc.clone(),
+                format!(" (via `{}`)", new_desc),
+                format!(" (via `{}`)", old_desc),
+                format!("`{}`", union_desc),
+            ),
+            None => {
+                // Name the previously borrowed place too when it isn't the
+                // place being borrowed now, e.g. `x` when borrowing `x.a`.
+                let noun_old = match self.describe_place(&issued_borrow.borrowed_place) {
+                    Some(ref old_desc) if *old_desc != desc_place => format!("`{}`", old_desc),
+                    _ => "it".to_owned(),
+                };
+                (desc_place.clone(), String::new(), String::new(), noun_old)
+            }
+        };
+
         let mut err = match (
             gen_borrow_kind,
             "immutable",
@@ -300,13 +735,13 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             | (BorrowKind::Mut { .. }, _, lft, BorrowKind::Shared, rgt, _) => tcx
                 .cannot_reborrow_already_borrowed(
                     span,
-                    &desc_place,
-                    "",
+                    &desc_conflict,
+                    &msg_new,
                     lft,
                     issued_span,
-                    "it",
+                    &noun_old,
                     rgt,
-                    "",
+                    &msg_old,
                     None,
                     Origin::Mir,
                 ),
@@ -314,10 +749,10 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             (BorrowKind::Mut { .. }, _, _, BorrowKind::Mut { .. }, _, _) => tcx
                 .cannot_mutably_borrow_multiply(
                     span,
-                    &desc_place,
-                    "",
+                    &desc_conflict,
+                    &msg_new,
                     issued_span,
-                    "",
+                    &msg_old,
                     None,
                     Origin::Mir,
                 ),
@@ -333,11 +768,11 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
 
             (BorrowKind::Unique, _, _, _, _, _) => tcx.cannot_uniquely_borrow_by_one_closure(
                 span,
-                &desc_place,
-                "",
+                &desc_conflict,
+                &msg_new,
                 issued_span,
-                "it",
-                "",
+                &noun_old,
+                &msg_old,
                 None,
                 Origin::Mir,
             ),
@@ -345,11 +780,11 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             (BorrowKind::Shared, lft, _, BorrowKind::Unique, _, _) => tcx
                 .cannot_reborrow_already_uniquely_borrowed(
                     span,
-                    &desc_place,
-                    "",
+                    &desc_conflict,
+                    &msg_new,
                     lft,
                     issued_span,
-                    "",
+                    &msg_old,
                     None,
                     Origin::Mir,
                 ),
@@ -357,41 +792,187 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             (BorrowKind::Mut { .. }, _, lft, BorrowKind::Unique, _, _) => tcx
                 .cannot_reborrow_already_uniquely_borrowed(
                     span,
-                    &desc_place,
-                    "",
+                    &desc_conflict,
+                    &msg_new,
                     lft,
                     issued_span,
-                    "",
+                    &msg_old,
                     None,
                     Origin::Mir,
                 ),
 
-            (BorrowKind::Shared, _, _, BorrowKind::Shared, _, _) => unreachable!(),
+            (BorrowKind::Shallow, _, _, _, _, _)
+            | (_, _, _, BorrowKind::Shallow, _, _)
+            | (BorrowKind::Shared, _, _, BorrowKind::Shared, _, _) => unreachable!(),
         };
 
-        if let Some((_, var_span)) = old_closure_span {
+        if let Some((_, var_span, capture)) = old_closure_span {
             let place = &issued_borrow.borrowed_place;
             let desc_place = self.describe_place(place).unwrap_or("_".to_owned());
 
             err.span_label(
                 var_span,
                 format!(
-                    "previous borrow occurs due to use of `{}` in closure",
-                    desc_place
+                    "previous borrow occurs due to use of `{}` in closure (captured by {})",
+                    desc_place, capture
                 ),
             );
         }
 
-        if let Some((_, var_span)) = new_closure_span {
+        if let Some((_, var_span, capture)) = new_closure_span {
             err.span_label(
                 var_span,
-                format!("borrow occurs due to use of `{}` in closure", desc_place),
+                format!(
+                    "borrow occurs due to use of `{}` in closure (captured by {})",
+                    desc_place, capture
+                ),
             );
         }
 
-        self.explain_why_borrow_contains_point(context, issued_borrow, None, &mut err);
+        if let Some((_, new_desc, old_desc, union_ty)) = union_conflict {
+            err.note(&format!(
+                "`{}` is a field of the union `{}`, so it overlaps the field `{}`",
+                new_desc, union_ty, old_desc
+            ));
+        }
+
+        // The labels already call the borrows "first" and "second" when they
+        // are of the same kind.
+        let borrow_desc = match (gen_borrow_kind, issued_borrow.kind) {
+            (BorrowKind::Mut { .. }, BorrowKind::Mut { .. })
+            | (BorrowKind::Unique, BorrowKind::Unique) => "first ",
+            _ => "",
+        };
+        self.explain_why_borrow_contains_point(context, issued_borrow, None, borrow_desc, &mut err);
 
-        err.emit();
+        self.suggest_binding_for_argument(span, issued_borrow, &mut err);
+
+        err.buffer(&mut self.errors_buffer);
+    }
+
+    /// When the conflicting borrow `span` occurs in an argument of a method
+    /// call whose receiver is `issued_borrow`, e.g. `v.push(v.pop().unwrap())`,
+    /// suggests computing the argument in a `let` before the statement.
+    fn suggest_binding_for_argument(
+        &self,
+        span: Span,
+        issued_borrow: &BorrowData<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+    ) {
+        let receiver = Operand::Move(issued_borrow.assigned_place.clone());
+        let call = self.mir.basic_blocks().iter().filter_map(|data| {
+            let terminator = data.terminator();
+            match terminator.kind {
+                TerminatorKind::Call { ref args, .. } if args.first() == Some(&receiver) => {
+                    Some((terminator.source_info.span, &args[1..]))
+                }
+                _ => None,
+            }
+        }).next();
+        let (call_span, args) = match call {
+            Some(call) => call,
+            None => return,
+        };
+
+        // Hoisting an argument that is itself a reference, like `&mut v`,
+        // would only move the conflict.
+        let argument = args.iter().filter_map(|arg| match *arg {
+            Operand::Move(Place::Local(temp)) | Operand::Copy(Place::Local(temp))
+                if self.mir.local_kind(temp) == LocalKind::Temp =>
+            {
+                let decl = &self.mir.local_decls[temp];
+                if decl.source_info.span.contains(span) && !decl.ty.is_region_ptr() {
+                    Some(decl.source_info.span)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }).next();
+        let arg_span = match argument {
+            Some(arg_span) if arg_span.ctxt() == call_span.ctxt() => arg_span,
+            _ => return,
+        };
+
+        // Insert the binding at the start of the statement containing the
+        // call, which must begin the line it is on.
+        let codemap = self.tcx.sess.codemap();
+        let (line_prefix, arg_snippet) = match (
+            codemap.span_to_prev_source(call_span),
+            codemap.span_to_snippet(arg_span),
+        ) {
+            (Ok(source), Ok(snippet)) => {
+                (source.rsplit('\n').next().unwrap_or("").to_owned(), snippet)
+            }
+            _ => return,
+        };
+        let statement = line_prefix.trim_left();
+        if !statement.is_empty() && !statement.starts_with("let ") {
+            return;
+        }
+        let indent = &line_prefix[..line_prefix.len() - statement.len()];
+        let statement_start = call_span.lo() - BytePos(statement.len() as u32);
+        err.multipart_suggestion_with_applicability(
+            "try computing this argument before the call",
+            vec![
+                (
+                    call_span.with_lo(statement_start).with_hi(statement_start),
+                    format!("let value = {};\n{}", arg_snippet, indent),
+                ),
+                (arg_span, "value".to_owned()),
+            ],
+            Applicability::MaybeIncorrect,
+        );
+    }
+
+    /// If `new_place` and `old_place` access different fields of the same
+    /// union, returns descriptions of the union, of both places and the name
+    /// of the union type.
+    fn describe_union_conflict(
+        &self,
+        new_place: &Place<'tcx>,
+        old_place: &Place<'tcx>,
+    ) -> Option<(String, String, String, String)> {
+        if new_place == old_place {
+            return None;
+        }
+
+        let (union_place, new_field) = self.union_field_access(new_place, None)?;
+        let (_, old_field) = self.union_field_access(old_place, Some(union_place))?;
+        if old_field == new_field {
+            return None;
+        }
+
+        let describe =
+            |place: &Place<'tcx>| self.describe_place(place).unwrap_or("_".to_owned());
+        let union_ty = union_place.ty(self.mir, self.tcx).to_ty(self.tcx);
+        Some((
+            describe(union_place),
+            describe(new_place),
+            describe(old_place),
+            union_ty.to_string(),
+        ))
+    }
+
+    /// Finds the innermost access to a field of a union in `place`, only
+    /// looking at accesses to `union_place` if it is given.
+    fn union_field_access<'p>(
+        &self,
+        mut place: &'p Place<'tcx>,
+        union_place: Option<&Place<'tcx>>,
+    ) -> Option<(&'p Place<'tcx>, Field)> {
+        while let Place::Projection(ref proj) = *place {
+            if let ProjectionElem::Field(field, _) = proj.elem {
+                let base_ty = proj.base.ty(self.mir, self.tcx).to_ty(self.tcx);
+                let is_union = base_ty.ty_adt_def().map_or(false, |def| def.is_union());
+                if is_union && union_place.map_or(true, |union_place| proj.base == *union_place)
+                {
+                    return Some((&proj.base, field));
+                }
+            }
+            place = &proj.base;
+        }
+        None
     }
 
     pub(super) fn report_borrowed_value_does_not_live_long_enough(
@@ -428,6 +1009,11 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         self.access_place_error_reported
             .insert((root_place.clone(), borrow_span));
 
+        if self.is_place_thread_local(root_place) {
+            self.report_thread_local_value_does_not_live_long_enough(drop_span, borrow_span);
+            return;
+        }
+
         match (borrow.region, &self.describe_place(&borrow.borrowed_place)) {
             (RegionKind::ReScope(_), Some(name)) => {
                 self.report_scoped_local_value_does_not_live_long_enough(
@@ -494,6 +1080,22 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         }
     }
 
+    fn report_thread_local_value_does_not_live_long_enough(
+        &mut self,
+        drop_span: Span,
+        borrow_span: Span,
+    ) {
+        let mut err = self
+            .tcx
+            .thread_local_value_does_not_live_long_enough(borrow_span, Origin::Mir);
+        err.span_label(
+            borrow_span,
+            "thread-local variables cannot be borrowed beyond the end of the function",
+        );
+        err.span_label(drop_span, "end of enclosing function is here");
+        err.buffer(&mut self.errors_buffer);
+    }
+
     fn report_scoped_local_value_does_not_live_long_enough(
         &mut self,
         context: Context,
@@ -512,8 +1114,8 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             drop_span,
             format!("`{}` dropped here while still borrowed", name),
         );
-        self.explain_why_borrow_contains_point(context, borrow, None, &mut err);
-        err.emit();
+        self.explain_why_borrow_contains_point(context, borrow, None, "", &mut err);
+        err.buffer(&mut self.errors_buffer);
     }
 
     fn report_scoped_temporary_value_does_not_live_long_enough(
@@ -534,8 +1136,8 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             "temporary value dropped here while still borrowed",
         );
         err.note("consider using a `let` binding to increase its lifetime");
-        self.explain_why_borrow_contains_point(context, borrow, None, &mut err);
-        err.emit();
+        self.explain_why_borrow_contains_point(context, borrow, None, "", &mut err);
+        err.buffer(&mut self.errors_buffer);
     }
 
     fn report_unscoped_local_value_does_not_live_long_enough(
@@ -562,8 +1164,65 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         err.span_label(borrow_span, "borrowed value does not live long enough");
         err.span_label(drop_span, "borrowed value only lives until here");
 
-        self.explain_why_borrow_contains_point(context, borrow, kind_place, &mut err);
-        err.emit();
+        self.explain_why_borrow_contains_point(context, borrow, kind_place, "", &mut err);
+
+        // A borrow of a local that has to outlive the function is most likely
+        // being returned.
+        let borrow_region_vid = self.nonlexical_regioncx.to_region_vid(borrow.region);
+        if self.nonlexical_regioncx.to_error_region(borrow_region_vid).is_some() {
+            self.suggest_returning_owned_value(borrow_span, &mut err);
+        }
+        err.buffer(&mut self.errors_buffer);
+    }
+
+    /// Suggests returning an owned `String` or `Vec<T>` instead of the
+    /// borrow at `borrow_span` when the function returns `&str` or `&[T]`.
+    fn suggest_returning_owned_value(&self, borrow_span: Span, err: &mut DiagnosticBuilder<'_>) {
+        let fn_decl = match self.tcx.hir.as_local_node_id(self.mir_def_id) {
+            Some(node_id) => self.tcx.hir.fn_decl(node_id),
+            None => None,
+        };
+        let return_ty = match fn_decl {
+            Some(hir::FnDecl { output: hir::FunctionRetTy::Return(ref ty), .. }) => ty.clone(),
+            _ => return,
+        };
+        let referent_ty = match return_ty.node {
+            hir::TyRptr(_, hir::MutTy { ref ty, mutbl: hir::MutImmutable }) => ty,
+            _ => return,
+        };
+
+        let codemap = self.tcx.sess.codemap();
+        let (owned_ty, method) = match referent_ty.node {
+            hir::TyPath(hir::QPath::Resolved(None, ref path))
+                if path.def == Def::PrimTy(hir::TyStr) =>
+            {
+                ("String".to_owned(), "to_owned")
+            }
+            hir::TySlice(ref elem_ty) => match codemap.span_to_snippet(elem_ty.span) {
+                Ok(elem_ty) => (format!("Vec<{}>", elem_ty), "to_vec"),
+                Err(_) => return,
+            },
+            _ => return,
+        };
+        // Only `&place` can be turned into `place.to_owned()` without
+        // changing what the method is called on.
+        let borrowed = match codemap.span_to_snippet(borrow_span) {
+            Ok(ref snippet) if snippet.starts_with('&') && !snippet.starts_with("&mut ") => {
+                snippet[1..].trim_left().to_owned()
+            }
+            _ => return,
+        };
+        if borrowed.starts_with('*') {
+            return;
+        }
+        err.multipart_suggestion_with_applicability(
+            &format!("consider returning an owned `{}` instead", owned_ty),
+            vec![
+                (return_ty.span, owned_ty),
+                (borrow_span, format!("{}.{}()", borrowed, method)),
+            ],
+            Applicability::MaybeIncorrect,
+        );
     }
 
     fn report_unscoped_temporary_value_does_not_live_long_enough(
@@ -588,8 +1247,15 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         err.span_label(proper_span, "temporary value does not live long enough");
         err.span_label(drop_span, "temporary value only lives until here");
 
-        self.explain_why_borrow_contains_point(context, borrow, None, &mut err);
-        err.emit();
+        self.explain_why_borrow_contains_point(context, borrow, None, "", &mut err);
+
+        // Unless the borrow has to outlive the function, binding the temporary
+        // to a local makes it live until the end of the enclosing block.
+        let borrow_region_vid = self.nonlexical_regioncx.to_region_vid(borrow.region);
+        if self.nonlexical_regioncx.to_error_region(borrow_region_vid).is_none() {
+            err.note("consider using a `let` binding to create a longer lived value");
+        }
+        err.buffer(&mut self.errors_buffer);
     }
 
     pub(super) fn report_illegal_mutation_of_borrowed(
@@ -599,6 +1265,17 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         loan: &BorrowData<'tcx>,
     ) {
         let tcx = self.tcx;
+        if loan.kind == BorrowKind::Shallow {
+            tcx.cannot_mutate_in_match_guard(
+                span,
+                self.retrieve_borrow_span(loan),
+                &self.describe_place(place).unwrap_or("_".to_owned()),
+                "assign",
+                Origin::Mir,
+            ).buffer(&mut self.errors_buffer);
+            return;
+        }
+
         let mut err = tcx.cannot_assign_to_borrowed(
             span,
             self.retrieve_borrow_span(loan),
@@ -606,9 +1283,9 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             Origin::Mir,
         );
 
-        self.explain_why_borrow_contains_point(context, loan, None, &mut err);
+        self.explain_why_borrow_contains_point(context, loan, None, "", &mut err);
 
-        err.emit();
+        err.buffer(&mut self.errors_buffer);
     }
 
     /// Reports an illegal reassignment; for example, an assignment to
@@ -671,20 +1348,40 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         if let Some(decl) = local_decl {
             if let Some(name) = decl.name {
                 if decl.can_be_made_mutable() {
-                    err.span_label(
+                    err.span_suggestion_with_applicability(
                         decl.source_info.span,
-                        format!("consider changing this to `mut {}`", name),
+                        "consider changing this to be mutable",
+                        format!("mut {}", name),
+                        Applicability::MachineApplicable,
                     );
                 }
             }
         }
         err.span_label(span, msg);
-        err.emit();
+        err.buffer(&mut self.errors_buffer);
     }
 }
 
 pub(super) struct IncludingDowncast(bool);
 
+/// What a variable was moved into when it is captured by value.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum MovedInto {
+    Closure,
+    /// A closure passed to a `spawn` function, e.g. `std::thread::spawn`.
+    SpawnedClosure,
+    AsyncBlock,
+}
+
+impl MovedInto {
+    fn description(self) -> &'static str {
+        match self {
+            MovedInto::Closure | MovedInto::SpawnedClosure => "closure",
+            MovedInto::AsyncBlock => "async block",
+        }
+    }
+}
+
 impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
     // End-user visible description of `place` if one can be found. If the
     // place is a temporary for instance, None will be returned.
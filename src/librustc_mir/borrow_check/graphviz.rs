@@ -0,0 +1,67 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Dumps the MIR as a graphviz file in which every statement is annotated
+//! with the borrowck dataflow state on entry to it, enabled by
+//! `-Z dump-mir-graphviz -Z dump-mir-dataflow` (and selected with the
+//! `borrowck` pass name in `-Z dump-mir`).
+
+use borrow_check::borrow_set::BorrowSet;
+use dataflow::move_paths::MoveData;
+use dataflow::{Borrows, FlowAtLocation, MaybeInitializedPlaces};
+use rustc::mir::{Location, Mir};
+use rustc::ty::TyCtxt;
+use std::io;
+use transform::MirSource;
+use util::pretty;
+use util::{self, write_mir_fn_graphviz_annotated};
+
+pub(super) fn dump_dataflow_graphviz<'cx, 'gcx, 'tcx>(
+    tcx: TyCtxt<'cx, 'gcx, 'tcx>,
+    mir: &Mir<'tcx>,
+    source: MirSource,
+    borrow_set: &BorrowSet<'tcx>,
+    move_data: &MoveData<'tcx>,
+    flow_borrows: &FlowAtLocation<Borrows<'cx, 'gcx, 'tcx>>,
+    flow_inits: &FlowAtLocation<MaybeInitializedPlaces<'cx, 'gcx, 'tcx>>,
+) {
+    let opts = &tcx.sess.opts.debugging_opts;
+    if !(opts.dump_mir_graphviz && opts.dump_mir_dataflow) {
+        return;
+    }
+    if !util::dump_enabled(tcx, "borrowck", source) {
+        return;
+    }
+
+    let annotate = |location: Location| {
+        let mut lines = vec![];
+
+        // The region printed for each borrow is the region of the
+        // temporary holding the reference.
+        for borrow_index in flow_borrows.state_at(location, mir).iter() {
+            lines.push(format!("in scope: {:?} = {}", borrow_index, borrow_set[borrow_index]));
+        }
+
+        let paths: Vec<_> = flow_inits.state_at(location, mir).iter()
+            .map(|mpi| format!("{}", move_data.move_paths[mpi]))
+            .collect();
+        if !paths.is_empty() {
+            lines.push(format!("maybe init: {}", paths.join(", ")));
+        }
+
+        lines
+    };
+
+    let _: io::Result<()> = do catch {
+        let mut file =
+            pretty::create_dump_file(tcx, "dot", None, "borrowck", &"dataflow", source)?;
+        write_mir_fn_graphviz_annotated(tcx, source.def_id, mir, &annotate, &mut file)?;
+    };
+}
@@ -16,10 +16,12 @@ use rustc::hir::def_id::DefId;
 use rustc::hir::map::definitions::DefPathData;
 use rustc::infer::InferCtxt;
 use rustc::lint::builtin::UNUSED_MUT;
+use rustc::middle::borrowck::SignalledError;
 use rustc::mir::{self, AggregateKind, BasicBlock, BorrowCheckResult, BorrowKind};
-use rustc::mir::{ClearCrossCrate, Local, Location, Mir, Mutability, Operand, Place};
+use rustc::mir::{ClearCrossCrate, Local, Location, Mir, MirPhase, Mutability, Operand, Place};
 use rustc::mir::{Field, Projection, ProjectionElem, Rvalue, Statement, StatementKind};
 use rustc::mir::{Terminator, TerminatorKind};
+use rustc::session::config::BorrowckMode;
 use rustc::ty::query::Providers;
 use rustc::ty::{self, ParamEnv, TyCtxt};
 
@@ -28,8 +30,11 @@ use rustc_data_structures::fx::FxHashSet;
 use rustc_data_structures::indexed_set::IdxSetBuf;
 use rustc_data_structures::indexed_vec::Idx;
 use rustc_data_structures::small_vec::SmallVec;
+use rustc_data_structures::sync::Lrc;
+use rustc_errors::{Applicability, Diagnostic, DiagnosticBuilder, Level};
 
 use std::rc::Rc;
+use std::sync::atomic::Ordering;
 
 use syntax_pos::Span;
 
@@ -42,6 +47,7 @@ use dataflow::MoveDataParamEnv;
 use dataflow::{do_dataflow, DebugFormatted};
 use dataflow::{EverInitializedPlaces, MovingOutStatements};
 use dataflow::{MaybeInitializedPlaces, MaybeUninitializedPlaces};
+use transform::MirSource;
 use util::borrowck_errors::{BorrowckErrors, Origin};
 use util::collect_writes::FindAssignments;
 
@@ -56,11 +62,13 @@ use self::path_utils::*;
 crate mod borrow_set;
 mod error_reporting;
 mod flows;
+mod graphviz;
 mod location;
 mod path_utils;
 crate mod place_ext;
 mod places_conflict;
 mod prefixes;
+mod summary;
 mod used_muts;
 mod move_errors;
 
@@ -69,10 +77,18 @@ pub(crate) mod nll;
 pub fn provide(providers: &mut Providers) {
     *providers = Providers {
         mir_borrowck,
+        mir_borrowck_summary,
         ..*providers
     };
 }
 
+fn mir_borrowck_summary<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    def_id: DefId,
+) -> Option<Lrc<mir::BorrowckSummary>> {
+    tcx.mir_borrowck(def_id).summary.map(Lrc::new)
+}
+
 fn mir_borrowck<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> BorrowCheckResult<'tcx> {
     let input_mir = tcx.mir_validated(def_id);
     debug!("run query mir_borrowck: {}", tcx.item_path_str(def_id));
@@ -111,6 +127,7 @@ fn mir_borrowck<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> BorrowC
         return BorrowCheckResult {
             closure_requirements: None,
             used_mut_upvars: SmallVec::new(),
+            summary: None,
         };
     }
 
@@ -129,7 +146,9 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
     def_id: DefId,
 ) -> BorrowCheckResult<'gcx> {
     debug!("do_mir_borrowck(def_id = {:?})", def_id);
+    debug_assert_eq!(input_mir.phase, MirPhase::Validated);
 
+    let mut errors_buffer = Vec::new();
     let tcx = infcx.tcx;
     let attributes = tcx.get_attrs(def_id);
     let param_env = tcx.param_env(def_id);
@@ -147,10 +166,27 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
     let mir = &mir; // no further changes
     let location_table = &LocationTable::new(mir);
 
+    if tcx.sess.borrowck_stats() {
+        let metrics = mir.metrics();
+        println!("--- borrowck stats for `{}` ---", tcx.item_path_str(def_id));
+        println!("basic blocks: {}", metrics.basic_blocks);
+        println!("statements: {}", metrics.statements);
+        println!("locals: {}", metrics.locals);
+        println!("call sites: {}", metrics.call_sites);
+        println!("loops: {}", metrics.loops);
+    }
+
     let move_data: MoveData<'tcx> = match MoveData::gather_moves(mir, tcx) {
         Ok(move_data) => move_data,
         Err((move_data, move_errors)) => {
-            move_errors::report_move_errors(&mir, tcx, move_errors, &move_data);
+            move_errors::report_move_errors(
+                &mir,
+                infcx,
+                param_env,
+                move_errors,
+                &move_data,
+                &mut errors_buffer,
+            );
             move_data
         }
     };
@@ -215,6 +251,7 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
         &mut flow_inits,
         &mdpe.move_data,
         &borrow_set,
+        &mut errors_buffer,
     );
     let regioncx = Rc::new(regioncx);
     let flow_inits = flow_inits; // remove mut
@@ -229,6 +266,16 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
         |rs, i| DebugFormatted::new(&rs.location(i)),
     ));
 
+    graphviz::dump_dataflow_graphviz(
+        tcx,
+        mir,
+        MirSource::item(def_id),
+        &borrow_set,
+        &mdpe.move_data,
+        &flow_borrows,
+        &flow_inits,
+    );
+
     let movable_generator = match tcx.hir.get(id) {
         hir::map::Node::NodeExpr(&hir::Expr {
             node: hir::ExprClosure(.., Some(hir::GeneratorMovability::Static)),
@@ -241,6 +288,7 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
 
     let mut mbcx = MirBorrowckCtxt {
         tcx: tcx,
+        infcx,
         mir: mir,
         mir_def_id: def_id,
         move_data: &mdpe.move_data,
@@ -257,8 +305,10 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
         nonlexical_regioncx: regioncx,
         used_mut: FxHashSet(),
         used_mut_upvars: SmallVec::new(),
+        accesses: vec![],
         borrow_set,
         dominators,
+        errors_buffer,
     };
 
     let mut state = Flows::new(
@@ -316,14 +366,53 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
                 vsi[local_decl.source_info.scope].lint_root,
                 span,
                 "variable does not need to be mutable",
-            ).span_suggestion_short(mut_span, "remove this `mut`", "".to_owned())
-                .emit();
+            ).span_suggestion_short_with_applicability(
+                mut_span,
+                "remove this `mut`",
+                "".to_owned(),
+                Applicability::MachineApplicable,
+            ).emit();
         }
     }
 
+    if tcx.sess.opts.borrowck_mode == BorrowckMode::Compare {
+        record_compare_verdicts(tcx, def_id, &mbcx.errors_buffer);
+    }
+
+    // In migrate mode, errors the AST borrowck didn't report are only
+    // warnings, so that code it accepted keeps compiling for now.
+    if tcx.migrate_borrowck() && mbcx.errors_buffer.iter().any(|diag| diag.is_error()) {
+        if tcx.borrowck(def_id).signalled_any_error == SignalledError::NoErrorsSeen {
+            for diag in &mut mbcx.errors_buffer {
+                if diag.is_error() {
+                    diag.level = Level::Warning;
+                    diag.warn(
+                        "this error has been downgraded to a warning for backwards \
+                         compatibility with previous releases",
+                    );
+                    diag.warn(
+                        "this represents potential undefined behavior in your code and \
+                         this warning will become a hard error in the future",
+                    );
+                }
+            }
+        }
+    }
+
+    for diag in mbcx.errors_buffer.drain(..) {
+        DiagnosticBuilder::new_diagnostic(mbcx.tcx.sess.diagnostic(), diag).emit();
+    }
+
+    let summary = if tcx.sess.borrowck_summary() {
+        Some(mbcx.summarize())
+    } else {
+        None
+    };
+
     let result = BorrowCheckResult {
         closure_requirements: opt_closure_req,
         used_mut_upvars: mbcx.used_mut_upvars,
+        summary,
     };
 
     debug!("do_mir_borrowck: result = {:#?}", result);
@@ -331,9 +420,30 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
     result
 }
 
+/// Records how the verdict of MIR borrowck on `def_id` compares with the one
+/// of AST borrowck, for the statistics of `-Z borrowck=compare`.
+fn record_compare_verdicts<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    def_id: DefId,
+    mir_errors: &[Diagnostic],
+) {
+    let rejected_by_mir = mir_errors.iter().any(|diag| diag.is_error());
+    let rejected_by_ast = tcx.borrowck(def_id).signalled_any_error == SignalledError::SawSomeError;
+    let stats = &tcx.sess.borrowck_compare_stats;
+    stats.bodies.fetch_add(1, Ordering::Relaxed);
+    let counter = match (rejected_by_ast, rejected_by_mir) {
+        (true, true) => &stats.rejected_by_both,
+        (true, false) => &stats.rejected_by_ast_only,
+        (false, true) => &stats.rejected_by_mir_only,
+        (false, false) => return,
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
 #[allow(dead_code)]
 pub struct MirBorrowckCtxt<'cx, 'gcx: 'tcx, 'tcx: 'cx> {
     tcx: TyCtxt<'cx, 'gcx, 'tcx>,
+    infcx: &'cx InferCtxt<'cx, 'gcx, 'tcx>,
     mir: &'cx Mir<'tcx>,
     mir_def_id: DefId,
     move_data: &'cx MoveData<'tcx>,
@@ -374,6 +484,9 @@ pub struct MirBorrowckCtxt<'cx, 'gcx: 'tcx, 'tcx: 'cx> {
     /// If the function we're checking is a closure, then we'll need to report back the list of
     /// mutable upvars that have been used. This field keeps track of them.
     used_mut_upvars: SmallVec<[Field; 8]>,
+    /// The accesses to user variables, recorded for the `mir_borrowck_summary`
+    /// query under `-Z borrowck-summary`.
+    accesses: Vec<mir::VariableAccess>,
     /// Non-lexical region inference context, if NLL is enabled.  This
     /// contains the results from region inference and lets us e.g.
     /// find out which CFG points are contained in each borrow region.
@@ -384,6 +497,10 @@ pub struct MirBorrowckCtxt<'cx, 'gcx: 'tcx, 'tcx: 'cx> {
 
     /// Dominators for MIR
     dominators: Dominators<BasicBlock>,
+
+    /// Errors to be reported at the end of the borrowck, buffered so that
+    /// they can be downgraded to warnings in migrate mode.
+    errors_buffer: Vec<Diagnostic>,
 }
 
 // Check that:
@@ -533,7 +650,6 @@ impl<'cx, 'gcx, 'tcx> DataflowResultsConsumer<'cx, 'tcx> for MirBorrowckCtxt<'cx
             TerminatorKind::SwitchInt {
                 ref discr,
                 switch_ty: _,
-                values: _,
                 targets: _,
             } => {
                 self.consume_operand(ContextKind::SwitchInt.new(loc), (discr, span), flow_state);
@@ -679,6 +795,7 @@ use self::ShallowOrDeep::{Deep, Shallow};
 enum ArtificialField {
     Discriminant,
     ArrayLength,
+    ShallowBorrow,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -892,6 +1009,10 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
     ) -> AccessErrorsReported {
         let (sd, rw) = kind;
 
+        if self.tcx.sess.borrowck_summary() {
+            self.record_access(place_span, rw, flow_state);
+        }
+
         if let Activation(_, borrow_index) = rw {
             if self.reservation_error_reported.contains(&place_span.0) {
                 debug!(
@@ -985,7 +1106,17 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                     Control::Continue
                 }
 
-                (Read(_), BorrowKind::Shared) | (Reservation(..), BorrowKind::Shared) => {
+                (Read(_), BorrowKind::Shared)
+                | (Read(_), BorrowKind::Shallow)
+                | (Reservation(..), BorrowKind::Shared)
+                | (Reservation(..), BorrowKind::Shallow) => {
+                    Control::Continue
+                }
+
+                // Fake borrows only care about writes to the places that a
+                // match has inspected, not about borrows that already exist.
+                (Read(ReadKind::Borrow(BorrowKind::Shallow)), BorrowKind::Unique)
+                | (Read(ReadKind::Borrow(BorrowKind::Shallow)), BorrowKind::Mut { .. }) => {
                     Control::Continue
                 }
 
@@ -1115,6 +1246,9 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         match *rvalue {
             Rvalue::Ref(_ /*rgn*/, bk, ref place) => {
                 let access_kind = match bk {
+                    BorrowKind::Shallow => {
+                        (Shallow(Some(ArtificialField::ShallowBorrow)), Read(ReadKind::Borrow(bk)))
+                    }
                     BorrowKind::Shared => (Deep, Read(ReadKind::Borrow(bk))),
                     BorrowKind::Unique | BorrowKind::Mut { .. } => {
                         let wk = WriteKind::MutableBorrow(bk);
@@ -1294,16 +1428,10 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         //
         // FIXME: allow thread-locals to borrow other thread locals?
         let (might_be_alive, will_be_dropped) = match root_place {
-            Place::Static(statik) => {
+            Place::Static(_) => {
                 // Thread-locals might be dropped after the function exits, but
                 // "true" statics will never be.
-                let is_thread_local = self
-                    .tcx
-                    .get_attrs(statik.def_id)
-                    .iter()
-                    .any(|attr| attr.check_name("thread_local"));
-
-                (true, is_thread_local)
+                (true, self.is_place_thread_local(root_place))
             }
             Place::Local(_) => {
                 // Locals are always dropped at function exit, and if they
@@ -1341,6 +1469,19 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         }
     }
 
+    /// Whether `place` is a `#[thread_local]` static, which only lives as long
+    /// as the current thread rather than for the whole program.
+    fn is_place_thread_local(&self, place: &Place<'tcx>) -> bool {
+        if let Place::Static(statik) = place {
+            self.tcx
+                .get_attrs(statik.def_id)
+                .iter()
+                .any(|attr| attr.check_name("thread_local"))
+        } else {
+            false
+        }
+    }
+
     /// Reports an error if this is a borrow of local data.
     /// This is called for all Yield statements on movable generators
     fn check_for_local_borrow(&mut self, borrow: &BorrowData<'tcx>, yield_span: Span) {
@@ -1353,7 +1494,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                     yield_span,
                     Origin::Mir,
                 )
-                .emit();
+                .buffer(&mut self.errors_buffer);
         }
     }
 
@@ -1376,7 +1517,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
 
             // only mutable borrows should be 2-phase
             assert!(match borrow.kind {
-                BorrowKind::Shared => false,
+                BorrowKind::Shared | BorrowKind::Shallow => false,
                 BorrowKind::Unique | BorrowKind::Mut { .. } => true,
             });
 
@@ -1437,7 +1578,6 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         let place = self.base_path(place_span.0);
 
         let maybe_uninits = &flow_state.uninits;
-        let curr_move_outs = &flow_state.move_outs;
 
         // Bad scenarios:
         //
@@ -1477,13 +1617,14 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         debug!("check_if_full_path_is_moved place: {:?}", place);
         match self.move_path_closest_to(place) {
             Ok(mpi) => {
-                if maybe_uninits.contains(&mpi) {
+                if maybe_uninits.contains(&mpi) && !self.is_initialized_by_fields(mpi, flow_state)
+                {
                     self.report_use_of_moved_or_uninitialized(
                         context,
                         desired_action,
                         place_span,
                         mpi,
-                        curr_move_outs,
+                        flow_state,
                     );
                     return; // don't bother finding other problems.
                 }
@@ -1511,7 +1652,6 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         let place = self.base_path(place_span.0);
 
         let maybe_uninits = &flow_state.uninits;
-        let curr_move_outs = &flow_state.move_outs;
 
         // Bad scenarios:
         //
@@ -1541,13 +1681,18 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
 
         debug!("check_if_path_or_subpath_is_moved place: {:?}", place);
         if let Some(mpi) = self.move_path_for_place(place) {
-            if let Some(child_mpi) = maybe_uninits.has_any_child_of(mpi) {
+            let uninit_child = if self.tcx.features().partial_init {
+                self.find_uninitialized_child(mpi, flow_state)
+            } else {
+                maybe_uninits.has_any_child_of(mpi)
+            };
+            if let Some(child_mpi) = uninit_child {
                 self.report_use_of_moved_or_uninitialized(
                     context,
                     desired_action,
                     place_span,
                     child_mpi,
-                    curr_move_outs,
+                    flow_state,
                 );
                 return; // don't bother finding other problems.
             }
@@ -1593,6 +1738,91 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         }
     }
 
+    /// If the place at `mpi` is a struct or tuple that can be initialized field by field,
+    /// returns the fields that are still maybe uninitialized, each as the move path of the
+    /// struct it belongs to and the field index. Fields that are structs themselves and were
+    /// partially initialized are looked into in turn.
+    ///
+    /// Returns `None` if the place is not such a struct, so it can only be initialized as a
+    /// whole.
+    fn uninitialized_fields(
+        &self,
+        mpi: MovePathIndex,
+        flow_state: &Flows<'cx, 'gcx, 'tcx>,
+    ) -> Option<Vec<(MovePathIndex, Field)>> {
+        let move_paths = &self.move_data.move_paths;
+        let ty = move_paths[mpi].place.ty(self.mir, self.tcx).to_ty(self.tcx);
+        let field_count = match ty.sty {
+            // Assigning to a field of a struct with a destructor requires the
+            // struct to be initialized already.
+            ty::TyAdt(def, _) if def.is_struct() && !def.has_dtor(self.tcx) => {
+                def.non_enum_variant().fields.len()
+            }
+            ty::TyTuple(tys) => tys.len(),
+            _ => return None,
+        };
+        if field_count == 0 {
+            return None;
+        }
+
+        let mut uninit_fields = vec![];
+        for index in 0..field_count {
+            let field = Field::new(index);
+            let mut child = move_paths[mpi].first_child;
+            while let Some(child_mpi) = child {
+                match move_paths[child_mpi].place {
+                    Place::Projection(box Projection {
+                        elem: ProjectionElem::Field(f, _),
+                        ..
+                    }) if f == field => break,
+                    _ => child = move_paths[child_mpi].next_sibling,
+                }
+            }
+            match child {
+                Some(child_mpi) if !flow_state.uninits.contains(&child_mpi) => {}
+                Some(child_mpi) => match self.uninitialized_fields(child_mpi, flow_state) {
+                    Some(fields) => uninit_fields.extend(fields),
+                    None => uninit_fields.push((mpi, field)),
+                },
+                None => uninit_fields.push((mpi, field)),
+            }
+        }
+        Some(uninit_fields)
+    }
+
+    /// Whether the maybe uninitialized place at `mpi` has had each of its fields initialized,
+    /// which makes it initialized as a whole under `#![feature(partial_init)]`.
+    fn is_initialized_by_fields(
+        &self,
+        mpi: MovePathIndex,
+        flow_state: &Flows<'cx, 'gcx, 'tcx>,
+    ) -> bool {
+        self.tcx.features().partial_init
+            && self
+                .uninitialized_fields(mpi, flow_state)
+                .map_or(false, |fields| fields.is_empty())
+    }
+
+    /// Like `has_any_child_of`, but does not count places that are initialized field by field
+    /// (see `is_initialized_by_fields`) as uninitialized.
+    fn find_uninitialized_child(
+        &self,
+        mpi: MovePathIndex,
+        flow_state: &Flows<'cx, 'gcx, 'tcx>,
+    ) -> Option<MovePathIndex> {
+        if flow_state.uninits.contains(&mpi) && !self.is_initialized_by_fields(mpi, flow_state) {
+            return Some(mpi);
+        }
+        let mut child = self.move_data.move_paths[mpi].first_child;
+        while let Some(child_mpi) = child {
+            if let Some(uninit_mpi) = self.find_uninitialized_child(child_mpi, flow_state) {
+                return Some(uninit_mpi);
+            }
+            child = self.move_data.move_paths[child_mpi].next_sibling;
+        }
+        None
+    }
+
     fn check_if_assigned_path_is_moved(
         &mut self,
         context: Context,
@@ -1698,7 +1928,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                 let is_local_mutation_allowed = match borrow_kind {
                     BorrowKind::Unique => LocalMutationIsAllowed::Yes,
                     BorrowKind::Mut { .. } => is_local_mutation_allowed,
-                    BorrowKind::Shared => unreachable!(),
+                    BorrowKind::Shared | BorrowKind::Shallow => unreachable!(),
                 };
                 match self.is_mutable(place, is_local_mutation_allowed) {
                     Ok(root_place) => {
@@ -1728,8 +1958,10 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             | Write(WriteKind::Move)
             | Reservation(WriteKind::StorageDeadOrDrop)
             | Reservation(WriteKind::MutableBorrow(BorrowKind::Shared))
+            | Reservation(WriteKind::MutableBorrow(BorrowKind::Shallow))
             | Write(WriteKind::StorageDeadOrDrop)
-            | Write(WriteKind::MutableBorrow(BorrowKind::Shared)) => {
+            | Write(WriteKind::MutableBorrow(BorrowKind::Shared))
+            | Write(WriteKind::MutableBorrow(BorrowKind::Shallow)) => {
                 if let Err(_place_err) = self.is_mutable(place, is_local_mutation_allowed) {
                     self.tcx.sess.delay_span_bug(
                         span,
@@ -1748,6 +1980,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             Read(ReadKind::Borrow(BorrowKind::Unique))
             | Read(ReadKind::Borrow(BorrowKind::Mut { .. }))
             | Read(ReadKind::Borrow(BorrowKind::Shared))
+            | Read(ReadKind::Borrow(BorrowKind::Shallow))
             | Read(ReadKind::Copy) => {
                 // Access authorized
                 return false;
@@ -1804,12 +2037,36 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                 // mutable)..
                 let local_decl = &self.mir.local_decls[*local];
                 assert_eq!(local_decl.mutability, Mutability::Not);
-
-                err.span_label(span, format!("cannot {ACT}", ACT = act));
-                err.span_suggestion(
+                let name = local_decl.name.unwrap();
+
+                // Calling a closure that mutates the variables it captures
+                // by reference borrows the closure mutably.
+                let is_explicit_borrow = self.tcx.sess.codemap().span_to_snippet(span)
+                    .map(|snippet| snippet.starts_with("&mut "))
+                    .unwrap_or(false);
+                match self.find_mutable_closure_capture(*local) {
+                    Some((upvar_name, upvar_span)) if !is_explicit_borrow => {
+                        err.span_label(
+                            span,
+                            format!("calling `{}` requires a mutable binding", name),
+                        );
+                        err.span_label(
+                            upvar_span,
+                            format!(
+                                "the closure captures `{}` by mutable reference here",
+                                upvar_name,
+                            ),
+                        );
+                    }
+                    _ => {
+                        err.span_label(span, format!("cannot {ACT}", ACT = act));
+                    }
+                }
+                err.span_suggestion_with_applicability(
                     local_decl.source_info.span,
                     "consider changing this to be mutable",
-                    format!("mut {}", local_decl.name.unwrap()),
+                    format!("mut {}", name),
+                    Applicability::MachineApplicable,
                 );
             }
 
@@ -1826,7 +2083,14 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                 } =>
             {
                 err.span_label(span, format!("cannot {ACT}", ACT = act));
-                err.span_label(span, "try removing `&mut` here");
+                if let Ok(snippet) = self.tcx.sess.codemap().span_to_snippet(span) {
+                    err.span_suggestion_with_applicability(
+                        span,
+                        "try removing `&mut` here",
+                        snippet["&mut ".len()..].to_owned(),
+                        Applicability::MaybeIncorrect,
+                    );
+                }
             }
 
             // We want to point out when a `&` can be readily replaced
@@ -1841,10 +2105,11 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             {
                 let (err_help_span, suggested_code) =
                     find_place_to_suggest_ampmut(self.tcx, self.mir, *local);
-                err.span_suggestion(
+                err.span_suggestion_with_applicability(
                     err_help_span,
                     "consider changing this to be a mutable reference",
                     suggested_code,
+                    Applicability::MachineApplicable,
                 );
 
                 let local_decl = &self.mir.local_decls[*local];
@@ -1871,7 +2136,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             }
         }
 
-        err.emit();
+        err.buffer(&mut self.errors_buffer);
         return true;
 
         // Returns the span to highlight and the associated text to
@@ -1913,6 +2178,26 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             }
 
             let local_decl = &mir.local_decls[local];
+
+            // A `&self` receiver is changed to `&mut self`, keeping its lifetime
+            // if it was given one.
+            if let Some(ClearCrossCrate::Set(mir::BindingForm::ImplicitSelf)) =
+                local_decl.is_user_variable
+            {
+                let snippet = tcx.sess.codemap().span_to_snippet(local_decl.source_info.span);
+                if let Ok(src) = snippet {
+                    if src.starts_with('&') && src.ends_with("self") {
+                        let lifetime = src[1..src.len() - "self".len()].trim();
+                        let suggested_code = if lifetime.is_empty() {
+                            "&mut self".to_owned()
+                        } else {
+                            format!("&{} mut self", lifetime)
+                        };
+                        return (local_decl.source_info.span, suggested_code);
+                    }
+                }
+            }
+
             let highlight_span = match local_decl.is_user_variable {
                 // if this is a variable binding with an explicit type,
                 // try to highlight that for the suggestion.
@@ -2190,9 +2475,9 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         let mut cursor = place;
         let mut deepest = place;
         loop {
-            let proj = match *cursor {
-                Place::Local(..) | Place::Static(..) => return deepest,
-                Place::Projection(ref proj) => proj,
+            let proj = match cursor.last_projection() {
+                None => return deepest,
+                Some(proj) => proj,
             };
             if proj.elem == ProjectionElem::Deref
                 && place.ty(self.mir, self.tcx).to_ty(self.tcx).is_box()
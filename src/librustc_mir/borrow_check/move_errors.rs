@@ -9,32 +9,41 @@
 // except according to those terms.
 
 use rustc::hir;
+use rustc::hir::def_id::LOCAL_CRATE;
+use rustc::infer::InferCtxt;
 use rustc::mir::*;
-use rustc::ty::{self, TyCtxt};
-use rustc_errors::DiagnosticBuilder;
+use rustc::traits;
+use rustc::ty::{self, ParamEnv, TyCtxt};
+use rustc_errors::{Applicability, Diagnostic, DiagnosticBuilder};
 use syntax_pos::Span;
 
 use dataflow::move_paths::{IllegalMoveOrigin, IllegalMoveOriginKind, MoveData};
 use dataflow::move_paths::{LookupResult, MoveError, MovePathIndex};
 use util::borrowck_errors::{BorrowckErrors, Origin};
 
-pub(crate) fn report_move_errors<'gcx, 'tcx>(
-    mir: &Mir<'tcx>,
-    tcx: TyCtxt<'_, 'gcx, 'tcx>,
+pub(crate) fn report_move_errors<'a, 'gcx, 'tcx>(
+    mir: &'a Mir<'tcx>,
+    infcx: &'a InferCtxt<'a, 'gcx, 'tcx>,
+    param_env: ParamEnv<'tcx>,
     move_errors: Vec<MoveError<'tcx>>,
-    move_data: &MoveData<'tcx>,
+    move_data: &'a MoveData<'tcx>,
+    errors_buffer: &mut Vec<Diagnostic>,
 ) {
     MoveErrorCtxt {
         mir,
-        tcx,
+        tcx: infcx.tcx,
+        infcx,
+        param_env,
         move_data,
-    }.report_errors(move_errors);
+    }.report_errors(move_errors, errors_buffer);
 }
 
 #[derive(Copy, Clone)]
 struct MoveErrorCtxt<'a, 'gcx: 'tcx, 'tcx: 'a> {
     mir: &'a Mir<'tcx>,
     tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    infcx: &'a InferCtxt<'a, 'gcx, 'tcx>,
+    param_env: ParamEnv<'tcx>,
     move_data: &'a MoveData<'tcx>,
 }
 
@@ -51,6 +60,9 @@ struct MoveErrorCtxt<'a, 'gcx: 'tcx, 'tcx: 'a> {
 // paths. For example, this generates two errors:
 //
 // let (&x, &y) = (&String::new(), &String::new());
+//
+// Similarly, moves out of the same reference into the arguments of a single
+// call, as in `f(x.a, x.b)` where `x: &S`, are reported together.
 #[derive(Debug)]
 enum GroupedMoveError<'tcx> {
     // Match place can't be moved from
@@ -72,15 +84,21 @@ enum GroupedMoveError<'tcx> {
     // Everything that isn't from pattern matching.
     OtherIllegalMove {
         span: Span,
+        // The place moved from, if the move is a plain `_ = move place`.
+        move_from: Option<Place<'tcx>>,
         kind: IllegalMoveOriginKind<'tcx>,
+        // The block of the call this is an argument of, if any.
+        call: Option<BasicBlock>,
+        // Moves out of the same reference into other arguments of that call.
+        other_moves: Vec<(Span, Place<'tcx>)>,
     },
 }
 
 impl<'a, 'gcx, 'tcx> MoveErrorCtxt<'a, 'gcx, 'tcx> {
-    fn report_errors(self, move_errors: Vec<MoveError<'tcx>>) {
+    fn report_errors(self, move_errors: Vec<MoveError<'tcx>>, errors_buffer: &mut Vec<Diagnostic>) {
         let grouped_errors = self.group_move_errors(move_errors);
         for error in grouped_errors {
-            self.report(error);
+            self.report(error, errors_buffer);
         }
     }
 
@@ -105,13 +123,14 @@ impl<'a, 'gcx, 'tcx> MoveErrorCtxt<'a, 'gcx, 'tcx> {
                 cannot_move_out_of: IllegalMoveOrigin { location, kind },
             } => {
                 let stmt_source_info = self.mir.source_info(location);
+                let stmt_kind = self.mir.basic_blocks()[location.block]
+                    .statements
+                    .get(location.statement_index)
+                    .map(|stmt| &stmt.kind);
                 if let Some(StatementKind::Assign(
                     Place::Local(local),
                     Rvalue::Use(Operand::Move(move_from)),
-                )) = self.mir.basic_blocks()[location.block]
-                    .statements
-                    .get(location.statement_index)
-                    .map(|stmt| &stmt.kind)
+                )) = stmt_kind
                 {
                     let local_decl = &self.mir.local_decls[*local];
                     if let Some(ClearCrossCrate::Set(BindingForm::Var(VarBindingForm {
@@ -149,14 +168,70 @@ impl<'a, 'gcx, 'tcx> MoveErrorCtxt<'a, 'gcx, 'tcx> {
                         return;
                     }
                 }
+                let move_from = match stmt_kind {
+                    Some(StatementKind::Assign(_, Rvalue::Use(Operand::Move(move_from)))) => {
+                        Some(move_from.clone())
+                    }
+                    _ => None,
+                };
+                let call = match (stmt_kind, &kind, &move_from) {
+                    (
+                        Some(StatementKind::Assign(Place::Local(temp), _)),
+                        IllegalMoveOriginKind::BorrowedContent { .. },
+                        Some(_),
+                    ) => self.call_with_argument(location.block, *temp),
+                    _ => None,
+                };
+                if let (Some(call), Some(ref move_from)) = (call, &move_from) {
+                    for ge in &mut *grouped_errors {
+                        if let GroupedMoveError::OtherIllegalMove {
+                            call: Some(other_call),
+                            move_from: Some(ref other_move_from),
+                            ref mut other_moves,
+                            ..
+                        } = *ge
+                        {
+                            let base = self.deref_base(move_from);
+                            if call == other_call
+                                && base.is_some()
+                                && base == self.deref_base(other_move_from)
+                            {
+                                debug!("appending move from {:?} to call error", move_from);
+                                other_moves.push((stmt_source_info.span, move_from.clone()));
+                                return;
+                            }
+                        }
+                    }
+                }
                 grouped_errors.push(GroupedMoveError::OtherIllegalMove {
                     span: stmt_source_info.span,
+                    move_from,
                     kind,
+                    call,
+                    other_moves: vec![],
                 });
             }
         }
     }
 
+    /// Returns the block of `block`'s terminator if it is a call taking `temp` as an argument.
+    fn call_with_argument(self, block: BasicBlock, temp: Local) -> Option<BasicBlock> {
+        match self.mir[block].terminator().kind {
+            TerminatorKind::Call { ref args, .. } => {
+                let is_argument = args.iter().any(|arg| match *arg {
+                    Operand::Move(Place::Local(local)) => local == temp,
+                    _ => false,
+                });
+                if is_argument {
+                    Some(block)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
     fn append_binding_error(
         self,
         grouped_errors: &mut Vec<GroupedMoveError<'tcx>>,
@@ -236,12 +311,12 @@ impl<'a, 'gcx, 'tcx> MoveErrorCtxt<'a, 'gcx, 'tcx> {
         };
     }
 
-    fn report(self, error: GroupedMoveError<'tcx>) {
+    fn report(self, error: GroupedMoveError<'tcx>, errors_buffer: &mut Vec<Diagnostic>) {
         let (mut err, err_span) = {
             let (span, kind): (Span, &IllegalMoveOriginKind) = match error {
                 GroupedMoveError::MovesFromMatchPlace { span, ref kind, .. }
                 | GroupedMoveError::MovesFromPattern { span, ref kind, .. }
-                | GroupedMoveError::OtherIllegalMove { span, ref kind } => (span, kind),
+                | GroupedMoveError::OtherIllegalMove { span, ref kind, .. } => (span, kind),
             };
             let origin = Origin::Mir;
             (
@@ -275,7 +350,7 @@ impl<'a, 'gcx, 'tcx> MoveErrorCtxt<'a, 'gcx, 'tcx> {
         };
 
         self.add_move_hints(error, &mut err, err_span);
-        err.emit();
+        err.buffer(errors_buffer);
     }
 
     fn add_move_hints(
@@ -297,20 +372,24 @@ impl<'a, 'gcx, 'tcx> MoveErrorCtxt<'a, 'gcx, 'tcx> {
                         Place::Projection(ref proj)
                             if self.suitable_to_remove_deref(proj, &snippet) =>
                         {
-                            err.span_suggestion(
+                            err.span_suggestion_with_applicability(
                                 span,
                                 "consider removing this dereference operator",
                                 format!("{}", &snippet[1..]),
+                                Applicability::MaybeIncorrect,
                             );
                         }
                         _ => {
-                            err.span_suggestion(
+                            err.span_suggestion_with_applicability(
                                 span,
                                 "consider using a reference instead",
                                 format!("&{}", snippet),
+                                Applicability::MaybeIncorrect,
                             );
                         }
                     }
+                    self.add_index_hints(err, span, &move_from);
+                    self.add_mem_replace_hint(err, span, &move_from);
 
                     binds_to.sort();
                     binds_to.dedup();
@@ -334,6 +413,7 @@ impl<'a, 'gcx, 'tcx> MoveErrorCtxt<'a, 'gcx, 'tcx> {
                 // another match arm
                 binds_to.sort();
                 binds_to.dedup();
+                let mut suggestions = vec![];
                 for local in binds_to {
                     let bind_to = &self.mir.local_decls[local];
                     let binding_span = bind_to.source_info.span;
@@ -345,11 +425,7 @@ impl<'a, 'gcx, 'tcx> MoveErrorCtxt<'a, 'gcx, 'tcx> {
                     };
                     match bind_to.name {
                         Some(name) => {
-                            err.span_suggestion(
-                                binding_span,
-                                "to prevent move, use ref or ref mut",
-                                format!("{} {:?}", ref_kind, name),
-                            );
+                            suggestions.push((binding_span, format!("{} {:?}", ref_kind, name)));
                         }
                         None => {
                             err.span_label(
@@ -359,12 +435,332 @@ impl<'a, 'gcx, 'tcx> MoveErrorCtxt<'a, 'gcx, 'tcx> {
                         }
                     }
                 }
+                // All of the bindings have to stop moving, so suggest that in
+                // a single suggestion.
+                if !suggestions.is_empty() {
+                    err.multipart_suggestion_with_applicability(
+                        "to prevent move, use ref or ref mut",
+                        suggestions,
+                        Applicability::MaybeIncorrect,
+                    );
+                }
+            }
+            GroupedMoveError::OtherIllegalMove {
+                move_from: Some(ref move_from),
+                kind: IllegalMoveOriginKind::BorrowedContent { .. },
+                ref other_moves,
+                ..
+            } => {
+                for &(other_span, _) in other_moves {
+                    err.span_label(other_span, "cannot move out of borrowed content");
+                }
+                if other_moves.is_empty() {
+                    self.add_index_hints(err, span, move_from);
+                }
+                let mut moves = vec![(span, move_from)];
+                moves.extend(other_moves.iter().map(|&(other_span, ref other)| {
+                    (other_span, other)
+                }));
+                self.add_borrowed_content_hints(err, &moves);
+                if other_moves.is_empty() {
+                    self.add_mem_replace_hint(err, span, move_from);
+                }
             }
             // Nothing to suggest.
             GroupedMoveError::OtherIllegalMove { .. } => (),
         }
     }
 
+    /// Points out the reference the moved values are behind, and suggests cloning the values
+    /// instead if their types implement `Clone`.
+    fn add_borrowed_content_hints(
+        self,
+        err: &mut DiagnosticBuilder<'a>,
+        moves: &[(Span, &Place<'tcx>)],
+    ) {
+        // All of the moves are out of the same reference.
+        let move_from = moves[0].1;
+        if let Some(&Place::Local(local)) = self.deref_base(move_from) {
+            let local_decl = &self.mir.local_decls[local];
+            if let (Some(name), &ty::TyRef(_, _, mutbl)) = (local_decl.name, &local_decl.ty.sty) {
+                if local_decl.is_user_variable.is_some() {
+                    let kind = match mutbl {
+                        hir::MutImmutable => "&",
+                        hir::MutMutable => "&mut",
+                    };
+                    err.span_label(
+                        local_decl.source_info.span,
+                        format!(
+                            "`{}` is a `{}` reference, so the data it refers to cannot be moved",
+                            name,
+                            kind,
+                        ),
+                    );
+                }
+            }
+        }
+
+        let mut suggestions = vec![];
+        for &(span, move_from) in moves {
+            let ty = move_from.ty(self.mir, self.tcx).to_ty(self.tcx);
+            let implements_clone = match self.tcx.lang_items().clone_trait() {
+                Some(clone_trait) => traits::type_known_to_meet_bound(
+                    self.infcx, self.param_env, ty, clone_trait, span,
+                ),
+                None => false,
+            };
+            if !implements_clone {
+                return;
+            }
+            let snippet = match self.tcx.sess.codemap().span_to_snippet(span) {
+                Ok(snippet) => snippet,
+                Err(_) => return,
+            };
+            let suggestion = match *move_from {
+                // `x.clone()` auto-derefs the reference, so there is no need to
+                // dereference it by hand.
+                Place::Projection(ref proj) if self.suitable_to_remove_deref(proj, &snippet) => {
+                    format!("{}.clone()", &snippet[1..])
+                }
+                _ if snippet.starts_with('*') => format!("({}).clone()", snippet),
+                _ => format!("{}.clone()", snippet),
+            };
+            suggestions.push((span, suggestion));
+        }
+        if suggestions.len() == 1 {
+            let (span, suggestion) = suggestions.pop().unwrap();
+            err.span_suggestion_with_applicability(
+                span,
+                "consider cloning the value",
+                suggestion,
+                Applicability::MachineApplicable,
+            );
+        } else {
+            err.multipart_suggestion_with_applicability(
+                "consider cloning the values",
+                suggestions,
+                Applicability::MachineApplicable,
+            );
+        }
+    }
+            None => false,
+        };
+        if !implements_clone {
+            return;
+        }
+        if let Ok(snippet) = self.tcx.sess.codemap().span_to_snippet(span) {
+            let suggestion = match *move_from {
+                // `x.clone()` auto-derefs the reference, so there is no need to
+                // dereference it by hand.
+                Place::Projection(ref proj) if self.suitable_to_remove_deref(proj, &snippet) => {
+                    format!("{}.clone()", &snippet[1..])
+                }
+                _ if snippet.starts_with('*') => format!("({}).clone()", snippet),
+                _ => format!("{}.clone()", snippet),
+            };
+            err.span_suggestion_with_applicability(
+                span,
+                "consider cloning the value",
+                suggestion,
+                Applicability::MachineApplicable,
+            );
+        }
+    }
+
+    /// Returns the place that is dereferenced last in `place`, e.g. `x.f` for `(*x.f).g`.
+    /// Suggests removing the element instead when `move_from` is the result
+    /// of indexing a mutable container, e.g. `v[i]` where `v: Vec<T>`, whose
+    /// type has inherent `remove` methods returning the element.
+    fn add_index_hints(self, err: &mut DiagnosticBuilder<'a>, span: Span, move_from: &Place<'tcx>) {
+        let temp = match *move_from {
+            Place::Projection(ref proj) if proj.elem == ProjectionElem::Deref => match proj.base {
+                Place::Local(local) if self.mir.local_kind(local) == LocalKind::Temp => local,
+                _ => return,
+            },
+            _ => return,
+        };
+
+        // Find the `Index::index(&container, index)` call producing `temp`.
+        let index_call = self.mir.basic_blocks().iter().filter_map(|data| {
+            match data.terminator().kind {
+                TerminatorKind::Call {
+                    func: Operand::Constant(box Constant { ty, .. }),
+                    ref args,
+                    destination: Some((Place::Local(dest), _)),
+                    ..
+                } if dest == temp => Some((data, ty, args)),
+                _ => None,
+            }
+        }).next();
+        let index_trait = self.tcx.lang_items().index_trait();
+        let (data, container_ty, receiver) = match index_call {
+            Some((data, ty, args)) => match (&ty.sty, args.first()) {
+                (&ty::TyFnDef(def_id, substs), Some(&Operand::Move(Place::Local(receiver))))
+                    if index_trait.is_some() && self.tcx.trait_of_item(def_id) == index_trait =>
+                {
+                    (data, substs.type_at(0), receiver)
+                }
+                _ => return,
+            },
+            None => return,
+        };
+        let adt_def = match container_ty.sty {
+            ty::TyAdt(adt_def, _) => adt_def,
+            _ => return,
+        };
+
+        // Removing the element needs mutable access to the container.
+        let container = data.statements.iter().filter_map(|stmt| match stmt.kind {
+            StatementKind::Assign(Place::Local(local), Rvalue::Ref(_, _, ref place))
+                if local == receiver => Some(place),
+            _ => None,
+        }).next();
+        match container {
+            Some(container) if self.is_mutable_place(container) => (),
+            _ => return,
+        }
+
+        let methods: Vec<_> = ["remove", "swap_remove"].iter().filter(|&&name| {
+            self.tcx.inherent_impls(adt_def.did).iter().any(|&impl_def_id| {
+                self.tcx.associated_items(impl_def_id).any(|item| {
+                    item.kind == ty::AssociatedKind::Method
+                        && item.ident.name.as_str() == name
+                        && match self.tcx.fn_sig(item.def_id).output().skip_binder().sty {
+                            ty::TyParam(_) => true,
+                            _ => false,
+                        }
+                })
+            })
+        }).collect();
+        if methods.is_empty() {
+            return;
+        }
+
+        // Split `container[index]` at its outermost brackets.
+        let snippet = match self.tcx.sess.codemap().span_to_snippet(span) {
+            Ok(ref snippet) if snippet.ends_with(']') => snippet.clone(),
+            _ => return,
+        };
+        let mut depth = 0;
+        let open = snippet.char_indices().rev().find(|&(_, c)| {
+            match c {
+                ']' => depth += 1,
+                '[' => depth -= 1,
+                _ => (),
+            }
+            depth == 0
+        });
+        let (container, index) = match open {
+            Some((open, _)) if open > 0 => {
+                (&snippet[..open], &snippet[open + 1..snippet.len() - 1])
+            }
+            _ => return,
+        };
+        err.span_suggestions_with_applicability(
+            span,
+            "consider removing the element instead",
+            methods.iter().map(|name| format!("{}.{}({})", container, name, index)).collect(),
+            Applicability::MaybeIncorrect,
+        );
+    }
+
+    /// Suggests replacing the value with its default instead when it is
+    /// behind a `&mut` and its type implements `Default`, e.g. `*r` where
+    /// `r: &mut String`.
+    fn add_mem_replace_hint(
+        self,
+        err: &mut DiagnosticBuilder<'a>,
+        span: Span,
+        move_from: &Place<'tcx>,
+    ) {
+        if !self.is_mutable_place(move_from) {
+            return;
+        }
+        let default_trait = self.tcx.all_traits(LOCAL_CRATE).iter().cloned().find(|&def_id| {
+            self.tcx.absolute_item_path_str(def_id) == "core::default::Default"
+        });
+        let ty = move_from.ty(self.mir, self.tcx).to_ty(self.tcx);
+        let implements_default = match default_trait {
+            Some(default_trait) => traits::type_known_to_meet_bound(
+                self.infcx, self.param_env, ty, default_trait, span,
+            ),
+            None => false,
+        };
+        if !implements_default {
+            return;
+        }
+        let snippet = match self.tcx.sess.codemap().span_to_snippet(span) {
+            Ok(snippet) => snippet,
+            Err(_) => return,
+        };
+        // `*r` is replaced through `r` itself, anything else through a new
+        // mutable borrow of it.
+        let dest = match *move_from {
+            Place::Projection(ref proj)
+                if proj.elem == ProjectionElem::Deref && snippet.starts_with('*') =>
+            {
+                match proj.base {
+                    Place::Local(local)
+                        if self.mir.local_decls[local].is_user_variable.is_some() =>
+                    {
+                        snippet[1..].to_owned()
+                    }
+                    _ => format!("&mut {}", snippet),
+                }
+            }
+            _ => format!("&mut {}", snippet),
+        };
+        err.span_suggestion_with_applicability(
+            span,
+            "consider replacing the value with its default value",
+            format!("std::mem::replace({}, Default::default())", dest),
+            Applicability::MaybeIncorrect,
+        );
+    }
+
+    fn is_mutable_place(self, place: &Place<'tcx>) -> bool {
+        match *place {
+            Place::Local(local) => self.mir.local_decls[local].mutability == Mutability::Mut,
+            Place::Static(_) => false,
+            Place::Projection(ref proj) => match proj.elem {
+                ProjectionElem::Deref => {
+                    match proj.base.ty(self.mir, self.tcx).to_ty(self.tcx).sty {
+                        ty::TyRef(_, _, hir::MutMutable) => self.is_unique_place(&proj.base),
+                        ty::TyAdt(def, _) if def.is_box() => self.is_mutable_place(&proj.base),
+                        _ => false,
+                    }
+                }
+                _ => self.is_mutable_place(&proj.base),
+            },
+        }
+    }
+
+    /// Whether `place` can be reached without going through a shared
+    /// reference, so that a `&mut` stored there can be used to mutate.
+    fn is_unique_place(self, place: &Place<'tcx>) -> bool {
+        match *place {
+            Place::Local(_) => true,
+            Place::Static(_) => false,
+            Place::Projection(ref proj) => match proj.elem {
+                ProjectionElem::Deref => {
+                    match proj.base.ty(self.mir, self.tcx).to_ty(self.tcx).sty {
+                        ty::TyRef(_, _, hir::MutImmutable) | ty::TyRawPtr(..) => false,
+                        _ => self.is_unique_place(&proj.base),
+                    }
+                }
+                _ => self.is_unique_place(&proj.base),
+            },
+        }
+    }
+
+    fn deref_base<'p>(self, place: &'p Place<'tcx>) -> Option<&'p Place<'tcx>> {
+        match *place {
+            Place::Projection(ref proj) if proj.elem == ProjectionElem::Deref => Some(&proj.base),
+            Place::Projection(ref proj) => self.deref_base(&proj.base),
+            Place::Local(_) | Place::Static(_) => None,
+        }
+    }
+
     fn suitable_to_remove_deref(self, proj: &PlaceProjection<'tcx>, snippet: &str) -> bool {
         let is_shared_ref = |ty: ty::Ty| match ty.sty {
             ty::TypeVariants::TyRef(.., hir::Mutability::MutImmutable) => true,
@@ -143,11 +143,9 @@ impl<'cg, 'cx, 'gcx, 'tcx> Visitor<'tcx> for ConstraintGeneration<'cg, 'cx, 'gcx
         // `(*X).foo` and so forth.
         if let Some(all_facts) = self.all_facts {
             if let Place::Local(temp) = place {
-                if let Some(borrow_indices) = self.borrow_set.local_map.get(temp) {
-                    for &borrow_index in borrow_indices {
-                        let location_index = self.location_table.mid_index(location);
-                        all_facts.killed.push((borrow_index, location_index));
-                    }
+                for &borrow_index in self.borrow_set.local_map.get_by_key(temp) {
+                    let location_index = self.location_table.mid_index(location);
+                    all_facts.killed.push((borrow_index, location_index));
                 }
             }
         }
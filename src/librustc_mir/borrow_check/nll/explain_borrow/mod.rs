@@ -8,11 +8,13 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use borrow_check::borrow_set::BorrowData;
+use borrow_check::borrow_set::{BorrowData, TwoPhaseActivation};
+use borrow_check::place_ext::PlaceExt;
 use borrow_check::nll::region_infer::Cause;
 use borrow_check::{Context, MirBorrowckCtxt, WriteKind};
-use rustc::mir::Place;
+use rustc::mir::{Local, Location, Place};
 use rustc_errors::DiagnosticBuilder;
+use syntax_pos::MultiSpan;
 
 mod find_use;
 
@@ -28,6 +30,8 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
     /// - `kind_place`: if Some, this describes the statement that triggered the error.
     ///   - first half is the kind of write, if any, being performed
     ///   - second half is the place being accessed
+    /// - `borrow_desc`: prefix naming the borrow in the labels, like `"first "`
+    ///   when the error is about two borrows of the same kind
     /// - `err`: where the error annotations are going to be added
     ///
     /// [d]: https://rust-lang.github.io/rfcs/2094-nll.html#leveraging-intuition-framing-errors-in-terms-of-points
@@ -36,6 +40,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         context: Context,
         borrow: &BorrowData<'tcx>,
         kind_place: Option<(WriteKind, &Place<'tcx>)>,
+        borrow_desc: &str,
         err: &mut DiagnosticBuilder<'_>,
     ) {
         debug!(
@@ -63,17 +68,41 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
 
         match find_use::find(mir, regioncx, tcx, region_sub, context.loc) {
             Some(Cause::LiveVar(_local, location)) => {
-                err.span_label(
-                    mir.source_info(location).span,
-                    format!("borrow later used here"),
-                );
+                // The use is found by walking forward from `context`, so if it
+                // also comes before `context` on every path, the borrow must
+                // have survived a trip around a loop.
+                let is_loop_use =
+                    location != context.loc && self.is_loop_use(location, context.loc);
+                let activated_here = !is_loop_use
+                    && borrow.activation_location == TwoPhaseActivation::ActivatedAt(location);
+                let msg = if is_loop_use {
+                    "borrow used here in later iteration of loop"
+                } else if activated_here {
+                    "borrow later activated here"
+                } else {
+                    "borrow later used here"
+                };
+                err.span_label(mir.source_info(location).span, format!("{}{}", borrow_desc, msg));
+                if activated_here {
+                    err.note(
+                        "a two-phase mutable borrow is only reserved where it occurs, \
+                         and is activated where it is first used mutably",
+                    );
+                }
             }
 
             Some(Cause::DropVar(local, location)) => match &mir.local_decls[local].name {
                 Some(local_name) => {
+                    // Point at the closing brace of the scope rather than the
+                    // whole span that is being dropped out of.
+                    let span = tcx.sess.codemap().end_point(mir.source_info(location).span);
                     err.span_label(
-                        mir.source_info(location).span,
-                        format!("borrow later used here, when `{}` is dropped", local_name),
+                        span,
+                        format!(
+                            "{}borrow might be used here, when `{}` is dropped \
+                             and runs its destructor",
+                            borrow_desc, local_name
+                        ),
                     );
 
                     if let Some((WriteKind::StorageDeadOrDrop, place)) = kind_place {
@@ -87,6 +116,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                                     "values in a scope are dropped \
                                      in the opposite order they are defined",
                                 );
+                                self.suggest_declaring_before(err, *borrowed_local, local);
                             }
                         }
                     }
@@ -106,5 +136,78 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                 }
             }
         }
+
+        self.explain_self_referential_borrow(borrow, err);
+    }
+
+    /// If the reference created by `borrow` is stored in another part of the
+    /// value it borrows from, as in `s.b = &s.a`, explains that the value now
+    /// refers to itself, which is what keeps it borrowed.
+    fn explain_self_referential_borrow(
+        &self,
+        borrow: &BorrowData<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+    ) {
+        let root = match borrow.borrowed_place.root_local() {
+            Some(root) if borrow.assigned_place.root_local() == Some(root) => root,
+            _ => return,
+        };
+        if borrow.assigned_place == Place::Local(root)
+            || borrow.borrowed_place == Place::Local(root)
+            || self.mir.local_decls[root].name.is_none()
+        {
+            return;
+        }
+
+        let describe = |place: &Place<'tcx>| self.describe_place(place).unwrap_or("_".to_owned());
+        err.note(&format!(
+            "the reference to `{}` is stored in `{}`, so `{}` holds a reference to its own data",
+            describe(&borrow.borrowed_place),
+            describe(&borrow.assigned_place),
+            describe(&Place::Local(root)),
+        ));
+        err.help(
+            "a value that refers to itself stays borrowed for as long as it exists; \
+             consider keeping the borrowed data outside of it, or storing an index instead",
+        );
+    }
+
+    /// If `borrowed` is declared after `dropped`, and so is dropped before it,
+    /// points at both declarations and suggests declaring `borrowed` first.
+    fn suggest_declaring_before(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        borrowed: Local,
+        dropped: Local,
+    ) {
+        let borrowed_decl = &self.mir.local_decls[borrowed];
+        let dropped_decl = &self.mir.local_decls[dropped];
+        let (borrowed_name, dropped_name) = match (borrowed_decl.name, dropped_decl.name) {
+            (Some(borrowed_name), Some(dropped_name)) => (borrowed_name, dropped_name),
+            _ => return,
+        };
+        let borrowed_span = borrowed_decl.source_info.span;
+        let dropped_span = dropped_decl.source_info.span;
+        if borrowed_span.lo() <= dropped_span.lo() {
+            return;
+        }
+
+        let mut spans = MultiSpan::from_span(borrowed_span);
+        spans.push_span_label(
+            borrowed_span,
+            format!("`{}` is declared later, so it is dropped first", borrowed_name),
+        );
+        spans.push_span_label(dropped_span, format!("`{}` is declared here", dropped_name));
+        err.span_help(
+            spans,
+            &format!("consider declaring `{}` before `{}`", borrowed_name, dropped_name),
+        );
+    }
+
+    /// Whether `use_location`, which is reachable from `location`, also
+    /// dominates it, i.e. reaching it again takes another loop iteration.
+    fn is_loop_use(&self, use_location: Location, location: Location) -> bool {
+        self.dominators.is_reachable(location.block)
+            && use_location.dominates(location, &self.dominators)
     }
 }
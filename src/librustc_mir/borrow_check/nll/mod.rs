@@ -21,6 +21,7 @@ use rustc::infer::InferCtxt;
 use rustc::mir::{ClosureOutlivesSubject, ClosureRegionRequirements, Mir};
 use rustc::ty::{self, RegionKind, RegionVid};
 use rustc::util::nodemap::FxHashMap;
+use rustc_errors::Diagnostic;
 use std::collections::BTreeSet;
 use std::fmt::Debug;
 use std::env;
@@ -28,6 +29,7 @@ use std::io;
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::str::FromStr;
+use std::sync::atomic::Ordering;
 use transform::MirSource;
 use util::liveness::{LivenessResults, LocalSet};
 
@@ -88,6 +90,7 @@ pub(in borrow_check) fn compute_regions<'cx, 'gcx, 'tcx>(
     flow_inits: &mut FlowAtLocation<MaybeInitializedPlaces<'cx, 'gcx, 'tcx>>,
     move_data: &MoveData<'tcx>,
     borrow_set: &BorrowSet<'tcx>,
+    errors_buffer: &mut Vec<Diagnostic>,
 ) -> (
     RegionInferenceContext<'tcx>,
     Option<Rc<Output<RegionVid, BorrowIndex, LocationIndex>>>,
@@ -99,21 +102,29 @@ pub(in borrow_check) fn compute_regions<'cx, 'gcx, 'tcx>(
         None
     };
 
+    let sess = infcx.tcx.sess;
+
     // Run the MIR type-checker.
-    let liveness = &LivenessResults::compute(mir);
-    let constraint_sets = type_check::type_check(
-        infcx,
-        param_env,
-        mir,
-        def_id,
-        &universal_regions,
-        location_table,
-        borrow_set,
-        &liveness,
-        &mut all_facts,
-        flow_inits,
-        move_data,
+    let liveness = &sess.profile_activity("NLL liveness", || LivenessResults::compute(mir));
+    sess.perf_stats.nll_liveness_iterations.fetch_add(
+        liveness.regular.iterations + liveness.drop.iterations,
+        Ordering::Relaxed,
     );
+    let constraint_sets = sess.profile_activity("NLL type check", || {
+        type_check::type_check(
+            infcx,
+            param_env,
+            mir,
+            def_id,
+            &universal_regions,
+            location_table,
+            borrow_set,
+            &liveness,
+            &mut all_facts,
+            flow_inits,
+            move_data,
+        )
+    });
 
     if let Some(all_facts) = &mut all_facts {
         all_facts
@@ -130,6 +141,10 @@ pub(in borrow_check) fn compute_regions<'cx, 'gcx, 'tcx>(
         outlives_constraints,
         type_tests,
     } = constraint_sets;
+    sess.perf_stats.nll_outlives_constraints.fetch_add(
+        outlives_constraints.len(),
+        Ordering::Relaxed,
+    );
     let mut regioncx = RegionInferenceContext::new(
         var_origins,
         universal_regions,
@@ -137,25 +152,31 @@ pub(in borrow_check) fn compute_regions<'cx, 'gcx, 'tcx>(
         outlives_constraints,
         type_tests,
     );
+    sess.perf_stats.nll_constraint_sccs.fetch_add(
+        regioncx.num_constraint_sccs(),
+        Ordering::Relaxed,
+    );
 
     // Generate various additional constraints.
-    constraint_generation::generate_constraints(
-        infcx,
-        &mut regioncx,
-        &mut all_facts,
-        location_table,
-        &mir,
-        borrow_set,
-        &liveness_set,
-    );
-    invalidation::generate_invalidates(
-        infcx,
-        &mut all_facts,
-        location_table,
-        &mir,
-        def_id,
-        borrow_set,
-    );
+    sess.profile_activity("NLL constraint generation", || {
+        constraint_generation::generate_constraints(
+            infcx,
+            &mut regioncx,
+            &mut all_facts,
+            location_table,
+            &mir,
+            borrow_set,
+            &liveness_set,
+        );
+        invalidation::generate_invalidates(
+            infcx,
+            &mut all_facts,
+            location_table,
+            &mir,
+            def_id,
+            borrow_set,
+        );
+    });
 
     // Dump facts if requested.
     let polonius_output = all_facts.and_then(|all_facts| {
@@ -182,7 +203,9 @@ pub(in borrow_check) fn compute_regions<'cx, 'gcx, 'tcx>(
     });
 
     // Solve the region constraints.
-    let closure_region_requirements = regioncx.solve(infcx, &mir, def_id);
+    let closure_region_requirements = sess.profile_activity("NLL region inference", || {
+        regioncx.solve(infcx, &mir, def_id, errors_buffer)
+    });
 
     // Dump MIR results into a file, if that is enabled. This let us
     // write unit-tests, as well as helping with debugging.
@@ -1107,7 +1107,7 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
             TerminatorKind::Goto { target } => {
                 self.assert_iscleanup(mir, block_data, target, is_cleanup)
             }
-            TerminatorKind::SwitchInt { ref targets, .. } => for target in targets {
+            TerminatorKind::SwitchInt { ref targets, .. } => for target in targets.all_targets() {
                 self.assert_iscleanup(mir, block_data, *target, is_cleanup);
             },
             TerminatorKind::Resume => if !is_cleanup {
@@ -1767,6 +1767,10 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
 pub struct TypeckMir;
 
 impl MirPass for TypeckMir {
+    fn required_phase(&self) -> Option<MirPhase> {
+        Some(MirPhase::Build)
+    }
+
     fn run_pass<'a, 'tcx>(&self, tcx: TyCtxt<'a, 'tcx, 'tcx>, src: MirSource, mir: &mut Mir<'tcx>) {
         let def_id = src.def_id;
         debug!("run_pass: {:?}", def_id);
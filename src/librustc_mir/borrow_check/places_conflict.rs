@@ -12,26 +12,55 @@ use borrow_check::ArtificialField;
 use borrow_check::Overlap;
 use borrow_check::{Deep, Shallow, ShallowOrDeep};
 use rustc::hir;
-use rustc::mir::{Mir, Place};
-use rustc::mir::{Projection, ProjectionElem};
+use rustc::mir::{BorrowKind, Mir, Place, PlaceProjection, ProjectionElem, ProjectionsIter};
 use rustc::ty::{self, TyCtxt};
 use std::cmp::max;
 
+/// Whether an access to `access_place` conflicts with `borrow_place`, where
+/// `borrow_place` is not necessarily borrowed; see `borrow_conflicts_with_place`
+/// for checking against an actual borrow.
 pub(super) fn places_conflict<'gcx, 'tcx>(
     tcx: TyCtxt<'_, 'gcx, 'tcx>,
     mir: &Mir<'tcx>,
     borrow_place: &Place<'tcx>,
     access_place: &Place<'tcx>,
     access: ShallowOrDeep,
+) -> bool {
+    borrow_conflicts_with_place(
+        tcx,
+        mir,
+        borrow_place,
+        BorrowKind::Mut { allow_two_phase_borrow: true },
+        access_place,
+        access,
+    )
+}
+
+/// Whether an access to `access_place` conflicts with a borrow of kind
+/// `borrow_kind` of `borrow_place`.
+pub(super) fn borrow_conflicts_with_place<'gcx, 'tcx>(
+    tcx: TyCtxt<'_, 'gcx, 'tcx>,
+    mir: &Mir<'tcx>,
+    borrow_place: &Place<'tcx>,
+    borrow_kind: BorrowKind,
+    access_place: &Place<'tcx>,
+    access: ShallowOrDeep,
 ) -> bool {
     debug!(
-        "places_conflict({:?},{:?},{:?})",
-        borrow_place, access_place, access
+        "borrow_conflicts_with_place({:?},{:?},{:?},{:?})",
+        borrow_place, borrow_kind, access_place, access
     );
 
-    unroll_place(borrow_place, None, |borrow_components| {
-        unroll_place(access_place, None, |access_components| {
-            place_components_conflict(tcx, mir, borrow_components, access_components, access)
+    borrow_place.iterate(|borrow_base, borrow_projections| {
+        access_place.iterate(|access_base, access_projections| {
+            place_components_conflict(
+                tcx,
+                mir,
+                (borrow_base, borrow_projections),
+                borrow_kind,
+                (access_base, access_projections),
+                access,
+            )
         })
     })
 }
@@ -39,8 +68,9 @@ pub(super) fn places_conflict<'gcx, 'tcx>(
 fn place_components_conflict<'gcx, 'tcx>(
     tcx: TyCtxt<'_, 'gcx, 'tcx>,
     mir: &Mir<'tcx>,
-    mut borrow_components: PlaceComponentsIter<'_, 'tcx>,
-    mut access_components: PlaceComponentsIter<'_, 'tcx>,
+    borrow_components: (&Place<'tcx>, ProjectionsIter<'_, 'tcx>),
+    borrow_kind: BorrowKind,
+    access_components: (&Place<'tcx>, ProjectionsIter<'_, 'tcx>),
     access: ShallowOrDeep,
 ) -> bool {
     // The borrowck rules for proving disjointness are applied from the "root" of the
@@ -84,12 +114,32 @@ fn place_components_conflict<'gcx, 'tcx>(
     //  - If we didn't run out of access to match, our borrow and access are comparable
     //    and either equal or disjoint.
     //  - If we did run out of accesss, the borrow can access a part of it.
+
+    let (borrow_base, mut borrow_projections) = borrow_components;
+    let (access_base, mut access_projections) = access_components;
+
+    // The places start from the same local or static, or are disjoint.
+    match place_base_conflict(tcx, borrow_base, access_base) {
+        Overlap::Arbitrary => {
+            bug!("Two bases can't return Arbitrary");
+        }
+        Overlap::EqualOrDisjoint => {
+            // This is the recursive case - proceed to the next element.
+        }
+        Overlap::Disjoint => {
+            // We have proven the borrow disjoint - further
+            // projections will remain disjoint.
+            debug!("places_conflict: disjoint");
+            return false;
+        }
+    }
+
     loop {
         // loop invariant: borrow_c is always either equal to access_c or disjoint from it.
-        if let Some(borrow_c) = borrow_components.next() {
+        if let Some(borrow_c) = borrow_projections.next() {
             debug!("places_conflict: borrow_c = {:?}", borrow_c);
 
-            if let Some(access_c) = access_components.next() {
+            if let Some(access_c) = access_projections.next() {
                 debug!("places_conflict: access_c = {:?}", access_c);
 
                 // Borrow and access path both have more components.
@@ -104,7 +154,7 @@ fn place_components_conflict<'gcx, 'tcx>(
                 // check whether the components being borrowed vs
                 // accessed are disjoint (as in the second example,
                 // but not the first).
-                match place_element_conflict(tcx, mir, borrow_c, access_c) {
+                match place_projection_conflict(tcx, mir, borrow_c, access_c) {
                     Overlap::Arbitrary => {
                         // We have encountered different fields of potentially
                         // the same union - the borrow now partially overlaps.
@@ -141,15 +191,12 @@ fn place_components_conflict<'gcx, 'tcx>(
                 // our place. This is a conflict if that is a part our
                 // access cares about.
 
-                let (base, elem) = match borrow_c {
-                    Place::Projection(box Projection { base, elem }) => (base, elem),
-                    _ => bug!("place has no base?"),
-                };
-                let base_ty = base.ty(mir, tcx).to_ty(tcx);
+                let base_ty = borrow_c.base.ty(mir, tcx).to_ty(tcx);
 
-                match (elem, &base_ty.sty, access) {
+                match (&borrow_c.elem, &base_ty.sty, access) {
                     (_, _, Shallow(Some(ArtificialField::Discriminant)))
-                    | (_, _, Shallow(Some(ArtificialField::ArrayLength))) => {
+                    | (_, _, Shallow(Some(ArtificialField::ArrayLength)))
+                    | (_, _, Shallow(Some(ArtificialField::ShallowBorrow))) => {
                         // The discriminant and array length are like
                         // additional fields on the type; they do not
                         // overlap any existing data there. Furthermore,
@@ -158,7 +205,9 @@ fn place_components_conflict<'gcx, 'tcx>(
                         // currently.)
                         //
                         // e.g. a (mutable) borrow of `a[5]` while we read the
-                        // array length of `a`.
+                        // array length of `a`. A shallow borrow is treated
+                        // the same way, e.g. a shallow borrow of `a` while
+                        // `a.b` is mutably borrowed.
                         debug!("places_conflict: implicit field");
                         return false;
                     }
@@ -208,96 +257,26 @@ fn place_components_conflict<'gcx, 'tcx>(
             // that the borrow can access a *part* of our place that
             // our access cares about, so we still have a conflict.
             //
+            // A shallow borrow only covers the borrowed place itself,
+            // so it doesn't conflict with accesses to parts of it, as
+            // in the first example.
+            //
             // FIXME: Differs from AST-borrowck; includes drive-by fix
             // to #38899. Will probably need back-compat mode flag.
+            if borrow_kind == BorrowKind::Shallow && access_projections.next().is_some() {
+                debug!("places_conflict: shallow borrow of a prefix, disjoint");
+                return false;
+            }
             debug!("places_conflict: full borrow, CONFLICT");
             return true;
         }
     }
 }
 
-/// A linked list of places running up the stack; begins with the
-/// innermost place and extends to projections (e.g., `a.b` would have
-/// the place `a` with a "next" pointer to `a.b`).  Created by
-/// `unroll_place`.
-///
-/// NB: This particular impl strategy is not the most obvious.  It was
-/// chosen because it makes a measurable difference to NLL
-/// performance, as this code (`places_conflict`) is somewhat hot.
-struct PlaceComponents<'p, 'tcx: 'p> {
-    component: &'p Place<'tcx>,
-    next: Option<&'p PlaceComponents<'p, 'tcx>>,
-}
-
-impl<'p, 'tcx> PlaceComponents<'p, 'tcx> {
-    /// Converts a list of `Place` components into an iterator; this
-    /// iterator yields up a never-ending stream of `Option<&Place>`.
-    /// These begin with the "innermst" place and then with each
-    /// projection therefrom. So given a place like `a.b.c` it would
-    /// yield up:
-    ///
-    /// ```notrust
-    /// Some(`a`), Some(`a.b`), Some(`a.b.c`), None, None, ...
-    /// ```
-    fn iter(&self) -> PlaceComponentsIter<'_, 'tcx> {
-        PlaceComponentsIter { value: Some(self) }
-    }
-}
-
-/// Iterator over components; see `PlaceComponents::iter` for more
-/// information.
-///
-/// NB: This is not a *true* Rust iterator -- the code above just
-/// manually invokes `next`. This is because we (sometimes) want to
-/// keep executing even after `None` has been returned.
-struct PlaceComponentsIter<'p, 'tcx: 'p> {
-    value: Option<&'p PlaceComponents<'p, 'tcx>>,
-}
-
-impl<'p, 'tcx> PlaceComponentsIter<'p, 'tcx> {
-    fn next(&mut self) -> Option<&'p Place<'tcx>> {
-        if let Some(&PlaceComponents { component, next }) = self.value {
-            self.value = next;
-            Some(component)
-        } else {
-            None
-        }
-    }
-}
-
-/// Recursively "unroll" a place into a `PlaceComponents` list,
-/// invoking `op` with a `PlaceComponentsIter`.
-fn unroll_place<'tcx, R>(
-    place: &Place<'tcx>,
-    next: Option<&PlaceComponents<'_, 'tcx>>,
-    op: impl FnOnce(PlaceComponentsIter<'_, 'tcx>) -> R,
-) -> R {
-    match place {
-        Place::Projection(interior) => unroll_place(
-            &interior.base,
-            Some(&PlaceComponents {
-                component: place,
-                next,
-            }),
-            op,
-        ),
-
-        Place::Local(_) | Place::Static(_) => {
-            let list = PlaceComponents {
-                component: place,
-                next,
-            };
-            op(list.iter())
-        }
-    }
-}
-
-// Given that the bases of `elem1` and `elem2` are always either equal
-// or disjoint (and have the same type!), return the overlap situation
-// between `elem1` and `elem2`.
-fn place_element_conflict<'a, 'gcx: 'tcx, 'tcx>(
+// Given two places without projections, i.e. locals or statics, return
+// the overlap situation between `elem1` and `elem2`.
+fn place_base_conflict<'a, 'gcx: 'tcx, 'tcx>(
     tcx: TyCtxt<'a, 'gcx, 'tcx>,
-    mir: &Mir<'tcx>,
     elem1: &Place<'tcx>,
     elem2: &Place<'tcx>,
 ) -> Overlap {
@@ -330,159 +309,168 @@ fn place_element_conflict<'a, 'gcx: 'tcx, 'tcx>(
             debug!("place_element_conflict: DISJOINT-STATIC-LOCAL");
             Overlap::Disjoint
         }
-        (Place::Projection(pi1), Place::Projection(pi2)) => {
-            match (&pi1.elem, &pi2.elem) {
-                (ProjectionElem::Deref, ProjectionElem::Deref) => {
-                    // derefs (e.g. `*x` vs. `*x`) - recur.
-                    debug!("place_element_conflict: DISJOINT-OR-EQ-DEREF");
-                    Overlap::EqualOrDisjoint
-                }
-                (ProjectionElem::Field(f1, _), ProjectionElem::Field(f2, _)) => {
-                    if f1 == f2 {
-                        // same field (e.g. `a.y` vs. `a.y`) - recur.
-                        debug!("place_element_conflict: DISJOINT-OR-EQ-FIELD");
-                        Overlap::EqualOrDisjoint
-                    } else {
-                        let ty = pi1.base.ty(mir, tcx).to_ty(tcx);
-                        match ty.sty {
-                            ty::TyAdt(def, _) if def.is_union() => {
-                                // Different fields of a union, we are basically stuck.
-                                debug!("place_element_conflict: STUCK-UNION");
-                                Overlap::Arbitrary
-                            }
-                            _ => {
-                                // Different fields of a struct (`a.x` vs. `a.y`). Disjoint!
-                                debug!("place_element_conflict: DISJOINT-FIELD");
-                                Overlap::Disjoint
-                            }
-                        }
+        (Place::Projection(_), _) | (_, Place::Projection(_)) => bug!(
+            "unexpected elements in place_base_conflict: {:?} and {:?}",
+            elem1,
+            elem2
+        ),
+    }
+}
+
+// Given that the bases of `pi1` and `pi2` are always either equal
+// or disjoint (and have the same type!), return the overlap situation
+// between `pi1` and `pi2`.
+fn place_projection_conflict<'a, 'gcx: 'tcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    mir: &Mir<'tcx>,
+    pi1: &PlaceProjection<'tcx>,
+    pi2: &PlaceProjection<'tcx>,
+) -> Overlap {
+    match (&pi1.elem, &pi2.elem) {
+        (ProjectionElem::Deref, ProjectionElem::Deref) => {
+            // derefs (e.g. `*x` vs. `*x`) - recur.
+            debug!("place_element_conflict: DISJOINT-OR-EQ-DEREF");
+            Overlap::EqualOrDisjoint
+        }
+        (ProjectionElem::Field(f1, _), ProjectionElem::Field(f2, _)) => {
+            if f1 == f2 {
+                // same field (e.g. `a.y` vs. `a.y`) - recur.
+                debug!("place_element_conflict: DISJOINT-OR-EQ-FIELD");
+                Overlap::EqualOrDisjoint
+            } else {
+                let ty = pi1.base.ty(mir, tcx).to_ty(tcx);
+                match ty.sty {
+                    ty::TyAdt(def, _) if def.is_union() => {
+                        // Different fields of a union, we are basically stuck.
+                        debug!("place_element_conflict: STUCK-UNION");
+                        Overlap::Arbitrary
                     }
-                }
-                (ProjectionElem::Downcast(_, v1), ProjectionElem::Downcast(_, v2)) => {
-                    // different variants are treated as having disjoint fields,
-                    // even if they occupy the same "space", because it's
-                    // impossible for 2 variants of the same enum to exist
-                    // (and therefore, to be borrowed) at the same time.
-                    //
-                    // Note that this is different from unions - we *do* allow
-                    // this code to compile:
-                    //
-                    // ```
-                    // fn foo(x: &mut Result<i32, i32>) {
-                    //     let mut v = None;
-                    //     if let Ok(ref mut a) = *x {
-                    //         v = Some(a);
-                    //     }
-                    //     // here, you would *think* that the
-                    //     // *entirety* of `x` would be borrowed,
-                    //     // but in fact only the `Ok` variant is,
-                    //     // so the `Err` variant is *entirely free*:
-                    //     if let Err(ref mut a) = *x {
-                    //         v = Some(a);
-                    //     }
-                    //     drop(v);
-                    // }
-                    // ```
-                    if v1 == v2 {
-                        debug!("place_element_conflict: DISJOINT-OR-EQ-FIELD");
-                        Overlap::EqualOrDisjoint
-                    } else {
+                    _ => {
+                        // Different fields of a struct (`a.x` vs. `a.y`). Disjoint!
                         debug!("place_element_conflict: DISJOINT-FIELD");
                         Overlap::Disjoint
                     }
                 }
-                (ProjectionElem::Index(..), ProjectionElem::Index(..))
-                | (ProjectionElem::Index(..), ProjectionElem::ConstantIndex { .. })
-                | (ProjectionElem::Index(..), ProjectionElem::Subslice { .. })
-                | (ProjectionElem::ConstantIndex { .. }, ProjectionElem::Index(..))
-                | (ProjectionElem::Subslice { .. }, ProjectionElem::Index(..)) => {
-                    // Array indexes (`a[0]` vs. `a[i]`). These can either be disjoint
-                    // (if the indexes differ) or equal (if they are the same), so this
-                    // is the recursive case that gives "equal *or* disjoint" its meaning.
-                    debug!("place_element_conflict: DISJOINT-OR-EQ-ARRAY-INDEX");
-                    Overlap::EqualOrDisjoint
-                }
-                (ProjectionElem::ConstantIndex { offset: o1, min_length: _, from_end: false },
-                    ProjectionElem::ConstantIndex { offset: o2, min_length: _, from_end: false })
-                | (ProjectionElem::ConstantIndex { offset: o1, min_length: _, from_end: true },
-                    ProjectionElem::ConstantIndex {
-                        offset: o2, min_length: _, from_end: true }) => {
-                    if o1 == o2 {
-                        debug!("place_element_conflict: DISJOINT-OR-EQ-ARRAY-CONSTANT-INDEX");
-                        Overlap::EqualOrDisjoint
-                    } else {
-                        debug!("place_element_conflict: DISJOINT-ARRAY-CONSTANT-INDEX");
-                        Overlap::Disjoint
-                    }
-                }
-                (ProjectionElem::ConstantIndex {
-                    offset: offset_from_begin, min_length: min_length1, from_end: false },
-                    ProjectionElem::ConstantIndex {
-                        offset: offset_from_end, min_length: min_length2, from_end: true })
-                | (ProjectionElem::ConstantIndex {
-                    offset: offset_from_end, min_length: min_length1, from_end: true },
-                   ProjectionElem::ConstantIndex {
-                       offset: offset_from_begin, min_length: min_length2, from_end: false }) => {
-                    // both patterns matched so it must be at least the greater of the two
-                    let min_length = max(min_length1, min_length2);
-                    // `offset_from_end` can be in range `[1..min_length]`, 1 indicates the last
-                    // element (like -1 in Python) and `min_length` the first.
-                    // Therefore, `min_length - offset_from_end` gives the minimal possible
-                    // offset from the beginning
-                    if *offset_from_begin >= min_length - offset_from_end {
-                        debug!("place_element_conflict: DISJOINT-OR-EQ-ARRAY-CONSTANT-INDEX-FE");
-                        Overlap::EqualOrDisjoint
-                    } else {
-                        debug!("place_element_conflict: DISJOINT-ARRAY-CONSTANT-INDEX-FE");
-                        Overlap::Disjoint
-                    }
-                }
-                (ProjectionElem::ConstantIndex { offset, min_length: _, from_end: false },
-                 ProjectionElem::Subslice {from, .. })
-                | (ProjectionElem::Subslice {from, .. },
-                    ProjectionElem::ConstantIndex { offset, min_length: _, from_end: false }) => {
-                    if offset >= from {
-                        debug!(
-                            "place_element_conflict: DISJOINT-OR-EQ-ARRAY-CONSTANT-INDEX-SUBSLICE");
-                        Overlap::EqualOrDisjoint
-                    } else {
-                        debug!("place_element_conflict: DISJOINT-ARRAY-CONSTANT-INDEX-SUBSLICE");
-                        Overlap::Disjoint
-                    }
-                }
-                (ProjectionElem::ConstantIndex { offset, min_length: _, from_end: true },
-                 ProjectionElem::Subslice {from: _, to })
-                | (ProjectionElem::Subslice {from: _, to },
-                    ProjectionElem::ConstantIndex { offset, min_length: _, from_end: true }) => {
-                    if offset > to {
-                        debug!("place_element_conflict: \
-                               DISJOINT-OR-EQ-ARRAY-CONSTANT-INDEX-SUBSLICE-FE");
-                        Overlap::EqualOrDisjoint
-                    } else {
-                        debug!("place_element_conflict: DISJOINT-ARRAY-CONSTANT-INDEX-SUBSLICE-FE");
-                        Overlap::Disjoint
-                    }
-                }
-                (ProjectionElem::Subslice { .. }, ProjectionElem::Subslice { .. }) => {
-                    debug!("place_element_conflict: DISJOINT-OR-EQ-ARRAY-SUBSLICES");
-                     Overlap::EqualOrDisjoint
-                }
-                (ProjectionElem::Deref, _)
-                | (ProjectionElem::Field(..), _)
-                | (ProjectionElem::Index(..), _)
-                | (ProjectionElem::ConstantIndex { .. }, _)
-                | (ProjectionElem::Subslice { .. }, _)
-                | (ProjectionElem::Downcast(..), _) => bug!(
-                    "mismatched projections in place_element_conflict: {:?} and {:?}",
-                    elem1,
-                    elem2
-                ),
             }
         }
-        (Place::Projection(_), _) | (_, Place::Projection(_)) => bug!(
-            "unexpected elements in place_element_conflict: {:?} and {:?}",
-            elem1,
-            elem2
+        (ProjectionElem::Downcast(_, v1), ProjectionElem::Downcast(_, v2)) => {
+            // different variants are treated as having disjoint fields,
+            // even if they occupy the same "space", because it's
+            // impossible for 2 variants of the same enum to exist
+            // (and therefore, to be borrowed) at the same time.
+            //
+            // Note that this is different from unions - we *do* allow
+            // this code to compile:
+            //
+            // ```
+            // fn foo(x: &mut Result<i32, i32>) {
+            //     let mut v = None;
+            //     if let Ok(ref mut a) = *x {
+            //         v = Some(a);
+            //     }
+            //     // here, you would *think* that the
+            //     // *entirety* of `x` would be borrowed,
+            //     // but in fact only the `Ok` variant is,
+            //     // so the `Err` variant is *entirely free*:
+            //     if let Err(ref mut a) = *x {
+            //         v = Some(a);
+            //     }
+            //     drop(v);
+            // }
+            // ```
+            if v1 == v2 {
+                debug!("place_element_conflict: DISJOINT-OR-EQ-FIELD");
+                Overlap::EqualOrDisjoint
+            } else {
+                debug!("place_element_conflict: DISJOINT-FIELD");
+                Overlap::Disjoint
+            }
+        }
+        (ProjectionElem::Index(..), ProjectionElem::Index(..))
+        | (ProjectionElem::Index(..), ProjectionElem::ConstantIndex { .. })
+        | (ProjectionElem::Index(..), ProjectionElem::Subslice { .. })
+        | (ProjectionElem::ConstantIndex { .. }, ProjectionElem::Index(..))
+        | (ProjectionElem::Subslice { .. }, ProjectionElem::Index(..)) => {
+            // Array indexes (`a[0]` vs. `a[i]`). These can either be disjoint
+            // (if the indexes differ) or equal (if they are the same), so this
+            // is the recursive case that gives "equal *or* disjoint" its meaning.
+            debug!("place_element_conflict: DISJOINT-OR-EQ-ARRAY-INDEX");
+            Overlap::EqualOrDisjoint
+        }
+        (ProjectionElem::ConstantIndex { offset: o1, min_length: _, from_end: false },
+            ProjectionElem::ConstantIndex { offset: o2, min_length: _, from_end: false })
+        | (ProjectionElem::ConstantIndex { offset: o1, min_length: _, from_end: true },
+            ProjectionElem::ConstantIndex {
+                offset: o2, min_length: _, from_end: true }) => {
+            if o1 == o2 {
+                debug!("place_element_conflict: DISJOINT-OR-EQ-ARRAY-CONSTANT-INDEX");
+                Overlap::EqualOrDisjoint
+            } else {
+                debug!("place_element_conflict: DISJOINT-ARRAY-CONSTANT-INDEX");
+                Overlap::Disjoint
+            }
+        }
+        (ProjectionElem::ConstantIndex {
+            offset: offset_from_begin, min_length: min_length1, from_end: false },
+            ProjectionElem::ConstantIndex {
+                offset: offset_from_end, min_length: min_length2, from_end: true })
+        | (ProjectionElem::ConstantIndex {
+            offset: offset_from_end, min_length: min_length1, from_end: true },
+           ProjectionElem::ConstantIndex {
+               offset: offset_from_begin, min_length: min_length2, from_end: false }) => {
+            // both patterns matched so it must be at least the greater of the two
+            let min_length = max(min_length1, min_length2);
+            // `offset_from_end` can be in range `[1..min_length]`, 1 indicates the last
+            // element (like -1 in Python) and `min_length` the first.
+            // Therefore, `min_length - offset_from_end` gives the minimal possible
+            // offset from the beginning
+            if *offset_from_begin >= min_length - offset_from_end {
+                debug!("place_element_conflict: DISJOINT-OR-EQ-ARRAY-CONSTANT-INDEX-FE");
+                Overlap::EqualOrDisjoint
+            } else {
+                debug!("place_element_conflict: DISJOINT-ARRAY-CONSTANT-INDEX-FE");
+                Overlap::Disjoint
+            }
+        }
+        (ProjectionElem::ConstantIndex { offset, min_length: _, from_end: false },
+         ProjectionElem::Subslice {from, .. })
+        | (ProjectionElem::Subslice {from, .. },
+            ProjectionElem::ConstantIndex { offset, min_length: _, from_end: false }) => {
+            if offset >= from {
+                debug!(
+                    "place_element_conflict: DISJOINT-OR-EQ-ARRAY-CONSTANT-INDEX-SUBSLICE");
+                Overlap::EqualOrDisjoint
+            } else {
+                debug!("place_element_conflict: DISJOINT-ARRAY-CONSTANT-INDEX-SUBSLICE");
+                Overlap::Disjoint
+            }
+        }
+        (ProjectionElem::ConstantIndex { offset, min_length: _, from_end: true },
+         ProjectionElem::Subslice {from: _, to })
+        | (ProjectionElem::Subslice {from: _, to },
+            ProjectionElem::ConstantIndex { offset, min_length: _, from_end: true }) => {
+            if offset > to {
+                debug!("place_element_conflict: \
+                       DISJOINT-OR-EQ-ARRAY-CONSTANT-INDEX-SUBSLICE-FE");
+                Overlap::EqualOrDisjoint
+            } else {
+                debug!("place_element_conflict: DISJOINT-ARRAY-CONSTANT-INDEX-SUBSLICE-FE");
+                Overlap::Disjoint
+            }
+        }
+        (ProjectionElem::Subslice { .. }, ProjectionElem::Subslice { .. }) => {
+            debug!("place_element_conflict: DISJOINT-OR-EQ-ARRAY-SUBSLICES");
+             Overlap::EqualOrDisjoint
+        }
+        (ProjectionElem::Deref, _)
+        | (ProjectionElem::Field(..), _)
+        | (ProjectionElem::Index(..), _)
+        | (ProjectionElem::ConstantIndex { .. }, _)
+        | (ProjectionElem::Subslice { .. }, _)
+        | (ProjectionElem::Downcast(..), _) => bug!(
+            "mismatched projections in place_element_conflict: {:?} and {:?}",
+            pi1,
+            pi2
         ),
     }
 }
@@ -97,14 +97,14 @@ impl<'cx, 'gcx, 'tcx> Iterator for Prefixes<'cx, 'gcx, 'tcx> {
         // downcasts here, but may return a base of a downcast).
 
         'cursor: loop {
-            let proj = match *cursor {
-                Place::Local(_) | // search yielded this leaf
-                Place::Static(_) => {
+            let proj = match cursor.last_projection() {
+                None => {
+                    // search yielded this leaf
                     self.next = None;
                     return Some(cursor);
                 }
 
-                Place::Projection(ref proj) => proj,
+                Some(proj) => proj,
             };
 
             match proj.elem {
@@ -0,0 +1,148 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use rustc::mir::{BorrowKind, BorrowSummary, BorrowckSummary, Local, Location, Mutability};
+use rustc::mir::{Place, ProjectionElem, VariableAccess, VariableAccessKind};
+use rustc_data_structures::indexed_vec::Idx;
+use syntax_pos::Span;
+
+use borrow_check::borrow_set::BorrowData;
+use borrow_check::flows::Flows;
+use borrow_check::{MirBorrowckCtxt, ReadKind, ReadOrWrite, WriteKind};
+
+impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
+    /// Collects what borrowck computed for this body, for the `mir_borrowck_summary`
+    /// query. Must be called once the `used_mut` set is complete.
+    crate fn summarize(&self) -> BorrowckSummary {
+        let borrows = self
+            .borrow_set
+            .borrows
+            .iter()
+            .map(|borrow| BorrowSummary {
+                kind: borrow.kind,
+                span: self.retrieve_borrow_span(borrow),
+                region: borrow.region.index() as u32,
+                borrowed_place: self.describe_place(&borrow.borrowed_place),
+                live_spans: self.live_spans(borrow),
+            })
+            .collect();
+
+        let moves = self
+            .move_data
+            .moves
+            .iter()
+            .map(|move_out| self.mir.source_info(move_out.source).span)
+            .collect();
+
+        let mut used_mut: Vec<Local> = self
+            .used_mut
+            .iter()
+            .filter(|&&local| {
+                let local_decl = &self.mir.local_decls[local];
+                local_decl.is_user_variable.is_some() && local_decl.mutability == Mutability::Mut
+            })
+            .cloned()
+            .collect();
+        used_mut.sort();
+        let used_mut_locals = used_mut
+            .into_iter()
+            .map(|local| self.mir.local_decls[local].source_info.span)
+            .collect();
+
+        BorrowckSummary {
+            borrows,
+            moves,
+            used_mut_locals,
+            accesses: self.accesses.clone(),
+        }
+    }
+
+    /// Records an access to a user variable for the summary. The write that
+    /// initializes a variable is part of its declaration and isn't recorded.
+    pub(super) fn record_access(
+        &mut self,
+        (place, span): (&Place<'tcx>, Span),
+        rw: ReadOrWrite,
+        flow_state: &Flows<'cx, 'gcx, 'tcx>,
+    ) {
+        let mut kind = match rw {
+            // Matching on a place only inspects it, the bindings of the
+            // patterns record whether it is moved or borrowed.
+            ReadOrWrite::Read(ReadKind::Copy) |
+            ReadOrWrite::Read(ReadKind::Borrow(BorrowKind::Shallow)) => VariableAccessKind::Read,
+            ReadOrWrite::Read(ReadKind::Borrow(_)) => VariableAccessKind::SharedBorrow,
+            ReadOrWrite::Write(WriteKind::Mutate) => VariableAccessKind::Write,
+            ReadOrWrite::Write(WriteKind::Move) => VariableAccessKind::Move,
+            ReadOrWrite::Write(WriteKind::MutableBorrow(_)) |
+            ReadOrWrite::Reservation(WriteKind::MutableBorrow(_)) => VariableAccessKind::MutBorrow,
+            // Drops and `StorageDead` end the variable rather than use it, and
+            // a two-phase borrow is recorded when it is reserved.
+            ReadOrWrite::Write(WriteKind::StorageDeadOrDrop) |
+            ReadOrWrite::Reservation(_) |
+            ReadOrWrite::Activation(..) => return,
+        };
+
+        let mut base = place;
+        let local = loop {
+            match *base {
+                Place::Local(local) => break local,
+                Place::Static(_) => return,
+                Place::Projection(ref proj) => {
+                    if let ProjectionElem::Deref = proj.elem {
+                        if !proj.base.ty(self.mir, self.tcx).to_ty(self.tcx).is_box() {
+                            kind = VariableAccessKind::Read;
+                        }
+                    }
+                    base = &proj.base;
+                }
+            }
+        };
+
+        let local_decl = &self.mir.local_decls[local];
+        if local_decl.is_user_variable.is_none() {
+            return;
+        }
+
+        if kind == VariableAccessKind::Write {
+            let mpi = self.move_data.rev_lookup.find_local(local);
+            let initialized = self.move_data.init_path_map[mpi]
+                .iter()
+                .any(|index| flow_state.ever_inits.contains(index));
+            if !initialized {
+                return;
+            }
+        }
+
+        self.accesses.push(VariableAccess {
+            kind,
+            span,
+            var_span: local_decl.source_info.span,
+        });
+    }
+
+    /// The spans of the statements and terminators at which the region of
+    /// `borrow` is live, in MIR order and without consecutive duplicates.
+    fn live_spans(&self, borrow: &BorrowData<'tcx>) -> Vec<Span> {
+        let mut spans: Vec<Span> = vec![];
+        for (block, data) in self.mir.basic_blocks().iter_enumerated() {
+            for statement_index in 0..=data.statements.len() {
+                let location = Location { block, statement_index };
+                if !self.nonlexical_regioncx.region_contains(borrow.region, location) {
+                    continue;
+                }
+                let span = self.mir.source_info(location).span;
+                if spans.last() != Some(&span) {
+                    spans.push(span);
+                }
+            }
+        }
+        spans
+    }
+}
@@ -0,0 +1,726 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Builds the MIR of a function from its textual form, for functions
+//! annotated with `#[rustc_custom_mir = "..."]`.
+//!
+//! This lets the MIR passes be tested on exactly the control flow graph
+//! they are meant to handle, instead of on whatever the MIR builder happens
+//! to produce for some surface Rust. The attribute holds the body of the
+//! function as `-Z dump-mir` prints it between the braces, e.g.
+//!
+//! ```text
+//! let mut _2: bool;
+//!
+//! bb0: {
+//!     _2 = Lt(_1, const 10i32);
+//!     switchInt(move _2) -> [false: bb1, otherwise: bb2];
+//! }
+//! ...
+//! ```
+//!
+//! The return place and the arguments come from the signature of the
+//! function, so only the other locals are declared; they must be declared
+//! in order. Only a subset of MIR is understood:
+//!
+//! - locals of primitive, tuple, reference and raw pointer types;
+//! - `StorageLive`, `StorageDead`, `nop` and assignments of `Use`, `Ref`,
+//!   `Cast`, (checked) binary and unary operations and tuple aggregates;
+//! - the `goto`, `switchInt`, `drop`, `return`, `resume`, `abort` and
+//!   `unreachable` terminators.
+//!
+//! Cleanup blocks are written `bbN (cleanup): { ... }`. Comments are
+//! ignored, so the `-Z dump-mir` output of such a body parses back into
+//! the same MIR.
+
+use build::Builder;
+use hair::cx::Cx;
+use rustc::mir::*;
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc::ty::layout::{Integer, IntegerExt};
+use rustc::util::nodemap::FxHashSet;
+use rustc_data_structures::indexed_vec::Idx;
+use std::fmt;
+use syntax::ast;
+use syntax::attr::IntType;
+
+type PResult<T> = Result<T, String>;
+
+pub fn construct_custom<'a, 'gcx, 'tcx>(hir: Cx<'a, 'gcx, 'tcx>,
+                                        fn_id: ast::NodeId,
+                                        source: &str)
+                                        -> PResult<Mir<'tcx>> {
+    let tcx = hir.tcx();
+    let fn_hir_id = tcx.hir.node_to_hir_id(fn_id);
+    let fn_sig = hir.tables().liberated_fn_sigs()[fn_hir_id].clone();
+    let span = tcx.hir.span(fn_id);
+
+    let mut builder = Builder::new(hir, span, fn_sig.inputs().len(), Safety::Safe,
+                                   fn_sig.output());
+    for &ty in fn_sig.inputs() {
+        builder.local_decls.push(LocalDecl::new_temp(ty, span));
+    }
+
+    {
+        let mut parser = Parser {
+            builder: &mut builder,
+            tokens: tokenize(source)?,
+            pos: 0,
+            defined: FxHashSet(),
+        };
+        parser.parse_body()?;
+    }
+
+    Ok(builder.finish(vec![], None))
+}
+
+#[derive(Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    /// An integer literal, including its suffix.
+    Literal(String),
+    Punct(char),
+    Eof,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Token::Ident(ref word) | Token::Literal(ref word) => write!(fmt, "`{}`", word),
+            Token::Punct(c) => write!(fmt, "`{}`", c),
+            Token::Eof => write!(fmt, "end of input"),
+        }
+    }
+}
+
+/// Splits `source` into tokens, each paired with the line it is on.
+fn tokenize(source: &str) -> PResult<Vec<(Token, usize)>> {
+    let mut tokens = vec![];
+    let mut line = 1;
+    let mut chars = source.chars().peekable();
+    while let Some(first) = chars.next() {
+        match first {
+            '\n' => line += 1,
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.peek().map_or(false, |&c| c != '\n') {
+                    chars.next();
+                }
+            }
+            c if c.is_whitespace() => {}
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                let token = if first.is_digit(10) {
+                    Token::Literal(word)
+                } else {
+                    Token::Ident(word)
+                };
+                tokens.push((token, line));
+            }
+            '{' | '}' | '(' | ')' | '[' | ']' | ';' | ':' | ',' | '=' | '&' | '*' | '-' | '>' |
+            '.' | '!' => tokens.push((Token::Punct(first), line)),
+            c => return Err(format!("line {}: unexpected character `{}`", line, c)),
+        }
+    }
+    tokens.push((Token::Eof, line));
+    Ok(tokens)
+}
+
+struct Parser<'p, 'a: 'p, 'gcx: 'a+'tcx, 'tcx: 'a> {
+    builder: &'p mut Builder<'a, 'gcx, 'tcx>,
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    /// The blocks whose body has been seen, as opposed to blocks that
+    /// have only been jumped to so far.
+    defined: FxHashSet<BasicBlock>,
+}
+
+impl<'p, 'a, 'gcx, 'tcx> Parser<'p, 'a, 'gcx, 'tcx> {
+    fn tcx(&self) -> TyCtxt<'a, 'gcx, 'tcx> {
+        self.builder.hir.tcx()
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.pos].0
+    }
+
+    fn line(&self) -> usize {
+        self.tokens[self.pos].1
+    }
+
+    fn bump(&mut self) -> Token {
+        let token = self.token().clone();
+        if token != Token::Eof {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn unexpected<T>(&self, expected: &str) -> PResult<T> {
+        Err(format!("line {}: expected {}, found {}", self.line(), expected, self.token()))
+    }
+
+    fn eat_punct(&mut self, c: char) -> bool {
+        if *self.token() == Token::Punct(c) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_punct(&mut self, c: char) -> PResult<()> {
+        if self.eat_punct(c) {
+            Ok(())
+        } else {
+            self.unexpected(&format!("`{}`", c))
+        }
+    }
+
+    fn eat_ident(&mut self, name: &str) -> bool {
+        let found = match *self.token() {
+            Token::Ident(ref word) => word == name,
+            _ => false,
+        };
+        if found {
+            self.bump();
+        }
+        found
+    }
+
+    fn expect_ident(&mut self, name: &str) -> PResult<()> {
+        if self.eat_ident(name) {
+            Ok(())
+        } else {
+            self.unexpected(&format!("`{}`", name))
+        }
+    }
+
+    fn expect_arrow(&mut self) -> PResult<()> {
+        self.expect_punct('-')?;
+        self.expect_punct('>')
+    }
+
+    /// Parses an identifier made of `prefix` and a number, like `_3` or `bb2`.
+    fn parse_index(&mut self, prefix: &str, expected: &str) -> PResult<usize> {
+        let index = match *self.token() {
+            Token::Ident(ref word) if word.starts_with(prefix) => {
+                word[prefix.len()..].parse().ok()
+            }
+            _ => None,
+        };
+        match index {
+            Some(index) => {
+                self.bump();
+                Ok(index)
+            }
+            None => self.unexpected(expected),
+        }
+    }
+
+    fn parse_local(&mut self) -> PResult<Local> {
+        let line = self.line();
+        let index = self.parse_index("_", "a local")?;
+        if index >= self.builder.local_decls.len() {
+            return Err(format!("line {}: `_{}` is not declared", line, index));
+        }
+        Ok(Local::new(index))
+    }
+
+    /// Parses a reference to a block, creating it if this is its first use.
+    fn parse_block_ref(&mut self) -> PResult<BasicBlock> {
+        let index = self.parse_index("bb", "a basic block")?;
+        while self.builder.cfg.basic_blocks.len() <= index {
+            self.builder.cfg.start_new_block();
+        }
+        Ok(BasicBlock::new(index))
+    }
+
+    fn parse_body(&mut self) -> PResult<()> {
+        while self.eat_ident("let") {
+            self.parse_local_decl()?;
+        }
+        while *self.token() != Token::Eof {
+            self.parse_block()?;
+        }
+        for block in self.builder.cfg.basic_blocks.indices() {
+            if !self.defined.contains(&block) {
+                return Err(format!("`{:?}` is used but never defined", block));
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_local_decl(&mut self) -> PResult<()> {
+        self.eat_ident("mut");
+        let line = self.line();
+        let index = self.parse_index("_", "a local")?;
+        self.expect_punct(':')?;
+        let ty = self.parse_ty()?;
+        self.expect_punct(';')?;
+
+        let tcx = self.tcx();
+        let arg_count = self.builder.arg_count;
+        let span = self.builder.fn_span;
+        let local_decls = &mut self.builder.local_decls;
+        if index == RETURN_PLACE.index() {
+            // The dumped MIR declares the return place too; accept that as
+            // long as it agrees with the signature.
+            let return_ty = tcx.erase_regions(&local_decls[RETURN_PLACE].ty);
+            if ty != return_ty {
+                return Err(format!("line {}: the return place has type `{}`", line, return_ty));
+            }
+        } else if index <= arg_count {
+            return Err(format!("line {}: `_{}` is an argument", line, index));
+        } else if index != local_decls.len() {
+            return Err(format!("line {}: expected `_{}`, locals must be declared in order",
+                               line, local_decls.len()));
+        } else {
+            local_decls.push(LocalDecl::new_temp(ty, span));
+        }
+        Ok(())
+    }
+
+    fn parse_block(&mut self) -> PResult<()> {
+        let line = self.line();
+        let block = self.parse_block_ref()?;
+        if !self.defined.insert(block) {
+            return Err(format!("line {}: `{:?}` is defined twice", line, block));
+        }
+        if self.eat_punct('(') {
+            self.expect_ident("cleanup")?;
+            self.expect_punct(')')?;
+            self.builder.cfg.block_data_mut(block).is_cleanup = true;
+        }
+        self.expect_punct(':')?;
+        self.expect_punct('{')?;
+
+        let source_info = self.builder.source_info(self.builder.fn_span);
+        loop {
+            if let Some(kind) = self.parse_terminator()? {
+                self.expect_punct(';')?;
+                self.builder.cfg.terminate(block, source_info, kind);
+                break;
+            }
+            let kind = self.parse_statement()?;
+            self.expect_punct(';')?;
+            self.builder.cfg.push(block, Statement { source_info, kind });
+        }
+        self.expect_punct('}')
+    }
+
+    fn parse_statement(&mut self) -> PResult<StatementKind<'tcx>> {
+        if self.eat_ident("StorageLive") {
+            self.expect_punct('(')?;
+            let local = self.parse_local()?;
+            self.expect_punct(')')?;
+            return Ok(StatementKind::StorageLive(local));
+        }
+        if self.eat_ident("StorageDead") {
+            self.expect_punct('(')?;
+            let local = self.parse_local()?;
+            self.expect_punct(')')?;
+            return Ok(StatementKind::StorageDead(local));
+        }
+        if self.eat_ident("nop") {
+            return Ok(StatementKind::Nop);
+        }
+        let place = self.parse_place()?;
+        self.expect_punct('=')?;
+        Ok(StatementKind::Assign(place, self.parse_rvalue()?))
+    }
+
+    /// Parses a terminator, or returns `None` if the next token does not
+    /// start one.
+    fn parse_terminator(&mut self) -> PResult<Option<TerminatorKind<'tcx>>> {
+        let name = match *self.token() {
+            Token::Ident(ref word) => word.clone(),
+            _ => return Ok(None),
+        };
+        let kind = match &name[..] {
+            "goto" => {
+                self.bump();
+                self.expect_arrow()?;
+                TerminatorKind::Goto { target: self.parse_block_ref()? }
+            }
+            "return" => {
+                self.bump();
+                TerminatorKind::Return
+            }
+            "resume" => {
+                self.bump();
+                TerminatorKind::Resume
+            }
+            "abort" => {
+                self.bump();
+                TerminatorKind::Abort
+            }
+            "unreachable" => {
+                self.bump();
+                TerminatorKind::Unreachable
+            }
+            "switchInt" => {
+                self.bump();
+                self.expect_punct('(')?;
+                let discr = self.parse_operand()?;
+                self.expect_punct(')')?;
+                self.expect_arrow()?;
+                self.expect_punct('[')?;
+
+                let switch_ty = discr.ty(&self.builder.local_decls, self.tcx());
+                let mut cases = vec![];
+                while !self.eat_ident("otherwise") {
+                    let value = self.parse_scalar(switch_ty)?;
+                    self.expect_punct(':')?;
+                    cases.push((value, self.parse_block_ref()?));
+                    self.expect_punct(',')?;
+                }
+                self.expect_punct(':')?;
+                let otherwise = self.parse_block_ref()?;
+                self.expect_punct(']')?;
+
+                TerminatorKind::SwitchInt {
+                    discr,
+                    switch_ty,
+                    targets: SwitchTargets::new(cases, otherwise),
+                }
+            }
+            "drop" => {
+                self.bump();
+                self.expect_punct('(')?;
+                let location = self.parse_place()?;
+                self.expect_punct(')')?;
+                self.expect_arrow()?;
+
+                let (target, unwind) = if self.eat_punct('[') {
+                    self.expect_ident("return")?;
+                    self.expect_punct(':')?;
+                    let target = self.parse_block_ref()?;
+                    let unwind = if self.eat_punct(',') {
+                        self.expect_ident("unwind")?;
+                        self.expect_punct(':')?;
+                        Some(self.parse_block_ref()?)
+                    } else {
+                        None
+                    };
+                    self.expect_punct(']')?;
+                    (target, unwind)
+                } else {
+                    (self.parse_block_ref()?, None)
+                };
+
+                TerminatorKind::Drop { location, target, unwind }
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(kind))
+    }
+
+    fn parse_rvalue(&mut self) -> PResult<Rvalue<'tcx>> {
+        if self.eat_punct('&') {
+            let borrow_kind = if self.eat_ident("mut") {
+                BorrowKind::Mut { allow_two_phase_borrow: false }
+            } else {
+                BorrowKind::Shared
+            };
+            let region = self.tcx().types.re_erased;
+            return Ok(Rvalue::Ref(region, borrow_kind, self.parse_place()?));
+        }
+
+        let name = match *self.token() {
+            Token::Ident(ref word) => word.clone(),
+            _ => String::new(),
+        };
+        if let Some(op) = unary_op(&name) {
+            self.bump();
+            self.expect_punct('(')?;
+            let operand = self.parse_operand()?;
+            self.expect_punct(')')?;
+            return Ok(Rvalue::UnaryOp(op, operand));
+        }
+        let (checked, op_name) = if name.starts_with("Checked") {
+            (true, &name["Checked".len()..])
+        } else {
+            (false, &name[..])
+        };
+        if let Some(op) = binary_op(op_name) {
+            self.bump();
+            self.expect_punct('(')?;
+            let lhs = self.parse_operand()?;
+            self.expect_punct(',')?;
+            let rhs = self.parse_operand()?;
+            self.expect_punct(')')?;
+            return Ok(if checked {
+                Rvalue::CheckedBinaryOp(op, lhs, rhs)
+            } else {
+                Rvalue::BinaryOp(op, lhs, rhs)
+            });
+        }
+
+        // A parenthesis starts either a projection or a tuple.
+        if *self.token() == Token::Punct('(') {
+            let start = self.pos;
+            let is_place = self.parse_place().is_ok();
+            self.pos = start;
+            if !is_place {
+                return self.parse_tuple();
+            }
+        }
+
+        let operand = self.parse_operand()?;
+        if self.eat_ident("as") {
+            let ty = self.parse_ty()?;
+            if self.eat_punct('(') {
+                self.expect_ident("Misc")?;
+                self.expect_punct(')')?;
+            }
+            return Ok(Rvalue::Cast(CastKind::Misc, operand, ty));
+        }
+        Ok(Rvalue::Use(operand))
+    }
+
+    fn parse_tuple(&mut self) -> PResult<Rvalue<'tcx>> {
+        self.expect_punct('(')?;
+        let mut operands = vec![];
+        while !self.eat_punct(')') {
+            operands.push(self.parse_operand()?);
+            if !self.eat_punct(',') {
+                self.expect_punct(')')?;
+                break;
+            }
+        }
+        Ok(Rvalue::Aggregate(box AggregateKind::Tuple, operands))
+    }
+
+    fn parse_operand(&mut self) -> PResult<Operand<'tcx>> {
+        if self.eat_ident("move") {
+            Ok(Operand::Move(self.parse_place()?))
+        } else if self.eat_ident("const") {
+            Ok(Operand::Constant(box self.parse_constant()?))
+        } else {
+            Ok(Operand::Copy(self.parse_place()?))
+        }
+    }
+
+    fn parse_place(&mut self) -> PResult<Place<'tcx>> {
+        if !self.eat_punct('(') {
+            return Ok(Place::Local(self.parse_local()?));
+        }
+        if self.eat_punct('*') {
+            let base = self.parse_place()?;
+            self.expect_punct(')')?;
+            return Ok(base.deref());
+        }
+
+        let base = self.parse_place()?;
+        self.expect_punct('.')?;
+        let field = match *self.token() {
+            Token::Literal(ref word) => word.parse().ok(),
+            _ => None,
+        };
+        let field = match field {
+            Some(field) => Field::new(field),
+            None => return self.unexpected("a field index"),
+        };
+        self.bump();
+        self.expect_punct(':')?;
+        let ty = self.parse_ty()?;
+        self.expect_punct(')')?;
+        Ok(base.field(field, ty))
+    }
+
+    fn parse_constant(&mut self) -> PResult<Constant<'tcx>> {
+        let tcx = self.tcx();
+        let start = self.pos;
+        self.eat_punct('-');
+        let ty = match self.bump() {
+            Token::Ident(ref word) if word == "true" || word == "false" => tcx.types.bool,
+            Token::Literal(ref word) => {
+                let suffix = word.trim_left_matches(|c: char| c.is_digit(10));
+                match primitive_ty(tcx, suffix) {
+                    Some(ty) if ty.is_integral() => ty,
+                    _ => {
+                        return Err(format!("line {}: `{}` needs an integer suffix",
+                                           self.tokens[start].1, word));
+                    }
+                }
+            }
+            _ => {
+                self.pos = start;
+                return self.unexpected("a constant");
+            }
+        };
+        self.pos = start;
+        let bits = self.parse_scalar(ty)?;
+
+        Ok(Constant {
+            span: self.builder.fn_span,
+            ty,
+            literal: Literal::Value {
+                value: ty::Const::from_bits(tcx, bits, ty::ParamEnv::empty().and(ty)),
+            },
+        })
+    }
+
+    /// Parses a boolean or integer of type `ty`, returning its bits.
+    fn parse_scalar(&mut self, ty: Ty<'tcx>) -> PResult<u128> {
+        let line = self.line();
+        if ty.is_bool() {
+            if self.eat_ident("false") {
+                return Ok(0);
+            }
+            if self.eat_ident("true") {
+                return Ok(1);
+            }
+            return self.unexpected("`true` or `false`");
+        }
+
+        let int_ty = match ty.sty {
+            ty::TyInt(ity) => IntType::SignedInt(ity),
+            ty::TyUint(uty) => IntType::UnsignedInt(uty),
+            _ => return Err(format!("line {}: constants of type `{}` are not supported", line, ty)),
+        };
+        let negative = self.eat_punct('-');
+        let word = match *self.token() {
+            Token::Literal(ref word) => word.clone(),
+            _ => return self.unexpected("an integer"),
+        };
+        self.bump();
+        let suffix_start = word.find(|c: char| !c.is_digit(10)).unwrap_or(word.len());
+        let (digits, suffix) = word.split_at(suffix_start);
+        if suffix != ty.to_string() {
+            return Err(format!("line {}: expected a `{}`, found `{}`", line, ty, word));
+        }
+
+        let bits = Integer::from_attr(self.tcx(), int_ty).size().bits();
+        let mask = !0u128 >> (128 - bits);
+        let (signed, max) = match int_ty {
+            IntType::SignedInt(_) => (true, mask >> 1),
+            IntType::UnsignedInt(_) => (false, mask),
+        };
+        let value = match digits.parse::<u128>() {
+            Ok(value) if !negative && value <= max => value,
+            Ok(value) if negative && signed && value <= max + 1 => value.wrapping_neg() & mask,
+            _ => {
+                let sign = if negative { "-" } else { "" };
+                return Err(format!("line {}: `{}{}` does not fit in `{}`", line, sign, word, ty));
+            }
+        };
+        Ok(value)
+    }
+
+    fn parse_ty(&mut self) -> PResult<Ty<'tcx>> {
+        let tcx = self.tcx();
+        if self.eat_punct('(') {
+            let mut tys = vec![];
+            while !self.eat_punct(')') {
+                tys.push(self.parse_ty()?);
+                if !self.eat_punct(',') {
+                    self.expect_punct(')')?;
+                    break;
+                }
+            }
+            return Ok(tcx.intern_tup(&tys));
+        }
+        if self.eat_punct('&') {
+            let mutable = self.eat_ident("mut");
+            let ty = self.parse_ty()?;
+            return Ok(if mutable {
+                tcx.mk_mut_ref(tcx.types.re_erased, ty)
+            } else {
+                tcx.mk_imm_ref(tcx.types.re_erased, ty)
+            });
+        }
+        if self.eat_punct('*') {
+            let mutable = if self.eat_ident("mut") {
+                true
+            } else {
+                self.expect_ident("const")?;
+                false
+            };
+            let ty = self.parse_ty()?;
+            return Ok(if mutable { tcx.mk_mut_ptr(ty) } else { tcx.mk_imm_ptr(ty) });
+        }
+        if self.eat_punct('!') {
+            return Ok(tcx.types.never);
+        }
+
+        let ty = match *self.token() {
+            Token::Ident(ref word) => primitive_ty(tcx, word),
+            _ => None,
+        };
+        match ty {
+            Some(ty) => {
+                self.bump();
+                Ok(ty)
+            }
+            None => self.unexpected("a type"),
+        }
+    }
+}
+
+fn primitive_ty<'a, 'gcx, 'tcx>(tcx: TyCtxt<'a, 'gcx, 'tcx>, name: &str) -> Option<Ty<'tcx>> {
+    Some(match name {
+        "bool" => tcx.types.bool,
+        "char" => tcx.types.char,
+        "isize" => tcx.types.isize,
+        "i8" => tcx.types.i8,
+        "i16" => tcx.types.i16,
+        "i32" => tcx.types.i32,
+        "i64" => tcx.types.i64,
+        "i128" => tcx.types.i128,
+        "usize" => tcx.types.usize,
+        "u8" => tcx.types.u8,
+        "u16" => tcx.types.u16,
+        "u32" => tcx.types.u32,
+        "u64" => tcx.types.u64,
+        "u128" => tcx.types.u128,
+        "f32" => tcx.types.f32,
+        "f64" => tcx.types.f64,
+        _ => return None,
+    })
+}
+
+fn unary_op(name: &str) -> Option<UnOp> {
+    Some(match name {
+        "Not" => UnOp::Not,
+        "Neg" => UnOp::Neg,
+        _ => return None,
+    })
+}
+
+fn binary_op(name: &str) -> Option<BinOp> {
+    Some(match name {
+        "Add" => BinOp::Add,
+        "Sub" => BinOp::Sub,
+        "Mul" => BinOp::Mul,
+        "Div" => BinOp::Div,
+        "Rem" => BinOp::Rem,
+        "BitXor" => BinOp::BitXor,
+        "BitAnd" => BinOp::BitAnd,
+        "BitOr" => BinOp::BitOr,
+        "Shl" => BinOp::Shl,
+        "Shr" => BinOp::Shr,
+        "Eq" => BinOp::Eq,
+        "Lt" => BinOp::Lt,
+        "Le" => BinOp::Le,
+        "Ne" => BinOp::Ne,
+        "Ge" => BinOp::Ge,
+        "Gt" => BinOp::Gt,
+        "Offset" => BinOp::Offset,
+        _ => return None,
+    })
+}
@@ -69,18 +69,29 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
         let dummy_temp = self.temp(dummy_ty, dummy_source_info.span);
         self.cfg.push_assign(block, dummy_source_info, &dummy_temp, dummy_access);
 
-        let source_info = self.source_info(span);
-        let borrowed_input_temp = if tcx.generate_borrow_of_any_match_input() {
-            // The region is unknown at this point; we rely on NLL
-            // inference to find an appropriate one. Therefore you can
-            // only use this when NLL is turned on.
+        if tcx.emit_read_for_match() {
+            // Inject a fake read of the match input, so that borrowck
+            // checks that it is initialized and not mutably borrowed
+            // when the match starts.
+            let source_info = self.source_info(discriminant_span);
+            self.cfg.push(block, Statement {
+                source_info,
+                kind: StatementKind::ReadForMatch(discriminant_place.clone()),
+            });
+        }
+
+        // The places inspected by the tests of the match are kept
+        // borrowed while guards run, so that a guard cannot change the
+        // outcome of tests which have already been performed. This is
+        // only needed, and only recorded, if there are any guards.
+        let mut fake_borrows = if arms.iter().any(|arm| arm.guard.is_some()) &&
+            tcx.generate_borrow_of_any_match_input()
+        {
+            // The regions of the borrows are unknown at this point; we
+            // rely on NLL inference to find appropriate ones. Therefore
+            // you can only use this when NLL is turned on.
             assert!(tcx.use_mir_borrowck());
-            let borrowed_input =
-                Rvalue::Ref(tcx.types.re_empty, BorrowKind::Shared, discriminant_place.clone());
-            let borrowed_input_ty = borrowed_input.ty(&self.local_decls, tcx);
-            let borrowed_input_temp = self.temp(borrowed_input_ty, span);
-            self.cfg.push_assign(block, source_info, &borrowed_input_temp, borrowed_input);
-            Some(borrowed_input_temp)
+            Some(FakeBorrows { span: discriminant_span, borrows: vec![] })
         } else {
             None
         };
@@ -124,44 +135,6 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
                 .zip(pre_binding_blocks.iter().zip(pre_binding_blocks.iter().skip(1)))
                 .map(|((arm_index, pattern, guard),
                        (pre_binding_block, next_candidate_pre_binding_block))| {
-
-                    if let (true, Some(borrow_temp)) = (tcx.emit_read_for_match(),
-                                                        borrowed_input_temp.clone()) {
-                        // inject a fake read of the borrowed input at
-                        // the start of each arm's pattern testing
-                        // code.
-                        //
-                        // This should ensure that you cannot change
-                        // the variant for an enum while you are in
-                        // the midst of matching on it.
-
-                        self.cfg.push(*pre_binding_block, Statement {
-                            source_info,
-                            kind: StatementKind::ReadForMatch(borrow_temp.clone()),
-                        });
-                    }
-
-                    // One might ask: why not build up the match pair such that it
-                    // matches via `borrowed_input_temp.deref()` instead of
-                    // using the `discriminant_place` directly, as it is doing here?
-                    //
-                    // The basic answer is that if you do that, then you end up with
-                    // accceses to a shared borrow of the input and that conflicts with
-                    // any arms that look like e.g.
-                    //
-                    // match Some(&4) {
-                    //     ref mut foo => {
-                    //         ... /* mutate `foo` in arm body */ ...
-                    //     }
-                    // }
-                    //
-                    // (Perhaps we could further revise the MIR
-                    //  construction here so that it only does a
-                    //  shared borrow at the outset and delays doing
-                    //  the mutable borrow until after the pattern is
-                    //  matched *and* the guard (if any) for the arm
-                    //  has been run.)
-
                     Candidate {
                         span: pattern.span,
                         match_pairs: vec![MatchPair::new(discriminant_place.clone(), pattern)],
@@ -180,7 +153,8 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
 
         // this will generate code to test discriminant_place and
         // branch to the appropriate arm block
-        let otherwise = self.match_candidates(span, &mut arm_blocks, candidates, block);
+        let otherwise = self.match_candidates(span, &mut arm_blocks, candidates, block,
+                                              &mut fake_borrows);
 
         if !otherwise.is_empty() {
             // All matches are exhaustive. However, because some matches
@@ -433,6 +407,15 @@ struct ArmBlocks {
     blocks: Vec<BasicBlock>,
 }
 
+/// The fake borrows which keep the places inspected by a match borrowed
+/// while its guards run; see `add_fake_borrow`.
+struct FakeBorrows<'tcx> {
+    /// The span of the match input, to which the borrows are attributed.
+    span: Span,
+    /// The borrowed places and the temporaries holding their borrows.
+    borrows: Vec<(Place<'tcx>, Local)>,
+}
+
 #[derive(Clone, Debug)]
 pub struct Candidate<'pat, 'tcx:'pat> {
     // span of the original pattern that gave rise to this candidate
@@ -548,11 +531,16 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
     /// up the list of candidates and recurse with a non-exhaustive
     /// list. This is important to keep the size of the generated code
     /// under control. See `test_candidates` for more details.
+    ///
+    /// If `fake_borrows` is `Some`, the places inspected by the tests
+    /// are recorded in it, and guards keep them borrowed (see
+    /// `add_fake_borrow`).
     fn match_candidates<'pat>(&mut self,
                               span: Span,
                               arm_blocks: &mut ArmBlocks,
                               mut candidates: Vec<Candidate<'pat, 'tcx>>,
-                              mut block: BasicBlock)
+                              mut block: BasicBlock,
+                              fake_borrows: &mut Option<FakeBorrows<'tcx>>)
                               -> Vec<BasicBlock>
     {
         debug!("matched_candidate(span={:?}, block={:?}, candidates={:?})",
@@ -586,7 +574,8 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
         for candidate in candidates {
             // If so, apply any bindings, test the guard (if any), and
             // branch to the arm.
-            if let Some(b) = self.bind_and_guard_matched_candidate(block, arm_blocks, candidate) {
+            if let Some(b) = self.bind_and_guard_matched_candidate(block, arm_blocks, candidate,
+                                                                    fake_borrows) {
                 block = b;
             } else {
                 // if None is returned, then any remaining candidates
@@ -599,7 +588,8 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
                     let target = self.cfg.start_new_block();
                     if let Some(otherwise) = self.bind_and_guard_matched_candidate(target,
                                                                                    arm_blocks,
-                                                                                   candidate) {
+                                                                                   candidate,
+                                                                                   fake_borrows) {
                         self.cfg.terminate(otherwise, source_info, TerminatorKind::Unreachable);
                     }
                 }
@@ -608,7 +598,8 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
                     return vec![]
                 } else {
                     let target = self.cfg.start_new_block();
-                    return self.match_candidates(span, arm_blocks, unmatched_candidates, target);
+                    return self.match_candidates(span, arm_blocks, unmatched_candidates, target,
+                                                 fake_borrows);
                 }
             }
         }
@@ -621,7 +612,7 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
 
         // Test candidates where possible.
         let (otherwise, tested_candidates) =
-            self.test_candidates(span, arm_blocks, &unmatched_candidates, block);
+            self.test_candidates(span, arm_blocks, &unmatched_candidates, block, fake_borrows);
 
         // If the target candidates were exhaustive, then we are done.
         // But for borrowck continue build decision tree.
@@ -635,7 +626,7 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
 
         // Otherwise, let's process those remaining candidates.
         let join_block = self.join_otherwise_blocks(span, otherwise);
-        self.match_candidates(span, arm_blocks, untested_candidates, join_block)
+        self.match_candidates(span, arm_blocks, untested_candidates, join_block, fake_borrows)
     }
 
     fn join_otherwise_blocks(&mut self,
@@ -774,13 +765,19 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
                              span: Span,
                              arm_blocks: &mut ArmBlocks,
                              candidates: &[Candidate<'pat, 'tcx>],
-                             block: BasicBlock)
+                             block: BasicBlock,
+                             fake_borrows: &mut Option<FakeBorrows<'tcx>>)
                              -> (Vec<BasicBlock>, usize)
     {
         // extract the match-pair from the highest priority candidate
         let match_pair = &candidates.first().unwrap().match_pairs[0];
         let mut test = self.test(match_pair);
 
+        // Any guard from here on depends on the outcome of this test.
+        if let Some(ref mut fake_borrows) = *fake_borrows {
+            self.add_fake_borrow(fake_borrows, &match_pair.place);
+        }
+
         // most of the time, the test to perform is simply a function
         // of the main candidate; but for a test like SwitchInt, we
         // may want to add cases based on the candidates that are
@@ -843,13 +840,48 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
                              self.match_candidates(span,
                                                    arm_blocks,
                                                    target_candidates,
-                                                   target_block)
+                                                   target_block,
+                                                   fake_borrows)
                          })
                          .collect();
 
         (otherwise, tested_candidates)
     }
 
+    /// Records that a test inspects `place`, creating the temporaries which
+    /// hold the fake borrows of it; the borrows themselves are made when
+    /// entering a guard and are read again once the guard has been
+    /// evaluated, so that borrowck rejects guards which mutate any of the
+    /// recorded places.
+    ///
+    /// The borrows are `Shallow`: a guard may still mutate the parts of a
+    /// place which are not inspected by any test, e.g. the contents of a
+    /// variant whose discriminant was tested. For every dereference in
+    /// `place`, the dereferenced pointer is borrowed as well, since
+    /// overwriting a reference would otherwise change what `place` refers to
+    /// without counting as a mutation of it.
+    fn add_fake_borrow(&mut self, fake_borrows: &mut FakeBorrows<'tcx>, place: &Place<'tcx>) {
+        let tcx = self.hir.tcx();
+        let mut cursor = place;
+        let mut places = vec![place];
+        while let Place::Projection(ref proj) = *cursor {
+            if let ProjectionElem::Deref = proj.elem {
+                places.push(&proj.base);
+            }
+            cursor = &proj.base;
+        }
+
+        for place in places {
+            if fake_borrows.borrows.iter().any(|&(ref borrowed, _)| borrowed == place) {
+                continue;
+            }
+            let place_ty = place.ty(&self.local_decls, tcx).to_ty(tcx);
+            let temp_ty = tcx.mk_imm_ref(tcx.types.re_empty, place_ty);
+            let temp = self.local_decls.push(LocalDecl::new_internal(temp_ty, fake_borrows.span));
+            fake_borrows.borrows.push((place.clone(), temp));
+        }
+    }
+
     /// Initializes each of the bindings from the candidate by
     /// moving/copying/ref'ing the source as appropriate. Tests the
     /// guard, if any, and then branches to the arm. Returns the block
@@ -865,7 +897,8 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
     fn bind_and_guard_matched_candidate<'pat>(&mut self,
                                               mut block: BasicBlock,
                                               arm_blocks: &mut ArmBlocks,
-                                              candidate: Candidate<'pat, 'tcx>)
+                                              candidate: Candidate<'pat, 'tcx>,
+                                              fake_borrows: &Option<FakeBorrows<'tcx>>)
                                               -> Option<BasicBlock> {
         debug!("bind_and_guard_matched_candidate(block={:?}, candidate={:?})",
                block, candidate);
@@ -969,8 +1002,29 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
         //      match input itself; it is up to us to create a place
         //      holding a `&` or `&mut` that we can then borrow).
 
+        //
+        // 3. Fake borrows: The places inspected by the tests which led
+        //    here are shallowly borrowed before the guard is entered,
+        //    and those borrows are read again once the guard has been
+        //    evaluated. This makes borrowck reject guards which would
+        //    change the outcome of these tests, whether the guard then
+        //    succeeds or falls through to the remaining candidates.
+
         let autoref = self.hir.tcx().all_pat_vars_are_implicit_refs_within_guards();
         if let Some(guard) = candidate.guard {
+            let re_empty = self.hir.tcx().types.re_empty;
+            let fake_borrows = match *fake_borrows {
+                Some(ref fake_borrows) => {
+                    let source_info = self.source_info(fake_borrows.span);
+                    for &(ref place, temp) in &fake_borrows.borrows {
+                        let rvalue = Rvalue::Ref(re_empty, BorrowKind::Shallow, place.clone());
+                        self.cfg.push_assign(block, source_info, &Place::Local(temp), rvalue);
+                    }
+                    &fake_borrows.borrows[..]
+                }
+                None => &[],
+            };
+
             if autoref {
                 self.bind_matched_candidate_for_guard(block, &candidate.bindings);
                 let guard_frame = GuardFrame {
@@ -994,6 +1048,17 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
                 debug!("Exiting guard building context with locals: {:?}", guard_frame);
             }
 
+            let guard_end = SourceInfo {
+                span: self.hir.tcx().sess.codemap().end_point(source_info.span),
+                scope: source_info.scope,
+            };
+            for &(_, temp) in fake_borrows {
+                self.cfg.push(block, Statement {
+                    source_info: guard_end,
+                    kind: StatementKind::ReadForMatch(Place::Local(temp)),
+                });
+            }
+
             let false_edge_block = self.cfg.start_new_block();
 
             // We want to ensure that the matched candidates are bound
@@ -1188,6 +1253,11 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
             }))),
         };
         let for_arm_body = self.local_decls.push(local.clone());
+        self.var_debug_info.push(VarDebugInfo {
+            name,
+            source_info: SourceInfo { span: source_info.span, scope: visibility_scope },
+            place: Place::Local(for_arm_body),
+        });
         let locals = if has_guard.0 && tcx.all_pat_vars_are_implicit_refs_within_guards() {
             let val_for_guard =  self.local_decls.push(local);
             let ref_for_guard = self.local_decls.push(LocalDecl::<'tcx> {
@@ -190,14 +190,13 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
                 let used_variants = variants.count();
                 let mut otherwise_block = None;
                 let mut target_blocks = Vec::with_capacity(num_enum_variants);
-                let mut targets = Vec::with_capacity(used_variants + 1);
-                let mut values = Vec::with_capacity(used_variants);
+                let mut cases = Vec::with_capacity(used_variants);
                 let tcx = self.hir.tcx();
                 for (idx, discr) in adt_def.discriminants(tcx).enumerate() {
                     target_blocks.push(if variants.contains(idx) {
-                        values.push(discr.val);
-                        targets.push(self.cfg.start_new_block());
-                        *targets.last().unwrap()
+                        let block = self.cfg.start_new_block();
+                        cases.push((discr.val, block));
+                        block
                     } else {
                         if otherwise_block.is_none() {
                             otherwise_block = Some(self.cfg.start_new_block());
@@ -205,23 +204,20 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
                         otherwise_block.unwrap()
                     });
                 }
-                if let Some(otherwise_block) = otherwise_block {
-                    targets.push(otherwise_block);
-                } else {
-                    targets.push(self.unreachable_block());
-                }
+                let otherwise_block = match otherwise_block {
+                    Some(otherwise_block) => otherwise_block,
+                    None => self.unreachable_block(),
+                };
                 debug!("num_enum_variants: {}, tested variants: {:?}, variants: {:?}",
-                       num_enum_variants, values, variants);
+                       num_enum_variants, cases, variants);
                 let discr_ty = adt_def.repr.discr_type().to_ty(tcx);
                 let discr = self.temp(discr_ty, test.span);
                 self.cfg.push_assign(block, source_info, &discr,
                                      Rvalue::Discriminant(place.clone()));
-                assert_eq!(values.len() + 1, targets.len());
                 self.cfg.terminate(block, source_info, TerminatorKind::SwitchInt {
                     discr: Operand::Move(discr),
                     switch_ty: discr_ty,
-                    values: From::from(values),
-                    targets,
+                    targets: SwitchTargets::new(cases, otherwise_block),
                 });
                 target_blocks
             }
@@ -242,15 +238,12 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
                     // The switch may be inexhaustive so we
                     // add a catch all block
                     let otherwise = self.cfg.start_new_block();
-                    let targets: Vec<_> =
-                        options.iter()
-                               .map(|_| self.cfg.start_new_block())
-                               .chain(Some(otherwise))
-                               .collect();
-                    (targets.clone(), TerminatorKind::SwitchInt {
+                    let targets = SwitchTargets::new(
+                        options.iter().map(|&value| (value, self.cfg.start_new_block())),
+                        otherwise);
+                    (targets.all_targets().to_vec(), TerminatorKind::SwitchInt {
                         discr: Operand::Copy(place.clone()),
                         switch_ty,
-                        values: options.clone().into(),
                         targets,
                     })
                 };
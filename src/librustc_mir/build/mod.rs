@@ -54,10 +54,24 @@ pub fn mir_build<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> Mir<'t
         },
     };
 
+    let attrs = tcx.get_attrs(def_id);
+    let custom_mir = attrs.iter().find(|attr| attr.check_name("rustc_custom_mir"));
+
     tcx.infer_ctxt().enter(|infcx| {
         let cx = Cx::new(&infcx, id);
         let mut mir = if cx.tables().tainted_by_errors {
             build::construct_error(cx, body_id)
+        } else if let Some(attr) = custom_mir {
+            let result = match (cx.body_owner_kind, attr.value_str()) {
+                (hir::BodyOwnerKind::Fn, Some(source)) if !tcx.is_closure(def_id) => {
+                    custom::construct_custom(cx.clone(), id, &source.as_str())
+                }
+                _ => Err("expected `#[rustc_custom_mir = \"...\"]` on a function".to_string()),
+            };
+            result.unwrap_or_else(|message| {
+                tcx.sess.span_err(attr.span, &format!("invalid custom MIR: {}", message));
+                build::construct_error(cx, body_id)
+            })
         } else if let hir::BodyOwnerKind::Fn = cx.body_owner_kind {
             // fetch the fully liberated fn signature (that is, all bound
             // types/lifetimes replaced)
@@ -273,7 +287,7 @@ struct Builder<'a, 'gcx: 'a+'tcx, 'tcx: 'a> {
 
     /// the vector of all scopes that we have created thus far;
     /// we track this for debuginfo later
-    source_scopes: IndexVec<SourceScope, SourceScopeData>,
+    source_scopes: IndexVec<SourceScope, SourceScopeData<'tcx>>,
     source_scope_local_data: IndexVec<SourceScope, SourceScopeLocalData>,
     source_scope: SourceScope,
 
@@ -286,6 +300,10 @@ struct Builder<'a, 'gcx: 'a+'tcx, 'tcx: 'a> {
     /// (A match binding can have two locals; the 2nd is for the arm's guard.)
     var_indices: NodeMap<LocalsForNode>,
     local_decls: IndexVec<Local, LocalDecl<'tcx>>,
+
+    /// Debuginfo for the user variables declared so far.
+    var_debug_info: Vec<VarDebugInfo<'tcx>>,
+
     unit_temp: Option<Place<'tcx>>,
 
     /// cached block with the RESUME terminator; this is created
@@ -629,6 +647,7 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
             local_decls: IndexVec::from_elem_n(LocalDecl::new_return_place(return_ty,
                                                                              span), 1),
             var_indices: NodeMap(),
+            var_debug_info: vec![],
             unit_temp: None,
             cached_resume_block: None,
             cached_return_block: None,
@@ -662,6 +681,7 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
                  self.local_decls,
                  self.arg_count,
                  upvar_decls,
+                 self.var_debug_info,
                  self.fn_span
         )
     }
@@ -694,7 +714,7 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
                 scope: OUTERMOST_SOURCE_SCOPE,
                 span: pattern.map_or(self.fn_span, |pat| pat.span)
             };
-            self.local_decls.push(LocalDecl {
+            let local = self.local_decls.push(LocalDecl {
                 mutability: Mutability::Mut,
                 ty,
                 source_info,
@@ -703,6 +723,13 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
                 internal: false,
                 is_user_variable: None,
             });
+            if let Some(name) = name {
+                self.var_debug_info.push(VarDebugInfo {
+                    name,
+                    source_info,
+                    place: Place::Local(local),
+                });
+            }
         }
 
         let mut scope = None;
@@ -804,6 +831,7 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
 
 mod block;
 mod cfg;
+mod custom;
 mod expr;
 mod into;
 mod matches;
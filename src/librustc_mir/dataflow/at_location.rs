@@ -11,11 +11,12 @@
 //! A nice wrapper to consume dataflow results at several CFG
 //! locations.
 
-use rustc::mir::{BasicBlock, Location};
+use rustc::mir::{BasicBlock, Location, Mir};
 use rustc_data_structures::indexed_set::{IdxSetBuf, Iter};
 use rustc_data_structures::indexed_vec::Idx;
 
 use dataflow::{BitDenotation, BlockSets, DataflowResults};
+use dataflow::state_for_location;
 use dataflow::move_paths::{HasMoveData, MovePathIndex};
 
 use std::iter;
@@ -116,6 +117,12 @@ where
         self.curr_state.contains(x)
     }
 
+    /// Computes the state on entry to `loc` from the underlying results,
+    /// independently of where this cursor currently is.
+    pub fn state_at<'tcx>(&self, loc: Location, mir: &Mir<'tcx>) -> IdxSetBuf<BD::Idx> {
+        state_for_location(loc, self.operator(), &self.base_results, mir)
+    }
+
     /// Returns an iterator over the elements present in the current state.
     pub fn iter_incoming(&self) -> iter::Peekable<Iter<BD::Idx>> {
         self.curr_state.iter().peekable()
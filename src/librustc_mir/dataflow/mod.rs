@@ -839,7 +839,7 @@ impl<'a, 'tcx: 'a, D> DataflowAnalysis<'a, 'tcx, D> where D: BitDenotation
                 }
             }
             mir::TerminatorKind::SwitchInt { ref targets, .. } => {
-                for target in targets {
+                for target in targets.all_targets() {
                     self.propagate_bits_into_entry_set_for(in_out, *target, dirty_list);
                 }
             }
@@ -2078,6 +2078,29 @@ fn main() {
 ```
 "##,
 
+E0510: r##"
+Cannot mutate place in this match guard.
+
+When matching on a variable it cannot be mutated in the match guards, as this
+could cause the match to be non-exhaustive:
+
+```compile_fail,E0510
+#![feature(nll)]
+
+fn main() {
+    let mut x = &mut Some(0);
+    match x {
+        &mut None => (),
+        &mut Some(_) if { (|| { *x = None; })(); false } => (),
+        &mut Some(_) => (), // No longer matches
+    }
+}
+```
+
+Here executing `*x = None` would modify the value being matched and require us
+to go "back in time" to the `None` arm.
+"##,
+
 E0579: r##"
 When matching against an exclusive range, the compiler verifies that the range
 is non-empty. Exclusive range patterns include the start point but not the end
@@ -2251,6 +2274,50 @@ unsafe { b.resume() };
 ```
 "##,
 
+E0711: r##"
+This error occurs when a reference to a thread-local static might outlive the
+function it was taken in.
+
+Example of erroneous code:
+
+```compile_fail,E0711
+#![feature(nll)]
+#![feature(thread_local)]
+
+#[thread_local]
+static FOO: u8 = 3;
+
+fn main() {
+    let a = &FOO; // error: thread-local variable borrowed past end of function
+
+    std::thread::spawn(move || {
+        println!("{}", a);
+    });
+}
+```
+
+Each thread has its own copy of a thread-local static, which is destroyed
+when the thread exits. A reference to it can therefore only be used until the
+end of the function that borrowed it, since the thread might exit right after
+the function returns. To share the value, copy it out of the thread-local
+static instead:
+
+```
+#![feature(thread_local)]
+
+#[thread_local]
+static FOO: u8 = 3;
+
+fn main() {
+    let a = FOO;
+
+    std::thread::spawn(move || {
+        println!("{}", a);
+    });
+}
+```
+"##,
+
 }
 
 register_diagnostics! {
@@ -33,7 +33,6 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
 
             SwitchInt {
                 ref discr,
-                ref values,
                 ref targets,
                 ..
             } => {
@@ -43,17 +42,7 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                 trace!("SwitchInt({:?}, {:#?})", discr_prim, discr_layout);
                 let discr_prim = discr_prim.to_bits(discr_layout.size)?;
 
-                // Branch to the `otherwise` case by default, if no match is found.
-                let mut target_block = targets[targets.len() - 1];
-
-                for (index, &const_int) in values.iter().enumerate() {
-                    if discr_prim == const_int {
-                        target_block = targets[index];
-                        break;
-                    }
-                }
-
-                self.goto_block(target_block);
+                self.goto_block(targets.target_for_value(discr_prim));
             }
 
             Call {
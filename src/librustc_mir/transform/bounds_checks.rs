@@ -0,0 +1,206 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Removes bounds checks that are known to succeed.
+//!
+//! Indexing produces
+//!
+//!     _4 = _2;
+//!     _5 = Len((*_1));
+//!     _6 = Lt(move _4, move _5);
+//!     assert(move _6, "index out of bounds: ...") -> bb1;
+//!
+//! where the temporaries are only assigned once. We follow them back to the values they
+//! hold, as far as these can't change during the whole body: constants, arguments that
+//! are never assigned to, and the length of a place behind such an argument. A bounds
+//! check is then removed if
+//!
+//! - both the index and the length are constants, and the index is in bounds (indexing a
+//!   fixed-size array with a constant, after `InstCombine` replaced its `Len`), or
+//! - it is dominated by a bounds check of the same index against the same length, which
+//!   already made sure the index is in bounds (indexing with the same index repeatedly).
+
+use rustc::mir::*;
+use rustc::mir::interpret::EvalErrorKind::BoundsCheck;
+use rustc::mir::visit::PlaceContext;
+use rustc::session::Session;
+use rustc::ty::{ParamEnv, TyCtxt};
+use rustc::util::nodemap::FxHashMap;
+use transform::{MirPass, MirSource};
+use util::def_use::DefUseAnalysis;
+
+pub struct BoundsCheckElimination;
+
+impl MirPass for BoundsCheckElimination {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.mir_opt_level > 1
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let redundant = {
+            let finder = ValueFinder::new(tcx, mir);
+            let checks: Vec<_> = mir.basic_blocks().iter_enumerated().filter_map(|(bb, data)| {
+                finder.bounds_check(&data.terminator().kind).map(|check| (bb, check))
+            }).collect();
+
+            let dominators = mir.dominators();
+            checks.iter().filter(|&&(bb, ref check)| {
+                if let (&Value::Const(index), &Value::Const(len)) = (&check.0, &check.1) {
+                    if index < len {
+                        return true;
+                    }
+                }
+                dominators.is_reachable(bb) && checks.iter().any(|&(other, ref other_check)| {
+                    other != bb && other_check == check && dominators.is_reachable(other) &&
+                        dominators.is_dominated_by(bb, other)
+                })
+            }).map(|&(bb, _)| bb).collect::<Vec<_>>()
+        };
+
+        for bb in redundant {
+            if !tcx.consider_optimizing(|| {
+                format!("BoundsCheckElimination - {:?} in {:?}", bb, source)
+            }) {
+                break;
+            }
+            debug!("Removing redundant bounds check in {:?}", bb);
+            let terminator = mir[bb].terminator_mut();
+            let target = match terminator.kind {
+                TerminatorKind::Assert { target, .. } => target,
+                _ => bug!("bounds check is not an `Assert`"),
+            };
+            terminator.kind = TerminatorKind::Goto { target };
+        }
+    }
+}
+
+/// A value that can't change during the whole body.
+#[derive(Debug, PartialEq)]
+enum Value<'tcx> {
+    Const(u128),
+    Arg(Local),
+    Len(Place<'tcx>),
+}
+
+struct ValueFinder<'b, 'a, 'tcx: 'a + 'b> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    mir: &'b Mir<'tcx>,
+    def_use_analysis: DefUseAnalysis<'tcx>,
+    /// The rvalue assigned to each local that is only assigned once.
+    definitions: FxHashMap<Local, &'b Rvalue<'tcx>>,
+}
+
+impl<'b, 'a, 'tcx> ValueFinder<'b, 'a, 'tcx> {
+    fn new(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir: &'b Mir<'tcx>) -> Self {
+        let mut def_use_analysis = DefUseAnalysis::new(mir);
+        def_use_analysis.analyze(mir);
+
+        let mut definitions = FxHashMap();
+        for data in mir.basic_blocks() {
+            for statement in &data.statements {
+                if let StatementKind::Assign(Place::Local(local), ref rvalue) = statement.kind {
+                    if def_use_analysis.local_info(local).def_count() == 1 {
+                        definitions.insert(local, rvalue);
+                    }
+                }
+            }
+        }
+
+        ValueFinder { tcx, mir, def_use_analysis, definitions }
+    }
+
+    /// Returns the index and the length compared by a bounds check.
+    fn bounds_check(&self, kind: &TerminatorKind<'tcx>) -> Option<(Value<'tcx>, Value<'tcx>)> {
+        let cond = match *kind {
+            TerminatorKind::Assert {
+                cond: Operand::Copy(Place::Local(cond)),
+                expected: true,
+                msg: BoundsCheck { .. },
+                ..
+            } |
+            TerminatorKind::Assert {
+                cond: Operand::Move(Place::Local(cond)),
+                expected: true,
+                msg: BoundsCheck { .. },
+                ..
+            } => cond,
+            _ => return None,
+        };
+        match self.definitions.get(&cond) {
+            Some(&&Rvalue::BinaryOp(BinOp::Lt, ref index, ref len)) => {
+                Some((self.operand_value(index)?, self.operand_value(len)?))
+            }
+            _ => None,
+        }
+    }
+
+    fn operand_value(&self, operand: &Operand<'tcx>) -> Option<Value<'tcx>> {
+        match *operand {
+            Operand::Constant(box Constant { ty, literal: Literal::Value { value }, .. }) => {
+                value.assert_bits(self.tcx, ParamEnv::empty().and(ty)).map(Value::Const)
+            }
+            Operand::Constant(_) => None,
+            Operand::Copy(Place::Local(local)) | Operand::Move(Place::Local(local)) => {
+                self.local_value(local)
+            }
+            Operand::Copy(_) | Operand::Move(_) => None,
+        }
+    }
+
+    fn local_value(&self, local: Local) -> Option<Value<'tcx>> {
+        if self.mir.local_kind(local) == LocalKind::Arg {
+            return if self.is_invariant_arg(local, false) {
+                Some(Value::Arg(local))
+            } else {
+                None
+            };
+        }
+        match self.definitions.get(&local) {
+            Some(&&Rvalue::Use(ref operand)) => self.operand_value(operand),
+            Some(&&Rvalue::Len(ref place)) if self.is_invariant_place(place) => {
+                Some(Value::Len(place.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the length of `place` can't change, i.e. it is an argument that is never
+    /// assigned to, or what such an argument points to.
+    fn is_invariant_place(&self, place: &Place<'tcx>) -> bool {
+        match *place {
+            Place::Local(local) => {
+                self.mir.local_kind(local) == LocalKind::Arg && self.is_invariant_arg(local, false)
+            }
+            Place::Projection(ref projection) => match (&projection.base, &projection.elem) {
+                (&Place::Local(local), &ProjectionElem::Deref) => {
+                    self.mir.local_kind(local) == LocalKind::Arg &&
+                        self.is_invariant_arg(local, true)
+                }
+                _ => false,
+            },
+            Place::Static(_) => false,
+        }
+    }
+
+    /// Whether the argument `local` is never assigned to. With `through_deref`, writes through
+    /// it are fine if it is a reference, since they can't change what it points to.
+    fn is_invariant_arg(&self, local: Local, through_deref: bool) -> bool {
+        let is_ref = self.mir.local_decls[local].ty.is_region_ptr();
+        self.def_use_analysis.local_info(local).defs_and_uses.iter().all(|place_use| {
+            match place_use.context {
+                PlaceContext::Projection(Mutability::Mut) => through_deref && is_ref,
+                context => !context.is_mutating_use(),
+            }
+        })
+    }
+}
@@ -11,6 +11,7 @@
 use rustc_data_structures::fx::FxHashSet;
 use rustc_data_structures::indexed_vec::IndexVec;
 use rustc_data_structures::sync::Lrc;
+use rustc_errors::{Applicability, DiagnosticBuilder};
 
 use rustc::ty::query::Providers;
 use rustc::ty::{self, TyCtxt};
@@ -22,6 +23,7 @@ use rustc::mir::visit::{PlaceContext, Visitor};
 
 use syntax::ast;
 use syntax::symbol::Symbol;
+use syntax_pos::Span;
 
 use util;
 
@@ -396,6 +398,25 @@ fn unsafe_derive_on_repr_packed<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: D
                   &message);
 }
 
+/// Suggests borrowing a copy of the packed field instead, which is always
+/// aligned, if the borrow at `span` is an explicit shared one.
+fn suggest_copying_packed_field<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                          err: &mut DiagnosticBuilder,
+                                          span: Span) {
+    let snippet = match tcx.sess.codemap().span_to_snippet(span) {
+        Ok(snippet) => snippet,
+        Err(_) => return,
+    };
+    if snippet.starts_with('&') && !snippet.starts_with("&mut ") {
+        err.span_suggestion_with_applicability(
+            span,
+            "copy the field out first",
+            format!("&{{ {} }}", snippet[1..].trim()),
+            Applicability::MaybeIncorrect,
+        );
+    }
+}
+
 /// Return the NodeId for an enclosing scope that is also `unsafe`
 fn is_enclosed(tcx: TyCtxt,
                used_unsafe: &FxHashSet<ast::NodeId>,
@@ -486,12 +507,15 @@ pub fn check_unsafety<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) {
                 if let Some(impl_def_id) = builtin_derive_def_id(tcx, def_id) {
                     tcx.unsafe_derive_on_repr_packed(impl_def_id);
                 } else {
-                    tcx.lint_node_note(SAFE_PACKED_BORROWS,
-                                  lint_node_id,
-                                  source_info.span,
-                                  &format!("{} is unsafe and requires unsafe function or block \
-                                            (error E0133)", &description.as_str()[..]),
-                                  &details.as_str()[..]);
+                    let mut err = tcx.struct_span_lint_node(
+                        SAFE_PACKED_BORROWS,
+                        lint_node_id,
+                        source_info.span,
+                        &format!("{} is unsafe and requires unsafe function or block \
+                                  (error E0133)", &description.as_str()[..]));
+                    err.note(&details.as_str()[..]);
+                    suggest_copying_packed_field(tcx, &mut err, source_info.span);
+                    err.emit();
                 }
             }
         }
@@ -8,12 +8,14 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-//! This module provides two passes:
+//! This module provides three passes:
 //!
 //!   - `CleanEndRegions`, that reduces the set of `EndRegion` statements
 //!     in the MIR.
 //!   - `CleanUserAssertTy`, that replaces all `UserAssertTy` statements
 //!     with `Nop`.
+//!   - `CleanFakeBorrows`, that replaces the fake borrows made for match
+//!     guards, and the `ReadForMatch` statements reading them, with `Nop`.
 //!
 //! The `CleanEndRegions` "pass" is actually implemented as two
 //! traversals (aka visits) of the input MIR. The first traversal,
@@ -29,11 +31,15 @@
 //! pass runs after the MIR borrowck so that the NLL type checker can
 //! perform the type assertion when it encounters the `UserAssertTy`
 //! statements.
+//!
+//! Like `CleanUserAssertTy`, `CleanFakeBorrows` has to run after the MIR
+//! borrowck, which is the only consumer of the fake borrows.
 
 use rustc_data_structures::fx::FxHashSet;
 
 use rustc::middle::region;
-use rustc::mir::{BasicBlock, Location, Mir, Rvalue, Statement, StatementKind};
+use rustc::mir::{BasicBlock, BorrowKind, Local, Location, Mir, Place, Rvalue, Statement};
+use rustc::mir::StatementKind;
 use rustc::mir::visit::{MutVisitor, Visitor, TyContext};
 use rustc::ty::{Ty, RegionKind, TyCtxt};
 use transform::{MirPass, MirSource};
@@ -135,3 +141,51 @@ impl<'tcx> MutVisitor<'tcx> for DeleteUserAssertTy {
         self.super_statement(block, statement, location);
     }
 }
+
+pub struct CleanFakeBorrows;
+
+pub struct DeleteFakeBorrows {
+    fake_borrow_temps: FxHashSet<Local>,
+}
+
+impl MirPass for CleanFakeBorrows {
+    fn run_pass<'a, 'tcx>(&self,
+                          _tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let mut delete = DeleteFakeBorrows { fake_borrow_temps: FxHashSet() };
+        for data in mir.basic_blocks() {
+            for statement in &data.statements {
+                if let StatementKind::Assign(Place::Local(temp),
+                                             Rvalue::Ref(_, BorrowKind::Shallow, _)) =
+                    statement.kind
+                {
+                    delete.fake_borrow_temps.insert(temp);
+                }
+            }
+        }
+        if !delete.fake_borrow_temps.is_empty() {
+            delete.visit_mir(mir);
+        }
+    }
+}
+
+impl<'tcx> MutVisitor<'tcx> for DeleteFakeBorrows {
+    fn visit_statement(&mut self,
+                       block: BasicBlock,
+                       statement: &mut Statement<'tcx>,
+                       location: Location) {
+        let delete_it = match statement.kind {
+            StatementKind::Assign(Place::Local(temp), _) |
+            StatementKind::ReadForMatch(Place::Local(temp)) => {
+                self.fake_borrow_temps.contains(&temp)
+            }
+            _ => false,
+        };
+
+        if delete_it {
+            statement.make_nop();
+        }
+        self.super_statement(block, statement, location);
+    }
+}
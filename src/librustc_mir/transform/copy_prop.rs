@@ -26,11 +26,13 @@
 //!     USE(SRC)
 //!
 //! The assignment `DEST = SRC` must be (a) the only mutation of `DEST` and (b) the only
-//! (non-mutating) use of `SRC`. These restrictions are conservative and may be relaxed in the
-//! future.
+//! (non-mutating) use of `SRC`. Restriction (b) is relaxed for copies whose `DEST` is used once,
+//! later in the same basic block, as long as `SRC` is never borrowed and is neither mutated nor
+//! moved in between. This gets rid of the temporaries MIR building introduces for every operand.
 
 use rustc::mir::{Constant, Local, LocalKind, Location, Place, Mir, Operand, Rvalue, StatementKind};
-use rustc::mir::visit::MutVisitor;
+use rustc::mir::visit::{MutVisitor, PlaceContext};
+use rustc::session::Session;
 use rustc::ty::TyCtxt;
 use transform::{MirPass, MirSource};
 use util::def_use::DefUseAnalysis;
@@ -38,16 +40,16 @@ use util::def_use::DefUseAnalysis;
 pub struct CopyPropagation;
 
 impl MirPass for CopyPropagation {
-    fn run_pass<'a, 'tcx>(&self,
-                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
-                          _source: MirSource,
-                          mir: &mut Mir<'tcx>) {
+    fn is_enabled(&self, sess: &Session) -> bool {
         // We only run when the MIR optimization level is > 1.
         // This avoids a slow pass, and messing up debug info.
-        if tcx.sess.opts.debugging_opts.mir_opt_level <= 1 {
-            return;
-        }
+        sess.opts.debugging_opts.mir_opt_level > 1
+    }
 
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
         let mut def_use_analysis = DefUseAnalysis::new(mir);
         loop {
             def_use_analysis.analyze(mir);
@@ -107,7 +109,13 @@ impl MirPass for CopyPropagation {
                         StatementKind::Assign(Place::Local(local), Rvalue::Use(ref operand)) if
                                 local == dest_local => {
                             let maybe_action = match *operand {
-                                Operand::Copy(ref src_place) |
+                                Operand::Copy(ref src_place) => {
+                                    Action::local_copy(&mir, &def_use_analysis, src_place)
+                                        .or_else(|| Action::shared_local_copy(&def_use_analysis,
+                                                                              src_place,
+                                                                              dest_local,
+                                                                              location))
+                                }
                                 Operand::Move(ref src_place) => {
                                     Action::local_copy(&mir, &def_use_analysis, src_place)
                                 }
@@ -128,6 +136,12 @@ impl MirPass for CopyPropagation {
                     }
                 }
 
+                if !tcx.consider_optimizing(|| {
+                    format!("CopyPropagation - {:?} in {:?}", dest_local, source)
+                }) {
+                    break
+                }
+
                 changed = action.perform(mir, &def_use_analysis, dest_local, location) || changed;
                 // FIXME(pcwalton): Update the use-def chains to delete the instructions instead of
                 // regenerating the chains.
@@ -179,6 +193,9 @@ fn eliminate_self_assignments<'tcx>(
 
 enum Action<'tcx> {
     PropagateLocalCopy(Local),
+    /// Like `PropagateLocalCopy`, but the source is still used elsewhere, so the use of the
+    /// destination must not move out of the source.
+    PropagateSharedLocalCopy(Local),
     PropagateConstant(Constant<'tcx>),
 }
 
@@ -233,6 +250,62 @@ impl<'tcx> Action<'tcx> {
         Some(Action::PropagateLocalCopy(src_local))
     }
 
+    /// Checks whether `DEST = copy SRC` at `location` can be propagated even though `SRC` has
+    /// other uses: `DEST` must be used exactly once, later in the same block, and `SRC` must keep
+    /// its value until then.
+    fn shared_local_copy(def_use_analysis: &DefUseAnalysis,
+                         src_place: &Place<'tcx>,
+                         dest_local: Local,
+                         location: Location)
+                         -> Option<Action<'tcx>> {
+        let src_local = if let Place::Local(local) = *src_place {
+            local
+        } else {
+            return None;
+        };
+
+        // The single use of the destination must follow the copy in the same block. It must
+        // not be a borrow, as a reference to `SRC` would observe later changes to it.
+        let dest_use_info = def_use_analysis.local_info(dest_local);
+        if dest_use_info.use_count() != 1 {
+            return None;
+        }
+        let dest_use = dest_use_info.defs_and_uses.iter()
+            .find(|place_use| place_use.context.is_nonmutating_use())
+            .unwrap();
+        if let PlaceContext::Borrow { .. } = dest_use.context {
+            debug!("  Can't copy-propagate local: dest is borrowed");
+            return None;
+        }
+        if dest_use.location.block != location.block ||
+           dest_use.location.statement_index <= location.statement_index {
+            debug!("  Can't copy-propagate local: dest used outside of the copy's block");
+            return None;
+        }
+
+        // `SRC` must not change between the copy and that use. Any borrow of it could be used
+        // to mutate it behind our back, so give up on those entirely.
+        let in_between = |use_location: Location| {
+            use_location.block == location.block &&
+            use_location.statement_index > location.statement_index &&
+            use_location.statement_index < dest_use.location.statement_index
+        };
+        for place_use in &def_use_analysis.local_info(src_local).defs_and_uses {
+            let changed = match place_use.context {
+                PlaceContext::Borrow { .. } => true,
+                PlaceContext::Move | PlaceContext::StorageDead => in_between(place_use.location),
+                context => context.is_mutating_use() && in_between(place_use.location),
+            };
+            if changed {
+                debug!("  Can't copy-propagate local: src {:?} may change before the use",
+                       src_local);
+                return None;
+            }
+        }
+
+        Some(Action::PropagateSharedLocalCopy(src_local))
+    }
+
     fn constant(src_constant: &Constant<'tcx>) -> Option<Action<'tcx>> {
         Some(Action::PropagateConstant((*src_constant).clone()))
     }
@@ -273,6 +346,32 @@ impl<'tcx> Action<'tcx> {
 
                 true
             }
+            Action::PropagateSharedLocalCopy(src_local) => {
+                debug!("  Replacing the use of {:?} with {:?} (shared local)",
+                       dest_local,
+                       src_local);
+                for place_use in &def_use_analysis.local_info(dest_local).defs_and_uses {
+                    if place_use.context.is_storage_marker() {
+                        mir.make_statement_nop(place_use.location)
+                    }
+                }
+
+                def_use_analysis.replace_all_defs_and_uses_with(dest_local, mir, src_local);
+
+                // The source stays live, so a `move DEST` has to become a `copy SRC`. This is
+                // fine, as `DEST = copy SRC` tells us the type is `Copy`.
+                let mut visitor = MoveToCopyVisitor { local: src_local };
+                for place_use in &def_use_analysis.local_info(dest_local).defs_and_uses {
+                    if place_use.context.is_nonmutating_use() {
+                        visitor.visit_location(mir, place_use.location);
+                    }
+                }
+
+                debug!("  Deleting assignment");
+                mir.make_statement_nop(location);
+
+                true
+            }
             Action::PropagateConstant(src_constant) => {
                 // First, remove all markers.
                 //
@@ -318,6 +417,22 @@ impl<'tcx> Action<'tcx> {
     }
 }
 
+struct MoveToCopyVisitor {
+    local: Local,
+}
+
+impl<'tcx> MutVisitor<'tcx> for MoveToCopyVisitor {
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>, location: Location) {
+        self.super_operand(operand, location);
+
+        if let Operand::Move(Place::Local(local)) = *operand {
+            if local == self.local {
+                *operand = Operand::Copy(Place::Local(local));
+            }
+        }
+    }
+}
+
 struct ConstantPropagationVisitor<'tcx> {
     dest_local: Local,
     constant: Constant<'tcx>,
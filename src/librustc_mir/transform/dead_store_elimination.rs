@@ -0,0 +1,131 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Removes assignments to locals whose value is never read afterwards.
+//!
+//! MIR building produces plenty of these for discarded values, e.g. the `()` results of
+//! expression statements, and copy propagation leaves more behind. We look for
+//!
+//!     DEST = RVALUE
+//!
+//! where `DEST` is a local that is dead right after the assignment, according to a backward
+//! liveness analysis that counts both regular uses and drops, and replace the assignment with a
+//! `nop`. Rvalues never have side effects, so the only thing to watch out for are locals that
+//! are borrowed: their value can be read through a reference without the liveness analysis
+//! seeing it, so they are left alone.
+
+use rustc::mir::{Local, Location, Mir, Place, ProjectionElem, Rvalue, StatementKind};
+use rustc::mir::RETURN_PLACE;
+use rustc::mir::visit::Visitor;
+use rustc::session::Session;
+use rustc::ty::TyCtxt;
+use transform::{MirPass, MirSource};
+use util::liveness::{self, LivenessMode, LocalSet};
+
+pub struct DeadStoreElimination;
+
+impl MirPass for DeadStoreElimination {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        // Removing stores to user variables makes them unavailable in the debugger,
+        // so only do this when optimizing MIR aggressively.
+        sess.opts.debugging_opts.mir_opt_level > 1
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let borrowed = borrowed_locals(mir);
+
+        // Removing a dead store can make the locals read by it dead as well, so repeat until
+        // nothing changes.
+        loop {
+            let mut changed = false;
+            for location in find_dead_stores(mir, &borrowed) {
+                if !tcx.consider_optimizing(|| {
+                    format!("DeadStoreElimination - {:?} in {:?}", location, source)
+                }) {
+                    break;
+                }
+                debug!("Removing dead store at {:?}", location);
+                mir.make_statement_nop(location);
+                changed = true;
+            }
+            if !changed {
+                break;
+            }
+        }
+    }
+}
+
+fn find_dead_stores<'tcx>(mir: &Mir<'tcx>, borrowed: &LocalSet) -> Vec<Location> {
+    let mode = LivenessMode { include_regular_use: true, include_drops: true };
+    let liveness = liveness::liveness_of_locals(mir, mode);
+
+    let mut dead_stores = vec![];
+    for block in mir.basic_blocks().indices() {
+        let statements = &mir[block].statements;
+        liveness.simulate_block(mir, block, |location, live| {
+            // `live` holds the locals live on entry to `location`, which are the ones live
+            // right after the statement preceding it.
+            if location.statement_index == 0 {
+                return;
+            }
+            let statement_index = location.statement_index - 1;
+            if let StatementKind::Assign(Place::Local(local), _) =
+                statements[statement_index].kind
+            {
+                if local != RETURN_PLACE && !live.contains(&local) && !borrowed.contains(&local) {
+                    dead_stores.push(Location { block, statement_index });
+                }
+            }
+        });
+    }
+    dead_stores
+}
+
+/// Returns the locals that are borrowed anywhere in `mir`, and so may be accessed through a
+/// reference.
+pub fn borrowed_locals<'tcx>(mir: &Mir<'tcx>) -> LocalSet {
+    let mut finder = BorrowedLocalsFinder {
+        borrowed: LocalSet::new_empty(mir.local_decls.len()),
+    };
+    finder.visit_mir(mir);
+    finder.borrowed
+}
+
+struct BorrowedLocalsFinder {
+    borrowed: LocalSet,
+}
+
+impl<'tcx> Visitor<'tcx> for BorrowedLocalsFinder {
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+        if let Rvalue::Ref(_, _, ref place) = *rvalue {
+            if let Some(local) = find_local(place) {
+                self.borrowed.add(&local);
+            }
+        }
+
+        self.super_rvalue(rvalue, location)
+    }
+}
+
+fn find_local<'tcx>(place: &Place<'tcx>) -> Option<Local> {
+    match *place {
+        Place::Local(l) => Some(l),
+        Place::Static(..) => None,
+        Place::Projection(ref proj) => {
+            match proj.elem {
+                ProjectionElem::Deref => None,
+                _ => find_local(&proj.base)
+            }
+        }
+    }
+}
@@ -8,6 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use rustc::session::Session;
 use rustc::ty::TyCtxt;
 use rustc::mir::*;
 use rustc_data_structures::indexed_vec::Idx;
@@ -16,9 +17,14 @@ use transform::{MirPass, MirSource};
 pub struct Deaggregator;
 
 impl MirPass for Deaggregator {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        // Codegen handles aggregates itself; this only helps later passes.
+        sess.opts.debugging_opts.mir_opt_level > 0
+    }
+
     fn run_pass<'a, 'tcx>(&self,
                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
-                          _source: MirSource,
+                          source: MirSource,
                           mir: &mut Mir<'tcx>) {
         let (basic_blocks, local_decls) = mir.basic_blocks_and_local_decls_mut();
         let local_decls = &*local_decls;
@@ -38,6 +44,12 @@ impl MirPass for Deaggregator {
                     return None;
                 }
 
+                if !tcx.consider_optimizing(|| {
+                    format!("Deaggregator - {:?} in {:?}", stmt.source_info, source)
+                }) {
+                    return None;
+                }
+
                 let stmt = stmt.replace_nop();
                 let source_info = stmt.source_info;
                 let (mut lhs, kind, operands) = match stmt.kind {
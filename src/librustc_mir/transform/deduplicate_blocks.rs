@@ -0,0 +1,185 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Redirects jumps to basic blocks which are identical to another block to that block.
+//!
+//! Bounds checks, drops and calls in a loop or in the arms of a match each get their own
+//! cleanup blocks, which frequently end up doing exactly the same thing. Blocks are compared
+//! structurally, ignoring their spans; the duplicates become unreachable and are removed by
+//! `SimplifyCfg`. Only the statements and terminators that commonly appear in such blocks are
+//! compared; blocks containing anything else are left alone.
+
+use rustc::mir::{BasicBlock, BasicBlockData, Mir, Operand, Rvalue, Statement, StatementKind};
+use rustc::mir::{TerminatorKind, traversal};
+use rustc::session::Session;
+use rustc::ty::TyCtxt;
+use rustc::util::nodemap::FxHashMap;
+use rustc_data_structures::indexed_vec::IndexVec;
+use transform::{MirPass, MirSource};
+
+pub struct DeduplicateBlocks;
+
+impl MirPass for DeduplicateBlocks {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.mir_opt_level > 1
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let consider_merging = |bb: BasicBlock, representative: BasicBlock| {
+            tcx.consider_optimizing(|| {
+                format!("DeduplicateBlocks - {:?} into {:?} in {:?}", bb, representative, source)
+            })
+        };
+
+        // Merging duplicates can make their predecessors identical, so repeat until nothing
+        // changes. Each round makes some blocks unreachable, so this terminates.
+        while let Some(replacements) = find_duplicates(mir, &consider_merging) {
+            for data in mir.basic_blocks_mut() {
+                for target in data.terminator_mut().successors_mut() {
+                    *target = replacements[*target];
+                }
+            }
+        }
+    }
+}
+
+/// Maps each reachable block to the first reachable block identical to it, or returns `None`
+/// if there are no duplicates. Only merges that `consider_merging` accepts are made.
+fn find_duplicates<'tcx, F>(mir: &Mir<'tcx>, consider_merging: F)
+                            -> Option<IndexVec<BasicBlock, BasicBlock>>
+    where F: Fn(BasicBlock, BasicBlock) -> bool
+{
+    let mut replacements: IndexVec<BasicBlock, BasicBlock> = mir.basic_blocks().indices().collect();
+    let mut any_duplicate = false;
+
+    // Only blocks with the same successors can be identical, so use those (and some cheap
+    // properties) to narrow down the candidates.
+    let mut candidates: FxHashMap<_, Vec<BasicBlock>> = FxHashMap();
+    let mut reachable: Vec<_> = traversal::preorder(mir).map(|(bb, _)| bb).collect();
+    reachable.sort();
+    for bb in reachable {
+        let data = &mir[bb];
+        let key = (data.is_cleanup,
+                   data.statements.len(),
+                   data.terminator().successors().cloned().collect::<Vec<_>>());
+        let same_key = candidates.entry(key).or_insert(vec![]);
+        match same_key.iter().cloned().find(|&other| blocks_eq(&mir[other], data)) {
+            Some(representative) if consider_merging(bb, representative) => {
+                debug!("{:?} is a duplicate of {:?}", bb, representative);
+                replacements[bb] = representative;
+                any_duplicate = true;
+            }
+            Some(_) => {}
+            None => same_key.push(bb),
+        }
+    }
+
+    if any_duplicate {
+        Some(replacements)
+    } else {
+        None
+    }
+}
+
+fn blocks_eq<'tcx>(a: &BasicBlockData<'tcx>, b: &BasicBlockData<'tcx>) -> bool {
+    a.is_cleanup == b.is_cleanup &&
+    a.statements.len() == b.statements.len() &&
+    a.statements.iter().zip(&b.statements).all(|(a, b)| statements_eq(a, b)) &&
+    terminators_eq(&a.terminator().kind, &b.terminator().kind)
+}
+
+fn statements_eq<'tcx>(a: &Statement<'tcx>, b: &Statement<'tcx>) -> bool {
+    match (&a.kind, &b.kind) {
+        (&StatementKind::Assign(ref place_a, ref rvalue_a),
+         &StatementKind::Assign(ref place_b, ref rvalue_b)) => {
+            place_a == place_b && rvalues_eq(rvalue_a, rvalue_b)
+        }
+        (&StatementKind::SetDiscriminant { place: ref place_a, variant_index: variant_a },
+         &StatementKind::SetDiscriminant { place: ref place_b, variant_index: variant_b }) => {
+            place_a == place_b && variant_a == variant_b
+        }
+        (&StatementKind::StorageLive(a), &StatementKind::StorageLive(b)) |
+        (&StatementKind::StorageDead(a), &StatementKind::StorageDead(b)) => a == b,
+        (&StatementKind::Nop, &StatementKind::Nop) => true,
+        _ => false,
+    }
+}
+
+fn rvalues_eq<'tcx>(a: &Rvalue<'tcx>, b: &Rvalue<'tcx>) -> bool {
+    match (a, b) {
+        (&Rvalue::Use(ref a), &Rvalue::Use(ref b)) => operands_eq(a, b),
+        (&Rvalue::BinaryOp(op_a, ref left_a, ref right_a),
+         &Rvalue::BinaryOp(op_b, ref left_b, ref right_b)) |
+        (&Rvalue::CheckedBinaryOp(op_a, ref left_a, ref right_a),
+         &Rvalue::CheckedBinaryOp(op_b, ref left_b, ref right_b)) => {
+            op_a == op_b && operands_eq(left_a, left_b) && operands_eq(right_a, right_b)
+        }
+        (&Rvalue::UnaryOp(op_a, ref a), &Rvalue::UnaryOp(op_b, ref b)) => {
+            op_a == op_b && operands_eq(a, b)
+        }
+        (&Rvalue::Cast(kind_a, ref a, ty_a), &Rvalue::Cast(kind_b, ref b, ty_b)) => {
+            kind_a == kind_b && operands_eq(a, b) && ty_a == ty_b
+        }
+        (&Rvalue::Discriminant(ref a), &Rvalue::Discriminant(ref b)) |
+        (&Rvalue::Len(ref a), &Rvalue::Len(ref b)) => a == b,
+        _ => false,
+    }
+}
+
+fn terminators_eq<'tcx>(a: &TerminatorKind<'tcx>, b: &TerminatorKind<'tcx>) -> bool {
+    match (a, b) {
+        (&TerminatorKind::Goto { target: a }, &TerminatorKind::Goto { target: b }) => a == b,
+        (&TerminatorKind::Resume, &TerminatorKind::Resume) |
+        (&TerminatorKind::Abort, &TerminatorKind::Abort) |
+        (&TerminatorKind::Return, &TerminatorKind::Return) |
+        (&TerminatorKind::Unreachable, &TerminatorKind::Unreachable) => true,
+        (&TerminatorKind::SwitchInt {
+            discr: ref discr_a, switch_ty: ty_a, targets: ref targets_a
+         },
+         &TerminatorKind::SwitchInt {
+            discr: ref discr_b, switch_ty: ty_b, targets: ref targets_b
+         }) => {
+            operands_eq(discr_a, discr_b) && ty_a == ty_b && targets_a == targets_b
+        }
+        (&TerminatorKind::Drop { location: ref location_a, target: target_a, unwind: unwind_a },
+         &TerminatorKind::Drop {
+            location: ref location_b, target: target_b, unwind: unwind_b
+         }) => {
+            location_a == location_b && target_a == target_b && unwind_a == unwind_b
+        }
+        (&TerminatorKind::Call {
+            func: ref func_a, args: ref args_a, destination: ref dest_a, cleanup: cleanup_a
+         },
+         &TerminatorKind::Call {
+            func: ref func_b, args: ref args_b, destination: ref dest_b, cleanup: cleanup_b
+         }) => {
+            operands_eq(func_a, func_b) &&
+            args_a.len() == args_b.len() &&
+            args_a.iter().zip(args_b).all(|(a, b)| operands_eq(a, b)) &&
+            dest_a == dest_b && cleanup_a == cleanup_b
+        }
+        _ => false,
+    }
+}
+
+/// Compares operands like `==` does, except for the spans of constants.
+fn operands_eq<'tcx>(a: &Operand<'tcx>, b: &Operand<'tcx>) -> bool {
+    match (a, b) {
+        (&Operand::Copy(ref a), &Operand::Copy(ref b)) |
+        (&Operand::Move(ref a), &Operand::Move(ref b)) => a == b,
+        (&Operand::Constant(ref a), &Operand::Constant(ref b)) => {
+            a.ty == b.ty && a.literal == b.literal
+        }
+        _ => false,
+    }
+}
@@ -13,8 +13,9 @@
 use std::borrow::Cow;
 use std::fmt;
 use std::fs::File;
-use std::io;
+use std::io::{self, Write};
 
+use rustc::hir::def_id::LOCAL_CRATE;
 use rustc::mir::Mir;
 use rustc::session::config::{OutputFilenames, OutputType};
 use rustc::ty::TyCtxt;
@@ -72,6 +73,21 @@ pub fn emit_mir<'a, 'tcx>(
 {
     let path = outputs.path(OutputType::Mir);
     let mut f = File::create(&path)?;
+    write_emit_mir_header(tcx, &mut f)?;
     mir_util::write_mir_pretty(tcx, None, &mut f)?;
     Ok(())
 }
+
+/// Writes the header of a `--emit=mir` file: the crate the MIR belongs to,
+/// the compiler that produced it and the MIR optimization level it was
+/// built with.
+fn write_emit_mir_header<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                   w: &mut dyn Write)
+                                   -> io::Result<()>
+{
+    writeln!(w, "// MIR for crate `{}` emitted by rustc {} ({})",
+             tcx.crate_name(LOCAL_CRATE),
+             option_env!("CFG_VERSION").unwrap_or("unknown version"),
+             option_env!("CFG_VER_HASH").unwrap_or("unknown commit hash"))?;
+    writeln!(w, "// mir-opt-level: {}", tcx.sess.opts.debugging_opts.mir_opt_level)
+}
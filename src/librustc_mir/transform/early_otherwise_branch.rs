@@ -0,0 +1,265 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Compares the discriminants of two enums up front when a nested switch on them only cares
+//! about them being equal.
+//!
+//! Matching on a pair, e.g.
+//!
+//!     match (a, b) {
+//!         (Some(x), Some(y)) => ...,
+//!         (None, None) => ...,
+//!         _ => OTHERWISE,
+//!     }
+//!
+//! switches on the discriminant of `a`, and then in every target on the one of `b`, each time
+//! testing for the same variant and falling back to `OTHERWISE`:
+//!
+//!     bb0: {
+//!         _3 = discriminant((_1.0: Option<u32>));
+//!         switchInt(move _3) -> [0isize: bb1, 1isize: bb2, otherwise: bb5];
+//!     }
+//!     bb1: {
+//!         _4 = discriminant((_1.1: Option<u32>));
+//!         switchInt(move _4) -> [0isize: bb3, otherwise: bb6];
+//!     }
+//!     bb2: {
+//!         _5 = discriminant((_1.1: Option<u32>));
+//!         switchInt(move _5) -> [1isize: bb4, otherwise: bb6];
+//!     }
+//!
+//! We instead branch to `OTHERWISE` right away if the discriminants differ, and otherwise
+//! switch once, straight to the arms:
+//!
+//!     bb0: {
+//!         _3 = discriminant((_1.0: Option<u32>));
+//!         StorageLive(_6);
+//!         _6 = discriminant((_1.1: Option<u32>));
+//!         StorageLive(_7);
+//!         _7 = Ne(_3, move _6);
+//!         StorageDead(_6);
+//!         switchInt(move _7) -> [false: bb7, otherwise: bb6];
+//!     }
+//!     bb7: {
+//!         StorageDead(_7);
+//!         switchInt(move _3) -> [0isize: bb3, 1isize: bb4, otherwise: bb5];
+//!     }
+//!
+//! The blocks of the inner switches are then removed by `SimplifyCfg`.
+
+use rustc::mir::{BasicBlock, BasicBlockData, BinOp, Local, LocalDecl, Mir, Operand, Place};
+use rustc::mir::{ProjectionElem, Rvalue, Statement, StatementKind, SwitchTargets, Terminator};
+use rustc::mir::TerminatorKind;
+use rustc::session::Session;
+use rustc::ty::{Ty, TyCtxt};
+use transform::{MirPass, MirSource};
+
+pub struct EarlyOtherwiseBranch;
+
+impl MirPass for EarlyOtherwiseBranch {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.mir_opt_level > 1
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let opts: Vec<_> = mir.basic_blocks().indices()
+            .filter_map(|bb| find_optimization(tcx, mir, bb))
+            .collect();
+
+        for opt in opts {
+            if !tcx.consider_optimizing(|| {
+                format!("EarlyOtherwiseBranch - {:?} in {:?}", opt.parent, source)
+            }) {
+                break;
+            }
+            debug!("Comparing discriminants early in {:?}: {:?}", opt.parent, opt);
+            let source_info = mir[opt.parent].terminator().source_info;
+            let span = source_info.span;
+            let second_discr = mir.local_decls.push(LocalDecl::new_temp(opt.switch_ty, span));
+            let not_equal = mir.local_decls.push(LocalDecl::new_temp(tcx.types.bool, span));
+
+            // Once the discriminants are known to be equal, switch straight to the arms.
+            let is_cleanup = mir[opt.parent].is_cleanup;
+            let equal_bb = mir.basic_blocks_mut().push(BasicBlockData {
+                statements: vec![Statement {
+                    source_info,
+                    kind: StatementKind::StorageDead(not_equal),
+                }],
+                terminator: Some(Terminator {
+                    source_info,
+                    kind: TerminatorKind::SwitchInt {
+                        discr: Operand::Move(Place::Local(opt.first_discr)),
+                        switch_ty: opt.switch_ty,
+                        targets: SwitchTargets::new(opt.arms, opt.parent_otherwise),
+                    },
+                }),
+                is_cleanup,
+            });
+
+            let parent = &mut mir.basic_blocks_mut()[opt.parent];
+            let new_statements = vec![
+                StatementKind::StorageLive(second_discr),
+                StatementKind::Assign(Place::Local(second_discr),
+                                      Rvalue::Discriminant(opt.second_place)),
+                StatementKind::StorageLive(not_equal),
+                StatementKind::Assign(Place::Local(not_equal),
+                                      Rvalue::BinaryOp(BinOp::Ne,
+                                                       Operand::Copy(Place::Local(opt.first_discr)),
+                                                       Operand::Move(Place::Local(second_discr)))),
+                StatementKind::StorageDead(second_discr),
+            ];
+            parent.statements.extend(new_statements.into_iter().map(|kind| Statement {
+                source_info,
+                kind,
+            }));
+            parent.terminator_mut().kind = TerminatorKind::SwitchInt {
+                discr: Operand::Move(Place::Local(not_equal)),
+                switch_ty: tcx.types.bool,
+                targets: SwitchTargets::static_if(0, equal_bb, opt.otherwise),
+            };
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Optimization<'tcx> {
+    /// The block switching on the first discriminant.
+    parent: BasicBlock,
+    /// The local holding the first discriminant.
+    first_discr: Local,
+    switch_ty: Ty<'tcx>,
+    /// The place whose discriminant the inner switches test.
+    second_place: Place<'tcx>,
+    /// For each value of the first discriminant, the block taken when the second is the same.
+    arms: Vec<(u128, BasicBlock)>,
+    /// Where the inner switches go when the discriminants differ.
+    otherwise: BasicBlock,
+    /// Where the outer switch goes for values it doesn't test.
+    parent_otherwise: BasicBlock,
+}
+
+fn find_optimization<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                               mir: &Mir<'tcx>,
+                               parent: BasicBlock)
+                               -> Option<Optimization<'tcx>> {
+    let parent_data = &mir[parent];
+    let (first_discr, first_place, switch_ty, targets) =
+        match_discriminant_switch(parent_data)?;
+    if targets.iter().count() < 2 {
+        return None;
+    }
+
+    let mut second = None;
+    let mut arms = vec![];
+    for (value, child) in targets.iter() {
+        let child_data = &mir[child];
+        let (_, place, child_ty, child_targets) = match_discriminant_switch(child_data)?;
+        let (child_value, arm, otherwise) = child_targets.as_static_if()?;
+        if child_value != value || child_ty != switch_ty ||
+           child_data.is_cleanup != parent_data.is_cleanup {
+            return None;
+        }
+        match second {
+            None => second = Some((place, otherwise)),
+            Some((second_place, second_otherwise)) => {
+                if place != second_place || otherwise != second_otherwise {
+                    return None;
+                }
+            }
+        }
+        arms.push((value, arm));
+    }
+    let (second_place, otherwise) = second?;
+
+    // The discriminant of the second place is now read even when the first switch wouldn't
+    // have gone on to read it. Only do that for fields of the same local, which are there
+    // whenever the first place is, and only compare discriminants of the same type.
+    let first_local = field_path_local(first_place)?;
+    if field_path_local(second_place) != Some(first_local) ||
+       first_place.ty(mir, tcx).to_ty(tcx) != second_place.ty(mir, tcx).to_ty(tcx) {
+        return None;
+    }
+
+    // For values the outer switch doesn't test, the discriminants being equal doesn't help;
+    // this is fine if those values can't happen, or lead to the same place anyway.
+    let parent_otherwise = targets.otherwise();
+    let parent_otherwise_unreachable = {
+        let data = &mir[parent_otherwise];
+        match data.terminator().kind {
+            TerminatorKind::Unreachable => data.statements.is_empty(),
+            _ => false,
+        }
+    };
+    if parent_otherwise != otherwise && !parent_otherwise_unreachable {
+        return None;
+    }
+
+    Some(Optimization {
+        parent,
+        first_discr,
+        switch_ty,
+        second_place: second_place.clone(),
+        arms,
+        otherwise,
+        parent_otherwise,
+    })
+}
+
+/// Matches a block of the shape
+///
+///     _D = discriminant(PLACE);
+///     switchInt(move _D) -> [...];
+///
+/// possibly with storage markers and `nop`s before the assignment.
+fn match_discriminant_switch<'a, 'tcx>(data: &'a BasicBlockData<'tcx>)
+    -> Option<(Local, &'a Place<'tcx>, Ty<'tcx>, &'a SwitchTargets)>
+{
+    let (discr, switch_ty, targets) = match data.terminator().kind {
+        TerminatorKind::SwitchInt {
+            discr: Operand::Move(Place::Local(discr)),
+            switch_ty,
+            ref targets,
+        } => (discr, switch_ty, targets),
+        _ => return None,
+    };
+    let (last, rest) = data.statements.split_last()?;
+    let only_markers = rest.iter().all(|statement| match statement.kind {
+        StatementKind::StorageLive(_) |
+        StatementKind::StorageDead(_) |
+        StatementKind::Nop => true,
+        _ => false,
+    });
+    if !only_markers {
+        return None;
+    }
+    match last.kind {
+        StatementKind::Assign(Place::Local(local), Rvalue::Discriminant(ref place))
+            if local == discr => Some((discr, place, switch_ty, targets)),
+        _ => None,
+    }
+}
+
+/// If `place` is a local or a (nested) field of one, without any dereference or downcast that
+/// might not be valid everywhere, returns that local.
+fn field_path_local<'tcx>(mut place: &Place<'tcx>) -> Option<Local> {
+    loop {
+        match *place {
+            Place::Local(local) => return Some(local),
+            Place::Static(_) => return None,
+            Place::Projection(ref proj) => match proj.elem {
+                ProjectionElem::Field(..) => place = &proj.base,
+                _ => return None,
+            },
+        }
+    }
+}
@@ -32,6 +32,10 @@ use std::fmt;
 pub struct ElaborateDrops;
 
 impl MirPass for ElaborateDrops {
+    fn required_phase(&self) -> Option<MirPhase> {
+        Some(MirPhase::Validated)
+    }
+
     fn run_pass<'a, 'tcx>(&self,
                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
                           src: MirSource,
@@ -65,6 +69,7 @@ impl MirPass for ElaborateDrops {
                 flow_inits,
                 flow_uninits,
                 drop_flags: FxHashMap(),
+                split_drops: FxHashMap(),
                 patch: MirPatch::new(mir),
             }.elaborate()
         };
@@ -262,6 +267,15 @@ impl<'a, 'b, 'tcx> DropElaborator<'a, 'tcx> for Elaborator<'a, 'b, 'tcx> {
         })
     }
 
+    fn has_array_subpaths(&self, path: Self::Path) -> bool {
+        dataflow::move_path_children_matching(self.ctxt.move_data(), path, |p| {
+            match p {
+                &Projection { elem: ProjectionElem::ConstantIndex { .. }, .. } => true,
+                _ => false
+            }
+        }).is_some()
+    }
+
     fn deref_subpath(&self, path: Self::Path) -> Option<Self::Path> {
         dataflow::move_path_children_matching(self.ctxt.move_data(), path, |p| {
             match p {
@@ -294,6 +308,10 @@ struct ElaborateDropsCtxt<'a, 'tcx: 'a> {
     flow_inits: DataflowResults<MaybeInitializedPlaces<'a, 'tcx, 'tcx>>,
     flow_uninits:  DataflowResults<MaybeUninitializedPlaces<'a, 'tcx, 'tcx>>,
     drop_flags: FxHashMap<MovePathIndex, Local>,
+    /// Conditional drops that are instead done statically on the edges into their block;
+    /// maps the block to its predecessors and whether the place is initialized coming
+    /// from them.
+    split_drops: FxHashMap<BasicBlock, Vec<(BasicBlock, bool)>>,
     patch: MirPatch<'tcx>,
 }
 
@@ -351,9 +369,10 @@ impl<'b, 'tcx> ElaborateDropsCtxt<'b, 'tcx> {
     {
         for (bb, data) in self.mir.basic_blocks().iter_enumerated() {
             let terminator = data.terminator();
-            let location = match terminator.kind {
-                TerminatorKind::Drop { ref location, .. } |
-                TerminatorKind::DropAndReplace { ref location, .. } => location,
+            let (location, splittable) = match terminator.kind {
+                TerminatorKind::Drop { ref location, .. } => (location, true),
+                // `elaborate_replace` needs a drop flag for a conditional drop.
+                TerminatorKind::DropAndReplace { ref location, .. } => (location, false),
                 _ => continue
             };
 
@@ -380,6 +399,19 @@ impl<'b, 'tcx> ElaborateDropsCtxt<'b, 'tcx> {
                 }
             };
 
+            let mut conditional = false;
+            on_all_drop_children_bits(self.tcx, self.mir, self.env, path, |child| {
+                let (maybe_live, maybe_dead) = init_data.state(child);
+                conditional |= maybe_live && maybe_dead;
+            });
+            if conditional && splittable {
+                if let Some(states) = self.split_drop_states(bb, path) {
+                    debug!("collect_drop_flags: splitting drop in {:?} over {:?}", bb, states);
+                    self.split_drops.insert(bb, states);
+                    continue;
+                }
+            }
+
             on_all_drop_children_bits(self.tcx, self.mir, self.env, path, |child| {
                 let (maybe_live, maybe_dead) = init_data.state(child);
                 debug!("collect_drop_flags: collecting {:?} from {:?}@{:?} - {:?}",
@@ -391,6 +423,110 @@ impl<'b, 'tcx> ElaborateDropsCtxt<'b, 'tcx> {
         }
     }
 
+    /// Checks whether the conditional drop of `path` terminating `bb` can be split into
+    /// static drops on the edges into `bb`, i.e. whether the initialization state of the
+    /// dropped place is known on each of them. Returns the predecessors together with
+    /// whether the place is initialized coming from them.
+    ///
+    /// This is only done for blocks that are only reached through gotos and whose
+    /// statements don't change any initialization state, which is what MIR building
+    /// produces for the end of scopes after a `match` moved out of a variable in some arms.
+    fn split_drop_states(&self, bb: BasicBlock, path: MovePathIndex)
+                         -> Option<Vec<(BasicBlock, bool)>>
+    {
+        let data = &self.mir[bb];
+        // The start block is also entered from outside the body.
+        if bb == START_BLOCK || data.is_cleanup {
+            return None;
+        }
+        let mut has_effects = false;
+        for statement_index in 0..data.statements.len() {
+            let loc = Location { block: bb, statement_index };
+            drop_flag_effects_for_location(self.tcx, self.mir, self.env, loc, |_, _| {
+                has_effects = true;
+            });
+        }
+        if has_effects {
+            return None;
+        }
+
+        let predecessors = self.mir.predecessors_for(bb);
+        let mut states = Vec::with_capacity(predecessors.len());
+        for &pred in predecessors.iter() {
+            let pred_data = &self.mir[pred];
+            match pred_data.terminator().kind {
+                TerminatorKind::Goto { .. } if !pred_data.is_cleanup => {}
+                _ => return None,
+            }
+
+            // Neither gotos nor the statements of `bb` change the initialization state.
+            let init_data = self.initialization_data_at(Location {
+                block: pred,
+                statement_index: pred_data.statements.len(),
+            });
+            let mut some_live = false;
+            let mut some_dead = false;
+            on_all_drop_children_bits(self.tcx, self.mir, self.env, path, |child| {
+                let (live, dead) = init_data.state(child);
+                some_live |= live;
+                some_dead |= dead;
+            });
+            if some_live && some_dead {
+                return None;
+            }
+            states.push((pred, some_live));
+        }
+
+        if states.len() > 1 { Some(states) } else { None }
+    }
+
+    /// Replaces the gotos into the block of a split drop with gotos to copies of it, which
+    /// either do a static drop or, if the place is uninitialized coming from there, go
+    /// straight to the target of the drop.
+    fn elaborate_split_drop(&mut self,
+                            bb: BasicBlock,
+                            location: &Place<'tcx>,
+                            path: MovePathIndex,
+                            target: BasicBlock,
+                            unwind: BasicBlock)
+    {
+        let mir = self.mir;
+        let data = &mir[bb];
+        let source_info = data.terminator().source_info;
+        let states = self.split_drops.remove(&bb).unwrap();
+        for (pred, live) in states {
+            let copy = self.patch.new_block(BasicBlockData {
+                statements: data.statements.clone(),
+                terminator: Some(Terminator {
+                    source_info,
+                    kind: TerminatorKind::Goto { target },
+                }),
+                is_cleanup: false,
+            });
+            if live {
+                let init_data = self.initialization_data_at(Location {
+                    block: pred,
+                    statement_index: mir[pred].statements.len(),
+                });
+                elaborate_drop(
+                    &mut Elaborator {
+                        init_data: &init_data,
+                        ctxt: self
+                    },
+                    source_info,
+                    location,
+                    path,
+                    target,
+                    Unwind::To(unwind),
+                    copy);
+            }
+            self.patch.patch_terminator(pred, TerminatorKind::Goto { target: copy });
+        }
+
+        // All edges into the block are redirected, so it is unreachable now.
+        self.patch.patch_terminator(bb, TerminatorKind::Goto { target });
+    }
+
     fn elaborate_drops(&mut self)
     {
         for (bb, data) in self.mir.basic_blocks().iter_enumerated() {
@@ -402,6 +538,10 @@ impl<'b, 'tcx> ElaborateDropsCtxt<'b, 'tcx> {
                 TerminatorKind::Drop { ref location, target, unwind } => {
                     let init_data = self.initialization_data_at(loc);
                     match self.move_data().rev_lookup.find(location) {
+                        LookupResult::Exact(path) if self.split_drops.contains_key(&bb) => {
+                            let unwind = Option::unwrap_or(unwind, resume_block);
+                            self.elaborate_split_drop(bb, location, path, target, unwind);
+                        }
                         LookupResult::Exact(path) => {
                             elaborate_drop(
                                 &mut Elaborator {
@@ -611,6 +751,10 @@ impl<'b, 'tcx> ElaborateDropsCtxt<'b, 'tcx> {
                             // (in particular it can be patched to be replaced with
                             // a Goto; see `MirPatch::new`).
                         }
+                        TerminatorKind::Goto { .. } => {
+                            // Gotos into a split drop are redirected, but have
+                            // no drop flag effects.
+                        }
                         _ => {
                             assert!(!self.patch.is_patched(bb));
                         }
@@ -35,6 +35,10 @@
 //!         mir_locals...,
 //!     }
 //!
+//! Locals which are never storage-live at the same time and have the same type share a field,
+//! which keeps generators from growing with every local that is saved across some suspension
+//! point. Which locals can share a field is determined by `compute_storage_conflicts`.
+//!
 //! This pass computes the meaning of the state field and the MIR locals which are live
 //! across a suspension point. There are however two hardcoded generator states:
 //!     0 - Generator have not been resumed yet
@@ -67,16 +71,15 @@ use rustc::ty::{self, TyCtxt, AdtDef, Ty};
 use rustc::ty::subst::Substs;
 use util::dump_mir;
 use util::liveness::{self, LivenessMode};
+use rustc_data_structures::bitvec::BitMatrix;
 use rustc_data_structures::indexed_vec::Idx;
-use rustc_data_structures::indexed_set::IdxSetBuf;
+use rustc_data_structures::indexed_set::{IdxSet, IdxSetBuf};
 use std::collections::HashMap;
-use std::borrow::Cow;
-use std::iter::once;
 use std::mem;
 use transform::{MirPass, MirSource};
 use transform::simplify;
 use transform::no_landing_pads::no_landing_pads;
-use dataflow::{do_dataflow, DebugFormatted, state_for_location};
+use dataflow::{do_dataflow, DataflowResults, DebugFormatted, state_for_location};
 use dataflow::{MaybeStorageLive, HaveBeenBorrowedLocals};
 
 pub struct StateTransform;
@@ -366,7 +369,8 @@ fn locals_live_across_suspend_points<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                                                source: MirSource,
                                                movable: bool) ->
                                                (liveness::LocalSet,
-                                                HashMap<BasicBlock, liveness::LocalSet>) {
+                                                HashMap<BasicBlock, liveness::LocalSet>,
+                                                BitMatrix) {
     let dead_unwinds = IdxSetBuf::new_empty(mir.basic_blocks().len());
     let node_id = tcx.hir.as_local_node_id(source.def_id).unwrap();
 
@@ -457,7 +461,69 @@ fn locals_live_across_suspend_points<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
     // The generator argument is ignored
     set.remove(&self_arg());
 
-    (set, storage_liveness_map)
+    let storage_conflicts = compute_storage_conflicts(mir, &set, &ignored.0, &storage_live);
+
+    (set, storage_liveness_map, storage_conflicts)
+}
+
+/// Computes which of the `saved_locals` may have live storage at the same time. Locals which
+/// don't conflict can be stored in the same generator field.
+///
+/// The result is a symmetric matrix indexed by `Local` in both dimensions. Locals without
+/// storage statements (`ignored`) are always live and conflict with every saved local.
+fn compute_storage_conflicts<'a, 'tcx>(
+    mir: &'a Mir<'tcx>,
+    saved_locals: &IdxSet<Local>,
+    ignored: &IdxSet<Local>,
+    storage_live: &DataflowResults<MaybeStorageLive<'a, 'tcx>>,
+) -> BitMatrix {
+    let local_count = mir.local_decls.len();
+    let mut conflicts = BitMatrix::new(local_count, local_count);
+
+    // Two locals are live at the same time if one of them becomes live while the other one
+    // already is, so it is enough to look at block entries and at `StorageLive` statements.
+    {
+        let mut record = |live: &IdxSet<Local>, local: Option<Local>| {
+            let mut live = live.to_owned();
+            live.union(ignored);
+            live.intersect(saved_locals);
+            match local {
+                Some(local) => {
+                    if saved_locals.contains(&local) {
+                        for other in live.iter() {
+                            conflicts.add(local.index(), other.index());
+                            conflicts.add(other.index(), local.index());
+                        }
+                    }
+                }
+                None => {
+                    for a in live.iter() {
+                        for b in live.iter() {
+                            conflicts.add(a.index(), b.index());
+                        }
+                    }
+                }
+            }
+        };
+
+        for (block, data) in mir.basic_blocks().iter_enumerated() {
+            let mut live = storage_live.sets().on_entry_set_for(block.index()).to_owned();
+            record(&live, None);
+
+            for statement in &data.statements {
+                match statement.kind {
+                    StatementKind::StorageLive(l) => {
+                        record(&live, Some(l));
+                        live.add(&l);
+                    }
+                    StatementKind::StorageDead(l) => { live.remove(&l); }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    conflicts
 }
 
 fn compute_layout<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
@@ -471,10 +537,8 @@ fn compute_layout<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
         HashMap<BasicBlock, liveness::LocalSet>)
 {
     // Use a liveness analysis to compute locals which are live across a suspension point
-    let (live_locals, storage_liveness) = locals_live_across_suspend_points(tcx,
-                                                                            mir,
-                                                                            source,
-                                                                            movable);
+    let (live_locals, storage_liveness, storage_conflicts) =
+        locals_live_across_suspend_points(tcx, mir, source, movable);
     // Erase regions from the types passed in from typeck so we can compare them with
     // MIR types
     let allowed_upvars = tcx.erase_regions(&upvars);
@@ -512,10 +576,30 @@ fn compute_layout<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
 
     // Create a map from local indices to generator struct indices.
     // These are offset by (upvar_len + 1) because of fields which comes before locals.
-    // We also create a vector of the LocalDecls of these locals.
-    let (remap, vars) = live_decls.enumerate().map(|(idx, (local, var))| {
-        ((local, (var.ty, upvar_len + 1 + idx)), var)
-    }).unzip();
+    // A local reuses the field of an earlier local of the same type if the storage of
+    // the two is never live at the same time. We also create a vector of the LocalDecls
+    // of the locals which got a field of their own.
+    let mut remap = HashMap::new();
+    let mut vars: Vec<LocalDecl<'tcx>> = Vec::new();
+    // The locals stored in each field.
+    let mut field_locals: Vec<Vec<Local>> = Vec::new();
+    for (local, var) in live_decls {
+        let shared = vars.iter().zip(&field_locals).position(|(field, locals)| {
+            field.ty == var.ty &&
+                locals.iter().all(|other| !storage_conflicts.contains(local.index(),
+                                                                      other.index()))
+        });
+        let idx = match shared {
+            Some(idx) => idx,
+            None => {
+                vars.push(var.clone());
+                field_locals.push(vec![]);
+                vars.len() - 1
+            }
+        };
+        field_locals[idx].push(local);
+        remap.insert(local, (var.ty, upvar_len + 1 + idx));
+    }
 
     let layout = GeneratorLayout {
         fields: vars
@@ -534,8 +618,7 @@ fn insert_switch<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
     let switch = TerminatorKind::SwitchInt {
         discr: Operand::Copy(transform.make_field(transform.state_field, tcx.types.u32)),
         switch_ty: tcx.types.u32,
-        values: Cow::from(cases.iter().map(|&(i, _)| i.into()).collect::<Vec<_>>()),
-        targets: cases.iter().map(|&(_, d)| d).chain(once(default_block)).collect(),
+        targets: SwitchTargets::new(cases.iter().map(|&(i, d)| (i.into(), d)), default_block),
     };
 
     let source_info = source_info(mir);
@@ -831,6 +914,10 @@ fn create_cases<'a, 'tcx, F>(mir: &mut Mir<'tcx>,
 }
 
 impl MirPass for StateTransform {
+    fn required_phase(&self) -> Option<MirPhase> {
+        Some(MirPhase::Validated)
+    }
+
     fn run_pass<'a, 'tcx>(&self,
                     tcx: TyCtxt<'a, 'tcx, 'tcx>,
                     source: MirSource,
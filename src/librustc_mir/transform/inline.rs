@@ -19,6 +19,7 @@ use rustc_data_structures::indexed_vec::{Idx, IndexVec};
 
 use rustc::mir::*;
 use rustc::mir::visit::*;
+use rustc::session::Session;
 use rustc::ty::{self, Instance, Ty, TyCtxt};
 use rustc::ty::subst::{Subst,Substs};
 
@@ -46,16 +47,27 @@ struct CallSite<'tcx> {
     substs: &'tcx Substs<'tcx>,
     bb: BasicBlock,
     location: SourceInfo,
+    /// How many inlined calls deep this callsite is nested in the caller.
+    depth: usize,
 }
 
 impl MirPass for Inline {
+    fn required_phase(&self) -> Option<MirPhase> {
+        Some(MirPhase::Validated)
+    }
+
+    fn is_enabled(&self, sess: &Session) -> bool {
+        if let Some(enabled) = sess.opts.debugging_opts.inline_mir {
+            return enabled;
+        }
+        sess.opts.debugging_opts.mir_opt_level >= 2
+    }
+
     fn run_pass<'a, 'tcx>(&self,
                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
                           source: MirSource,
                           mir: &mut Mir<'tcx>) {
-        if tcx.sess.opts.debugging_opts.mir_opt_level >= 2 {
-            Inliner { tcx, source }.run_pass(mir);
-        }
+        Inliner { tcx, source }.run_pass(mir);
     }
 }
 
@@ -104,7 +116,8 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
                                     callee: instance.def_id(),
                                     substs: instance.substs,
                                     bb,
-                                    location: terminator.source_info
+                                    location: terminator.source_info,
+                                    depth: 0,
                                 });
                             }
                         }
@@ -114,6 +127,8 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
             return;
         }
 
+        let recursion_limit = *self.tcx.sess.recursion_limit.get();
+
         let mut local_change;
         let mut changed = false;
 
@@ -121,6 +136,11 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
             local_change = false;
             while let Some(callsite) = callsites.pop_front() {
                 debug!("checking whether to inline callsite {:?}", callsite);
+                if callsite.depth >= recursion_limit {
+                    debug!("checking whether to inline callsite {:?} - recursion limit reached",
+                           callsite);
+                    continue;
+                }
                 if !self.tcx.is_mir_available(callsite.callee) {
                     debug!("checking whether to inline callsite {:?} - MIR unavailable", callsite);
                     continue;
@@ -144,6 +164,12 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
                     }
                 };
 
+                if !self.tcx.consider_optimizing(|| {
+                    format!("Inline {:?} into {:?}", callsite, self.source)
+                }) {
+                    continue;
+                }
+
                 let start = caller_mir.basic_blocks().len();
                 debug!("attempting to inline callsite {:?} - mir={:?}", callsite, callee_mir);
                 if !self.inline_call(callsite, caller_mir, callee_mir) {
@@ -165,7 +191,8 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
                                     callee: callee_def_id,
                                     substs,
                                     bb,
-                                    location: terminator.source_info
+                                    location: terminator.source_info,
+                                    depth: callsite.depth + 1,
                                 });
                             }
                         }
@@ -184,7 +211,9 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
         // Simplify if we inlined anything.
         if changed {
             debug!("Running simplify cfg on {:?}", self.source);
-            CfgSimplifier::new(caller_mir).simplify();
+            if CfgSimplifier::new(caller_mir).simplify() {
+                caller_mir.invalidate_cfg_cache();
+            }
             remove_dead_blocks(caller_mir);
         }
     }
@@ -242,10 +271,11 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
             }
         }
 
+        let opts = &tcx.sess.opts.debugging_opts;
         let mut threshold = if hinted {
-            HINT_THRESHOLD
+            opts.inline_mir_hint_threshold.unwrap_or(HINT_THRESHOLD)
         } else {
-            DEFAULT_THRESHOLD
+            opts.inline_mir_threshold.unwrap_or(DEFAULT_THRESHOLD)
         };
 
         // Significantly lower the threshold for inlining cold functions
@@ -255,8 +285,15 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
 
         // Give a bonus functions with a small number of blocks,
         // We normally have two or three blocks for even
-        // very small functions.
-        if callee_mir.basic_blocks().len() <= 3 {
+        // very small functions. A loop means the body is not
+        // as cheap as its size suggests though. The query only
+        // has a provider for local items.
+        let metrics = if callsite.callee.is_local() {
+            tcx.mir_body_metrics(callsite.callee)
+        } else {
+            callee_mir.metrics()
+        };
+        if metrics.basic_blocks <= 3 && metrics.loops == 0 {
             threshold += threshold / 4;
         }
         debug!("    final inline threshold = {}", threshold);
@@ -372,6 +409,8 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
                    callsite: CallSite<'tcx>,
                    caller_mir: &mut Mir<'tcx>,
                    mut callee_mir: Mir<'tcx>) -> bool {
+        debug_assert_eq!(callee_mir.phase, MirPhase::Optimized);
+
         let terminator = caller_mir[callsite.bb].terminator.take().unwrap();
         match terminator.kind {
             // FIXME: Handle inlining of diverging calls
@@ -382,14 +421,16 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
                 let mut scope_map = IndexVec::with_capacity(callee_mir.source_scopes.len());
                 let mut promoted_map = IndexVec::with_capacity(callee_mir.promoted.len());
 
+                // The callee's scopes keep their spans, the root scope records where
+                // it was inlined so debuginfo can describe the inlined call.
                 for mut scope in callee_mir.source_scopes.iter().cloned() {
                     if scope.parent_scope.is_none() {
                         scope.parent_scope = Some(callsite.location.scope);
                         scope.span = callee_mir.span;
+                        let callee = Instance::new(callsite.callee, callsite.substs);
+                        scope.inlined = Some((callee, callsite.location.span));
                     }
 
-                    scope.span = callsite.location.span;
-
                     let idx = caller_mir.source_scopes.push(scope);
                     scope_map.push(idx);
                 }
@@ -482,6 +523,12 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
                     caller_mir.basic_blocks_mut().push(block);
                 }
 
+                // Keep the callee's variables visible in the debugger.
+                for mut var_debug_info in callee_mir.var_debug_info.drain(..) {
+                    integrator.visit_var_debug_info(&mut var_debug_info);
+                    caller_mir.var_debug_info.push(var_debug_info);
+                }
+
                 let terminator = Terminator {
                     source_info: callsite.location,
                     kind: TerminatorKind::Goto { target: BasicBlock::new(bb_len) }
@@ -687,7 +734,7 @@ impl<'a, 'tcx> MutVisitor<'tcx> for Integrator<'a, 'tcx> {
                 *target = self.update_target(*target);
             }
             TerminatorKind::SwitchInt { ref mut targets, .. } => {
-                for tgt in targets {
+                for tgt in targets.all_targets_mut() {
                     *tgt = self.update_target(*tgt);
                 }
             }
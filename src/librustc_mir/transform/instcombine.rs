@@ -11,8 +11,10 @@
 //! Performs various peephole optimizations.
 
 use rustc::mir::{Constant, Literal, Location, Place, Mir, Operand, ProjectionElem, Rvalue, Local};
+use rustc::mir::{AggregateKind, BinOp, StatementKind, UnOp};
 use rustc::mir::visit::{MutVisitor, Visitor};
-use rustc::ty::{TyCtxt, TypeVariants};
+use rustc::session::Session;
+use rustc::ty::{self, ParamEnv, TyCtxt, TypeVariants};
 use rustc::util::nodemap::{FxHashMap, FxHashSet};
 use rustc_data_structures::indexed_vec::Idx;
 use std::mem;
@@ -21,20 +23,20 @@ use transform::{MirPass, MirSource};
 pub struct InstCombine;
 
 impl MirPass for InstCombine {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        // We only run when optimizing MIR (at any level).
+        sess.opts.debugging_opts.mir_opt_level > 0
+    }
+
     fn run_pass<'a, 'tcx>(&self,
                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
-                          _: MirSource,
+                          source: MirSource,
                           mir: &mut Mir<'tcx>) {
-        // We only run when optimizing MIR (at any level).
-        if tcx.sess.opts.debugging_opts.mir_opt_level == 0 {
-            return
-        }
-
         // First, find optimization opportunities. This is done in a pre-pass to keep the MIR
         // read-only so that we can do global analyses on the MIR in the process (e.g.
         // `Place::ty()`).
         let optimizations = {
-            let mut optimization_finder = OptimizationFinder::new(mir, tcx);
+            let mut optimization_finder = OptimizationFinder::new(mir, tcx, source);
             optimization_finder.visit_mir(mir);
             optimization_finder.optimizations
         };
@@ -67,6 +69,31 @@ impl<'tcx> MutVisitor<'tcx> for InstCombineVisitor<'tcx> {
             *rvalue = Rvalue::Use(Operand::Constant(box constant));
         }
 
+        if let Some(negate) = self.optimizations.bool_compares.remove(&location) {
+            debug!("Replacing boolean comparison: {:?}", rvalue);
+            let operand = match *rvalue {
+                Rvalue::BinaryOp(_, ref mut lhs, ref mut rhs) => {
+                    let dummy = Operand::Copy(Place::Local(Local::new(0)));
+                    if let Operand::Constant(_) = *lhs {
+                        mem::replace(rhs, dummy)
+                    } else {
+                        mem::replace(lhs, dummy)
+                    }
+                }
+                _ => bug!("Detected boolean comparison but didn't find one!"),
+            };
+            *rvalue = if negate {
+                Rvalue::UnaryOp(UnOp::Not, operand)
+            } else {
+                Rvalue::Use(operand)
+            };
+        }
+
+        if let Some(constant) = self.optimizations.known_discriminants.remove(&location) {
+            debug!("Replacing `Discriminant` of a known variant: {:?}", rvalue);
+            *rvalue = Rvalue::Use(Operand::Constant(box constant));
+        }
+
         self.super_rvalue(rvalue, location)
     }
 }
@@ -75,24 +102,37 @@ impl<'tcx> MutVisitor<'tcx> for InstCombineVisitor<'tcx> {
 struct OptimizationFinder<'b, 'a, 'tcx:'a+'b> {
     mir: &'b Mir<'tcx>,
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    source: MirSource,
     optimizations: OptimizationList<'tcx>,
 }
 
 impl<'b, 'a, 'tcx:'b> OptimizationFinder<'b, 'a, 'tcx> {
-    fn new(mir: &'b Mir<'tcx>, tcx: TyCtxt<'a, 'tcx, 'tcx>) -> OptimizationFinder<'b, 'a, 'tcx> {
+    fn new(mir: &'b Mir<'tcx>,
+           tcx: TyCtxt<'a, 'tcx, 'tcx>,
+           source: MirSource)
+           -> OptimizationFinder<'b, 'a, 'tcx> {
         OptimizationFinder {
             mir,
             tcx,
+            source,
             optimizations: OptimizationList::default(),
         }
     }
+
+    /// Spends one unit of `-Z fuel` on replacing `rvalue`, if there is any left.
+    fn consider_replacing(&self, rvalue: &Rvalue<'tcx>, location: Location) -> bool {
+        self.tcx.consider_optimizing(|| {
+            format!("InstCombine - replace {:?} at {:?} in {:?}", rvalue, location, self.source)
+        })
+    }
 }
 
 impl<'b, 'a, 'tcx> Visitor<'tcx> for OptimizationFinder<'b, 'a, 'tcx> {
     fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
         if let Rvalue::Ref(_, _, Place::Projection(ref projection)) = *rvalue {
             if let ProjectionElem::Deref = projection.elem {
-                if projection.base.ty(self.mir, self.tcx).to_ty(self.tcx).is_region_ptr() {
+                if projection.base.ty(self.mir, self.tcx).to_ty(self.tcx).is_region_ptr() &&
+                    self.consider_replacing(rvalue, location) {
                     self.optimizations.and_stars.insert(location);
                 }
             }
@@ -101,11 +141,36 @@ impl<'b, 'a, 'tcx> Visitor<'tcx> for OptimizationFinder<'b, 'a, 'tcx> {
         if let Rvalue::Len(ref place) = *rvalue {
             let place_ty = place.ty(&self.mir.local_decls, self.tcx).to_ty(self.tcx);
             if let TypeVariants::TyArray(_, len) = place_ty.sty {
-                let span = self.mir.source_info(location).span;
-                let ty = self.tcx.types.usize;
-                let literal = Literal::Value { value: len };
-                let constant = Constant { span, ty, literal };
-                self.optimizations.arrays_lengths.insert(location, constant);
+                if self.consider_replacing(rvalue, location) {
+                    let span = self.mir.source_info(location).span;
+                    let ty = self.tcx.types.usize;
+                    let literal = Literal::Value { value: len };
+                    let constant = Constant { span, ty, literal };
+                    self.optimizations.arrays_lengths.insert(location, constant);
+                }
+            }
+        }
+
+        if let Rvalue::BinaryOp(op @ BinOp::Eq, ref lhs, ref rhs) |
+               Rvalue::BinaryOp(op @ BinOp::Ne, ref lhs, ref rhs) = *rvalue {
+            let constant = match (self.bool_constant(lhs), self.bool_constant(rhs)) {
+                (Some(value), None) | (None, Some(value)) => Some(value),
+                _ => None,
+            };
+            if let Some(value) = constant {
+                if self.consider_replacing(rvalue, location) {
+                    // `x == false` and `x != true` are `!x`, the other two are just `x`.
+                    let negate = (op == BinOp::Eq) != value;
+                    self.optimizations.bool_compares.insert(location, negate);
+                }
+            }
+        }
+
+        if let Rvalue::Discriminant(ref place) = *rvalue {
+            if let Some(constant) = self.known_discriminant(place, location) {
+                if self.consider_replacing(rvalue, location) {
+                    self.optimizations.known_discriminants.insert(location, constant);
+                }
             }
         }
 
@@ -113,8 +178,96 @@ impl<'b, 'a, 'tcx> Visitor<'tcx> for OptimizationFinder<'b, 'a, 'tcx> {
     }
 }
 
+impl<'b, 'a, 'tcx> OptimizationFinder<'b, 'a, 'tcx> {
+    fn bool_constant(&self, operand: &Operand<'tcx>) -> Option<bool> {
+        match *operand {
+            Operand::Constant(box Constant { ty, literal: Literal::Value { value }, .. })
+                if ty.is_bool() => value.assert_bool(self.tcx),
+            _ => None,
+        }
+    }
+
+    /// Returns the discriminant of `place` as a constant if its variant is known at
+    /// `location`: either the enum has only one variant, or the variant was set earlier
+    /// in the same block and `place` can't have been written to since.
+    fn known_discriminant(&self, place: &Place<'tcx>, location: Location)
+                          -> Option<Constant<'tcx>> {
+        let adt_def = match place.ty(self.mir, self.tcx).to_ty(self.tcx).sty {
+            TypeVariants::TyAdt(adt_def, _) if adt_def.is_enum() => adt_def,
+            _ => return None,
+        };
+
+        let variant_index = if adt_def.variants.len() == 1 {
+            Some(0)
+        } else {
+            let statements = &self.mir[location.block].statements[..location.statement_index];
+            let mut variant_index = None;
+            for statement in statements.iter().rev() {
+                match statement.kind {
+                    StatementKind::Assign(ref lhs, Rvalue::Aggregate(box ref kind, _))
+                        if lhs == place => {
+                        if let AggregateKind::Adt(_, index, ..) = *kind {
+                            variant_index = Some(index);
+                        }
+                        break;
+                    }
+                    StatementKind::SetDiscriminant { place: ref lhs, variant_index: index }
+                        if lhs == place => {
+                        variant_index = Some(index);
+                        break;
+                    }
+                    StatementKind::Assign(ref lhs, _) |
+                    StatementKind::SetDiscriminant { place: ref lhs, .. } => {
+                        if may_overlap(lhs, place) {
+                            break;
+                        }
+                    }
+                    StatementKind::StorageLive(local) |
+                    StatementKind::StorageDead(local) => {
+                        if base_local(place) == Some(local) {
+                            break;
+                        }
+                    }
+                    StatementKind::InlineAsm { .. } => break,
+                    _ => {}
+                }
+            }
+            variant_index
+        }?;
+
+        let ty = adt_def.repr.discr_type().to_ty(self.tcx);
+        let bits = adt_def.discriminant_for_variant(self.tcx, variant_index).val;
+        let value = ty::Const::from_bits(self.tcx, bits, ParamEnv::empty().and(ty));
+        let span = self.mir.source_info(location).span;
+        Some(Constant { span, ty, literal: Literal::Value { value } })
+    }
+}
+
+/// The local a place is rooted in, unless it goes through a deref.
+fn base_local(place: &Place) -> Option<Local> {
+    match *place {
+        Place::Local(local) => Some(local),
+        Place::Static(_) => None,
+        Place::Projection(ref projection) => match projection.elem {
+            ProjectionElem::Deref => None,
+            _ => base_local(&projection.base),
+        },
+    }
+}
+
+/// Whether a write to `lhs` may change `place`. Writes through pointers and to
+/// statics are assumed to change anything.
+fn may_overlap(lhs: &Place, place: &Place) -> bool {
+    match (base_local(lhs), base_local(place)) {
+        (Some(lhs_local), Some(local)) => lhs_local == local,
+        _ => true,
+    }
+}
+
 #[derive(Default)]
 struct OptimizationList<'tcx> {
     and_stars: FxHashSet<Location>,
     arrays_lengths: FxHashMap<Location, Constant<'tcx>>,
+    bool_compares: FxHashMap<Location, bool>,
+    known_discriminants: FxHashMap<Location, Constant<'tcx>>,
 }
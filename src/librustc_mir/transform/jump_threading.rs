@@ -0,0 +1,210 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Threads jumps through `SwitchInt`s whose outcome is known on the incoming edge.
+//!
+//! Building and deaggregating a `match`, in particular the one `?` desugars to, often produces
+//!
+//!     bb1: {
+//!         ((_5 as Ok).0: T) = move _6;
+//!         discriminant(_5) = 0;
+//!         goto -> bb3;
+//!     }
+//!
+//!     bb3: {
+//!         _7 = discriminant(_5);
+//!         switchInt(move _7) -> [0isize: bb4, 1isize: bb5, otherwise: bb6];
+//!     }
+//!
+//! where the switch in `bb3` always goes to `bb4` when entered from `bb1`. We copy the
+//! statements of `bb3` into `bb1` and make it jump to `bb4` directly. The same is done for
+//! switches on a local that the predecessor assigns a constant to. Once all its predecessors
+//! have been threaded, the switch block is removed by `SimplifyCfg`.
+
+use rustc::mir::{BasicBlock, Constant, Literal, Local, Location, Mir, Operand, Place};
+use rustc::mir::{ProjectionElem, Rvalue, Statement, StatementKind, TerminatorKind};
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::session::Session;
+use rustc::ty::{self, ParamEnv, Ty, TyCtxt};
+use transform::{MirPass, MirSource};
+use transform::dead_store_elimination::borrowed_locals;
+
+pub struct JumpThreading;
+
+impl MirPass for JumpThreading {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.mir_opt_level > 1
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let borrowed = borrowed_locals(mir);
+
+        // Collect the edges to thread first; the predecessors are only valid until the CFG
+        // changes.
+        let mut threads = vec![];
+        for (switch_bb, data) in mir.basic_blocks().iter_enumerated() {
+            let (tracked, switch_ty, targets) = match data.terminator().kind {
+                TerminatorKind::SwitchInt {
+                    discr: Operand::Copy(Place::Local(local)),
+                    switch_ty,
+                    ref targets,
+                } |
+                TerminatorKind::SwitchInt {
+                    discr: Operand::Move(Place::Local(local)),
+                    switch_ty,
+                    ref targets,
+                } => match tracked_value(&data.statements, local) {
+                    Some(tracked) => (tracked, switch_ty, targets),
+                    None => continue,
+                },
+                _ => continue,
+            };
+            if borrowed.contains(&tracked.local()) {
+                continue;
+            }
+
+            for &pred in mir.predecessors_for(switch_bb).iter() {
+                match mir[pred].terminator().kind {
+                    TerminatorKind::Goto { target } if target == switch_bb => {}
+                    _ => continue,
+                }
+                let value = match known_value(tcx, mir, pred, tracked, switch_ty) {
+                    Some(value) => value,
+                    None => continue,
+                };
+                let target = targets.target_for_value(value);
+                if target != switch_bb && tcx.consider_optimizing(|| {
+                    format!("JumpThreading - {:?} -> {:?} in {:?}", pred, switch_bb, source)
+                }) {
+                    debug!("Threading {:?} -> {:?} -> {:?}", pred, switch_bb, target);
+                    threads.push((pred, switch_bb, target));
+                }
+            }
+        }
+
+        for (pred, switch_bb, target) in threads {
+            let statements = mir[switch_bb].statements.clone();
+            let pred_data = &mut mir.basic_blocks_mut()[pred];
+            pred_data.statements.extend(statements);
+            pred_data.terminator_mut().kind = TerminatorKind::Goto { target };
+        }
+    }
+}
+
+/// What a `SwitchInt` switches on.
+#[derive(Copy, Clone, Debug)]
+enum Tracked {
+    /// The value of this local.
+    Value(Local),
+    /// The discriminant of this local.
+    Discriminant(Local),
+}
+
+impl Tracked {
+    fn local(self) -> Local {
+        match self {
+            Tracked::Value(local) | Tracked::Discriminant(local) => local,
+        }
+    }
+}
+
+/// Determines what a switch on `discr` at the end of a block with `statements` depends on.
+/// Apart from storage markers, the block may only compute the discriminant it switches on.
+fn tracked_value<'tcx>(statements: &[Statement<'tcx>], discr: Local) -> Option<Tracked> {
+    let mut tracked = Tracked::Value(discr);
+    for statement in statements {
+        match statement.kind {
+            StatementKind::StorageLive(_) |
+            StatementKind::StorageDead(_) |
+            StatementKind::Nop => {}
+            StatementKind::Assign(Place::Local(local),
+                                  Rvalue::Discriminant(Place::Local(adt)))
+                    if local == discr => {
+                tracked = Tracked::Discriminant(adt);
+            }
+            _ => return None,
+        }
+    }
+    Some(tracked)
+}
+
+/// Finds the value of `tracked` at the end of `block`, if one of its statements sets it to a
+/// known constant and nothing changes it afterwards.
+fn known_value<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                         mir: &Mir<'tcx>,
+                         block: BasicBlock,
+                         tracked: Tracked,
+                         switch_ty: Ty<'tcx>)
+                         -> Option<u128> {
+    for statement in mir[block].statements.iter().rev() {
+        match (tracked, &statement.kind) {
+            (Tracked::Value(local),
+             &StatementKind::Assign(Place::Local(dest), Rvalue::Use(Operand::Constant(box Constant {
+                 literal: Literal::Value { value }, ..
+             })))) if dest == local => {
+                return value.assert_bits(tcx, ParamEnv::empty().and(switch_ty));
+            }
+            (Tracked::Discriminant(local),
+             &StatementKind::SetDiscriminant { place: Place::Local(dest), variant_index })
+                    if dest == local => {
+                return match mir.local_decls[local].ty.sty {
+                    ty::TyAdt(adt_def, _) if adt_def.is_enum() => {
+                        Some(adt_def.discriminant_for_variant(tcx, variant_index).val)
+                    }
+                    _ => None,
+                };
+            }
+            // Writing to the fields of a variant leaves the discriminant alone.
+            (Tracked::Discriminant(local), &StatementKind::Assign(ref place, _))
+                    if is_variant_field_of(place, local) => {}
+            _ => {
+                let mut finder = LocalFinder { local: tracked.local(), found: false };
+                finder.visit_statement(block, statement, Location {
+                    block,
+                    statement_index: 0,
+                });
+                if finder.found {
+                    return None;
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Whether `place` is (part of) a field of a variant of `local`, e.g. `((_1 as Some).0)`.
+fn is_variant_field_of<'tcx>(mut place: &Place<'tcx>, local: Local) -> bool {
+    while let Place::Projection(ref proj) = *place {
+        if let Place::Local(base) = proj.base {
+            if let ProjectionElem::Downcast(..) = proj.elem {
+                return base == local;
+            }
+            return false;
+        }
+        place = &proj.base;
+    }
+    false
+}
+
+struct LocalFinder {
+    local: Local,
+    found: bool,
+}
+
+impl<'tcx> Visitor<'tcx> for LocalFinder {
+    fn visit_local(&mut self, &local: &Local, _: PlaceContext<'tcx>, _: Location) {
+        if local == self.local {
+            self.found = true;
+        }
+    }
+}
@@ -0,0 +1,142 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Lowers calls to simple intrinsics into plain MIR statements.
+//!
+//! Calls are opaque to the other MIR passes, so e.g. `size_of::<T>()` can't be constant
+//! propagated while it's still a call. The intrinsics handled here map directly to MIR:
+//!
+//! - `forget(x)` goes away, as the value moved into it is then never dropped,
+//! - `size_of::<T>()` becomes `SizeOf(T)`,
+//! - `discriminant_value(x)` becomes `discriminant((*x)) as u64` for enums,
+//! - `wrapping_add`, `wrapping_sub` and `wrapping_mul`, and the `overflowing_*` intrinsics
+//!   they are the same as, become the first field of a `CheckedAdd`, `CheckedSub` and
+//!   `CheckedMul`. A plain `Add` would be reported by const propagation when it overflows,
+//!   but these intrinsics are defined to wrap.
+
+use rustc::mir::*;
+use rustc::session::Session;
+use rustc::ty::{self, TyCtxt};
+use rustc::ty::subst::Substs;
+use syntax::symbol::InternedString;
+use syntax_pos::Span;
+use rustc_target::spec::abi::Abi;
+use transform::{MirPass, MirSource};
+
+pub struct LowerIntrinsics;
+
+impl MirPass for LowerIntrinsics {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        // Codegen handles all of these intrinsics itself.
+        sess.opts.debugging_opts.mir_opt_level > 0
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let (basic_blocks, local_decls) = mir.basic_blocks_and_local_decls_mut();
+        for block in basic_blocks.iter_mut() {
+            let source_info = block.terminator().source_info;
+            let (statements, target) = match block.terminator().kind {
+                TerminatorKind::Call {
+                    func: Operand::Constant(box Constant { ty: func_ty, .. }),
+                    ref args,
+                    destination: Some((ref destination, target)),
+                    ..
+                } => {
+                    let (name, substs) = match func_ty.sty {
+                        ty::TyFnDef(def_id, substs)
+                            if tcx.fn_sig(def_id).abi() == Abi::RustIntrinsic => {
+                            (tcx.item_name(def_id), substs)
+                        }
+                        _ => continue,
+                    };
+                    match lower_intrinsic(tcx, name, substs, args, destination,
+                                          source_info.span, local_decls) {
+                        Some(statements) => (statements, target),
+                        None => continue,
+                    }
+                }
+                _ => continue,
+            };
+            if !tcx.consider_optimizing(|| {
+                format!("LowerIntrinsics - {:?} in {:?}", source_info, source)
+            }) {
+                continue;
+            }
+            debug!("Lowering intrinsic call in {:?}", source_info);
+
+            block.statements.extend(statements.into_iter().map(|kind| {
+                Statement { source_info, kind }
+            }));
+            block.terminator_mut().kind = TerminatorKind::Goto { target };
+        }
+    }
+}
+
+/// Returns the statements replacing a call to the intrinsic `name`, if it can be lowered.
+fn lower_intrinsic<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                             name: InternedString,
+                             substs: &'tcx Substs<'tcx>,
+                             args: &[Operand<'tcx>],
+                             destination: &Place<'tcx>,
+                             span: Span,
+                             local_decls: &mut LocalDecls<'tcx>)
+                             -> Option<Vec<StatementKind<'tcx>>> {
+    let assign = |rvalue| StatementKind::Assign(destination.clone(), rvalue);
+    match &name.as_str()[..] {
+        "forget" => Some(vec![]),
+        "size_of" => {
+            Some(vec![assign(Rvalue::NullaryOp(NullOp::SizeOf, substs.type_at(0)))])
+        }
+        "discriminant_value" => {
+            let place = match args[0] {
+                Operand::Copy(ref place) | Operand::Move(ref place) => place.clone().deref(),
+                Operand::Constant(_) => return None,
+            };
+            let adt_def = match substs.type_at(0).sty {
+                ty::TyAdt(adt_def, _) if adt_def.is_enum() => adt_def,
+                _ => return None,
+            };
+            let discr_ty = adt_def.repr.discr_type().to_ty(tcx);
+            let discr = local_decls.push(LocalDecl::new_temp(discr_ty, span));
+            Some(vec![
+                StatementKind::StorageLive(discr),
+                StatementKind::Assign(Place::Local(discr), Rvalue::Discriminant(place)),
+                assign(Rvalue::Cast(CastKind::Misc,
+                                    Operand::Move(Place::Local(discr)),
+                                    tcx.types.u64)),
+                StatementKind::StorageDead(discr),
+            ])
+        }
+        "wrapping_add" | "wrapping_sub" | "wrapping_mul" |
+        "overflowing_add" | "overflowing_sub" | "overflowing_mul" => {
+            let op = match &name.as_str()[..] {
+                "wrapping_add" | "overflowing_add" => BinOp::Add,
+                "wrapping_sub" | "overflowing_sub" => BinOp::Sub,
+                _ => BinOp::Mul,
+            };
+            let ty = substs.type_at(0);
+            let result_ty = tcx.intern_tup(&[ty, tcx.types.bool]);
+            let result = local_decls.push(LocalDecl::new_temp(result_ty, span));
+            Some(vec![
+                StatementKind::StorageLive(result),
+                StatementKind::Assign(Place::Local(result),
+                                      Rvalue::CheckedBinaryOp(op,
+                                                              args[0].clone(),
+                                                              args[1].clone())),
+                assign(Rvalue::Use(Operand::Move(Place::Local(result).field(Field::new(0), ty)))),
+                StatementKind::StorageDead(result),
+            ])
+        }
+        _ => None,
+    }
+}
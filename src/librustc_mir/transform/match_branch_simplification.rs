@@ -0,0 +1,207 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Replaces two-armed switches which only pick between two boolean constants by a comparison.
+//!
+//! `matches!`, derived comparisons and `if`s producing a `bool` often lower to
+//!
+//!     bb0: {
+//!         switchInt(move _2) -> [1isize: bb1, otherwise: bb2];
+//!     }
+//!     bb1: {
+//!         _0 = const true;
+//!         goto -> bb3;
+//!     }
+//!     bb2: {
+//!         _0 = const false;
+//!         goto -> bb3;
+//!     }
+//!
+//! which is the same as
+//!
+//!     bb0: {
+//!         StorageLive(_3);
+//!         _3 = move _2;
+//!         _0 = Eq(_3, const 1isize);
+//!         StorageDead(_3);
+//!         goto -> bb3;
+//!     }
+//!
+//! Both targets may assign several places, as long as each gets complementary constants on the
+//! two sides; they may also contain the same storage markers. Blocks left unreachable are
+//! removed by `SimplifyCfg`.
+
+use rustc::mir::{BasicBlock, BinOp, Constant, Literal, LocalDecl, Mir, Operand, Place, Rvalue};
+use rustc::mir::{SourceInfo, Statement, StatementKind, TerminatorKind};
+use rustc::session::Session;
+use rustc::ty::{self, ParamEnv, TyCtxt};
+use transform::{MirPass, MirSource};
+
+pub struct MatchBranchSimplification;
+
+impl MirPass for MatchBranchSimplification {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.mir_opt_level > 1
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        for bb in mir.basic_blocks().indices() {
+            let (discr, switch_ty, value, first, second) = match mir[bb].terminator().kind {
+                TerminatorKind::SwitchInt { ref discr, switch_ty, ref targets } => {
+                    match targets.as_static_if() {
+                        Some((value, first, second)) if first != second => {
+                            (discr.clone(), switch_ty, value, first, second)
+                        }
+                        _ => continue,
+                    }
+                }
+                _ => continue,
+            };
+
+            let merged = match merge_arms(tcx, mir, first, second) {
+                Some(merged) => merged,
+                None => continue,
+            };
+            if !tcx.consider_optimizing(|| {
+                format!("MatchBranchSimplification - {:?} in {:?}", bb, source)
+            }) {
+                break;
+            }
+            debug!("Replacing the switch in {:?} by comparisons", bb);
+
+            let source_info = mir[bb].terminator().source_info;
+            let discr_local = mir.local_decls.push(LocalDecl::new_temp(switch_ty,
+                                                                       source_info.span));
+            let value = Constant {
+                span: source_info.span,
+                ty: switch_ty,
+                literal: Literal::Value {
+                    value: ty::Const::from_bits(tcx, value, ParamEnv::empty().and(switch_ty)),
+                },
+            };
+
+            // The switch evaluated its operand before either arm ran, so do the same.
+            let mut statements = vec![
+                Statement {
+                    source_info,
+                    kind: StatementKind::StorageLive(discr_local),
+                },
+                Statement {
+                    source_info,
+                    kind: StatementKind::Assign(Place::Local(discr_local), Rvalue::Use(discr)),
+                },
+            ];
+            for stmt in merged.statements {
+                statements.push(match stmt {
+                    MergedStatement::Same(statement) => statement,
+                    MergedStatement::Compare { source_info, place, true_if_equal } => {
+                        let op = if true_if_equal { BinOp::Eq } else { BinOp::Ne };
+                        let rvalue = Rvalue::BinaryOp(op,
+                                                      Operand::Copy(Place::Local(discr_local)),
+                                                      Operand::Constant(box value.clone()));
+                        Statement {
+                            source_info,
+                            kind: StatementKind::Assign(place, rvalue),
+                        }
+                    }
+                });
+            }
+            statements.push(Statement {
+                source_info,
+                kind: StatementKind::StorageDead(discr_local),
+            });
+
+            let data = &mut mir.basic_blocks_mut()[bb];
+            data.statements.extend(statements);
+            data.terminator_mut().kind = TerminatorKind::Goto { target: merged.target };
+        }
+    }
+}
+
+struct MergedArms<'tcx> {
+    statements: Vec<MergedStatement<'tcx>>,
+    target: BasicBlock,
+}
+
+enum MergedStatement<'tcx> {
+    /// A statement both arms have in common.
+    Same(Statement<'tcx>),
+    /// An assignment of `true` to `place` in the first arm, and of `false` in the second,
+    /// or the other way around.
+    Compare {
+        source_info: SourceInfo,
+        place: Place<'tcx>,
+        true_if_equal: bool,
+    },
+}
+
+/// Checks whether the blocks `first` and `second` only differ in the boolean constants they
+/// assign, and continue to the same block.
+fn merge_arms<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                        mir: &Mir<'tcx>,
+                        first: BasicBlock,
+                        second: BasicBlock)
+                        -> Option<MergedArms<'tcx>> {
+    let (first, second) = (&mir[first], &mir[second]);
+    let target = match (&first.terminator().kind, &second.terminator().kind) {
+        (&TerminatorKind::Goto { target }, &TerminatorKind::Goto { target: other })
+            if target == other => target,
+        _ => return None,
+    };
+    if first.is_cleanup || second.is_cleanup ||
+       first.statements.len() != second.statements.len() {
+        return None;
+    }
+
+    let mut statements = vec![];
+    let mut any_compare = false;
+    for (first, second) in first.statements.iter().zip(&second.statements) {
+        let merged = match (&first.kind, &second.kind) {
+            (&StatementKind::Assign(ref place, Rvalue::Use(Operand::Constant(ref a))),
+             &StatementKind::Assign(ref other, Rvalue::Use(Operand::Constant(ref b))))
+                    if place == other => {
+                match (const_bool(tcx, a), const_bool(tcx, b)) {
+                    (Some(a), Some(b)) if a != b => {
+                        any_compare = true;
+                        MergedStatement::Compare {
+                            source_info: first.source_info,
+                            place: place.clone(),
+                            true_if_equal: a,
+                        }
+                    }
+                    _ => return None,
+                }
+            }
+            (&StatementKind::StorageLive(a), &StatementKind::StorageLive(b)) |
+            (&StatementKind::StorageDead(a), &StatementKind::StorageDead(b)) if a == b => {
+                MergedStatement::Same(first.clone())
+            }
+            (&StatementKind::Nop, &StatementKind::Nop) => MergedStatement::Same(first.clone()),
+            _ => return None,
+        };
+        statements.push(merged);
+    }
+
+    if any_compare {
+        Some(MergedArms { statements, target })
+    } else {
+        None
+    }
+}
+
+fn const_bool<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, constant: &Constant<'tcx>) -> Option<bool> {
+    match constant.literal {
+        Literal::Value { value } if constant.ty.is_bool() => value.assert_bool(tcx),
+        _ => None,
+    }
+}
@@ -11,7 +11,8 @@
 use borrow_check::nll::type_check;
 use build;
 use rustc::hir::def_id::{CrateNum, DefId, LOCAL_CRATE};
-use rustc::mir::{Mir, Promoted};
+use rustc::mir::{BodyMetrics, Mir, MirPhase, Promoted};
+use rustc::session::Session;
 use rustc::ty::TyCtxt;
 use rustc::ty::query::Providers;
 use rustc::ty::steal::Steal;
@@ -29,23 +30,35 @@ pub mod cleanup_post_borrowck;
 pub mod check_unsafety;
 pub mod simplify_branches;
 pub mod simplify;
+pub mod simplify_try;
 pub mod erase_regions;
 pub mod no_landing_pads;
 pub mod rustc_peek;
 pub mod elaborate_drops;
 pub mod add_call_guards;
+pub mod bounds_checks;
 pub mod promote_consts;
 pub mod qualify_consts;
 pub mod remove_noop_landing_pads;
 pub mod dump_mir;
 pub mod deaggregator;
+pub mod deduplicate_blocks;
 pub mod instcombine;
 pub mod copy_prop;
+pub mod early_otherwise_branch;
+pub mod dead_store_elimination;
+pub mod storage_markers;
 pub mod const_prop;
 pub mod generator;
+pub mod sroa;
 pub mod inline;
+pub mod jump_threading;
+pub mod match_branch_simplification;
 pub mod lower_128bit;
+pub mod lower_intrinsics;
 pub mod uniform_array_move_out;
+pub mod unreachable_prop;
+pub mod validate;
 
 pub(crate) fn provide(providers: &mut Providers) {
     self::qualify_consts::provide(providers);
@@ -56,6 +69,7 @@ pub(crate) fn provide(providers: &mut Providers) {
         mir_const,
         mir_validated,
         optimized_mir,
+        mir_body_metrics,
         is_mir_available,
         ..*providers
     };
@@ -148,32 +162,85 @@ pub trait MirPass {
         default_name::<Self>()
     }
 
+    /// The phase the MIR has to be in for this pass to be run on it, if the
+    /// pass only makes sense in one phase. Note that while a suite of passes
+    /// runs, the MIR is still in the phase the suite started from.
+    fn required_phase(&self) -> Option<MirPhase> {
+        None
+    }
+
+    /// Whether this pass runs with the options of this session. Passes
+    /// named in `-Z mir-enable-passes` are run or skipped regardless.
+    fn is_enabled(&self, _sess: &Session) -> bool {
+        true
+    }
+
     fn run_pass<'a, 'tcx>(&self,
                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
                           source: MirSource,
                           mir: &mut Mir<'tcx>);
 }
 
-pub macro run_passes($tcx:ident, $mir:ident, $def_id:ident, $suite_index:expr; $($pass:expr,)*) {{
-    let suite_index: usize = $suite_index;
-    let run_passes = |mir: &mut _, promoted| {
+/// Runs a suite of passes taking MIR from the phase preceding `$mir_phase`
+/// to `$mir_phase`, on the MIR itself and on all of its promoted constants.
+pub macro run_passes($tcx:ident, $mir:ident, $def_id:ident, $mir_phase:expr, $passes:expr) {{
+    let mir_phase: MirPhase = $mir_phase;
+    let passes: &[Box<dyn MirPass>] = &$passes;
+    // Suites are numbered by the phase they start from, as seen in MIR dumps.
+    let suite_index: usize = mir_phase.phase_index() - 1;
+    let run_passes = |mir: &mut Mir, promoted| {
+        debug_assert_eq!(mir.phase.phase_index() + 1, mir_phase.phase_index(),
+                         "running the {:?} suite on MIR in phase {:?}", mir_phase, mir.phase);
+
         let source = MirSource {
             def_id: $def_id,
             promoted
         };
-        let mut index = 0;
-        let mut run_pass = |pass: &dyn MirPass| {
-            let run_hooks = |mir: &_, index, is_after| {
-                dump_mir::on_mir_pass($tcx, &format_args!("{:03}-{:03}", suite_index, index),
-                                      &pass.name(), source, mir, is_after);
+        {
+            let mut index = 0;
+            let mut run_pass = |pass: &dyn MirPass| {
+                let enabled = $tcx.sess.opts.debugging_opts.mir_enable_passes.iter()
+                    .find(|&&(ref name, _)| *name == pass.name())
+                    .map_or_else(|| pass.is_enabled($tcx.sess), |&(_, enabled)| enabled);
+                if !enabled {
+                    // Keep the numbering of the other passes' dumps stable.
+                    index += 1;
+                    return;
+                }
+
+                if let Some(required_phase) = pass.required_phase() {
+                    debug_assert_eq!(mir.phase, required_phase,
+                                     "pass {} run on MIR in the wrong phase", pass.name());
+                }
+
+                let run_hooks = |mir: &_, index, is_after| {
+                    dump_mir::on_mir_pass($tcx, &format_args!("{:03}-{:03}", suite_index, index),
+                                          &pass.name(), source, mir, is_after);
+                };
+                run_hooks(mir, index, false);
+                pass.run_pass($tcx, source, mir);
+                run_hooks(mir, index, true);
+
+                if $tcx.sess.opts.debugging_opts.validate_mir {
+                    validate::Validator {
+                        when: format!("after {} in suite {}", pass.name(), suite_index),
+                    }.run_pass($tcx, source, mir);
+                }
+
+                index += 1;
             };
-            run_hooks(mir, index, false);
-            pass.run_pass($tcx, source, mir);
-            run_hooks(mir, index, true);
+            for pass in passes {
+                run_pass(&**pass);
+            }
+        }
 
-            index += 1;
-        };
-        $(run_pass(&$pass);)*
+        mir.phase = mir_phase;
+
+        if $tcx.sess.opts.debugging_opts.validate_mir {
+            validate::Validator {
+                when: format!("at the end of suite {}", suite_index),
+            }.run_pass($tcx, source, mir);
+        }
     };
 
     run_passes(&mut $mir, None);
@@ -186,21 +253,120 @@ pub macro run_passes($tcx:ident, $mir:ident, $def_id:ident, $suite_index:expr; $
     }
 }}
 
+/// The suite taking `mir_built` to `mir_const`.
+fn const_passes() -> Vec<Box<dyn MirPass>> {
+    vec![
+        // Remove all `EndRegion` statements that are not involved in borrows.
+        box cleanup_post_borrowck::CleanEndRegions,
+
+        // What we need to do constant evaluation.
+        box simplify::SimplifyCfg::new("initial"),
+        box type_check::TypeckMir,
+        box rustc_peek::SanityCheck,
+        box uniform_array_move_out::UniformArrayMoveOut,
+    ]
+}
+
+/// The suite taking `mir_const` to `mir_validated`.
+fn validated_passes() -> Vec<Box<dyn MirPass>> {
+    vec![
+        // What we need to run borrowck etc.
+        box qualify_consts::QualifyAndPromoteConstants,
+        box simplify::SimplifyCfg::new_borrowck_safe("qualify-consts"),
+    ]
+}
+
+/// The suite taking `mir_validated` to `optimized_mir`.
+fn optimized_passes() -> Vec<Box<dyn MirPass>> {
+    vec![
+        // Remove all things not needed by analysis
+        box no_landing_pads::NoLandingPads,
+        box simplify_branches::SimplifyBranches::new("initial"),
+        box remove_noop_landing_pads::RemoveNoopLandingPads,
+        box simplify::SimplifyCfg::new("early-opt"),
+        // Remove all `UserAssertTy` statements.
+        box cleanup_post_borrowck::CleanUserAssertTy,
+        // Remove the fake borrows made for match guards.
+        box cleanup_post_borrowck::CleanFakeBorrows,
+
+        // These next passes must be executed together
+        box add_call_guards::CriticalCallEdges,
+        box elaborate_drops::ElaborateDrops,
+        box no_landing_pads::NoLandingPads,
+        // AddValidation needs to run after ElaborateDrops and before EraseRegions, and it needs
+        // an AllCallEdges pass right before it.
+        box add_call_guards::AllCallEdges,
+        box add_validation::AddValidation,
+        // AddMovesForPackedDrops needs to run after drop
+        // elaboration.
+        box add_moves_for_packed_drops::AddMovesForPackedDrops,
+
+        box simplify::SimplifyCfg::new("elaborate-drops"),
+
+        // No lifetime analysis based on borrowing can be done from here on out.
+
+        // From here on out, regions are gone.
+        box erase_regions::EraseRegions,
+
+        box lower_128bit::Lower128Bit,
+
+
+        // Optimizations begin.
+        box uniform_array_move_out::RestoreSubsliceArrayMoveOut,
+        box inline::Inline,
+        // Callees may have been compiled with a different panic strategy, so their
+        // cleanup blocks have to be removed again after inlining them.
+        box no_landing_pads::NoLandingPads,
+
+        // Lowering generator control-flow and variables
+        // has to happen before we do anything else to them.
+        box generator::StateTransform,
+
+        box lower_intrinsics::LowerIntrinsics,
+        box instcombine::InstCombine,
+        box const_prop::ConstProp,
+        box bounds_checks::BoundsCheckElimination,
+        box simplify_branches::SimplifyBranches::new("after-const-prop"),
+        box deaggregator::Deaggregator,
+        box simplify_try::SimplifyArmIdentity,
+        box sroa::ScalarReplacementOfAggregates,
+        box jump_threading::JumpThreading,
+        box match_branch_simplification::MatchBranchSimplification,
+        box early_otherwise_branch::EarlyOtherwiseBranch,
+        box unreachable_prop::UnreachablePropagation,
+        box copy_prop::CopyPropagation,
+        box dead_store_elimination::DeadStoreElimination,
+        box deduplicate_blocks::DeduplicateBlocks,
+        box storage_markers::OptimizeStorageMarkers,
+        box remove_noop_landing_pads::RemoveNoopLandingPads,
+        box simplify::SimplifyCfg::new("final"),
+        box simplify::SimplifyLocals,
+
+        box add_call_guards::CriticalCallEdges,
+        box dump_mir::Marker("PreCodegen"),
+    ]
+}
+
+/// Warns about the names in `-Z mir-enable-passes` which no MIR pass has,
+/// instead of silently ignoring them.
+pub fn check_mir_enable_passes(sess: &Session) {
+    let suites = [const_passes(), validated_passes(), optimized_passes()];
+    for &(ref name, _) in &sess.opts.debugging_opts.mir_enable_passes {
+        let known = suites.iter()
+            .flat_map(|passes| passes.iter())
+            .any(|pass| pass.name() == name.as_str());
+        if !known {
+            sess.warn(&format!("`-Z mir-enable-passes` names unknown MIR pass `{}`", name));
+        }
+    }
+}
+
 fn mir_const<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx Steal<Mir<'tcx>> {
     // Unsafety check uses the raw mir, so make sure it is run
     let _ = tcx.unsafety_check_result(def_id);
 
     let mut mir = tcx.mir_built(def_id).steal();
-    run_passes![tcx, mir, def_id, 0;
-        // Remove all `EndRegion` statements that are not involved in borrows.
-        cleanup_post_borrowck::CleanEndRegions,
-
-        // What we need to do constant evaluation.
-        simplify::SimplifyCfg::new("initial"),
-        type_check::TypeckMir,
-        rustc_peek::SanityCheck,
-        uniform_array_move_out::UniformArrayMoveOut,
-    ];
+    run_passes!(tcx, mir, def_id, MirPhase::Const, const_passes());
     tcx.alloc_steal_mir(mir)
 }
 
@@ -213,11 +379,7 @@ fn mir_validated<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx
     }
 
     let mut mir = tcx.mir_const(def_id).steal();
-    run_passes![tcx, mir, def_id, 1;
-        // What we need to run borrowck etc.
-        qualify_consts::QualifyAndPromoteConstants,
-        simplify::SimplifyCfg::new("qualify-consts"),
-    ];
+    run_passes!(tcx, mir, def_id, MirPhase::Validated, validated_passes());
     tcx.alloc_steal_mir(mir)
 }
 
@@ -231,56 +393,10 @@ fn optimized_mir<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx
     }
 
     let mut mir = tcx.mir_validated(def_id).steal();
-    run_passes![tcx, mir, def_id, 2;
-        // Remove all things not needed by analysis
-        no_landing_pads::NoLandingPads,
-        simplify_branches::SimplifyBranches::new("initial"),
-        remove_noop_landing_pads::RemoveNoopLandingPads,
-        simplify::SimplifyCfg::new("early-opt"),
-        // Remove all `UserAssertTy` statements.
-        cleanup_post_borrowck::CleanUserAssertTy,
-
-        // These next passes must be executed together
-        add_call_guards::CriticalCallEdges,
-        elaborate_drops::ElaborateDrops,
-        no_landing_pads::NoLandingPads,
-        // AddValidation needs to run after ElaborateDrops and before EraseRegions, and it needs
-        // an AllCallEdges pass right before it.
-        add_call_guards::AllCallEdges,
-        add_validation::AddValidation,
-        // AddMovesForPackedDrops needs to run after drop
-        // elaboration.
-        add_moves_for_packed_drops::AddMovesForPackedDrops,
-
-        simplify::SimplifyCfg::new("elaborate-drops"),
-
-        // No lifetime analysis based on borrowing can be done from here on out.
-
-        // From here on out, regions are gone.
-        erase_regions::EraseRegions,
-
-        lower_128bit::Lower128Bit,
-
-
-        // Optimizations begin.
-        uniform_array_move_out::RestoreSubsliceArrayMoveOut,
-        inline::Inline,
-
-        // Lowering generator control-flow and variables
-        // has to happen before we do anything else to them.
-        generator::StateTransform,
-
-        instcombine::InstCombine,
-        const_prop::ConstProp,
-        simplify_branches::SimplifyBranches::new("after-const-prop"),
-        deaggregator::Deaggregator,
-        copy_prop::CopyPropagation,
-        remove_noop_landing_pads::RemoveNoopLandingPads,
-        simplify::SimplifyCfg::new("final"),
-        simplify::SimplifyLocals,
-
-        add_call_guards::CriticalCallEdges,
-        dump_mir::Marker("PreCodegen"),
-    ];
+    run_passes!(tcx, mir, def_id, MirPhase::Optimized, optimized_passes());
     tcx.alloc_mir(mir)
 }
+
+fn mir_body_metrics<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> BodyMetrics {
+    tcx.optimized_mir(def_id).metrics()
+}
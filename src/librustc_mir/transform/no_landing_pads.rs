@@ -8,13 +8,15 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-//! This pass removes the unwind branch of all the terminators when the no-landing-pads option is
-//! specified.
+//! This pass removes the unwind branch of all the terminators, and the cleanup blocks which are
+//! no longer reachable without them, when the no-landing-pads option is specified or the panic
+//! strategy is abort.
 
 use rustc::ty::TyCtxt;
 use rustc::mir::*;
 use rustc::mir::visit::MutVisitor;
 use transform::{MirPass, MirSource};
+use transform::simplify;
 
 pub struct NoLandingPads;
 
@@ -23,7 +25,10 @@ impl MirPass for NoLandingPads {
                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
                           _: MirSource,
                           mir: &mut Mir<'tcx>) {
-        no_landing_pads(tcx, mir)
+        if tcx.sess.no_landing_pads() {
+            no_landing_pads(tcx, mir);
+            simplify::remove_dead_blocks(mir);
+        }
     }
 }
 
@@ -420,20 +420,26 @@ pub fn promote_candidates<'a, 'tcx>(mir: &mut Mir<'tcx>,
             LocalDecl::new_return_place(tcx.types.never, mir.span)
         ).collect();
 
+        let mut promoted = Mir::new(
+            IndexVec::new(),
+            // FIXME: maybe try to filter this to avoid blowing up
+            // memory usage?
+            mir.source_scopes.clone(),
+            mir.source_scope_local_data.clone(),
+            IndexVec::new(),
+            None,
+            initial_locals,
+            0,
+            vec![],
+            vec![],
+            mir.span
+        );
+        // Promoted MIR is created while its parent is being validated, and
+        // goes through the rest of that suite on its own afterwards.
+        promoted.phase = MirPhase::Const;
+
         let mut promoter = Promoter {
-            promoted: Mir::new(
-                IndexVec::new(),
-                // FIXME: maybe try to filter this to avoid blowing up
-                // memory usage?
-                mir.source_scopes.clone(),
-                mir.source_scope_local_data.clone(),
-                IndexVec::new(),
-                None,
-                initial_locals,
-                0,
-                vec![],
-                mir.span
-            ),
+            promoted,
             tcx,
             source: mir,
             temps: &mut temps,
@@ -688,8 +688,9 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
 
                 if forbidden_mut {
                     self.add(Qualif::NOT_CONST);
-                } else {
-                    // We might have a candidate for promotion.
+                } else if kind != BorrowKind::Shallow {
+                    // We might have a candidate for promotion. Fake borrows
+                    // are not, since they have to refer to the matched place.
                     let candidate = Candidate::Ref(location);
                     // We can only promote interior borrows of promotable temps.
                     let mut place = place;
@@ -1125,6 +1126,10 @@ fn mir_const_qualif<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
 pub struct QualifyAndPromoteConstants;
 
 impl MirPass for QualifyAndPromoteConstants {
+    fn required_phase(&self) -> Option<MirPhase> {
+        Some(MirPhase::Const)
+    }
+
     fn run_pass<'a, 'tcx>(&self,
                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
                           src: MirSource,
@@ -1159,21 +1164,7 @@ impl MirPass for QualifyAndPromoteConstants {
         };
 
         if mode == Mode::Fn || mode == Mode::ConstFn {
-            // This is ugly because Qualifier holds onto mir,
-            // which can't be mutated until its scope ends.
-            let (temps, candidates) = {
-                let mut qualifier = Qualifier::new(tcx, def_id, mir, mode);
-                if mode == Mode::ConstFn {
-                    // Enforce a constant-like CFG for `const fn`.
-                    qualifier.qualify_const();
-                } else {
-                    while let Some((bb, data)) = qualifier.rpo.next() {
-                        qualifier.visit_basic_block_data(bb, data);
-                    }
-                }
-
-                (qualifier.temp_promotion_state, qualifier.promotion_candidates)
-            };
+            let (temps, candidates) = promotion_candidates(tcx, def_id, mir, mode);
 
             // Do the actual promotion, now that we know what's viable.
             promote_consts::promote_candidates(mir, tcx, temps, candidates);
@@ -1237,6 +1228,28 @@ impl MirPass for QualifyAndPromoteConstants {
     }
 }
 
+/// Finds what can be promoted out of the body of a (`const`) function:
+/// the borrows of constant temps and the arguments that are required to
+/// be constant, along with the promotion state of every temp, which
+/// `promote_consts::promote_candidates` needs to extract them.
+fn promotion_candidates<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                  def_id: DefId,
+                                  mir: &Mir<'tcx>,
+                                  mode: Mode)
+                                  -> (IndexVec<Local, TempState>, Vec<Candidate>) {
+    let mut qualifier = Qualifier::new(tcx, def_id, mir, mode);
+    if mode == Mode::ConstFn {
+        // Enforce a constant-like CFG for `const fn`.
+        qualifier.qualify_const();
+    } else {
+        while let Some((bb, data)) = qualifier.rpo.next() {
+            qualifier.visit_basic_block_data(bb, data);
+        }
+    }
+
+    (qualifier.temp_promotion_state, qualifier.promotion_candidates)
+}
+
 fn args_required_const(tcx: TyCtxt, def_id: DefId) -> Option<FxHashSet<usize>> {
     let attrs = tcx.get_attrs(def_id);
     let attr = attrs.iter().find(|a| a.check_name("rustc_args_required_const"))?;
@@ -39,6 +39,7 @@
 
 use rustc_data_structures::bitvec::BitVector;
 use rustc_data_structures::indexed_vec::{Idx, IndexVec};
+use rustc::session::Session;
 use rustc::ty::TyCtxt;
 use rustc::mir::*;
 use rustc::mir::visit::{MutVisitor, Visitor, PlaceContext};
@@ -46,20 +47,41 @@ use rustc::session::config::FullDebugInfo;
 use std::borrow::Cow;
 use transform::{MirPass, MirSource};
 
-pub struct SimplifyCfg { label: String }
+pub struct SimplifyCfg {
+    label: String,
+    borrowck_safe: bool,
+}
 
 impl SimplifyCfg {
     pub fn new(label: &str) -> Self {
-        SimplifyCfg { label: format!("SimplifyCfg-{}", label) }
+        SimplifyCfg { label: format!("SimplifyCfg-{}", label), borrowck_safe: false }
+    }
+
+    /// A `SimplifyCfg` that can run right before borrowck: it keeps the `FalseEdges` and
+    /// `FalseUnwind` terminators where they are and doesn't move statements to other blocks,
+    /// so locations seen by borrowck and in the `nll` dump stay meaningful.
+    pub fn new_borrowck_safe(label: &str) -> Self {
+        SimplifyCfg { label: format!("SimplifyCfg-{}", label), borrowck_safe: true }
     }
 }
 
 pub fn simplify_cfg(mir: &mut Mir) {
-    CfgSimplifier::new(mir).simplify();
+    simplify_cfg_with(mir, false)
+}
+
+fn simplify_cfg_with(mir: &mut Mir, borrowck_safe: bool) {
+    let simplifier = if borrowck_safe {
+        CfgSimplifier::new_borrowck_safe(mir)
+    } else {
+        CfgSimplifier::new(mir)
+    };
+    if simplifier.simplify() {
+        mir.invalidate_cfg_cache();
+    }
     remove_dead_blocks(mir);
 
     // FIXME: Should probably be moved into some kind of pass manager
-    mir.basic_blocks_mut().raw.shrink_to_fit();
+    mir.basic_blocks_mut_preserves_cfg().raw.shrink_to_fit();
 }
 
 impl MirPass for SimplifyCfg {
@@ -72,16 +94,24 @@ impl MirPass for SimplifyCfg {
                           _src: MirSource,
                           mir: &mut Mir<'tcx>) {
         debug!("SimplifyCfg({:?}) - simplifying {:?}", self.label, mir);
-        simplify_cfg(mir);
+        simplify_cfg_with(mir, self.borrowck_safe);
     }
 }
 
 pub struct CfgSimplifier<'a, 'tcx: 'a> {
     basic_blocks: &'a mut IndexVec<BasicBlock, BasicBlockData<'tcx>>,
-    pred_count: IndexVec<BasicBlock, u32>
+    pred_count: IndexVec<BasicBlock, u32>,
+    /// The block all jumps to empty blocks that just `return` are redirected to.
+    return_block: Option<BasicBlock>,
+    /// Don't move statements or false edges, see `SimplifyCfg::new_borrowck_safe`.
+    borrowck_safe: bool,
 }
 
 impl<'a, 'tcx: 'a> CfgSimplifier<'a, 'tcx> {
+    pub fn new_borrowck_safe(mir: &'a mut Mir<'tcx>) -> Self {
+        CfgSimplifier { borrowck_safe: true, ..CfgSimplifier::new(mir) }
+    }
+
     pub fn new(mir: &'a mut Mir<'tcx>) -> Self {
         let mut pred_count = IndexVec::from_elem(0u32, mir.basic_blocks());
 
@@ -97,17 +127,25 @@ impl<'a, 'tcx: 'a> CfgSimplifier<'a, 'tcx> {
             }
         }
 
-        let basic_blocks = mir.basic_blocks_mut();
+        // Most runs find nothing to simplify, so don't throw away the cached
+        // predecessors up front; `simplify` reports whether the CFG changed.
+        let basic_blocks = mir.basic_blocks_mut_preserves_cfg();
 
         CfgSimplifier {
             basic_blocks,
             pred_count,
+            return_block: None,
+            borrowck_safe: false,
         }
     }
 
-    pub fn simplify(mut self) {
+    /// Simplifies the CFG and returns whether it was changed, in which case
+    /// the caller has to invalidate the CFG cache of the MIR.
+    #[must_use]
+    pub fn simplify(mut self) -> bool {
         self.strip_nops();
 
+        let mut any_changed = false;
         loop {
             let mut changed = false;
 
@@ -123,6 +161,7 @@ impl<'a, 'tcx: 'a> CfgSimplifier<'a, 'tcx> {
 
                 for successor in terminator.successors_mut() {
                     self.collapse_goto_chain(successor, &mut changed);
+                    self.merge_return_block(successor, &mut changed);
                 }
 
                 let mut new_stmts = vec![];
@@ -130,6 +169,7 @@ impl<'a, 'tcx: 'a> CfgSimplifier<'a, 'tcx> {
                 while inner_changed {
                     inner_changed = false;
                     inner_changed |= self.simplify_branch(&mut terminator);
+                    inner_changed |= self.collapse_to_return(&mut terminator);
                     inner_changed |= self.merge_successor(&mut new_stmts, &mut terminator);
                     changed |= inner_changed;
                 }
@@ -141,7 +181,9 @@ impl<'a, 'tcx: 'a> CfgSimplifier<'a, 'tcx> {
             }
 
             if !changed { break }
+            any_changed = true;
         }
+        any_changed
     }
 
     // Collapse a goto chain starting from `start`
@@ -183,6 +225,51 @@ impl<'a, 'tcx: 'a> CfgSimplifier<'a, 'tcx> {
         *start = target;
     }
 
+    fn is_empty_return_block(&self, bb: BasicBlock) -> bool {
+        match self.basic_blocks[bb] {
+            BasicBlockData {
+                ref statements,
+                terminator: Some(Terminator { kind: TerminatorKind::Return, .. }),
+                ..
+            } => statements.is_empty(),
+            _ => false
+        }
+    }
+
+    // Redirect a jump to an empty `return` block to the one all such jumps go to,
+    // so that the inliner's and the generator transform's many copies of it die
+    fn merge_return_block(&mut self, target: &mut BasicBlock, changed: &mut bool) {
+        if !self.is_empty_return_block(*target) {
+            return;
+        }
+        match self.return_block {
+            Some(return_block) if return_block != *target &&
+                                  self.is_empty_return_block(return_block) => {
+                debug!("merging return block {:?} into {:?}", *target, return_block);
+                self.pred_count[*target] -= 1;
+                self.pred_count[return_block] += 1;
+                *target = return_block;
+                *changed = true;
+            }
+            Some(return_block) if return_block == *target => {}
+            // The previous return block was merged into its predecessor in the meantime.
+            _ => self.return_block = Some(*target),
+        }
+    }
+
+    // turn a `goto` to an empty `return` block into a `return`
+    fn collapse_to_return(&mut self, terminator: &mut Terminator<'tcx>) -> bool {
+        let target = match terminator.kind {
+            TerminatorKind::Goto { target } if self.is_empty_return_block(target) => target,
+            _ => return false
+        };
+
+        debug!("collapsing goto to {:?} into a return", target);
+        self.pred_count[target] -= 1;
+        terminator.kind = TerminatorKind::Return;
+        true
+    }
+
     // merge a block with 1 `goto` predecessor to its parent
     fn merge_successor(&mut self,
                        new_stmts: &mut Vec<Statement<'tcx>>,
@@ -196,6 +283,18 @@ impl<'a, 'tcx: 'a> CfgSimplifier<'a, 'tcx> {
             _ => return false
         };
 
+        if self.borrowck_safe {
+            // Merging a block with statements moves them to another block, and
+            // merging a false edge moves where it starts.
+            let target_data = &self.basic_blocks[target];
+            match target_data.terminator {
+                _ if !target_data.statements.is_empty() => return false,
+                Some(Terminator { kind: TerminatorKind::FalseEdges { .. }, .. }) |
+                Some(Terminator { kind: TerminatorKind::FalseUnwind { .. }, .. }) => return false,
+                _ => {}
+            }
+        }
+
         debug!("merging block {:?} into {:?}", target, terminator);
         *terminator = match self.basic_blocks[target].terminator.take() {
             Some(terminator) => terminator,
@@ -254,9 +353,14 @@ pub fn remove_dead_blocks(mir: &mut Mir) {
         seen.insert(bb.index());
     }
 
+    let num_blocks = mir.basic_blocks().len();
+    if seen.count() == num_blocks {
+        // Every block is reachable, so the CFG and its cache stay as they are.
+        return;
+    }
+
     let basic_blocks = mir.basic_blocks_mut();
 
-    let num_blocks = basic_blocks.len();
     let mut replacements : Vec<_> = (0..num_blocks).map(BasicBlock::new).collect();
     let mut used_blocks = 0;
     for alive_index in seen.iter() {
@@ -281,6 +385,11 @@ pub fn remove_dead_blocks(mir: &mut Mir) {
 pub struct SimplifyLocals;
 
 impl MirPass for SimplifyLocals {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        // Unused locals cost stack space, but are otherwise harmless.
+        sess.opts.debugging_opts.mir_opt_level > 0
+    }
+
     fn run_pass<'a, 'tcx>(&self,
                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
                           _: MirSource,
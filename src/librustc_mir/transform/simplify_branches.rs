@@ -31,34 +31,31 @@ impl MirPass for SimplifyBranches {
 
     fn run_pass<'a, 'tcx>(&self,
                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
-                          _src: MirSource,
+                          src: MirSource,
                           mir: &mut Mir<'tcx>) {
-        for block in mir.basic_blocks_mut() {
+        for (bb, block) in mir.basic_blocks_mut().iter_enumerated_mut() {
+            let consider_optimizing = || {
+                tcx.consider_optimizing(|| format!("{} - {:?} in {:?}", self.label, bb, src))
+            };
             let terminator = block.terminator_mut();
             terminator.kind = match terminator.kind {
                 TerminatorKind::SwitchInt { discr: Operand::Constant(box Constant {
                     literal: Literal::Value { ref value }, ..
-                }), switch_ty, ref values, ref targets, .. } => {
+                }), switch_ty, ref targets, .. } => {
                     let switch_ty = ParamEnv::empty().and(switch_ty);
-                    if let Some(constint) = value.assert_bits(tcx, switch_ty) {
-                        let (otherwise, targets) = targets.split_last().unwrap();
-                        let mut ret = TerminatorKind::Goto { target: *otherwise };
-                        for (&v, t) in values.iter().zip(targets.iter()) {
-                            if v == constint {
-                                ret = TerminatorKind::Goto { target: *t };
-                                break;
-                            }
+                    match value.assert_bits(tcx, switch_ty) {
+                        Some(constint) if consider_optimizing() => {
+                            TerminatorKind::Goto { target: targets.target_for_value(constint) }
                         }
-                        ret
-                    } else {
-                        continue
+                        _ => continue,
                     }
                 },
                 TerminatorKind::Assert { target, cond: Operand::Constant(box Constant {
                     literal: Literal::Value {
                         value
                     }, ..
-                }), expected, .. } if (value.assert_bool(tcx) == Some(true)) == expected => {
+                }), expected, .. } if (value.assert_bool(tcx) == Some(true)) == expected &&
+                                      consider_optimizing() => {
                     TerminatorKind::Goto { target: target }
                 },
                 TerminatorKind::FalseEdges { real_target, .. } => {
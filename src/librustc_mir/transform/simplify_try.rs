@@ -0,0 +1,166 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Simplifies match arms which take an enum apart only to build the same variant again.
+//!
+//! The `Err(e) => return Err(e)` arm `?` desugars to looks like this once deaggregated:
+//!
+//!     _TMP = move ((_SRC as Err).0: E);
+//!     ((_DEST as Err).0: E) = move _TMP;
+//!     discriminant(_DEST) = 1;
+//!
+//! When `_SRC` and `_DEST` have the same type and the variant has a single field, this is
+//! just a move of the whole value:
+//!
+//!     _DEST = move _SRC;
+//!     nop;
+//!     nop;
+
+use rustc::mir::{Field, Local, Mir, Operand, Place, ProjectionElem, Rvalue};
+use rustc::mir::{Statement, StatementKind};
+use rustc::session::Session;
+use rustc::ty::{self, TyCtxt};
+use transform::{MirPass, MirSource};
+
+pub struct SimplifyArmIdentity;
+
+impl MirPass for SimplifyArmIdentity {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.mir_opt_level > 1
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let (basic_blocks, local_decls) = mir.basic_blocks_and_local_decls_mut();
+        for data in basic_blocks {
+            // The three statements may be separated by the storage markers of the temporary,
+            // but by nothing else.
+            let significant: Vec<usize> = data.statements.iter().enumerate()
+                .filter(|&(_, statement)| match statement.kind {
+                    StatementKind::StorageLive(_) |
+                    StatementKind::StorageDead(_) |
+                    StatementKind::Nop => false,
+                    _ => true,
+                })
+                .map(|(index, _)| index)
+                .collect();
+
+            for window in significant.windows(3) {
+                let (get, set, discr) = (window[0], window[1], window[2]);
+                let (tmp, src, field, is_move) = match get_variant_field(&data.statements[get]) {
+                    Some(found) => found,
+                    None => continue,
+                };
+                let dest = match set_variant_field(&data.statements[set]) {
+                    Some((set_tmp, dest, set_field))
+                        if set_tmp == tmp && set_field == field => dest,
+                    _ => continue,
+                };
+                if set_discriminant(&data.statements[discr]) != Some((dest, field.1)) {
+                    continue;
+                }
+                if src == dest || local_decls[src].ty != local_decls[dest].ty {
+                    continue;
+                }
+                let single_field = match local_decls[dest].ty.sty {
+                    ty::TyAdt(adt_def, _) => {
+                        adt_def.is_enum() && adt_def.variants[field.1].fields.len() == 1
+                    }
+                    _ => false,
+                };
+                if !single_field {
+                    continue;
+                }
+                let only_tmp_markers = data.statements[get + 1..discr].iter()
+                    .all(|statement| match statement.kind {
+                        StatementKind::StorageLive(local) |
+                        StatementKind::StorageDead(local) => local == tmp,
+                        _ => true,
+                    });
+                if !only_tmp_markers {
+                    continue;
+                }
+                if !tcx.consider_optimizing(|| {
+                    format!("SimplifyArmIdentity - {:?} into {:?} in {:?}", src, dest, source)
+                }) {
+                    return;
+                }
+
+                debug!("Replacing the rebuild of {:?} into {:?} by a move", src, dest);
+                let operand = if is_move {
+                    Operand::Move(Place::Local(src))
+                } else {
+                    Operand::Copy(Place::Local(src))
+                };
+                data.statements[get].kind =
+                    StatementKind::Assign(Place::Local(dest), Rvalue::Use(operand));
+                data.statements[set].make_nop();
+                data.statements[discr].make_nop();
+            }
+        }
+    }
+}
+
+/// A field of an enum variant, as `(field, variant_index)`.
+type VariantField = (Field, usize);
+
+/// Returns the local a place projects a variant field out of, e.g. `_1` in `((_1 as Err).0)`.
+fn variant_field_of<'tcx>(place: &Place<'tcx>) -> Option<(Local, VariantField)> {
+    if let Place::Projection(ref field_proj) = *place {
+        if let ProjectionElem::Field(field, _) = field_proj.elem {
+            if let Place::Projection(ref downcast_proj) = field_proj.base {
+                if let (&Place::Local(local), &ProjectionElem::Downcast(_, variant_index)) =
+                    (&downcast_proj.base, &downcast_proj.elem)
+                {
+                    return Some((local, (field, variant_index)));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Matches `_TMP = move ((_SRC as V).F: T)`, or the same with a copy.
+fn get_variant_field<'tcx>(statement: &Statement<'tcx>)
+                           -> Option<(Local, Local, VariantField, bool)> {
+    match statement.kind {
+        StatementKind::Assign(Place::Local(tmp), Rvalue::Use(ref operand)) => {
+            let (place, is_move) = match *operand {
+                Operand::Move(ref place) => (place, true),
+                Operand::Copy(ref place) => (place, false),
+                Operand::Constant(_) => return None,
+            };
+            variant_field_of(place).map(|(src, field)| (tmp, src, field, is_move))
+        }
+        _ => None,
+    }
+}
+
+/// Matches `((_DEST as V).F: T) = move _TMP`.
+fn set_variant_field<'tcx>(statement: &Statement<'tcx>) -> Option<(Local, Local, VariantField)> {
+    match statement.kind {
+        StatementKind::Assign(ref place, Rvalue::Use(Operand::Move(Place::Local(tmp)))) => {
+            variant_field_of(place).map(|(dest, field)| (tmp, dest, field))
+        }
+        _ => None,
+    }
+}
+
+/// Matches `discriminant(_DEST) = V`.
+fn set_discriminant<'tcx>(statement: &Statement<'tcx>) -> Option<(Local, usize)> {
+    match statement.kind {
+        StatementKind::SetDiscriminant { place: Place::Local(dest), variant_index } => {
+            Some((dest, variant_index))
+        }
+        _ => None,
+    }
+}
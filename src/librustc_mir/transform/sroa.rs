@@ -0,0 +1,197 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Scalar replacement of aggregates.
+//!
+//! Locals of struct or tuple type which are only ever accessed through their fields are split
+//! into one new local per field:
+//!
+//!     (_1.0: u32) = const 1u32;
+//!     (_1.1: u32) = _2;
+//!     _0 = Add(move (_1.0: u32), move (_1.1: u32));
+//!
+//! becomes
+//!
+//!     _3 = const 1u32;
+//!     _4 = _2;
+//!     _0 = Add(move _3, move _4);
+//!
+//! Together with the deaggregator this turns aggregates that never need to exist as a whole
+//! into independent scalars, which the later const and copy propagation can see through, and
+//! which don't need to be copied around in memory as a whole.
+
+use rustc::mir::{Local, Location, Mir, Place, ProjectionElem, LocalDecl, Statement, StatementKind};
+use rustc::mir::visit::{MutVisitor, PlaceContext, Visitor};
+use rustc::session::Session;
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc_data_structures::indexed_vec::{Idx, IndexVec};
+use transform::{MirPass, MirSource};
+use util::liveness::LocalSet;
+
+pub struct ScalarReplacementOfAggregates;
+
+impl MirPass for ScalarReplacementOfAggregates {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        // The fields of split user variables are no longer visible in the debugger.
+        sess.opts.debugging_opts.mir_opt_level > 1
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let (field_used, escaping) = {
+            let mut finder = CandidateFinder {
+                field_used: LocalSet::new_empty(mir.local_decls.len()),
+                escaping: LocalSet::new_empty(mir.local_decls.len()),
+            };
+            finder.visit_mir(mir);
+            (finder.field_used, finder.escaping)
+        };
+
+        let param_env = tcx.param_env(source.def_id);
+        let mut replacements: IndexVec<Local, Option<Vec<Local>>> =
+            IndexVec::from_elem(None, &mir.local_decls);
+        let mut any_replaced = false;
+        for local in mir.vars_and_temps_iter().collect::<Vec<_>>() {
+            if !field_used.contains(&local) || escaping.contains(&local) {
+                continue;
+            }
+            let field_tys = match split_fields(tcx, mir.local_decls[local].ty) {
+                Some(field_tys) => field_tys,
+                None => continue,
+            };
+            if !tcx.consider_optimizing(|| {
+                format!("ScalarReplacementOfAggregates - {:?} in {:?}", local, source)
+            }) {
+                break;
+            }
+            debug!("Splitting {:?} into {} locals", local, field_tys.len());
+            let span = mir.local_decls[local].source_info.span;
+            let fields = field_tys.into_iter().map(|ty| {
+                let ty = tcx.normalize_erasing_regions(param_env, ty);
+                mir.local_decls.push(LocalDecl::new_temp(ty, span))
+            }).collect();
+            replacements[local] = Some(fields);
+            any_replaced = true;
+        }
+        if !any_replaced {
+            return;
+        }
+
+        // The storage markers of a split local now apply to each of its fields.
+        for data in mir.basic_blocks_mut_preserves_cfg() {
+            data.expand_statements(|stmt| {
+                let (local, live) = match stmt.kind {
+                    StatementKind::StorageLive(local) => (local, true),
+                    StatementKind::StorageDead(local) => (local, false),
+                    _ => return None,
+                };
+                let fields = replacements[local].as_ref()?;
+                let source_info = stmt.source_info;
+                Some(fields.iter().map(move |&field| Statement {
+                    source_info,
+                    kind: if live {
+                        StatementKind::StorageLive(field)
+                    } else {
+                        StatementKind::StorageDead(field)
+                    },
+                }))
+            });
+        }
+
+        FieldReplacer { replacements: &replacements }.visit_mir(mir);
+
+        // Debuginfo referring to a split local as a whole has nothing left to point to.
+        mir.var_debug_info.retain(|var_debug_info| match var_debug_info.place {
+            Place::Local(local) => replacements[local].is_none(),
+            _ => true,
+        });
+    }
+}
+
+/// Returns the types of the fields of `ty`, if it is an aggregate we know how to split.
+fn split_fields<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, ty: Ty<'tcx>) -> Option<Vec<Ty<'tcx>>> {
+    match ty.sty {
+        ty::TyTuple(tys) if !tys.is_empty() => Some(tys.to_vec()),
+        ty::TyAdt(adt_def, substs) if adt_def.is_struct() &&
+                                      !adt_def.has_dtor(tcx) &&
+                                      !ty.is_simd() => {
+            let variant = adt_def.non_enum_variant();
+            if variant.fields.is_empty() {
+                return None;
+            }
+            Some(variant.fields.iter().map(|field| field.ty(tcx, substs)).collect())
+        }
+        _ => None,
+    }
+}
+
+/// Returns the local and field `place` refers to, if it is a field of a local.
+fn local_field<'tcx>(place: &Place<'tcx>) -> Option<(Local, usize)> {
+    if let Place::Projection(ref proj) = *place {
+        if let (&Place::Local(local), &ProjectionElem::Field(field, _)) =
+            (&proj.base, &proj.elem)
+        {
+            return Some((local, field.index()));
+        }
+    }
+    None
+}
+
+struct CandidateFinder {
+    /// Locals with at least one of their fields accessed.
+    field_used: LocalSet,
+    /// Locals which are accessed as a whole, and therefore can't be split.
+    escaping: LocalSet,
+}
+
+impl<'tcx> Visitor<'tcx> for CandidateFinder {
+    fn visit_place(&mut self,
+                   place: &Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        if let Some((local, _)) = local_field(place) {
+            self.field_used.add(&local);
+            return;
+        }
+        self.super_place(place, context, location);
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext<'tcx>, _: Location) {
+        match context {
+            PlaceContext::StorageLive |
+            PlaceContext::StorageDead |
+            PlaceContext::VarDebugInfo => {}
+            _ => {
+                self.escaping.add(&local);
+            }
+        }
+    }
+}
+
+struct FieldReplacer<'a> {
+    replacements: &'a IndexVec<Local, Option<Vec<Local>>>,
+}
+
+impl<'a, 'tcx> MutVisitor<'tcx> for FieldReplacer<'a> {
+    fn visit_place(&mut self,
+                   place: &mut Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        let replacement = local_field(place).and_then(|(local, field)| {
+            self.replacements[local].as_ref().map(|fields| fields[field])
+        });
+        match replacement {
+            Some(field_local) => *place = Place::Local(field_local),
+            None => self.super_place(place, context, location),
+        }
+    }
+}
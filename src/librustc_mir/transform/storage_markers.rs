@@ -0,0 +1,191 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Tightens the `StorageLive`/`StorageDead` markers of locals that are never borrowed.
+//!
+//! MIR building places storage markers at scope boundaries, which is often much earlier
+//! (`StorageLive`) or later (`StorageDead`) than the first or last use of the local. Codegen
+//! turns the markers into LLVM lifetime intrinsics, so overly wide ranges keep LLVM from
+//! sharing stack slots between locals. Within each block, this pass
+//!
+//! - moves every `StorageLive` down to right before the first statement mentioning the local,
+//! - moves every `StorageDead` up to right after the last statement mentioning the local,
+//!
+//! and removes the markers altogether for locals that are live for the whole body anyway:
+//! those that only have a single `StorageLive` in the start block and only die right before
+//! returning or resuming.
+//!
+//! Borrowed locals are left alone, since they may be accessed through a reference by
+//! statements that don't mention them.
+
+use rustc::mir::*;
+use rustc::mir::visit::Visitor;
+use rustc::session::Session;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::indexed_vec::IndexVec;
+use std::mem;
+use transform::{MirPass, MirSource};
+use transform::dead_store_elimination::borrowed_locals;
+use util::liveness::LocalSet;
+
+pub struct OptimizeStorageMarkers;
+
+impl MirPass for OptimizeStorageMarkers {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        // Shrinking the storage of user variables also shrinks the range in which the
+        // debugger can show them, so only do this when optimizing MIR aggressively.
+        sess.opts.debugging_opts.mir_opt_level > 1
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let borrowed = borrowed_locals(mir);
+        let mut redundant = redundant_markers(mir, &borrowed);
+        for local in mir.local_decls.indices() {
+            if redundant.contains(&local) && !tcx.consider_optimizing(|| {
+                format!("OptimizeStorageMarkers - remove {:?} in {:?}", local, source)
+            }) {
+                redundant.remove(&local);
+            }
+        }
+
+        for (bb, block) in mir.basic_blocks_mut_preserves_cfg().iter_enumerated_mut() {
+            for statement in &mut block.statements {
+                match statement.kind {
+                    StatementKind::StorageLive(local) |
+                    StatementKind::StorageDead(local) if redundant.contains(&local) => {
+                        statement.make_nop();
+                    }
+                    _ => {}
+                }
+            }
+
+            if !tcx.consider_optimizing(|| {
+                format!("OptimizeStorageMarkers - sink in {:?} in {:?}", bb, source)
+            }) {
+                continue;
+            }
+            sink_storage_live(&mut block.statements, &borrowed);
+            block.statements.reverse();
+            sink_storage_dead(&mut block.statements, &borrowed);
+            block.statements.reverse();
+        }
+    }
+}
+
+/// Finds the locals that are unborrowed and live during the whole body anyway, so that
+/// their storage markers don't tell codegen anything.
+fn redundant_markers<'tcx>(mir: &Mir<'tcx>, borrowed: &LocalSet) -> LocalSet {
+    let mut lives = IndexVec::from_elem(0, &mir.local_decls);
+    let mut early_deaths = LocalSet::new_empty(mir.local_decls.len());
+    let start_is_loop_header = !mir.predecessors_for(START_BLOCK).is_empty();
+
+    for (bb, block) in mir.basic_blocks().iter_enumerated() {
+        let exits = match block.terminator().kind {
+            TerminatorKind::Return | TerminatorKind::Resume => true,
+            _ => false,
+        };
+        for (index, statement) in block.statements.iter().enumerate() {
+            match statement.kind {
+                StatementKind::StorageLive(local) => {
+                    lives[local] += 1;
+                    if bb != START_BLOCK || start_is_loop_header {
+                        early_deaths.add(&local);
+                    }
+                }
+                StatementKind::StorageDead(local) => {
+                    let rest = &block.statements[index + 1..];
+                    if !exits || rest.iter().any(|statement| mentions(statement, local)) {
+                        early_deaths.add(&local);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut redundant = LocalSet::new_empty(mir.local_decls.len());
+    for (local, &count) in lives.iter_enumerated() {
+        if count == 1 && !early_deaths.contains(&local) && !borrowed.contains(&local) {
+            redundant.add(&local);
+        }
+    }
+    redundant
+}
+
+/// Moves each `StorageLive` of an unborrowed local down to the first statement mentioning
+/// the local, or the end of the block if there is none.
+fn sink_storage_live<'tcx>(statements: &mut Vec<Statement<'tcx>>, borrowed: &LocalSet) {
+    sink_markers(statements, borrowed, |kind| match *kind {
+        StatementKind::StorageLive(local) => Some(local),
+        _ => None,
+    })
+}
+
+/// Same as `sink_storage_live`, for `StorageDead`s in a reversed block, which moves them up
+/// to the last statement mentioning the local.
+fn sink_storage_dead<'tcx>(statements: &mut Vec<Statement<'tcx>>, borrowed: &LocalSet) {
+    sink_markers(statements, borrowed, |kind| match *kind {
+        StatementKind::StorageDead(local) => Some(local),
+        _ => None,
+    })
+}
+
+fn sink_markers<'tcx, F>(statements: &mut Vec<Statement<'tcx>>, borrowed: &LocalSet, marker: F)
+    where F: Fn(&StatementKind<'tcx>) -> Option<Local>
+{
+    let mut pending: Vec<(Local, Statement<'tcx>)> = vec![];
+    let mut result = Vec::with_capacity(statements.len());
+
+    for statement in mem::replace(statements, vec![]) {
+        // Markers whose local this statement mentions have to stay in front of it.
+        let mut index = 0;
+        while index < pending.len() {
+            if mentions(&statement, pending[index].0) {
+                result.push(pending.remove(index).1);
+            } else {
+                index += 1;
+            }
+        }
+
+        match marker(&statement.kind) {
+            Some(local) if !borrowed.contains(&local) => pending.push((local, statement)),
+            _ => result.push(statement),
+        }
+    }
+
+    result.extend(pending.into_iter().map(|(_, statement)| statement));
+    *statements = result;
+}
+
+fn mentions<'tcx>(statement: &Statement<'tcx>, local: Local) -> bool {
+    let mut finder = LocalFinder { local, found: false };
+    // The location is only used for error reporting.
+    finder.visit_statement(START_BLOCK, statement, Location {
+        block: START_BLOCK,
+        statement_index: 0,
+    });
+    finder.found
+}
+
+struct LocalFinder {
+    local: Local,
+    found: bool,
+}
+
+impl<'tcx> Visitor<'tcx> for LocalFinder {
+    fn visit_local(&mut self, &local: &Local, _: PlaceContext<'tcx>, _: Location) {
+        if local == self.local {
+            self.found = true;
+        }
+    }
+}
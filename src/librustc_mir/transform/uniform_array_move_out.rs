@@ -37,6 +37,7 @@
 // FIXME: integrate this transformation to the mir build
 
 use rustc::ty;
+use rustc::session::Session;
 use rustc::ty::TyCtxt;
 use rustc::mir::*;
 use rustc::mir::visit::{Visitor, PlaceContext};
@@ -169,6 +170,11 @@ impl<'a, 'tcx> UniformArrayMoveOutVisitor<'a, 'tcx> {
 pub struct RestoreSubsliceArrayMoveOut;
 
 impl MirPass for RestoreSubsliceArrayMoveOut {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        // The element-wise moves are correct as they are, just slower.
+        sess.opts.debugging_opts.mir_opt_level > 0
+    }
+
     fn run_pass<'a, 'tcx>(&self,
                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
                           _src: MirSource,
@@ -0,0 +1,128 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Propagates `unreachable` terminators backwards.
+//!
+//! A block which can only lead to blocks ending in `unreachable` can't be reached either,
+//! unless something in it may diverge. Monomorphized matches on uninhabited types leave many
+//! such chains of gotos and switches behind; we turn switch targets that can't be reached into
+//! the `otherwise` case and blocks without any reachable successor into `unreachable`
+//! themselves, which `SimplifyCfg` then cleans up.
+
+use rustc::mir::{BasicBlock, Mir, StatementKind, SwitchTargets, TerminatorKind};
+use rustc::mir::traversal;
+use rustc::session::Session;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::bitvec::BitVector;
+use rustc_data_structures::indexed_vec::Idx;
+use transform::{MirPass, MirSource};
+
+pub struct UnreachablePropagation;
+
+impl MirPass for UnreachablePropagation {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.mir_opt_level > 1
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let mut unreachable_blocks = BitVector::new(mir.basic_blocks().len());
+        let mut replacements = vec![];
+
+        // Visiting successors first lets a single pass handle whole chains of them.
+        for (bb, data) in traversal::postorder(mir) {
+            // Inline assembly might never return, so it keeps its block reachable.
+            let has_asm = data.statements.iter().any(|statement| match statement.kind {
+                StatementKind::InlineAsm { .. } => true,
+                _ => false,
+            });
+            let kind = match data.terminator().kind {
+                TerminatorKind::Unreachable => {
+                    if !has_asm {
+                        unreachable_blocks.insert(bb.index());
+                    }
+                    continue;
+                }
+                ref kind => {
+                    let is_unreachable = |succ: BasicBlock| {
+                        unreachable_blocks.contains(succ.index())
+                    };
+                    match remove_successors(kind, is_unreachable) {
+                        Some(kind) => kind,
+                        None => continue,
+                    }
+                }
+            };
+            if !tcx.consider_optimizing(|| {
+                format!("UnreachablePropagation - {:?} in {:?}", bb, source)
+            }) {
+                continue;
+            }
+            if let TerminatorKind::Unreachable = kind {
+                if !has_asm {
+                    unreachable_blocks.insert(bb.index());
+                }
+            }
+            replacements.push((bb, kind));
+        }
+
+        for (bb, kind) in replacements {
+            debug!("Replacing the terminator of {:?} by {:?}", bb, kind);
+            mir.basic_blocks_mut()[bb].terminator_mut().kind = kind;
+        }
+    }
+}
+
+/// Returns the terminator `kind` becomes once the successors for which `is_unreachable` holds
+/// are removed, if that changes anything. Only terminators without side effects are changed.
+fn remove_successors<'tcx, F>(kind: &TerminatorKind<'tcx>, is_unreachable: F)
+                              -> Option<TerminatorKind<'tcx>>
+    where F: Fn(BasicBlock) -> bool
+{
+    match *kind {
+        TerminatorKind::Goto { target } if is_unreachable(target) => {
+            Some(TerminatorKind::Unreachable)
+        }
+        TerminatorKind::SwitchInt { ref discr, switch_ty, ref targets } => {
+            let otherwise = targets.otherwise();
+            let mut cases: Vec<_> = targets.iter()
+                .filter(|&(_, target)| !is_unreachable(target))
+                .collect();
+            let retained = cases.len() + 1;
+
+            // If the `otherwise` block can't be reached, the discriminant always has one of
+            // the other values, so the last of them can take its place.
+            let otherwise = if !is_unreachable(otherwise) {
+                otherwise
+            } else {
+                match cases.pop() {
+                    Some((_, target)) => target,
+                    None => return Some(TerminatorKind::Unreachable),
+                }
+            };
+
+            if cases.is_empty() {
+                Some(TerminatorKind::Goto { target: otherwise })
+            } else if retained != targets.all_targets().len() ||
+                      otherwise != targets.otherwise() {
+                Some(TerminatorKind::SwitchInt {
+                    discr: discr.clone(),
+                    switch_ty,
+                    targets: SwitchTargets::new(cases, otherwise),
+                })
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,261 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Structural validation of MIR, run after every pass under `-Z validate-mir`.
+//!
+//! A pass which produces malformed MIR otherwise tends to be noticed much
+//! later, e.g. as an LLVM assertion, far away from the pass at fault. The
+//! validator checks that:
+//!
+//! - every block has a terminator and only jumps to existing blocks, and
+//!   cleanup and non-cleanup blocks are only connected through unwind edges;
+//! - locals whose storage is tracked by `StorageLive`/`StorageDead` are only
+//!   used where their storage may be live;
+//! - both sides of an assignment have the same type (up to regions);
+//! - no generator-only constructs remain after the generator transform, and
+//!   no borrowck-only `FalseEdges`/`FalseUnwind` or shallow borrows remain
+//!   in optimized MIR.
+//!
+//! Problems are reported as delayed bugs naming the pass after which they
+//! were found.
+
+use dataflow::{do_dataflow, DebugFormatted, MaybeStorageLive};
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::mir::*;
+use rustc::ty::{self, Ty, TyCtxt, TypeFlags, TypeFoldable};
+use rustc_data_structures::indexed_set::{IdxSet, IdxSetBuf};
+use rustc_data_structures::indexed_vec::Idx;
+use transform::{MirPass, MirSource};
+
+pub struct Validator {
+    /// Describes at which point in the pipeline this validation is happening.
+    pub when: String,
+}
+
+impl MirPass for Validator {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let mir: &Mir<'tcx> = mir;
+        let mut checker = MirValidator {
+            when: &self.when,
+            source,
+            mir,
+            tcx,
+            param_env: tcx.param_env(source.def_id),
+        };
+        checker.check_cfg();
+        checker.check_storage();
+        checker.visit_mir(mir);
+    }
+}
+
+struct MirValidator<'a, 'tcx: 'a> {
+    when: &'a str,
+    source: MirSource,
+    mir: &'a Mir<'tcx>,
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+}
+
+impl<'a, 'tcx> MirValidator<'a, 'tcx> {
+    fn fail(&self, location: Location, msg: &str) {
+        let span = self.mir.source_info(location).span;
+        self.tcx.sess.delay_span_bug(span, &format!(
+            "broken MIR in {:?} ({}) at {:?}:\n{}",
+            self.source.def_id,
+            self.when,
+            location,
+            msg,
+        ));
+    }
+
+    fn check_cfg(&self) {
+        let blocks = self.mir.basic_blocks();
+        if blocks.is_empty() {
+            self.tcx.sess.delay_span_bug(self.mir.span, &format!(
+                "broken MIR in {:?} ({}): no basic blocks", self.source.def_id, self.when));
+            return;
+        }
+        if blocks[START_BLOCK].is_cleanup {
+            self.fail(START_BLOCK.start_location(), "the start block is a cleanup block");
+        }
+
+        for (bb, data) in blocks.iter_enumerated() {
+            let location = Location { block: bb, statement_index: data.statements.len() };
+            let terminator = match data.terminator {
+                Some(ref terminator) => terminator,
+                None => {
+                    self.fail(location, &format!("{:?} has no terminator", bb));
+                    continue;
+                }
+            };
+
+            let unwind = terminator.kind.unwind().and_then(|unwind| *unwind);
+            for &target in terminator.successors() {
+                if target.index() >= blocks.len() {
+                    self.fail(location, &format!("edge to out-of-range block {:?}", target));
+                    continue;
+                }
+
+                let target_is_cleanup = blocks[target].is_cleanup;
+                if Some(target) == unwind && !data.is_cleanup {
+                    if !target_is_cleanup {
+                        self.fail(location, &format!(
+                            "unwind edge to non-cleanup block {:?}", target));
+                    }
+                } else if target_is_cleanup != data.is_cleanup {
+                    self.fail(location, &format!(
+                        "edge between cleanup and non-cleanup blocks ({:?} -> {:?})",
+                        bb, target));
+                }
+            }
+        }
+    }
+
+    /// Checks that every use of a local whose storage is explicitly managed
+    /// happens where that storage may be live.
+    fn check_storage(&self) {
+        let mut tracked = IdxSetBuf::new_empty(self.mir.local_decls.len());
+        for data in self.mir.basic_blocks() {
+            for statement in &data.statements {
+                match statement.kind {
+                    StatementKind::StorageLive(local) |
+                    StatementKind::StorageDead(local) => { tracked.add(&local); }
+                    _ => {}
+                }
+            }
+        }
+        if tracked.iter().next().is_none() {
+            return;
+        }
+
+        // Shims for upstream items have no node to report dataflow against.
+        let node_id = match self.tcx.hir.as_local_node_id(self.source.def_id) {
+            Some(node_id) => node_id,
+            None => return,
+        };
+        let dead_unwinds = IdxSetBuf::new_empty(self.mir.basic_blocks().len());
+        let storage_live = do_dataflow(self.tcx, self.mir, node_id, &[], &dead_unwinds,
+                                       MaybeStorageLive::new(self.mir),
+                                       |bd, p| DebugFormatted::new(&bd.mir().local_decls[p]));
+
+        for (bb, data) in self.mir.basic_blocks().iter_enumerated() {
+            let mut live = storage_live.sets().on_entry_set_for(bb.index()).to_owned();
+
+            for (statement_index, statement) in data.statements.iter().enumerate() {
+                let location = Location { block: bb, statement_index };
+                StorageChecker { validator: self, tracked: &tracked, live: &live }
+                    .visit_statement(bb, statement, location);
+                match statement.kind {
+                    StatementKind::StorageLive(local) => { live.add(&local); }
+                    StatementKind::StorageDead(local) => { live.remove(&local); }
+                    _ => {}
+                }
+            }
+
+            if let Some(ref terminator) = data.terminator {
+                let location = Location { block: bb, statement_index: data.statements.len() };
+                StorageChecker { validator: self, tracked: &tracked, live: &live }
+                    .visit_terminator(bb, terminator, location);
+            }
+        }
+    }
+
+    /// Whether a value of type `src` can be assigned to a place of type `dest`.
+    fn types_agree(&self, src: Ty<'tcx>, dest: Ty<'tcx>) -> bool {
+        if src.references_error() || dest.references_error() {
+            return true;
+        }
+
+        // Reveal `impl Trait` types, so that the return place of a function
+        // returning one agrees with the concrete type assigned to it.
+        let param_env = self.param_env.with_reveal_all();
+        let src = self.tcx.normalize_erasing_regions(param_env, src);
+        let dest = self.tcx.normalize_erasing_regions(param_env, dest);
+        if src == dest {
+            return true;
+        }
+
+        // Subtyping can relate types which only differ in late-bound regions,
+        // e.g. `for<'a> fn(&'a u8)` and `fn(&'static u8)`; erasing regions
+        // does not get rid of those, so don't compare them structurally.
+        src.has_type_flags(TypeFlags::HAS_RE_LATE_BOUND) ||
+            dest.has_type_flags(TypeFlags::HAS_RE_LATE_BOUND)
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for MirValidator<'a, 'tcx> {
+    fn visit_statement(&mut self,
+                       block: BasicBlock,
+                       statement: &Statement<'tcx>,
+                       location: Location) {
+        if let StatementKind::Assign(ref place, ref rvalue) = statement.kind {
+            let dest = place.ty(self.mir, self.tcx).to_ty(self.tcx);
+            let src = rvalue.ty(self.mir, self.tcx);
+            if !self.types_agree(src, dest) {
+                self.fail(location, &format!(
+                    "assignment of `{:?}` to `{:?}` with mismatched types `{}` and `{}`",
+                    rvalue, place, src, dest));
+            }
+
+            if let Rvalue::Ref(_, BorrowKind::Shallow, _) = *rvalue {
+                if self.mir.phase >= MirPhase::Optimized {
+                    self.fail(location, &format!("`{:?}` remains in optimized MIR", rvalue));
+                }
+            }
+        }
+
+        self.super_statement(block, statement, location);
+    }
+
+    fn visit_terminator_kind(&mut self,
+                             block: BasicBlock,
+                             kind: &TerminatorKind<'tcx>,
+                             location: Location) {
+        match *kind {
+            TerminatorKind::Yield { .. } |
+            TerminatorKind::GeneratorDrop if self.mir.generator_layout.is_some() => {
+                self.fail(location, &format!(
+                    "`{:?}` remains after the generator transform", kind));
+            }
+            // The imaginary edges only exist for borrowck and must not reach
+            // codegen or miri.
+            TerminatorKind::FalseEdges { .. } |
+            TerminatorKind::FalseUnwind { .. } if self.mir.phase >= MirPhase::Optimized => {
+                self.fail(location, &format!("`{:?}` remains in optimized MIR", kind));
+            }
+            _ => {}
+        }
+
+        self.super_terminator_kind(block, kind, location);
+    }
+}
+
+struct StorageChecker<'v, 'a: 'v, 'tcx: 'a> {
+    validator: &'v MirValidator<'a, 'tcx>,
+    tracked: &'v IdxSet<Local>,
+    live: &'v IdxSet<Local>,
+}
+
+impl<'v, 'a, 'tcx> Visitor<'tcx> for StorageChecker<'v, 'a, 'tcx> {
+    fn visit_local(&mut self, local: &Local, context: PlaceContext<'tcx>, location: Location) {
+        match context {
+            PlaceContext::StorageLive | PlaceContext::StorageDead => return,
+            _ => {}
+        }
+
+        if self.tracked.contains(local) && !self.live.contains(local) {
+            self.validator.fail(location, &format!(
+                "use of {:?} ({:?}) while its storage is dead", local, context));
+        }
+    }
+}
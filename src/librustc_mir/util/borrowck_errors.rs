@@ -12,6 +12,7 @@ use rustc::session::config::BorrowckMode;
 use rustc::ty::{self, TyCtxt};
 use rustc_errors::{DiagnosticBuilder, DiagnosticId};
 use syntax_pos::{MultiSpan, Span};
+use syntax_pos::hygiene::{ExpnFormat, ExpnInfo};
 
 use std::fmt;
 
@@ -338,6 +339,29 @@ pub trait BorrowckErrors<'cx>: Sized + Copy {
         self.cancel_if_wrong_origin(err, o)
     }
 
+    fn cannot_mutate_in_match_guard(
+        self,
+        mutate_span: Span,
+        match_span: Span,
+        match_place: &str,
+        action: &str,
+        o: Origin,
+    ) -> DiagnosticBuilder<'cx> {
+        let mut err = struct_span_err!(
+            self,
+            mutate_span,
+            E0510,
+            "cannot {} `{}` in match guard{OGN}",
+            action,
+            match_place,
+            OGN = o
+        );
+        err.span_label(mutate_span, format!("cannot {}", action));
+        err.span_label(match_span, "value is immutable in match guard");
+
+        self.cancel_if_wrong_origin(err, o)
+    }
+
     fn cannot_move_into_closure(self, span: Span, desc: &str, o: Origin) -> DiagnosticBuilder<'cx> {
         let err = struct_span_err!(
             self,
@@ -550,7 +574,21 @@ pub trait BorrowckErrors<'cx>: Sized + Copy {
             "borrow may still be in use when generator yields{OGN}",
             OGN = o
         );
-        err.span_label(yield_span, "possible yield occurs here");
+        match await_call_site(yield_span) {
+            Some(await_span) => {
+                err.span_label(await_span, "borrow is held across this await point");
+                // Borrows made by the `await!` itself can't be ended any earlier.
+                if await_call_site(span).is_none() {
+                    err.help(
+                        "consider ending the borrow before the `await!`, for example by \
+                         binding the value computed from it to a local first",
+                    );
+                }
+            }
+            None => {
+                err.span_label(yield_span, "possible yield occurs here");
+            }
+        }
 
         self.cancel_if_wrong_origin(err, o)
     }
@@ -573,6 +611,22 @@ pub trait BorrowckErrors<'cx>: Sized + Copy {
         self.cancel_if_wrong_origin(err, o)
     }
 
+    fn thread_local_value_does_not_live_long_enough(
+        self,
+        span: Span,
+        o: Origin,
+    ) -> DiagnosticBuilder<'cx> {
+        let err = struct_span_err!(
+            self,
+            span,
+            E0711,
+            "thread-local variable borrowed past end of function{OGN}",
+            OGN = o
+        );
+
+        self.cancel_if_wrong_origin(err, o)
+    }
+
     fn lifetime_too_short_for_reborrow(
         self,
         span: Span,
@@ -684,3 +738,13 @@ impl<'cx, 'gcx, 'tcx> BorrowckErrors<'cx> for TyCtxt<'cx, 'gcx, 'tcx> {
         diag
     }
 }
+
+/// If `span` comes from the expansion of an `await!`, such as the `yield` it
+/// expands to, returns the span of the `await!` invocation itself.
+fn await_call_site(span: Span) -> Option<Span> {
+    match span.ctxt().outer().expn_info() {
+        Some(ExpnInfo { call_site, format: ExpnFormat::MacroBang(name), .. })
+            if name == "await" => Some(call_site),
+        _ => None,
+    }
+}
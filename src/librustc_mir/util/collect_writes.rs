@@ -61,6 +61,7 @@ crate fn is_place_assignment(place_context: &PlaceContext) -> bool {
         | PlaceContext::Move
         | PlaceContext::StorageLive
         | PlaceContext::StorageDead
-        | PlaceContext::Validate => false,
+        | PlaceContext::Validate
+        | PlaceContext::VarDebugInfo => false,
     }
 }
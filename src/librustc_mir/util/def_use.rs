@@ -10,7 +10,7 @@
 
 //! Def-use analysis.
 
-use rustc::mir::{Local, Location, Mir};
+use rustc::mir::{Local, Location, Mir, VarDebugInfo};
 use rustc::mir::visit::{PlaceContext, MutVisitor, Visitor};
 use rustc_data_structures::indexed_vec::IndexVec;
 use std::marker::PhantomData;
@@ -25,6 +25,7 @@ pub struct DefUseAnalysis<'tcx> {
 #[derive(Clone)]
 pub struct Info<'tcx> {
     pub defs_and_uses: Vec<Use<'tcx>>,
+    var_debug_info_indices: Vec<usize>,
 }
 
 #[derive(Clone)]
@@ -45,6 +46,7 @@ impl<'tcx> DefUseAnalysis<'tcx> {
 
         let mut finder = DefUseFinder {
             info: mem::replace(&mut self.info, IndexVec::new()),
+            var_debug_info_index: 0,
         };
         finder.visit_mir(mir);
         self.info = finder.info
@@ -69,6 +71,12 @@ impl<'tcx> DefUseAnalysis<'tcx> {
                                   &mut callback,
                                   mir).visit_location(mir, place_use.location)
         }
+
+        for &i in &self.info[local].var_debug_info_indices {
+            MutateUseVisitor::new(local,
+                                  &mut callback,
+                                  mir).visit_var_debug_info(&mut mir.var_debug_info[i]);
+        }
     }
 
     /// FIXME(pcwalton): This should update the def-use chains.
@@ -82,6 +90,7 @@ impl<'tcx> DefUseAnalysis<'tcx> {
 
 struct DefUseFinder<'tcx> {
     info: IndexVec<Local, Info<'tcx>>,
+    var_debug_info_index: usize,
 }
 
 impl<'tcx> Visitor<'tcx> for DefUseFinder<'tcx> {
@@ -89,10 +98,20 @@ impl<'tcx> Visitor<'tcx> for DefUseFinder<'tcx> {
                    &local: &Local,
                    context: PlaceContext<'tcx>,
                    location: Location) {
-        self.info[local].defs_and_uses.push(Use {
-            context,
-            location,
-        });
+        let info = &mut self.info[local];
+        if context == PlaceContext::VarDebugInfo {
+            info.var_debug_info_indices.push(self.var_debug_info_index);
+        } else {
+            info.defs_and_uses.push(Use {
+                context,
+                location,
+            });
+        }
+    }
+
+    fn visit_var_debug_info(&mut self, var_debug_info: &VarDebugInfo<'tcx>) {
+        self.super_var_debug_info(var_debug_info);
+        self.var_debug_info_index += 1;
     }
 }
 
@@ -100,11 +119,13 @@ impl<'tcx> Info<'tcx> {
     fn new() -> Info<'tcx> {
         Info {
             defs_and_uses: vec![],
+            var_debug_info_indices: vec![],
         }
     }
 
     fn clear(&mut self) {
         self.defs_and_uses.clear();
+        self.var_debug_info_indices.clear();
     }
 
     pub fn def_count(&self) -> usize {
@@ -96,6 +96,9 @@ pub trait DropElaborator<'a, 'tcx: 'a> : fmt::Debug {
     fn deref_subpath(&self, path: Self::Path) -> Option<Self::Path>;
     fn downcast_subpath(&self, path: Self::Path, variant: usize) -> Option<Self::Path>;
     fn array_subpath(&self, path: Self::Path, index: u32, size: u32) -> Option<Self::Path>;
+    /// Whether any element of the array at `path` is tracked separately, in
+    /// which case the array has to be dropped element by element.
+    fn has_array_subpaths(&self, path: Self::Path) -> bool;
 }
 
 #[derive(Debug)]
@@ -480,7 +483,7 @@ impl<'l, 'b, 'tcx, D> DropCtxt<'l, 'b, 'tcx, D>
 
     fn adt_switch_block(&mut self,
                         adt: &'tcx ty::AdtDef,
-                        blocks: Vec<BasicBlock>,
+                        mut blocks: Vec<BasicBlock>,
                         values: &[u128],
                         succ: BasicBlock,
                         unwind: Unwind)
@@ -495,6 +498,7 @@ impl<'l, 'b, 'tcx, D> DropCtxt<'l, 'b, 'tcx, D>
         let discr_ty = adt.repr.discr_type().to_ty(self.tcx());
         let discr = Place::Local(self.new_temp(discr_ty));
         let discr_rv = Rvalue::Discriminant(self.place.clone());
+        let otherwise = blocks.pop().unwrap();
         let switch_block = BasicBlockData {
             statements: vec![self.assign(&discr, discr_rv)],
             terminator: Some(Terminator {
@@ -502,8 +506,7 @@ impl<'l, 'b, 'tcx, D> DropCtxt<'l, 'b, 'tcx, D>
                 kind: TerminatorKind::SwitchInt {
                     discr: Operand::Move(discr),
                     switch_ty: discr_ty,
-                    values: From::from(values.to_owned()),
-                    targets: blocks,
+                    targets: SwitchTargets::new(values.iter().cloned().zip(blocks), otherwise),
                 }
             }),
             is_cleanup: unwind.is_cleanup(),
@@ -645,7 +648,13 @@ impl<'l, 'b, 'tcx, D> DropCtxt<'l, 'b, 'tcx, D>
         //     ptr_based_loop
         // }
 
-        if let Some(size) = opt_size {
+        // Unless some elements have been moved out, drop the array with a
+        // loop, so that the MIR doesn't grow with the length of the array.
+        let size = match opt_size {
+            Some(size) if self.elaborator.has_array_subpaths(self.path) => Some(size),
+            _ => None,
+        };
+        if let Some(size) = size {
             assert!(size <= (u32::MAX as u64),
                     "move out check doesn't implemented for array bigger then u32");
             let size = size as u32;
@@ -39,6 +39,29 @@ pub fn write_mir_fn_graphviz<'tcx, W>(tcx: TyCtxt<'_, '_, 'tcx>,
                                       mir: &Mir,
                                       w: &mut W) -> io::Result<()>
     where W: Write
+{
+    write_mir_fn_graphviz_with(tcx, def_id, mir, None, w)
+}
+
+/// Write a graphviz DOT graph of the MIR where each statement and terminator
+/// is accompanied by the lines `annotate` returns for its location. This is
+/// used to show dataflow state next to the code it applies to.
+pub fn write_mir_fn_graphviz_annotated<'tcx, W>(tcx: TyCtxt<'_, '_, 'tcx>,
+                                                def_id: DefId,
+                                                mir: &Mir,
+                                                annotate: &dyn Fn(Location) -> Vec<String>,
+                                                w: &mut W) -> io::Result<()>
+    where W: Write
+{
+    write_mir_fn_graphviz_with(tcx, def_id, mir, Some(annotate), w)
+}
+
+fn write_mir_fn_graphviz_with<'tcx, W>(tcx: TyCtxt<'_, '_, 'tcx>,
+                                       def_id: DefId,
+                                       mir: &Mir,
+                                       annotate: Option<&dyn Fn(Location) -> Vec<String>>,
+                                       w: &mut W) -> io::Result<()>
+    where W: Write
 {
     writeln!(w, "digraph Mir_{} {{", tcx.hir.as_local_node_id(def_id).unwrap())?;
 
@@ -52,7 +75,10 @@ pub fn write_mir_fn_graphviz<'tcx, W>(tcx: TyCtxt<'_, '_, 'tcx>,
 
     // Nodes
     for (block, _) in mir.basic_blocks().iter_enumerated() {
-        write_node(block, mir, w)?;
+        match annotate {
+            Some(annotate) => write_annotated_node(block, mir, annotate, w)?,
+            None => write_node(block, mir, w)?,
+        }
     }
 
     // Edges
@@ -120,6 +146,41 @@ fn write_node<W: Write>(block: BasicBlock, mir: &Mir, w: &mut W) -> io::Result<(
     writeln!(w, ">];")
 }
 
+/// Write a graphviz DOT node for the given basic block, with one row per
+/// statement and the annotations for that statement in a second column.
+fn write_annotated_node<W: Write>(block: BasicBlock,
+                                  mir: &Mir,
+                                  annotate: &dyn Fn(Location) -> Vec<String>,
+                                  w: &mut W) -> io::Result<()> {
+    let data = &mir[block];
+
+    write!(w, r#"    {} [shape="none", label=<"#, node(block))?;
+    write!(w, r#"<table border="0" cellborder="1" cellspacing="0">"#)?;
+    write!(w, r#"<tr><td bgcolor="gray" align="center" colspan="2">{}</td></tr>"#,
+           block.index())?;
+
+    let write_row = |w: &mut W, code: String, location: Location| -> io::Result<()> {
+        write!(w, r#"<tr><td align="left" balign="left">{}</td>"#, code)?;
+        write!(w, r#"<td align="left" balign="left">"#)?;
+        for annotation in annotate(location) {
+            write!(w, "{}<br/>", dot::escape_html(&annotation))?;
+        }
+        write!(w, "</td></tr>")
+    };
+
+    for (statement_index, statement) in data.statements.iter().enumerate() {
+        write_row(w, escape(statement), Location { block, statement_index })?;
+    }
+
+    let mut terminator_head = String::new();
+    data.terminator().kind.fmt_head(&mut terminator_head).unwrap();
+    write_row(w,
+              dot::escape_html(&terminator_head),
+              Location { block, statement_index: data.statements.len() })?;
+
+    writeln!(w, "</table>>];")
+}
+
 /// Write graphviz DOT edges with labels between the given basic block and all of its successors.
 fn write_edges<W: Write>(source: BasicBlock, mir: &Mir, w: &mut W) -> io::Result<()> {
     let terminator = mir[source].terminator();
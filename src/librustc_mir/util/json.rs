@@ -0,0 +1,238 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Serialization of MIR bodies to JSON, enabled by `-Z dump-mir-json`.
+//!
+//! The output is meant for external tools, so it only contains strings,
+//! numbers, booleans, `null`, arrays and objects, and it never references
+//! rustc's internal data structures. Its layout is:
+//!
+//! ```text
+//! body := {
+//!     "schema_version": 1,
+//!     "item": string,               // path of the item the MIR belongs to
+//!     "promoted": null | number,    // index of the promoted constant, if any
+//!     "span": span,
+//!     "arg_count": number,          // locals 1..=arg_count are the arguments
+//!     "locals": [local],            // indexed by local; `_0` is the return place
+//!     "source_scopes": [scope],     // indexed by source scope
+//!     "basic_blocks": [block],      // indexed by basic block; `bb0` is the entry
+//!     "promoted_bodies": [body],
+//! }
+//! local := {
+//!     "name": null | string,
+//!     "ty": string,
+//!     "mutable": bool,
+//!     "user_variable": bool,
+//!     "internal": bool,
+//!     "source_info": source_info,
+//! }
+//! scope := {
+//!     "parent": null | number,
+//!     "span": span,
+//!     "inlined": null | { "callee": string, "callsite": span },
+//! }
+//! block := {
+//!     "is_cleanup": bool,
+//!     "statements": [statement],
+//!     "terminator": null | terminator,
+//! }
+//! statement := { "kind": string, "text": string, "source_info": source_info }
+//! terminator := {
+//!     "kind": string,
+//!     "text": string,              // the terminator without its successors
+//!     "successors": [number],
+//!     "successor_labels": [string], // parallel to "successors"
+//!     "source_info": source_info,
+//! }
+//! source_info := { "scope": number, "span": span }
+//! span := string                    // `file:line:col: line:col`
+//! ```
+//!
+//! `kind` is the name of the statement or terminator variant, while `text`
+//! is the same rendering that is used by the textual MIR dumps. New fields
+//! may be added without bumping `schema_version`; removing or changing the
+//! meaning of a field requires bumping it.
+
+use rustc::mir::*;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::indexed_vec::Idx;
+use rustc_serialize::json::{Json, Object};
+use std::io::{self, Write};
+use syntax_pos::Span;
+use transform::MirSource;
+
+/// The version of the format documented above.
+pub const SCHEMA_VERSION: u64 = 1;
+
+/// Writes `mir` as a single JSON document into `w`.
+pub fn write_mir_json<'a, 'gcx, 'tcx, W: Write>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    node_path: &str,
+    source: MirSource,
+    mir: &Mir<'tcx>,
+    w: &mut W,
+) -> io::Result<()> {
+    let json = body_to_json(tcx, node_path, source.promoted, mir);
+    writeln!(w, "{}", json.pretty())
+}
+
+fn body_to_json<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    node_path: &str,
+    promoted: Option<Promoted>,
+    mir: &Mir<'tcx>,
+) -> Json {
+    let mut body = Object::new();
+    body.insert("schema_version".to_string(), Json::U64(SCHEMA_VERSION));
+    body.insert("item".to_string(), Json::String(node_path.to_string()));
+    body.insert("promoted".to_string(), match promoted {
+        Some(promoted) => Json::U64(promoted.index() as u64),
+        None => Json::Null,
+    });
+    body.insert("span".to_string(), span_to_json(tcx, mir.span));
+    body.insert("arg_count".to_string(), Json::U64(mir.arg_count as u64));
+
+    let locals = mir.local_decls.iter().map(|decl| {
+        let mut local = Object::new();
+        local.insert("name".to_string(), match decl.name {
+            Some(name) => Json::String(name.to_string()),
+            None => Json::Null,
+        });
+        local.insert("ty".to_string(), Json::String(format!("{}", decl.ty)));
+        local.insert("mutable".to_string(),
+                     Json::Boolean(decl.mutability == Mutability::Mut));
+        local.insert("user_variable".to_string(),
+                     Json::Boolean(decl.is_user_variable.is_some()));
+        local.insert("internal".to_string(), Json::Boolean(decl.internal));
+        local.insert("source_info".to_string(), source_info_to_json(tcx, decl.source_info));
+        Json::Object(local)
+    }).collect();
+    body.insert("locals".to_string(), Json::Array(locals));
+
+    let scopes = mir.source_scopes.iter().map(|scope_data| {
+        let mut scope = Object::new();
+        scope.insert("parent".to_string(), match scope_data.parent_scope {
+            Some(parent) => Json::U64(parent.index() as u64),
+            None => Json::Null,
+        });
+        scope.insert("span".to_string(), span_to_json(tcx, scope_data.span));
+        scope.insert("inlined".to_string(), match scope_data.inlined {
+            Some((callee, callsite_span)) => {
+                let mut inlined = Object::new();
+                inlined.insert("callee".to_string(), Json::String(callee.to_string()));
+                inlined.insert("callsite".to_string(), span_to_json(tcx, callsite_span));
+                Json::Object(inlined)
+            }
+            None => Json::Null,
+        });
+        Json::Object(scope)
+    }).collect();
+    body.insert("source_scopes".to_string(), Json::Array(scopes));
+
+    let blocks = mir.basic_blocks().iter().map(|data| {
+        let mut block = Object::new();
+        block.insert("is_cleanup".to_string(), Json::Boolean(data.is_cleanup));
+
+        let statements = data.statements.iter().map(|statement| {
+            let mut json = Object::new();
+            json.insert("kind".to_string(),
+                        Json::String(statement_kind_name(&statement.kind).to_string()));
+            json.insert("text".to_string(), Json::String(format!("{:?}", statement)));
+            json.insert("source_info".to_string(),
+                        source_info_to_json(tcx, statement.source_info));
+            Json::Object(json)
+        }).collect();
+        block.insert("statements".to_string(), Json::Array(statements));
+
+        let terminator = match data.terminator {
+            Some(ref terminator) => terminator_to_json(tcx, terminator),
+            None => Json::Null,
+        };
+        block.insert("terminator".to_string(), terminator);
+        Json::Object(block)
+    }).collect();
+    body.insert("basic_blocks".to_string(), Json::Array(blocks));
+
+    let promoted = mir.promoted.iter_enumerated().map(|(index, promoted)| {
+        body_to_json(tcx, node_path, Some(index), promoted)
+    }).collect();
+    body.insert("promoted_bodies".to_string(), Json::Array(promoted));
+
+    Json::Object(body)
+}
+
+fn terminator_to_json<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    terminator: &Terminator<'tcx>,
+) -> Json {
+    let mut head = String::new();
+    terminator.kind.fmt_head(&mut head).unwrap();
+
+    let mut json = Object::new();
+    json.insert("kind".to_string(),
+                Json::String(terminator_kind_name(&terminator.kind).to_string()));
+    json.insert("text".to_string(), Json::String(head));
+    json.insert("successors".to_string(), Json::Array(
+        terminator.successors().map(|bb| Json::U64(bb.index() as u64)).collect()
+    ));
+    json.insert("successor_labels".to_string(), Json::Array(
+        terminator.kind.fmt_successor_labels().into_iter()
+            .map(|label| Json::String(label.into_owned()))
+            .collect()
+    ));
+    json.insert("source_info".to_string(), source_info_to_json(tcx, terminator.source_info));
+    Json::Object(json)
+}
+
+fn source_info_to_json(tcx: TyCtxt, source_info: SourceInfo) -> Json {
+    let mut json = Object::new();
+    json.insert("scope".to_string(), Json::U64(source_info.scope.index() as u64));
+    json.insert("span".to_string(), span_to_json(tcx, source_info.span));
+    Json::Object(json)
+}
+
+fn span_to_json(tcx: TyCtxt, span: Span) -> Json {
+    Json::String(tcx.sess.codemap().span_to_string(span))
+}
+
+fn statement_kind_name(kind: &StatementKind) -> &'static str {
+    match *kind {
+        StatementKind::Assign(..) => "Assign",
+        StatementKind::ReadForMatch(..) => "ReadForMatch",
+        StatementKind::SetDiscriminant { .. } => "SetDiscriminant",
+        StatementKind::StorageLive(..) => "StorageLive",
+        StatementKind::StorageDead(..) => "StorageDead",
+        StatementKind::InlineAsm { .. } => "InlineAsm",
+        StatementKind::Validate(..) => "Validate",
+        StatementKind::EndRegion(..) => "EndRegion",
+        StatementKind::UserAssertTy(..) => "UserAssertTy",
+        StatementKind::Nop => "Nop",
+    }
+}
+
+fn terminator_kind_name(kind: &TerminatorKind) -> &'static str {
+    match *kind {
+        TerminatorKind::Goto { .. } => "Goto",
+        TerminatorKind::SwitchInt { .. } => "SwitchInt",
+        TerminatorKind::Resume => "Resume",
+        TerminatorKind::Abort => "Abort",
+        TerminatorKind::Return => "Return",
+        TerminatorKind::Unreachable => "Unreachable",
+        TerminatorKind::Drop { .. } => "Drop",
+        TerminatorKind::DropAndReplace { .. } => "DropAndReplace",
+        TerminatorKind::Call { .. } => "Call",
+        TerminatorKind::Assert { .. } => "Assert",
+        TerminatorKind::Yield { .. } => "Yield",
+        TerminatorKind::GeneratorDrop => "GeneratorDrop",
+        TerminatorKind::FalseEdges { .. } => "FalseEdges",
+        TerminatorKind::FalseUnwind { .. } => "FalseUnwind",
+    }
+}
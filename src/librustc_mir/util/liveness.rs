@@ -59,6 +59,9 @@ pub struct LivenessResult {
     /// Live variables on exit to each basic block. This is equal to
     /// the union of the `ins` for each successor.
     pub outs: IndexVec<BasicBlock, LocalSet>,
+
+    /// Number of basic blocks processed before reaching the fixed point.
+    pub iterations: usize,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -135,7 +138,10 @@ pub fn liveness_of_locals<'tcx>(mir: &Mir<'tcx>, mode: LivenessMode) -> Liveness
 
     let predecessors = mir.predecessors();
 
+    let mut iterations = 0;
     while let Some(bb) = dirty_queue.pop() {
+        iterations += 1;
+
         // bits = use ∪ (bits - def)
         bits.overwrite(&outs[bb]);
         def_use[bb].apply(&mut bits);
@@ -153,7 +159,7 @@ pub fn liveness_of_locals<'tcx>(mir: &Mir<'tcx>, mode: LivenessMode) -> Liveness
         }
     }
 
-    LivenessResult { mode, outs }
+    LivenessResult { mode, outs, iterations }
 }
 
 impl LivenessResult {
@@ -278,6 +284,9 @@ pub fn categorize<'tcx>(context: PlaceContext<'tcx>, mode: LivenessMode) -> Opti
                 None
             }
         }
+
+        // Debuginfo only refers to the place, it neither reads nor writes it.
+        PlaceContext::VarDebugInfo => None,
     }
 }
 
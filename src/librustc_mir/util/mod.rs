@@ -15,11 +15,14 @@ pub mod patch;
 
 mod alignment;
 mod graphviz;
+mod json;
+mod spanview;
 pub(crate) mod pretty;
 pub mod liveness;
 pub mod collect_writes;
 
 pub use self::alignment::is_disaligned;
 pub use self::pretty::{dump_enabled, dump_mir, write_mir_pretty, PassWhere};
-pub use self::graphviz::{write_mir_graphviz};
+pub use self::graphviz::{write_mir_graphviz, write_mir_fn_graphviz_annotated};
 pub use self::graphviz::write_node_label as write_graphviz_node_label;
+pub use self::json::write_mir_json;
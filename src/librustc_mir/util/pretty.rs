@@ -21,6 +21,8 @@ use std::fs;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use super::graphviz::write_mir_fn_graphviz;
+use super::json::write_mir_json;
+use super::spanview::write_mir_fn_spanview;
 use transform::MirSource;
 
 const INDENT: &'static str = "    ";
@@ -73,6 +75,18 @@ pub enum PassWhere {
 ///   or `typeck` appears in the name.
 /// - `foo & nll | bar & typeck` == match if `foo` and `nll` both appear in the name
 ///   or `typeck` and `bar` both appear in the name.
+///
+/// A substring containing `*` or `?` is instead treated as a glob which must
+/// match the whole pass name or the whole item path; the item path is tried
+/// both with and without the crate name. `*` matches any sequence of
+/// characters (including `::`) and `?` matches any single character:
+///
+/// - `nll & my_crate::foo::*` == match the `nll` passes of everything inside
+///   `my_crate::foo`.
+/// - `SimplifyCfg-*` == match every instance of the `SimplifyCfg` pass.
+///
+/// Items matching `-Z dump-mir-exclude=<filter>`, which uses the same syntax,
+/// are never dumped.
 pub fn dump_mir<'a, 'gcx, 'tcx, F>(
     tcx: TyCtxt<'a, 'gcx, 'tcx>,
     pass_num: Option<&dyn Display>,
@@ -117,11 +131,76 @@ pub fn dump_enabled<'a, 'gcx, 'tcx>(
         // see notes on #41697 below
         tcx.item_path_str(source.def_id)
     });
-    filters.split("|").any(|or_filter| {
+    let crate_name = tcx.crate_name(source.def_id.krate);
+    let absolute_path = if node_path.starts_with(&format!("{}::", crate_name)) {
+        node_path.clone()
+    } else {
+        format!("{}::{}", crate_name, node_path)
+    };
+
+    let matches = |filters: &str| filters.split("|").any(|or_filter| {
         or_filter.split("&").all(|and_filter| {
-            and_filter == "all" || pass_name.contains(and_filter) || node_path.contains(and_filter)
+            filter_matches(and_filter.trim(), pass_name, &node_path, &absolute_path)
         })
-    })
+    });
+
+    if let Some(ref exclude) = tcx.sess.opts.debugging_opts.dump_mir_exclude {
+        if matches(exclude) {
+            return false;
+        }
+    }
+    matches(filters)
+}
+
+/// Checks a single `&`-separated term of a `-Z dump-mir` filter.
+fn filter_matches(filter: &str, pass_name: &str, node_path: &str, absolute_path: &str) -> bool {
+    if filter == "all" {
+        return true;
+    }
+
+    if filter.contains(|c: char| c == '*' || c == '?') {
+        glob_matches(filter.as_bytes(), pass_name.as_bytes())
+            || glob_matches(filter.as_bytes(), node_path.as_bytes())
+            || glob_matches(filter.as_bytes(), absolute_path.as_bytes())
+    } else {
+        pass_name.contains(filter) || node_path.contains(filter)
+    }
+}
+
+/// Matches `text` against a glob `pattern` in which `*` stands for any
+/// (possibly empty) sequence and `?` for any single byte.
+fn glob_matches(pattern: &[u8], text: &[u8]) -> bool {
+    // Position in `pattern`/`text` to backtrack to after the last `*`.
+    let mut backtrack = None;
+    let (mut p, mut t) = (0, 0);
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(&b'*') => {
+                backtrack = Some((p, t));
+                p += 1;
+                continue;
+            }
+            Some(&c) if c == b'?' || c == text[t] => {
+                p += 1;
+                t += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        match backtrack {
+            // Let the last `*` swallow one more byte and try again.
+            Some((star_p, star_t)) => {
+                backtrack = Some((star_p, star_t + 1));
+                p = star_p + 1;
+                t = star_t + 1;
+            }
+            None => return false,
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == b'*')
 }
 
 // #41697 -- we use `with_forced_impl_filename_line()` because
@@ -162,6 +241,25 @@ fn dump_matched_mir_node<'a, 'gcx, 'tcx, F>(
             write_mir_fn_graphviz(tcx, source.def_id, mir, &mut file)?;
         };
     }
+
+    if let Some(spanview) = tcx.sess.opts.debugging_opts.dump_mir_spanview {
+        // Promoted constants only cover a fragment of their parent's source.
+        if source.promoted.is_none() {
+            let _: io::Result<()> = do catch {
+                let mut file =
+                    create_dump_file(tcx, "html", pass_num, pass_name, disambiguator, source)?;
+                write_mir_fn_spanview(tcx, mir, spanview, node_path, &mut file)?;
+            };
+        }
+    }
+
+    if tcx.sess.opts.debugging_opts.dump_mir_json {
+        let _: io::Result<()> = do catch {
+            let mut file =
+                create_dump_file(tcx, "json", pass_num, pass_name, disambiguator, source)?;
+            write_mir_json(tcx, node_path, source, mir, &mut file)?;
+        };
+    }
 }
 
 /// Returns the path to the filename where we should dump a given MIR.
@@ -466,7 +564,13 @@ fn write_scope_tree(
     for &child in children {
         let data = &mir.source_scopes[child];
         assert_eq!(data.parent_scope, Some(parent));
-        writeln!(w, "{0:1$}scope {2} {{", "", indent, child.index())?;
+        match data.inlined {
+            Some((callee, _)) => {
+                writeln!(w, "{0:1$}scope {2} (inlined {3}) {{",
+                         "", indent, child.index(), callee)?;
+            }
+            None => writeln!(w, "{0:1$}scope {2} {{", "", indent, child.index())?,
+        }
 
         // User variable types (including the user's name in a comment).
         for local in mir.vars_iter() {
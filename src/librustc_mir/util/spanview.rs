@@ -0,0 +1,190 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Renders the source code of a MIR body as HTML in which the span of each
+//! statement, terminator or basic block (see `-Z dump-mir-spanview`) is
+//! highlighted. Hovering over a highlighted region shows the MIR it came
+//! from, which makes it easy to see which parts of the source a block or
+//! statement covers.
+
+use rustc::mir::*;
+use rustc::session::config::MirSpanview;
+use rustc::ty::TyCtxt;
+use std::cmp;
+use std::io::{self, Write};
+use syntax_pos::{BytePos, Span};
+
+const HEADER: &'static str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<style>
+.code {
+    white-space: pre-wrap;
+    font-family: monospace;
+}
+.region {
+    border-radius: 3px;
+    background-color: rgba(170, 200, 255, 0.25);
+}
+.region:hover {
+    background-color: rgba(170, 200, 255, 0.75);
+}
+</style>
+</head>
+<body>
+"#;
+
+const FOOTER: &'static str = r#"
+</body>
+</html>
+"#;
+
+/// A span of the source which is highlighted, and the MIR shown for it.
+struct SpanViewable {
+    span: Span,
+    title: String,
+    tooltip: String,
+}
+
+/// Writes an HTML page showing the source of `mir`, with spans highlighted
+/// at the granularity selected by `spanview`.
+pub fn write_mir_fn_spanview<'a, 'gcx, 'tcx, W: Write>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    mir: &Mir<'tcx>,
+    spanview: MirSpanview,
+    title: &str,
+    w: &mut W,
+) -> io::Result<()> {
+    let body_span = mir.span;
+    let mut viewables = vec![];
+
+    for (bb, data) in mir.basic_blocks().iter_enumerated() {
+        match spanview {
+            MirSpanview::Statement => {
+                for (i, statement) in data.statements.iter().enumerate() {
+                    viewables.push(SpanViewable {
+                        span: statement.source_info.span,
+                        title: format!("{:?}[{}]", bb, i),
+                        tooltip: format!("{:?}", statement),
+                    });
+                }
+                viewables.push(terminator_viewable(bb, data));
+            }
+            MirSpanview::Terminator => {
+                viewables.push(terminator_viewable(bb, data));
+            }
+            MirSpanview::Block => {
+                let span = data.statements.iter()
+                    .map(|statement| statement.source_info.span)
+                    .fold(data.terminator().source_info.span, |a, b| a.to(b));
+                let mut tooltip = String::new();
+                for statement in &data.statements {
+                    tooltip.push_str(&format!("{:?}\n", statement));
+                }
+                tooltip.push_str(&format!("{:?}", data.terminator().kind));
+                viewables.push(SpanViewable {
+                    span,
+                    title: format!("{:?}", bb),
+                    tooltip,
+                });
+            }
+        }
+    }
+
+    // Only spans from the same expansion context as the body can be mapped
+    // back to its source; leave everything else out.
+    viewables.retain(|v| {
+        !v.span.is_dummy() && v.span.ctxt() == body_span.ctxt() &&
+            body_span.contains(v.span)
+    });
+
+    // Order by start, outermost first, so that nested spans can be written
+    // as nested HTML elements.
+    viewables.sort_by(|a, b| {
+        a.span.lo().cmp(&b.span.lo()).then(b.span.hi().cmp(&a.span.hi()))
+    });
+
+    let source = match tcx.sess.codemap().span_to_snippet(body_span) {
+        Ok(source) => source,
+        Err(_) => return Ok(()),
+    };
+    let base = body_span.lo();
+
+    write!(w, "{}", HEADER)?;
+    writeln!(w, "<h3>{}</h3>", escape_html(title))?;
+    write!(w, r#"<div class="code">"#)?;
+
+    // The end positions of the currently open regions, innermost last.
+    let mut open: Vec<BytePos> = vec![];
+    let mut pos = base;
+    for viewable in &viewables {
+        let lo = cmp::max(viewable.span.lo(), pos);
+        // Close the regions which end before this one starts.
+        while open.last().map_or(false, |&hi| hi <= lo) {
+            let hi = open.pop().unwrap();
+            write_source(w, &source, base, pos, hi)?;
+            pos = hi;
+            write!(w, "</span>")?;
+        }
+        write_source(w, &source, base, pos, lo)?;
+        pos = lo;
+
+        // A region which partially overlaps the enclosing one is clipped to it.
+        let hi = match open.last() {
+            Some(&outer_hi) => cmp::min(viewable.span.hi(), outer_hi),
+            None => viewable.span.hi(),
+        };
+        if hi <= lo {
+            continue;
+        }
+        write!(w, r#"<span class="region" title="{}: {}">"#,
+               escape_html(&viewable.title), escape_html(&viewable.tooltip))?;
+        open.push(hi);
+    }
+    while let Some(hi) = open.pop() {
+        write_source(w, &source, base, pos, hi)?;
+        pos = hi;
+        write!(w, "</span>")?;
+    }
+    write_source(w, &source, base, pos, body_span.hi())?;
+
+    write!(w, "</div>")?;
+    write!(w, "{}", FOOTER)
+}
+
+fn terminator_viewable(bb: BasicBlock, data: &BasicBlockData) -> SpanViewable {
+    let terminator = data.terminator();
+    SpanViewable {
+        span: terminator.source_info.span,
+        title: format!("{:?}[{}]", bb, data.statements.len()),
+        tooltip: format!("{:?}", terminator.kind),
+    }
+}
+
+fn write_source<W: Write>(w: &mut W,
+                          source: &str,
+                          base: BytePos,
+                          from: BytePos,
+                          to: BytePos) -> io::Result<()> {
+    let from = (from - base).0 as usize;
+    let to = (to - base).0 as usize;
+    if from < to {
+        write!(w, "{}", escape_html(&source[from..to]))?;
+    }
+    Ok(())
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace("&", "&amp;")
+     .replace("<", "&lt;")
+     .replace(">", "&gt;")
+     .replace("\"", "&quot;")
+}
@@ -73,7 +73,7 @@ impl<'a, 'tcx> mir_visit::Visitor<'tcx> for StatCollector<'a, 'tcx> {
     }
 
     fn visit_source_scope_data(&mut self,
-                                   scope_data: &SourceScopeData) {
+                                   scope_data: &SourceScopeData<'tcx>) {
         self.record("SourceScopeData", scope_data);
         self.super_source_scope_data(scope_data);
     }
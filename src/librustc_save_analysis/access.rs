@@ -0,0 +1,96 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The accesses to local variables, as MIR borrowck records them in the
+//! `mir_borrowck_summary` of each body: every use of a variable is a read, a
+//! write, a move or a borrow, the way the compiler sees it.
+//!
+//! There is only something to report for the bodies MIR borrowck runs on,
+//! i.e. with `-Z borrowck=mir` or NLL. Upvars are used through the closure
+//! environment, so their uses inside a closure aren't attributed to them.
+
+use rustc::hir::{self, intravisit};
+use rustc::hir::intravisit::{NestedVisitorMap, Visitor};
+use rustc::mir;
+use rustc::util::nodemap::FxHashMap;
+
+use syntax::ast::NodeId;
+use syntax_pos::Span;
+
+use rls_data::{VariableAccess, VariableAccessKind};
+
+use {generated_code, id_from_node_id, SaveContext};
+
+/// Collects the accesses to local variables in all the bodies of the crate.
+pub fn variable_accesses(save_ctxt: &SaveContext) -> Vec<VariableAccess> {
+    let tcx = save_ctxt.tcx;
+    let mut accesses = vec![];
+
+    for &body_id in &tcx.hir.krate().body_ids {
+        let def_id = tcx.hir.body_owner_def_id(body_id);
+        let summary = match tcx.mir_borrowck_summary(def_id) {
+            Some(summary) => summary,
+            None => continue,
+        };
+
+        // Borrowck identifies a variable by the span of its declaration,
+        // which is the span of the binding that introduces it.
+        let mut collector = BindingCollector {
+            bindings: FxHashMap(),
+        };
+        intravisit::walk_body(&mut collector, tcx.hir.body(body_id));
+
+        for access in &summary.accesses {
+            let var_id = match collector.bindings.get(&access.var_span) {
+                Some(&var_id) => var_id,
+                None => continue,
+            };
+            if generated_code(access.span) {
+                continue;
+            }
+            accesses.push(VariableAccess {
+                kind: access_kind(access.kind),
+                span: save_ctxt.span_from_span(access.span),
+                ref_id: id_from_node_id(var_id, save_ctxt),
+            });
+        }
+    }
+
+    accesses
+}
+
+fn access_kind(kind: mir::VariableAccessKind) -> VariableAccessKind {
+    match kind {
+        mir::VariableAccessKind::Read => VariableAccessKind::Read,
+        mir::VariableAccessKind::Write => VariableAccessKind::Write,
+        mir::VariableAccessKind::Move => VariableAccessKind::Move,
+        mir::VariableAccessKind::SharedBorrow => VariableAccessKind::SharedBorrow,
+        mir::VariableAccessKind::MutBorrow => VariableAccessKind::MutBorrow,
+    }
+}
+
+/// Maps the span of each binding in a body to the binding's id. Nested bodies
+/// are not visited, their bindings belong to their own MIR.
+struct BindingCollector {
+    bindings: FxHashMap<Span, NodeId>,
+}
+
+impl<'v> Visitor<'v> for BindingCollector {
+    fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'v> {
+        NestedVisitorMap::None
+    }
+
+    fn visit_pat(&mut self, pat: &'v hir::Pat) {
+        if let hir::PatKind::Binding(_, id, ..) = pat.node {
+            self.bindings.insert(pat.span, id);
+        }
+        intravisit::walk_pat(self, pat);
+    }
+}
@@ -44,7 +44,7 @@ use syntax::ptr::P;
 use syntax::codemap::{Spanned, DUMMY_SP, respan};
 use syntax_pos::*;
 
-use {escape, generated_code, lower_attributes, PathCollector, SaveContext};
+use {access, escape, generated_code, lower_attributes, PathCollector, SaveContext};
 use json_dumper::{Access, DumpOutput, JsonDumper};
 use span_utils::SpanUtils;
 use sig;
@@ -169,6 +169,12 @@ impl<'l, 'tcx: 'l, 'll, O: DumpOutput + 'll> DumpVisitor<'l, 'tcx, 'll, O> {
         self.dumper.crate_prelude(data);
     }
 
+    pub fn dump_variable_accesses(&mut self) {
+        for access in access::variable_accesses(&self.save_ctxt) {
+            self.dumper.variable_access(access);
+        }
+    }
+
     // Return all non-empty prefixes of a path.
     // For each prefix, we return the span for the last segment in the prefix and
     // a str representation of the entire prefix.
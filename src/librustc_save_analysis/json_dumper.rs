@@ -13,7 +13,7 @@ use std::io::Write;
 use rustc_serialize::json::as_json;
 
 use rls_data::{self, Analysis, CratePreludeData, Def, DefKind, Import, MacroRef, Ref, RefKind,
-               Relation, Impl};
+               Relation, Impl, VariableAccess};
 use rls_data::config::Config;
 use rls_span::{Column, Row};
 
@@ -111,6 +111,13 @@ impl<'b, O: DumpOutput + 'b> JsonDumper<O> {
         self.result.refs.push(data);
     }
 
+    pub fn variable_access(&mut self, data: VariableAccess) {
+        if self.config.pub_only || self.config.reachable_only {
+            return;
+        }
+        self.result.variable_accesses.push(data);
+    }
+
     pub fn dump_def(&mut self, access: &Access, mut data: Def) {
         if !access.public && self.config.pub_only
             || !access.reachable && self.config.reachable_only {
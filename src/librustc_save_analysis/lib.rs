@@ -33,6 +33,7 @@ extern crate rls_data;
 extern crate rls_span;
 
 
+mod access;
 mod json_dumper;
 mod dump_visitor;
 #[macro_use]
@@ -1083,6 +1084,7 @@ impl<'a> SaveHandler for DumpHandler<'a> {
 
         visitor.dump_crate_info(cratename, krate);
         visit::walk_crate(&mut visitor, krate);
+        visitor.dump_variable_accesses();
     }
 }
 
@@ -1108,6 +1110,7 @@ impl<'b> SaveHandler for CallbackHandler<'b> {
 
         visitor.dump_crate_info(cratename, krate);
         visit::walk_crate(&mut visitor, krate);
+        visitor.dump_variable_accesses();
     }
 }
 
@@ -128,6 +128,7 @@ pub fn check_intrinsic_type<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
             "init" => (1, Vec::new(), param(0)),
             "uninit" => (1, Vec::new(), param(0)),
             "transmute" => (2, vec![ param(0) ], param(1)),
+            "forget" => (1, vec![ param(0) ], tcx.mk_nil()),
             "move_val_init" => {
                 (1,
                  vec![
@@ -288,7 +289,8 @@ pub fn check_intrinsic_type<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
             "unchecked_shl" | "unchecked_shr" =>
                 (1, vec![param(0), param(0)], param(0)),
 
-            "overflowing_add" | "overflowing_sub" | "overflowing_mul" =>
+            "overflowing_add" | "overflowing_sub" | "overflowing_mul" |
+            "wrapping_add" | "wrapping_sub" | "wrapping_mul" =>
                 (1, vec![param(0), param(0)], param(0)),
             "fadd_fast" | "fsub_fast" | "fmul_fast" | "fdiv_fast" | "frem_fast" =>
                 (1, vec![param(0), param(0)], param(0)),
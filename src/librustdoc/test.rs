@@ -89,7 +89,7 @@ pub fn run(input_path: &Path,
         let codemap = Lrc::new(CodeMap::new(sessopts.file_path_mapping()));
         let handler =
             errors::Handler::with_tty_emitter(ColorConfig::Auto,
-                                            true, false,
+                                            true, None,
                                             Some(codemap.clone()));
 
         let mut sess = session::build_session_(
@@ -268,7 +268,7 @@ fn run_test(test: &str, cratename: &str, filename: &FileName, line: usize,
         let _bomb = Bomb(data.clone(), old.unwrap_or(box io::stdout()));
 
         // Compile the code
-        let diagnostic_handler = errors::Handler::with_emitter(true, false, box emitter);
+        let diagnostic_handler = errors::Handler::with_emitter(true, None, box emitter);
 
         let mut sess = session::build_session_(
             sessopts, None, diagnostic_handler, codemap,
@@ -484,6 +484,10 @@ declare_features! (
 
     // #[alloc_error_handler]
     (active, alloc_error_handler, "1.29.0", Some(51540), None),
+
+    // Allows using a struct or tuple once each of its fields has been assigned,
+    // without it having been initialized as a whole
+    (active, partial_init, "1.29.0", None, None),
 );
 
 declare_features! (
@@ -888,6 +892,12 @@ pub const BUILTIN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeG
                                       is just used for rustc unit tests \
                                       and will never be stable",
                                      cfg_fn!(rustc_attrs))),
+    ("rustc_custom_mir", Whitelisted, Gated(Stability::Unstable,
+                                            "rustc_attrs",
+                                            "the `#[rustc_custom_mir]` attribute \
+                                             is just used for rustc unit tests \
+                                             and will never be stable",
+                                            cfg_fn!(rustc_attrs))),
     ("rustc_inherit_overflow_checks", Whitelisted, Gated(Stability::Unstable,
                                                          "rustc_attrs",
                                                          "the `#[rustc_inherit_overflow_checks]` \
@@ -857,6 +857,16 @@ LLVMRustDIBuilderCreateDebugLocation(LLVMContextRef ContextRef, unsigned Line,
   return wrap(MetadataAsValue::get(Context, debug_loc.getAsMDNode()));
 }
 
+extern "C" LLVMMetadataRef
+LLVMRustDIBuilderCreateLocation(unsigned Line, unsigned Column,
+                                LLVMMetadataRef Scope,
+                                LLVMMetadataRef InlinedAt) {
+  DebugLoc debug_loc = DebugLoc::get(Line, Column, unwrapDIPtr<MDNode>(Scope),
+                                     unwrapDIPtr<MDNode>(InlinedAt));
+
+  return wrap(debug_loc.getAsMDNode());
+}
+
 extern "C" int64_t LLVMRustDIBuilderCreateOpDeref() {
   return dwarf::DW_OP_deref;
 }
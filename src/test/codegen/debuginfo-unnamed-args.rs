@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -g -C no-prepopulate-passes
+
+// Check that arguments which are not bound to a single variable, because
+// they are matched by `_` or destructured, still get an unnamed debuginfo
+// entry at their position, next to the variables bound inside them.
+
+#![crate_type = "lib"]
+
+#[no_mangle]
+pub fn unnamed_args(_: u32, (a, b): (u32, u32)) -> u32 {
+    a + b
+}
+
+// CHECK-DAG: !DILocalVariable(arg: 1,
+// CHECK-DAG: !DILocalVariable(arg: 2,
+// CHECK-DAG: !DILocalVariable(name: "a",
+// CHECK-DAG: !DILocalVariable(name: "b",
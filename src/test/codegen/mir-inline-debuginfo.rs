@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that code inlined by the MIR inliner is described as inlined in debuginfo.
+
+// compile-flags: -g -C no-prepopulate-passes -Z mir-opt-level=2
+// ignore-tidy-linelength
+
+#![crate_type = "lib"]
+
+#[inline(always)]
+fn callee(x: u32) -> u32 {
+    x.wrapping_mul(3)
+}
+
+#[no_mangle]
+pub fn caller(x: u32) -> u32 {
+    callee(x)
+}
+
+// CHECK-DAG: ![[CALLEE:[0-9]+]] = distinct DISubprogram(name: "callee"
+// CHECK-DAG: !DILocation(line: {{[0-9]+}}, column: {{[0-9]+}}, scope: ![[CALLEE]], inlinedAt: !{{[0-9]+}})
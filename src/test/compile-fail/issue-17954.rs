@@ -18,10 +18,9 @@ static FOO: u8 = 3;
 
 fn main() {
     let a = &FOO;
-    //[mir]~^ ERROR `FOO` does not live long enough [E0597]
-    //[mir]~| does not live long enough
-    //[mir]~| NOTE borrowed value must be valid for the static lifetime
-    //[ast]~^^^^ ERROR borrowed value does not live long enough
+    //[mir]~^ ERROR thread-local variable borrowed past end of function [E0711]
+    //[mir]~| NOTE thread-local variables cannot be borrowed beyond the end of the function
+    //[ast]~^^^ ERROR borrowed value does not live long enough
     //[ast]~| does not live long enough
     //[ast]~| NOTE borrowed value must be valid for the static lifetime
 
@@ -29,5 +28,5 @@ fn main() {
         println!("{}", a);
     });
 }
-//[mir]~^ borrowed value only lives until here
+//[mir]~^ NOTE end of enclosing function is here
 //[ast]~^^ temporary value only lives until here
@@ -0,0 +1,61 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that user variables stay visible when MIR optimizations move them
+// around, e.g. copy propagation and inlining.
+
+// min-lldb-version: 310
+
+// compile-flags:-g -Zmir-opt-level=3
+
+// === GDB TESTS ===================================================================================
+
+// gdb-command:run
+
+// gdb-command:print b
+// gdb-check:$1 = 7
+// gdb-command:print c
+// gdb-check:$2 = 8
+// gdb-command:continue
+
+
+// === LLDB TESTS ==================================================================================
+
+// lldb-command:run
+
+// lldb-command:print b
+// lldb-check:[...]$0 = 7
+// lldb-command:print c
+// lldb-check:[...]$1 = 8
+// lldb-command:continue
+
+#![allow(unused_variables)]
+#![feature(omit_gdb_pretty_printer_section)]
+#![omit_gdb_pretty_printer_section]
+
+fn main() {
+    let a = 7u32;
+    let b = a;
+    let c = add(b, 1);
+
+    zzz(); // #break
+    sentinel(b, c);
+}
+
+#[inline]
+fn add(x: u32, y: u32) -> u32 {
+    x + y
+}
+
+#[inline(never)]
+fn zzz() {()}
+
+#[inline(never)]
+fn sentinel(_: u32, _: u32) {()}
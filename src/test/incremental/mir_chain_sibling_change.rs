@@ -0,0 +1,46 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Here we change the body of `changed::make` and check that every MIR query
+// of its unchanged sibling stays green, from `mir_built` up to
+// `optimized_mir`, so that the codegen unit of the sibling is reused.
+
+// revisions:rpass1 rpass2
+// compile-flags: -Z query-dep-graph
+
+#![feature(rustc_attrs)]
+
+#![rustc_partition_codegened(module="mir_chain_sibling_change-changed", cfg="rpass2")]
+#![rustc_partition_reused(module="mir_chain_sibling_change-unchanged", cfg="rpass2")]
+
+mod changed {
+    #[cfg(rpass1)]
+    pub fn make() -> u32 {
+        22
+    }
+
+    #[cfg(rpass2)]
+    #[rustc_dirty(label="MirBuilt,MirConst,MirValidated,MirOptimized", cfg="rpass2")]
+    pub fn make() -> u32 {
+        11 + 11
+    }
+}
+
+mod unchanged {
+    #[rustc_clean(label="MirBuilt,MirConst,MirValidated,MirOptimized", cfg="rpass2")]
+    pub fn make() -> u32 {
+        let x = 20;
+        x + 2
+    }
+}
+
+pub fn main() {
+    assert_eq!(changed::make(), unchanged::make());
+}
@@ -0,0 +1,38 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that bounds checks that are known to succeed are removed.
+
+fn constant(a: [u32; 4]) -> u32 {
+    a[2]
+}
+
+fn twice(a: &[u32], i: usize) -> u32 {
+    a[i] + a[i]
+}
+
+fn main() {
+    assert_eq!(constant([1, 2, 3, 4]), 3);
+    assert_eq!(twice(&[1, 2, 3], 1), 4);
+}
+
+// END RUST SOURCE
+// START rustc.constant.BoundsCheckElimination.after.mir
+// bb0: {
+//     ...
+//     goto -> bb1;
+// }
+// END rustc.constant.BoundsCheckElimination.after.mir
+// START rustc.twice.BoundsCheckElimination.after.mir
+// bb1: {
+//     ...
+//     goto -> bb2;
+// }
+// END rustc.twice.BoundsCheckElimination.after.mir
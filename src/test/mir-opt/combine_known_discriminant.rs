@@ -0,0 +1,32 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn is_some(x: i32) -> bool {
+    match Some(x) {
+        Some(_) => true,
+        None => false,
+    }
+}
+
+fn main() {
+    assert!(is_some(1));
+}
+
+// END RUST SOURCE
+// START rustc.is_some.InstCombine.before.mir
+//     _2 = std::option::Option<i32>::Some(move _3,);
+//     ...
+//     _4 = discriminant(_2);
+// END rustc.is_some.InstCombine.before.mir
+// START rustc.is_some.InstCombine.after.mir
+//     _2 = std::option::Option<i32>::Some(move _3,);
+//     ...
+//     _4 = const 1isize;
+// END rustc.is_some.InstCombine.after.mir
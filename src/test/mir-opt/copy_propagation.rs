@@ -13,9 +13,15 @@ fn test(x: u32) -> u32 {
     y
 }
 
+fn shared(x: u32) -> (u32, u32) {
+    // `x` is used twice, but not changed before the temporaries are used
+    (x, x)
+}
+
 fn main() {
     // Make sure the function actually gets instantiated.
     test(0);
+    shared(0);
 }
 
 // END RUST SOURCE
@@ -37,3 +43,22 @@ fn main() {
 //      return;
 //  }
 // END rustc.test.CopyPropagation.after.mir
+// START rustc.shared.CopyPropagation.before.mir
+//  bb0: {
+//      ...
+//      _2 = _1;
+//      ...
+//      _3 = _1;
+//      _0 = (move _2, move _3);
+//      ...
+//      return;
+//  }
+// END rustc.shared.CopyPropagation.before.mir
+// START rustc.shared.CopyPropagation.after.mir
+//  bb0: {
+//      ...
+//      _0 = (_1, _1);
+//      ...
+//      return;
+//  }
+// END rustc.shared.CopyPropagation.after.mir
@@ -60,8 +60,7 @@ fn main() {
 // START rustc.foo.CopyPropagation.after.mir
 // bb0: {
 //     ...
-//     _3 = _1;
-//     _2 = const dummy(move _3) -> bb1;
+//     _2 = const dummy(_1) -> bb1;
 // }
 // bb1: {
 //     ...
@@ -85,8 +84,7 @@ fn main() {
 // START rustc.bar.CopyPropagation.after.mir
 // bb0: {
 //     ...
-//     _3 = _1;
-//     _2 = const dummy(move _3) -> bb1;
+//     _2 = const dummy(_1) -> bb1;
 // }
 // bb1: {
 //     ...
@@ -107,9 +105,10 @@ fn main() {
 // END rustc.baz.CopyPropagation.before.mir
 // START rustc.baz.CopyPropagation.after.mir
 // bb0: {
-//     ...
-//     _2 = _1;
-//     _1 = move _2;
+//     nop;
+//     nop;
+//     nop;
+//     nop;
 //     ...
 //     return;
 // }
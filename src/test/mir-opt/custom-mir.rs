@@ -0,0 +1,73 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that `#[rustc_custom_mir]` builds exactly the MIR it is given.
+
+#![feature(rustc_attrs)]
+
+#[rustc_custom_mir = "
+    let mut _0: i32;
+    let mut _3: bool;
+
+    bb0: {
+        StorageLive(_3);
+        _3 = Lt(_1, _2);
+        switchInt(move _3) -> [false: bb1, otherwise: bb2];
+    }
+
+    bb1: {
+        _0 = _1;
+        goto -> bb3;
+    }
+
+    bb2: {
+        _0 = _2;
+        goto -> bb3;
+    }
+
+    bb3: {
+        StorageDead(_3);
+        return;
+    }
+"]
+fn max(_a: i32, _b: i32) -> i32 {
+    loop {}
+}
+
+fn main() {
+    assert_eq!(max(1, 2), 2);
+    assert_eq!(max(-3, -4), -3);
+}
+
+// END RUST SOURCE
+// START rustc.max.mir_map.0.mir
+// fn max(_1: i32, _2: i32) -> i32{
+//     let mut _0: i32;
+//     let mut _3: bool;
+//
+//     bb0: {
+//         StorageLive(_3);
+//         _3 = Lt(_1, _2);
+//         switchInt(move _3) -> [false: bb1, otherwise: bb2];
+//     }
+//     bb1: {
+//         _0 = _1;
+//         goto -> bb3;
+//     }
+//     bb2: {
+//         _0 = _2;
+//         goto -> bb3;
+//     }
+//     bb3: {
+//         StorageDead(_3);
+//         return;
+//     }
+// }
+// END rustc.max.mir_map.0.mir
@@ -0,0 +1,46 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that assignments to locals that are never read again are removed.
+
+fn unused(x: u32) -> u32 {
+    let _y = !x;
+    x
+}
+
+fn main() {
+    unused(0);
+}
+
+// END RUST SOURCE
+// START rustc.unused.DeadStoreElimination.before.mir
+// bb0: {
+//     StorageLive(_2);
+//     nop;
+//     nop;
+//     _2 = Not(_1);
+//     nop;
+//     _0 = _1;
+//     ...
+//     return;
+// }
+// END rustc.unused.DeadStoreElimination.before.mir
+// START rustc.unused.DeadStoreElimination.after.mir
+// bb0: {
+//     StorageLive(_2);
+//     nop;
+//     nop;
+//     nop;
+//     nop;
+//     _0 = _1;
+//     ...
+//     return;
+// }
+// END rustc.unused.DeadStoreElimination.after.mir
@@ -0,0 +1,51 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that jumps to a block identical to an earlier one are redirected to
+// that block.
+
+#![feature(rustc_attrs)]
+
+#[rustc_custom_mir = "
+    let mut _0: u32;
+
+    bb0: {
+        switchInt(_1) -> [false: bb1, otherwise: bb2];
+    }
+
+    bb1: {
+        _0 = const 1u32;
+        return;
+    }
+
+    bb2: {
+        _0 = const 1u32;
+        return;
+    }
+"]
+fn one(_b: bool) -> u32 {
+    loop {}
+}
+
+fn main() {
+    assert_eq!(one(true), 1);
+}
+
+// END RUST SOURCE
+// START rustc.one.DeduplicateBlocks.before.mir
+// bb0: {
+//     switchInt(_1) -> [false: bb1, otherwise: bb2];
+// }
+// END rustc.one.DeduplicateBlocks.before.mir
+// START rustc.one.DeduplicateBlocks.after.mir
+// bb0: {
+//     switchInt(_1) -> [false: bb1, otherwise: bb1];
+// }
+// END rustc.one.DeduplicateBlocks.after.mir
@@ -0,0 +1,38 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that matching on a pair of options compares their discriminants once
+// instead of switching on the second one in every arm of the first.
+
+fn opt(x: Option<u32>, y: Option<u32>) -> u32 {
+    match (x, y) {
+        (Some(_), Some(_)) => 0,
+        (None, None) => 1,
+        _ => 2,
+    }
+}
+
+fn main() {
+    opt(None, Some(0));
+}
+
+// END RUST SOURCE
+// START rustc.opt.EarlyOtherwiseBranch.after.mir
+// bb0: {
+//     ...
+//     _6 = discriminant((_3.0: std::option::Option<u32>));
+//     StorageLive(_9);
+//     _9 = discriminant((_3.1: std::option::Option<u32>));
+//     StorageLive(_10);
+//     _10 = Ne(_6, move _9);
+//     StorageDead(_9);
+//     ...
+// }
+// END rustc.opt.EarlyOtherwiseBranch.after.mir
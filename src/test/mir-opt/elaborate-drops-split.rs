@@ -0,0 +1,63 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-wasm32-bare compiled with panic=abort by default
+
+// Check that a drop whose place is moved out of on some paths is split into
+// a static drop on the other paths, instead of using a drop flag.
+
+fn maybe_drop(c: bool) {
+    let x = S;
+    if c {
+        drop(x);
+    }
+}
+
+struct S;
+impl Drop for S {
+    fn drop(&mut self) {
+    }
+}
+
+fn main() {
+    maybe_drop(false);
+    maybe_drop(true);
+}
+
+// END RUST SOURCE
+// START rustc.maybe_drop.ElaborateDrops.after.mir
+//     ...
+//     let mut _5: S;
+//     bb0: {
+//     ...
+//     bb2: {
+//         _0 = ();
+//         goto -> bb10;
+//     }
+//     ...
+//     bb7: {
+//         StorageDead(_5);
+//         _0 = ();
+//         goto -> bb11;
+//     }
+//     bb8: {
+//         StorageDead(_3);
+//         goto -> bb9;
+//     }
+//     ...
+//     bb10: {
+//         StorageDead(_3);
+//         drop(_2) -> [return: bb9, unwind: bb4];
+//     }
+//     bb11: {
+//         StorageDead(_3);
+//         goto -> bb9;
+//     }
+// END rustc.maybe_drop.ElaborateDrops.after.mir
@@ -0,0 +1,31 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z inline-mir-hint-threshold=0
+
+// Check that `-Z inline-mir-hint-threshold` keeps the inliner from inlining
+// `#[inline]` callees that cost more than the threshold.
+
+fn main() {
+    foo(2);
+}
+
+#[inline]
+fn foo(x: u32) -> u32 {
+    x * 3 + 1
+}
+
+// END RUST SOURCE
+// START rustc.main.PreCodegen.after.mir
+// bb0: {
+//     ...
+//     _1 = const foo(const 2u32) -> bb1;
+// }
+// END rustc.main.PreCodegen.after.mir
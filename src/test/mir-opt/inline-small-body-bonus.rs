@@ -0,0 +1,39 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z inline-mir-hint-threshold=8
+
+// Check that the inliner gives callees with few blocks and no loops a bonus
+// of a quarter of the threshold, based on their `mir_body_metrics`: `id`
+// costs 10 and is inlined because the threshold of 8 is raised to 10, while
+// `spin` contains a loop and keeps the threshold of 8.
+
+fn main() {
+    let x = id(2);
+    spin(x == 2);
+}
+
+#[inline]
+fn id(x: u32) -> u32 {
+    x
+}
+
+#[inline]
+fn spin(stop: bool) {
+    while !stop {}
+}
+
+// END RUST SOURCE
+// START rustc.main.Inline.after.mir
+// bb0: {
+//     ...
+//     _2 = const spin(move _3) -> bb1;
+// }
+// END rustc.main.Inline.after.mir
@@ -0,0 +1,44 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that matching on an enum right after constructing it jumps straight
+// to the matching arm.
+
+fn thread(b: bool) -> u32 {
+    let o = if b { Some(1u32) } else { None };
+    match o {
+        Some(y) => y,
+        None => 0,
+    }
+}
+
+fn main() {
+    thread(true);
+}
+
+// END RUST SOURCE
+// START rustc.thread.JumpThreading.before.mir
+//     ...
+//     discriminant(_2) = 1;
+//     goto -> bb3;
+// }
+// ...
+// bb3: {
+//     _4 = discriminant(_2);
+//     switchInt(move _4) -> [0isize: bb4, 1isize: bb6, otherwise: bb5];
+// }
+// END rustc.thread.JumpThreading.before.mir
+// START rustc.thread.JumpThreading.after.mir
+//     ...
+//     discriminant(_2) = 1;
+//     _4 = discriminant(_2);
+//     goto -> bb6;
+// }
+// END rustc.thread.JumpThreading.after.mir
@@ -0,0 +1,85 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that calls to simple intrinsics are replaced by MIR statements.
+
+#![feature(core_intrinsics)]
+#![deny(const_err)]
+
+use std::intrinsics;
+
+fn size() -> usize {
+    unsafe { intrinsics::size_of::<u32>() }
+}
+
+fn overflowing(a: i32, b: i32) -> i32 {
+    unsafe { intrinsics::overflowing_add(a, b) }
+}
+
+fn wrapping(a: u32, b: u32) -> u32 {
+    unsafe { intrinsics::wrapping_sub(a, b) }
+}
+
+struct Token;
+
+fn forget_token(token: Token) {
+    unsafe { intrinsics::forget(token) }
+}
+
+// The lowered addition overflows, which must not be reported by const propagation.
+fn overflowing_const() -> u8 {
+    unsafe { intrinsics::overflowing_add(255u8, 1) }
+}
+
+fn main() {
+    assert_eq!(size(), 4);
+    assert_eq!(overflowing(i32::max_value(), 1), i32::min_value());
+    assert_eq!(overflowing_const(), 0);
+    assert_eq!(wrapping(0, 1), u32::max_value());
+    forget_token(Token);
+}
+
+// END RUST SOURCE
+// START rustc.size.LowerIntrinsics.before.mir
+//     _0 = const std::intrinsics::size_of::<u32>() -> bb1;
+// END rustc.size.LowerIntrinsics.before.mir
+// START rustc.size.LowerIntrinsics.after.mir
+//     _0 = SizeOf(u32);
+//     goto -> bb1;
+// END rustc.size.LowerIntrinsics.after.mir
+// START rustc.overflowing.LowerIntrinsics.after.mir
+//     StorageLive(_5);
+//     _5 = CheckedAdd(move _3, move _4);
+//     _0 = move (_5.0: i32);
+//     StorageDead(_5);
+//     goto -> bb1;
+// END rustc.overflowing.LowerIntrinsics.after.mir
+// START rustc.wrapping.LowerIntrinsics.after.mir
+//     StorageLive(_5);
+//     _5 = CheckedSub(move _3, move _4);
+//     _0 = move (_5.0: u32);
+//     StorageDead(_5);
+//     goto -> bb1;
+// END rustc.wrapping.LowerIntrinsics.after.mir
+// START rustc.forget_token.LowerIntrinsics.before.mir
+//     _2 = move _1;
+//     _0 = const std::intrinsics::forget::<Token>(move _2) -> bb1;
+// END rustc.forget_token.LowerIntrinsics.before.mir
+// START rustc.forget_token.LowerIntrinsics.after.mir
+//     _2 = move _1;
+//     goto -> bb1;
+// END rustc.forget_token.LowerIntrinsics.after.mir
+// START rustc.overflowing_const.LowerIntrinsics.after.mir
+//     StorageLive(_1);
+//     _1 = CheckedAdd(const 255u8, const 1u8);
+//     _0 = move (_1.0: u8);
+//     StorageDead(_1);
+//     goto -> bb1;
+// END rustc.overflowing_const.LowerIntrinsics.after.mir
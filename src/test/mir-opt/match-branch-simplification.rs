@@ -0,0 +1,55 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a switch which only picks between `true` and `false` becomes a
+// comparison.
+
+enum E {
+    A,
+    B,
+}
+
+fn is_a(e: E) -> bool {
+    match e {
+        E::A => true,
+        _ => false,
+    }
+}
+
+fn main() {
+    is_a(E::A);
+    is_a(E::B);
+}
+
+// END RUST SOURCE
+// START rustc.is_a.MatchBranchSimplification.before.mir
+// bb0: {
+//     _2 = discriminant(_1);
+//     switchInt(move _2) -> [0isize: bb2, otherwise: bb1];
+// }
+// bb1: {
+//     _0 = const false;
+//     goto -> bb3;
+// }
+// bb2: {
+//     _0 = const true;
+//     goto -> bb3;
+// }
+// END rustc.is_a.MatchBranchSimplification.before.mir
+// START rustc.is_a.MatchBranchSimplification.after.mir
+// bb0: {
+//     _2 = discriminant(_1);
+//     StorageLive(_3);
+//     _3 = move _2;
+//     _0 = Eq(_3, const 0isize);
+//     StorageDead(_3);
+//     goto -> bb3;
+// }
+// END rustc.is_a.MatchBranchSimplification.after.mir
@@ -0,0 +1,30 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z mir-enable-passes=+Inline,-Inline
+
+// Check that a pass disabled with `-Z mir-enable-passes` does not run, even
+// though the MIR optimization level would enable it. The last mention of a
+// pass wins.
+
+fn main() {
+    foo();
+}
+
+#[inline]
+fn foo() {}
+
+// END RUST SOURCE
+// START rustc.main.PreCodegen.after.mir
+// bb0: {
+//     ...
+//     _1 = const foo() -> bb1;
+// }
+// END rustc.main.PreCodegen.after.mir
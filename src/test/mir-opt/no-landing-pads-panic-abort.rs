@@ -0,0 +1,44 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -C panic=abort
+
+// Check that calls and drops have no unwind edges, and that no cleanup
+// blocks are left, when the panic strategy is abort.
+
+fn main() {
+    let _s = S;
+    foo();
+}
+
+fn foo() {}
+
+struct S;
+impl Drop for S {
+    fn drop(&mut self) {
+    }
+}
+
+// END RUST SOURCE
+// START rustc.main.SimplifyCfg-elaborate-drops.after.mir
+//     bb0: {
+//         ...
+//         _2 = const foo() -> bb1;
+//     }
+//     bb1: {
+//         ...
+//         drop(_1) -> bb2;
+//     }
+//     bb2: {
+//         ...
+//         return;
+//     }
+// }
+// END rustc.main.SimplifyCfg-elaborate-drops.after.mir
@@ -0,0 +1,31 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z fuel=foo=0
+
+// Without fuel, the optional MIR optimizations leave the MIR alone.
+
+#![crate_name="foo"]
+
+fn not(x: bool) -> bool {
+    x == false
+}
+
+fn main() {
+    assert!(not(false));
+}
+
+// END RUST SOURCE
+// START rustc.not.InstCombine.before.mir
+//     _0 = Eq(move _2, const false);
+// END rustc.not.InstCombine.before.mir
+// START rustc.not.InstCombine.after.mir
+//     _0 = Eq(move _2, const false);
+// END rustc.not.InstCombine.after.mir
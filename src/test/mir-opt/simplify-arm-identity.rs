@@ -0,0 +1,39 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a match arm rebuilding the variant it took apart becomes a move
+// of the whole value.
+
+fn id(r: Result<u32, i32>) -> Result<u32, i32> {
+    match r {
+        Ok(x) => Ok(x),
+        Err(e) => Err(e),
+    }
+}
+
+fn main() {
+    id(Ok(1));
+}
+
+// END RUST SOURCE
+// START rustc.id.SimplifyArmIdentity.before.mir
+//     ...
+//     _4 = ((_1 as Err).0: i32);
+//     ((_0 as Err).0: i32) = move _4;
+//     discriminant(_0) = 1;
+//     ...
+// END rustc.id.SimplifyArmIdentity.before.mir
+// START rustc.id.SimplifyArmIdentity.after.mir
+//     ...
+//     _0 = _1;
+//     nop;
+//     nop;
+//     ...
+// END rustc.id.SimplifyArmIdentity.after.mir
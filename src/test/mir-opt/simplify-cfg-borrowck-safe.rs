@@ -0,0 +1,58 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z borrowck=mir
+
+// Check that the simplification right before borrowck keeps the false edges
+// of a match with a guard, and doesn't merge blocks into them.
+
+fn guard() -> bool {
+    false
+}
+
+fn full_tested_match() {
+    let _ = match Some(42) {
+        Some(x) if guard() => (1, x),
+        Some(y) => (2, y),
+        None => (3, 3),
+    };
+}
+
+fn main() {
+    full_tested_match();
+}
+
+// END RUST SOURCE
+// START rustc.full_tested_match.SimplifyCfg-qualify-consts.after.mir
+//  bb3: {
+//      falseEdges -> [real: bb8, imaginary: bb4];
+//  }
+//  bb4: {
+//      falseEdges -> [real: bb12, imaginary: bb5];
+//  }
+//  bb5: {
+//      falseEdges -> [real: bb2, imaginary: bb6];
+//  }
+//  bb6: {
+//      unreachable;
+//  }
+//  bb7: {
+//      unreachable;
+//  }
+//  ...
+//  bb9: {
+//      ReadForMatch(_8);
+//      switchInt(move _10) -> [false: bb10, otherwise: bb11];
+//  }
+//  bb10: {
+//      falseEdges -> [real: bb4, imaginary: bb4];
+//  }
+//  ...
+// END rustc.full_tested_match.SimplifyCfg-qualify-consts.after.mir
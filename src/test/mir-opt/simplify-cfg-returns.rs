@@ -0,0 +1,46 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that SimplifyCfg merges empty `return` blocks, and turns jumps to
+// them into returns.
+
+#![feature(rustc_attrs)]
+
+#[rustc_custom_mir = "
+    let mut _0: u32;
+
+    bb0: {
+        _0 = const 0u32;
+        switchInt(_1) -> [false: bb1, otherwise: bb2];
+    }
+
+    bb1: {
+        return;
+    }
+
+    bb2: {
+        return;
+    }
+"]
+fn zero(_b: bool) -> u32 {
+    loop {}
+}
+
+fn main() {
+    assert_eq!(zero(true), 0);
+}
+
+// END RUST SOURCE
+// START rustc.zero.SimplifyCfg-initial.after.mir
+// bb0: {
+//     _0 = const 0u32;
+//     return;
+// }
+// END rustc.zero.SimplifyCfg-initial.after.mir
@@ -0,0 +1,48 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a tuple whose fields are only accessed individually is split into
+// one local per field.
+
+fn pair(x: u32) -> u32 {
+    let p = (x, 5u32);
+    p.1
+}
+
+fn main() {
+    pair(0);
+}
+
+// END RUST SOURCE
+// START rustc.pair.ScalarReplacementOfAggregates.before.mir
+// bb0: {
+//     StorageLive(_2);
+//     ...
+//     (_2.0: u32) = move _3;
+//     (_2.1: u32) = const 5u32;
+//     ...
+//     _0 = (_2.1: u32);
+//     ...
+//     return;
+// }
+// END rustc.pair.ScalarReplacementOfAggregates.before.mir
+// START rustc.pair.ScalarReplacementOfAggregates.after.mir
+// bb0: {
+//     StorageLive(_4);
+//     StorageLive(_5);
+//     ...
+//     _4 = move _3;
+//     _5 = const 5u32;
+//     ...
+//     _0 = _5;
+//     ...
+//     return;
+// }
+// END rustc.pair.ScalarReplacementOfAggregates.after.mir
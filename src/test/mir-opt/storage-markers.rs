@@ -0,0 +1,63 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that storage markers are moved next to the uses of their local, and
+// removed for locals that are live during the whole body anyway.
+
+#![feature(rustc_attrs)]
+
+#[rustc_custom_mir = "
+    let mut _0: i32;
+    let mut _2: i32;
+    let mut _3: i32;
+
+    bb0: {
+        StorageLive(_2);
+        StorageLive(_3);
+        _2 = Add(_1, const 1i32);
+        _3 = Mul(_2, const 2i32);
+        _0 = Sub(_3, _1);
+        _2 = Mul(_2, _1);
+        StorageDead(_3);
+        goto -> bb1;
+    }
+
+    bb1: {
+        _0 = Add(_0, _2);
+        StorageDead(_2);
+        return;
+    }
+"]
+fn compute(_x: i32) -> i32 {
+    loop {}
+}
+
+fn main() {
+    assert_eq!(compute(1), 5);
+}
+
+// END RUST SOURCE
+// START rustc.compute.OptimizeStorageMarkers.after.mir
+// bb0: {
+//     nop;
+//     _2 = Add(_1, const 1i32);
+//     StorageLive(_3);
+//     _3 = Mul(_2, const 2i32);
+//     _0 = Sub(_3, _1);
+//     StorageDead(_3);
+//     _2 = Mul(_2, _1);
+//     goto -> bb1;
+// }
+// bb1: {
+//     _0 = Add(_0, _2);
+//     nop;
+//     return;
+// }
+// END rustc.compute.OptimizeStorageMarkers.after.mir
@@ -0,0 +1,47 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a match on an uninhabited type becomes `unreachable`, along with
+// the blocks only leading there.
+
+enum Empty {}
+
+fn empty() -> Option<Empty> {
+    None
+}
+
+fn main() {
+    if let Some(_x) = empty() {
+        let mut _y;
+        if true {
+            _y = 1;
+        } else {
+            _y = 2;
+        }
+        match _x {}
+    }
+}
+
+// END RUST SOURCE
+// START rustc.main.UnreachablePropagation.after.mir
+// bb0: {
+//     ...
+//     _1 = const empty() -> bb1;
+// }
+// bb1: {
+//     _2 = discriminant(_1);
+//     switchInt(move _2) -> [1isize: bb2, otherwise: bb3];
+// }
+// bb2: {
+//     ...
+//     unreachable;
+// }
+// ...
+// END rustc.main.UnreachablePropagation.after.mir
@@ -0,0 +1,139 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(rustc_private)]
+
+extern crate rustc;
+extern crate rustc_driver;
+extern crate rustc_errors;
+extern crate rustc_lint;
+extern crate rustc_metadata;
+extern crate syntax;
+extern crate syntax_pos;
+
+use rustc::mir::{BorrowKind, BorrowckSummary};
+use rustc::session::build_session;
+use rustc::session::config::{basic_options, BorrowckMode, CrateType, Input};
+use rustc::session::config::{OutputType, OutputTypes};
+use rustc_driver::Compilation;
+use rustc_driver::driver::{self, compile_input, CompileController};
+use rustc_errors::registry::Registry;
+use rustc_metadata::cstore::CStore;
+use syntax::codemap::FileName;
+use syntax_pos::Span;
+
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+const SRC: &str = r#"
+pub fn f() {
+    let mut v = vec![1];
+    let r = &v;
+    let n = r.len();
+    v.push(n);
+    let w = v;
+    drop(w);
+}
+"#;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 3 {
+        panic!("expected the output directory and the rustc path");
+    }
+
+    let out_dir = PathBuf::from(&args[1]);
+    let mut sysroot = PathBuf::from(&args[2]);
+    sysroot.pop();
+    sysroot.pop();
+
+    syntax::with_globals(|| {
+        let mut opts = basic_options();
+        opts.crate_types = vec![CrateType::CrateTypeRlib];
+        opts.output_types = OutputTypes::new(&[(OutputType::Metadata, None)]);
+        opts.maybe_sysroot = Some(sysroot);
+        opts.borrowck_mode = BorrowckMode::Mir;
+        opts.debugging_opts.borrowck_summary = true;
+
+        driver::spawn_thread_pool(opts, |opts| {
+            let descriptions = Registry::new(&rustc::DIAGNOSTICS);
+            let sess = build_session(opts, None, descriptions);
+            let codegen_backend = rustc_driver::get_codegen_backend(&sess);
+            let cstore = Rc::new(CStore::new(codegen_backend.metadata_loader()));
+            rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
+
+            // Snippets of everything the summary of `f` points at.
+            let found = RefCell::new(None);
+            {
+                let mut control = CompileController::basic();
+                control.after_analysis.stop = Compilation::Stop;
+                control.after_analysis.callback = Box::new(|state| {
+                    let tcx = state.tcx.unwrap();
+                    let def_id = tcx.body_owners().next().unwrap();
+                    let summary = tcx.mir_borrowck_summary(def_id)
+                        .expect("no summary under -Z borrowck-summary");
+                    // Spans from the expansion of `vec!` have no snippet.
+                    let snippet = |span: Span| {
+                        tcx.sess.codemap().span_to_snippet(span).unwrap_or_default()
+                    };
+                    *found.borrow_mut() = Some(Found::new(&summary, snippet));
+                });
+                let input = Input::Str { name: FileName::Anon, input: SRC.to_string() };
+                let _ = compile_input(codegen_backend, &sess, &cstore, &None, &input,
+                                      &Some(out_dir), &None, None, &control);
+            }
+
+            check(found.into_inner().expect("analysis callback did not run"));
+        });
+    });
+}
+
+struct Found {
+    /// Kind, borrowed place and snippets of the spans the borrow is live at.
+    borrows: Vec<(BorrowKind, String, Option<String>, Vec<String>)>,
+    moves: Vec<String>,
+    used_mut_locals: Vec<String>,
+}
+
+impl Found {
+    fn new<F: Fn(Span) -> String>(summary: &BorrowckSummary, snippet: F) -> Found {
+        Found {
+            borrows: summary.borrows.iter().map(|borrow| {
+                (borrow.kind,
+                 snippet(borrow.span),
+                 borrow.borrowed_place.clone(),
+                 borrow.live_spans.iter().map(|&span| snippet(span)).collect())
+            }).collect(),
+            moves: summary.moves.iter().map(|&span| snippet(span)).collect(),
+            used_mut_locals: summary.used_mut_locals.iter().map(|&span| snippet(span)).collect(),
+        }
+    }
+}
+
+fn check(found: Found) {
+    let shared = found.borrows.iter()
+        .find(|&&(kind, ref span, _, _)| kind == BorrowKind::Shared && span == "&v")
+        .expect("no shared borrow of `v`");
+    assert_eq!(shared.2, Some("v".to_string()));
+    // The borrow ends at its last use through `r`, before `v` is mutated.
+    assert!(shared.3.iter().any(|span| span == "r.len()"), "{:?}", shared.3);
+    assert!(!shared.3.iter().any(|span| span == "v.push(n)"), "{:?}", shared.3);
+
+    assert!(found.borrows.iter().any(|&(kind, _, ref place, _)| {
+        match kind {
+            BorrowKind::Mut { .. } => place.as_ref().map(|p| &p[..]) == Some("v"),
+            _ => false,
+        }
+    }), "no mutable borrow of `v`");
+
+    assert!(found.moves.iter().any(|span| span == "v"), "{:?}", found.moves);
+    assert_eq!(found.used_mut_locals, vec!["mut v".to_string()]);
+}
@@ -0,0 +1,40 @@
+#!/usr/bin/env python
+
+# Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+# file at the top-level directory of this distribution and at
+# http://rust-lang.org/COPYRIGHT.
+#
+# Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+# http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+# <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+# option. This file may not be copied, modified, or distributed
+# except according to those terms.
+
+import re
+import sys
+
+with open(sys.argv[1]) as f:
+    lines = f.read().splitlines()
+
+assert re.match(r'^digraph Mir_\d+ \{$', lines[0]), lines[0]
+assert lines[-1] == '}', lines[-1]
+
+nodes = set()
+edges = []
+for line in lines[1:-1]:
+    node = re.match(r'^    (bb\d+) \[shape="none", label=<.*>\];$', line)
+    if node:
+        nodes.add(node.group(1))
+        continue
+    edge = re.match(r'^    (bb\d+) -> (bb\d+) \[label="[^"]*"\];$', line)
+    if edge:
+        edges.append((edge.group(1), edge.group(2)))
+
+assert 'bb0' in nodes
+assert edges
+for source, target in edges:
+    assert source in nodes and target in nodes, (source, target)
+
+text = '\n'.join(lines)
+assert 'in scope: bw0 = ' in text
+assert 'maybe init: ' in text
@@ -0,0 +1,36 @@
+#!/usr/bin/env python
+
+# Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+# file at the top-level directory of this distribution and at
+# http://rust-lang.org/COPYRIGHT.
+#
+# Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+# http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+# <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+# option. This file may not be copied, modified, or distributed
+# except according to those terms.
+
+import sys
+import json
+
+with open(sys.argv[1]) as f:
+    body = json.load(f)
+
+assert body["schema_version"] == 1
+assert body["item"].endswith("add_one")
+assert body["promoted"] is None
+assert body["arg_count"] == 1
+
+locals = body["locals"]
+assert locals[0]["ty"] == "u32"
+assert locals[1]["name"] == "x"
+assert any(local["name"] == "y" and local["user_variable"] for local in locals)
+
+blocks = body["basic_blocks"]
+assert blocks[0]["terminator"]["kind"] == "Assert"
+assert any(block["terminator"]["kind"] == "Return" for block in blocks)
+for block in blocks:
+    terminator = block["terminator"]
+    assert len(terminator["successors"]) == len(terminator["successor_labels"])
+    for successor in terminator["successors"]:
+        assert successor < len(blocks)
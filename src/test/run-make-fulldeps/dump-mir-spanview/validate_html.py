@@ -0,0 +1,77 @@
+#!/usr/bin/env python
+
+# Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+# file at the top-level directory of this distribution and at
+# http://rust-lang.org/COPYRIGHT.
+#
+# Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+# http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+# <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+# option. This file may not be copied, modified, or distributed
+# except according to those terms.
+
+# Usage: validate_html.py <source> <spanview.html> <expected title prefix>
+#
+# Checks that every tag is closed, that the text of the code block is a
+# verbatim part of the source, and that some region has the expected title.
+
+import sys
+
+try:
+    from html.parser import HTMLParser
+except ImportError:
+    from HTMLParser import HTMLParser
+
+VOID_TAGS = ['meta']
+
+
+class SpanviewParser(HTMLParser):
+    def __init__(self):
+        HTMLParser.__init__(self)
+        self.stack = []
+        self.in_code = False
+        self.code = []
+        self.titles = []
+
+    def handle_starttag(self, tag, attrs):
+        if tag in VOID_TAGS:
+            return
+        attrs = dict(attrs)
+        self.stack.append(tag)
+        if tag == 'div' and attrs.get('class') == 'code':
+            self.in_code = True
+        if tag == 'span':
+            assert self.in_code
+            assert attrs.get('class') == 'region'
+            self.titles.append(attrs['title'])
+
+    def handle_endtag(self, tag):
+        assert self.stack and self.stack[-1] == tag, (self.stack, tag)
+        self.stack.pop()
+        if tag == 'div':
+            self.in_code = False
+
+    def handle_data(self, data):
+        if self.in_code:
+            self.code.append(data)
+
+    # Only called by Python 2, Python 3 already unescapes the data.
+    def handle_entityref(self, name):
+        if self.in_code:
+            self.code.append({'amp': '&', 'lt': '<', 'gt': '>', 'quot': '"'}[name])
+
+
+with open(sys.argv[1]) as f:
+    source = f.read()
+with open(sys.argv[2]) as f:
+    html = f.read()
+
+parser = SpanviewParser()
+parser.feed(html)
+parser.close()
+
+assert not parser.stack, parser.stack
+code = ''.join(parser.code)
+assert 'x + 1' in code, code
+assert code in source, code
+assert any(title.startswith(sys.argv[3]) for title in parser.titles), parser.titles
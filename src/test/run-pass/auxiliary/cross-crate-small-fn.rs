@@ -0,0 +1,39 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z cross-crate-inline-threshold=5
+
+#![crate_type = "rlib"]
+
+pub struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    pub fn new() -> Counter {
+        Counter { count: 0 }
+    }
+
+    pub fn get(&self) -> u32 {
+        self.count
+    }
+
+    pub fn bump(&mut self) {
+        self.count = step(self.count);
+    }
+}
+
+fn step(n: u32) -> u32 {
+    n + 1
+}
+
+pub fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
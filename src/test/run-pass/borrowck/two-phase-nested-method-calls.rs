@@ -0,0 +1,39 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z borrowck=mir
+
+// Check that the MIR borrowck accepts two-phase borrows for nested method
+// calls without `-Z two-phase-borrows`.
+
+struct Counter {
+    count: usize,
+}
+
+impl Counter {
+    fn get(&self) -> usize {
+        self.count
+    }
+
+    fn add(&mut self, n: usize) {
+        self.count += n;
+    }
+}
+
+fn main() {
+    let mut v = vec![0, 1, 2];
+    v.push(v.len());
+    v.insert(v.len(), v[0]);
+    assert_eq!(v, [0, 1, 2, 3, 0]);
+
+    let mut counter = Counter { count: 1 };
+    counter.add(counter.get());
+    assert_eq!(counter.get(), 2);
+}
@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:cross-crate-small-fn.rs
+// compile-flags: -O
+
+// Check that calling small non-generic functions whose MIR was exported into
+// another crate's metadata works, including ones that call private items.
+
+extern crate cross_crate_small_fn as small;
+
+use small::Counter;
+
+fn main() {
+    let mut c = Counter::new();
+    c.bump();
+    c.bump();
+    assert_eq!(c.get(), 2);
+    assert_eq!(small::add(c.get(), 3), 5);
+}
@@ -0,0 +1,44 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that every element of large arrays and slices of a type with a
+// destructor is dropped, and in order. The drop glue for these is a loop,
+// so its size doesn't depend on the length of the array.
+
+use std::cell::Cell;
+
+thread_local!(static NEXT: Cell<usize> = Cell::new(0));
+
+struct D(usize);
+
+impl Drop for D {
+    fn drop(&mut self) {
+        NEXT.with(|next| {
+            assert_eq!(next.get(), self.0);
+            next.set(self.0 + 1);
+        });
+    }
+}
+
+const N: usize = 100_000;
+
+fn main() {
+    let array: Box<[D; N]> = {
+        let v: Vec<D> = (0..N).map(D).collect();
+        let slice = v.into_boxed_slice();
+        unsafe { Box::from_raw(Box::into_raw(slice) as *mut [D; N]) }
+    };
+    drop(array);
+    NEXT.with(|next| { assert_eq!(next.get(), N); next.set(0); });
+
+    let slice: Box<[D]> = (0..N).map(D).collect::<Vec<_>>().into_boxed_slice();
+    drop(slice);
+    NEXT.with(|next| assert_eq!(next.get(), N));
+}
@@ -0,0 +1,26 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a `DropAndReplace` of a place that is only moved out of on
+// some paths still gets a drop flag, instead of being split like a `Drop`.
+
+fn replace(c: bool, y: String) -> String {
+    let mut x = String::new();
+    if c {
+        drop(x);
+    }
+    x = y;
+    x
+}
+
+fn main() {
+    assert_eq!(replace(false, "a".to_string()), "a");
+    assert_eq!(replace(true, "b".to_string()), "b");
+}
@@ -0,0 +1,64 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that locals which are saved across different suspension points and
+// are never live at the same time share their storage in the generator.
+
+#![feature(generators, generator_trait)]
+
+use std::mem::size_of_val;
+use std::ops::{Generator, GeneratorState};
+
+fn main() {
+    let mut gen = || {
+        {
+            let a = [1u8; 1024];
+            yield;
+            assert_eq!(a[0], 1);
+        }
+        {
+            let b = [2u8; 1024];
+            yield;
+            assert_eq!(b[1023], 2);
+        }
+        {
+            let c = [3u8; 1024];
+            yield;
+            assert_eq!(c[512], 3);
+        }
+    };
+    assert!(size_of_val(&gen) < 2 * 1024);
+
+    for _ in 0..3 {
+        match unsafe { gen.resume() } {
+            GeneratorState::Yielded(()) => {}
+            s => panic!("bad state: {:?}", s),
+        }
+    }
+    match unsafe { gen.resume() } {
+        GeneratorState::Complete(()) => {}
+        s => panic!("bad state: {:?}", s),
+    }
+
+    // Locals which are live at the same time need their own storage.
+    let mut gen = || {
+        let a = [1u8; 1024];
+        yield;
+        let b = [2u8; 1024];
+        yield;
+        assert_eq!(a[0] + b[0], 3);
+    };
+    assert!(size_of_val(&gen) >= 2 * 1024);
+    unsafe {
+        gen.resume();
+        gen.resume();
+        gen.resume();
+    }
+}
@@ -0,0 +1,43 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(core_intrinsics)]
+
+use std::intrinsics;
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+
+static DROPS: AtomicUsize = ATOMIC_USIZE_INIT;
+
+struct Counted;
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        DROPS.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn main() {
+    unsafe {
+        intrinsics::forget(Counted);
+        intrinsics::forget(vec![Counted, Counted]);
+    }
+    assert_eq!(DROPS.load(Ordering::SeqCst), 0);
+    drop(Counted);
+    assert_eq!(DROPS.load(Ordering::SeqCst), 1);
+
+    unsafe {
+        assert_eq!(intrinsics::wrapping_add(255u8, 1), 0);
+        assert_eq!(intrinsics::wrapping_add(i32::max_value(), 1), i32::min_value());
+        assert_eq!(intrinsics::wrapping_sub(0u32, 1), u32::max_value());
+        assert_eq!(intrinsics::wrapping_sub(i64::min_value(), 1), i64::max_value());
+        assert_eq!(intrinsics::wrapping_mul(16u8, 16), 0);
+        assert_eq!(intrinsics::wrapping_mul(-128i8, -1), -128);
+    }
+}
@@ -0,0 +1,26 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:mir_inline_cross_crate.rs
+// compile-flags: -Z mir-opt-level=2
+
+// Check that the MIR inliner can inline callees from another crate, whose
+// body metrics are not available through the `mir_body_metrics` query.
+
+extern crate mir_inline_cross_crate;
+
+use mir_inline_cross_crate::{add_one, count_up_to, pick};
+
+fn main() {
+    assert_eq!(add_one(1), 2);
+    assert_eq!(count_up_to(3), 3);
+    assert_eq!(pick(true, "a", "b"), "a");
+    assert_eq!(pick(false, 1u8, 2u8), 2);
+}
@@ -0,0 +1,85 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z validate-mir
+
+// Check that the MIR of ordinary code passes validation after every pass.
+
+#![feature(generators, generator_trait)]
+
+use std::ops::{Generator, GeneratorState};
+
+struct Droppy(u32);
+
+impl Drop for Droppy {
+    fn drop(&mut self) {}
+}
+
+fn pick(b: bool, x: Droppy, y: Droppy) -> Droppy {
+    let z = if b { x } else { y };
+    let arr = [Droppy(1), Droppy(2)];
+    for d in arr.iter() {
+        if d.0 == z.0 {
+            return Droppy(d.0);
+        }
+    }
+    z
+}
+
+fn classify(x: Option<u32>) -> u32 {
+    let mut n = 0;
+    loop {
+        n += match x {
+            Some(y) if y > 10 => 2,
+            Some(_) => 1,
+            None => 0,
+        };
+        if n > 3 {
+            break n;
+        }
+    }
+}
+
+fn sum(v: &[u32]) -> u32 {
+    v.iter().map(|x| x * 2).fold(0, |a, b| a + b)
+}
+
+fn evens(n: u32) -> impl Iterator<Item = u32> {
+    (0..n).filter(|x| x % 2 == 0)
+}
+
+fn adder(y: u32) -> impl Fn(u32) -> u32 {
+    move |x| x + y
+}
+
+fn main() {
+    assert_eq!(pick(true, Droppy(1), Droppy(3)).0, 1);
+    assert_eq!(sum(&[1, 2, 3]), 12);
+    assert_eq!(classify(Some(11)), 4);
+    assert_eq!(evens(5).sum::<u32>(), 6);
+
+    let add = adder(2);
+    let f: &Fn(u32) -> u32 = &add;
+    assert_eq!(f(1), 3);
+
+    let mut gen = || {
+        let d = Droppy(7);
+        yield d.0;
+        d.0 + 1
+    };
+    match unsafe { gen.resume() } {
+        GeneratorState::Yielded(7) => {}
+        _ => panic!(),
+    }
+    match unsafe { gen.resume() } {
+        GeneratorState::Complete(8) => {}
+        _ => panic!(),
+    }
+}
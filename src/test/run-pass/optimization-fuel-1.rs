@@ -12,7 +12,7 @@
 
 use std::mem::size_of;
 
-// compile-flags: -Z fuel=foo=1
+// compile-flags: -Z fuel=foo=1 -Z mir-opt-level=0
 
 struct S1(u8, u16, u8);
 struct S2(u8, u16, u8);
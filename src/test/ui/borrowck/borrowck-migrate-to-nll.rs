@@ -0,0 +1,30 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check `-Z borrowck=migrate`: this code is (unsoundly) accepted by the AST
+// borrowck, due to #27282, but rejected by the MIR borrowck. As the AST
+// borrowck didn't report any errors, the MIR borrowck errors are downgraded
+// to warnings.
+
+// compile-flags: -Z borrowck=migrate
+// compile-pass
+
+fn main() {
+    match Some(&4) {
+        None => {},
+        ref mut foo
+            if {
+                (|| { let bar = foo; bar.take() })();
+                false
+            } => {},
+        Some(ref _s) => {},
+        _ => {},
+    }
+}
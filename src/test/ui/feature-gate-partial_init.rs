@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that using a struct once each of its fields has been assigned
+// requires `#![feature(partial_init)]`.
+
+#![feature(nll)]
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn main() {
+    let p: Point;
+    p.x = 1;
+    p.y = 2;
+    drop(p);
+    //~^ ERROR use of possibly uninitialized variable: `p`
+}
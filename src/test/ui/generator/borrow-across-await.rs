@@ -0,0 +1,33 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a borrow held across an `await!` points at the `await!` invocation
+// instead of at the `yield` in its expansion.
+
+#![feature(generators)]
+
+// Stands in for `std::await!`, whose expansion also holds a borrow of the awaited
+// future across its `yield`.
+macro_rules! await {
+    ($e:expr) => { yield $e }
+}
+
+fn borrow_across_await() {
+    let _g = || {
+        let a = 3;
+        let b = &a;
+        //~^ ERROR borrow may still be in use when generator yields
+        let c = *b;
+        await!(());
+        println!("{}", b + c);
+    };
+}
+
+fn main() { }
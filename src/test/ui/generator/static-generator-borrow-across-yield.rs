@@ -0,0 +1,46 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that immovable generators may hold borrows of their own locals
+// across yield points, with both borrow checkers.
+
+// compile-pass
+// compile-flags: -Z borrowck=compare
+
+#![feature(generators)]
+
+struct Holder<'a> {
+    r: &'a mut Vec<u32>,
+}
+
+fn main() {
+    static || {
+        let mut v = vec![1, 2, 3];
+        let h = Holder { r: &mut v };
+        yield ();
+        h.r.push(4);
+    };
+
+    static || {
+        let v = vec![1, 2, 3];
+        for x in &v {
+            yield *x;
+        }
+    };
+
+    static || {
+        let mut a = 0;
+        let b = &mut a;
+        loop {
+            *b += 1;
+            yield ();
+        }
+    };
+}
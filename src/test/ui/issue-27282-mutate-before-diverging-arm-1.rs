@@ -12,8 +12,9 @@
 // arm in a guard, followed by an attempt to continue matching on that
 // corrupted discriminant in the remaining match arms.
 //
-// Basically this is testing that our new NLL feature of emitting a
-// fake read on each match arm is catching cases like this.
+// Basically this is testing that our new NLL feature of emitting fake
+// borrows of the places inspected by the match, which are kept alive
+// across each guard, is catching cases like this.
 //
 // This case is interesting because it includes a guard that
 // diverges, and therefore a single final fake-read at the very end
@@ -31,7 +32,7 @@ fn main() {
         &mut Some(&_) if {
             // ForceFnOnce needed to exploit #27282
             (|| { *x = None; drop(force_fn_once); })();
-            //~^ ERROR closure requires unique access to `x` but it is already borrowed [E0500]
+            //~^ ERROR cannot mutably borrow `x` in match guard [E0510]
             false
         } => {}
         &mut Some(&a) if { // this binds to garbage if we've corrupted discriminant
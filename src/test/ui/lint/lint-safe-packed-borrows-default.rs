@@ -0,0 +1,25 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that safe borrows of packed fields are denied by default, and that
+// copying the field out first is suggested.
+
+#[repr(packed)]
+struct Packed {
+    a: u8,
+    b: u32,
+}
+
+fn main() {
+    let p = Packed { a: 0, b: 1 };
+    let b = &p.b; //~ ERROR borrow of packed field is unsafe
+                  //~| hard error
+    println!("{} {}", p.a, b);
+}
@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// run-rustfix
+
+// Check that the `mut` suggestions of borrowck can be applied automatically.
+
+#![feature(nll)]
+
+fn main() {
+    let x = 1;
+    println!("{}", x);
+    x = 2; //~ ERROR cannot assign twice to immutable variable `x`
+    println!("{}", x);
+
+    let mut y = 0;
+    let c = || y += 1;
+    c(); //~ ERROR cannot borrow immutable item `c` as mutable
+    println!("{}", y);
+}
@@ -22,7 +22,7 @@ fn bump<'a>(mut block: &mut Block<'a>) {
     let x = &mut block;
     println!("{}", x.current);
     let p: &'a u8 = &*block.current;
-    //~^ ERROR cannot borrow `*block.current` as immutable because it is also borrowed as mutable
+    //~^ ERROR cannot borrow `*block.current` as immutable because `block` is also borrowed
     drop(x);
     drop(p);
 }
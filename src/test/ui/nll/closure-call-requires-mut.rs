@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that calling a closure which mutates its captures through an
+// immutable binding explains the capture and suggests `let mut`.
+
+#![feature(nll)]
+
+fn mutable_capture() {
+    let mut x = 0;
+    let c = || x += 1;
+    c();
+    //~^ ERROR cannot borrow immutable item `c` as mutable
+}
+
+fn unique_capture(r: &mut Vec<u32>) {
+    let push = || r.push(1);
+    push();
+    //~^ ERROR cannot borrow immutable item `push` as mutable
+}
+
+fn main() {}
@@ -0,0 +1,39 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that MIR borrowck checks the bodies of statics, consts, enum
+// discriminants and array lengths without ICEs.
+
+// compile-flags:-Zborrowck=mir
+// compile-pass
+
+#![feature(const_let)]
+
+static S: &[u8] = &[1, 2, 3];
+
+const C: (u8, u8) = {
+    let a = 1;
+    let pair = (a, a);
+    pair
+};
+
+enum E {
+    A = C.0 as isize,
+    B = {
+        let b = 3;
+        b
+    },
+}
+
+fn main() {
+    let array = [0u8; { let len = 4; len }];
+    assert_eq!(S.len() + C.1 as usize, array.len());
+    assert_eq!(E::A as isize + 2, E::B as isize);
+}
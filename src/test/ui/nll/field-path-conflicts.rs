@@ -0,0 +1,38 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that conflicting borrows of a field and one of its parents name both
+// places, while borrows of disjoint fields are accepted through nested field
+// projections.
+
+#![feature(nll)]
+
+struct Inner { b: u32, c: u32 }
+struct Outer { a: Inner, d: (u32, u32) }
+
+impl Outer {
+    fn disjoint(&mut self) {
+        let b = &mut self.a.b;
+        let c = &self.a.c;
+        let d0 = &mut self.d.0;
+        let d1 = &self.d.1;
+        *b += *c + *d1;
+        *d0 += 1;
+    }
+
+    fn conflict(&mut self) {
+        let a = &self.a;
+        let b = &mut self.a.b;
+        //~^ ERROR cannot borrow `self.a.b` as mutable because `self.a` is also borrowed
+        *b += a.c;
+    }
+}
+
+fn main() {}
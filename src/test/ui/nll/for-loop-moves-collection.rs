@@ -0,0 +1,33 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that using a collection after a `for` loop consumed it suggests
+// iterating over a reference to it instead.
+
+#![feature(nll)]
+
+fn shared(v: Vec<String>) -> usize {
+    for s in v {
+        drop(s);
+    }
+    v.len()
+    //~^ ERROR borrow of moved value: `v`
+}
+
+fn mutable() {
+    let mut v = vec![String::new()];
+    for s in v {
+        drop(s);
+    }
+    v.push(String::new());
+    //~^ ERROR borrow of moved value: `v`
+}
+
+fn main() {}
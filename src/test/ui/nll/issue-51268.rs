@@ -26,7 +26,7 @@ struct Foo {
 impl Foo {
     fn foo(&mut self) {
         self.thing.bar(|| {
-        //~^ ERROR cannot borrow `self.thing` as mutable because it is also borrowed as immutable [E0502]
+        //~^ ERROR cannot borrow `self.thing` as mutable because `self` is also borrowed as immutable [E0502]
             &self.number;
         });
     }
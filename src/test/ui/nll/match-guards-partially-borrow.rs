@@ -0,0 +1,60 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a (partially) mutably borrowed place can be matched on, so long
+// as we don't have to read from that place, and that guards cannot change
+// the places which were inspected to get to them.
+
+// compile-flags: -Z disable-ast-check-for-mutation-in-guard
+
+#![feature(nll)]
+
+fn ok_mutation_in_guard(mut q: (bool, bool)) {
+    match q {
+        // Only `q.0` is inspected, so the guard may write to `q.1`.
+        (true, _) if { q.1 = true; false } => {}
+        _ => {}
+    }
+}
+
+fn ok_indirect_mutation_in_guard(r: &mut (bool, bool)) {
+    match *r {
+        // `r` is only borrowed shallowly, so the part of `*r` which is not
+        // inspected can still be written to through it.
+        (true, _) if { r.1 = true; false } => {}
+        _ => {}
+    }
+}
+
+fn bad_mutation_in_guard(mut r: Option<bool>) {
+    match r {
+        None => {}
+        Some(_) if { r = None; false } => {} //~ ERROR
+        _ => {}
+    }
+}
+
+fn bad_mutation_in_guard2(mut t: (Option<bool>, bool)) {
+    match t {
+        (Some(true), _) => {}
+        (Some(false), _) if { t.0 = None; false } => {} //~ ERROR
+        _ => {}
+    }
+}
+
+fn bad_mutation_in_guard3(r: &mut Option<bool>) {
+    match r {
+        &mut None => {}
+        &mut Some(_) if { (|| { *r = None; })(); false } => {} //~ ERROR
+        _ => {}
+    }
+}
+
+fn main() {}
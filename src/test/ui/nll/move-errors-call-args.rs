@@ -0,0 +1,25 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that moves out of the same reference into the arguments of one call
+// are reported as a single error.
+
+#![feature(nll)]
+
+struct S { a: String, b: String }
+
+fn take_two(_: String, _: String) {}
+
+fn group_args(x: &S) {
+    take_two(x.a, x.b);
+    //~^ ERROR cannot move out of borrowed content
+}
+
+fn main() {}
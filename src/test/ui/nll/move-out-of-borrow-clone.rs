@@ -0,0 +1,23 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that moving out of a reference points out the reference, and
+// suggests cloning the value when it implements `Clone`.
+
+#![feature(nll)]
+
+fn take(_: String) {}
+
+fn suggest_clone(s: &String) {
+    take(*s);
+    //~^ ERROR cannot move out of borrowed content
+}
+
+fn main() {}
@@ -0,0 +1,43 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that moving out of a `Box` dereference, in whole or in part, is
+// accepted and deinitializes the moved-from contents only.
+
+// compile-pass
+
+#![feature(nll)]
+
+struct Pair {
+    a: String,
+    b: String,
+}
+
+fn whole(x: Box<String>) -> String {
+    *x
+}
+
+fn partial(x: Box<Pair>) -> (String, String) {
+    let a = x.a;
+    let b = (*x).b;
+    (a, b)
+}
+
+fn nested(x: Box<Box<Pair>>) -> String {
+    let Pair { a, .. } = **x;
+    a
+}
+
+fn main() {
+    let pair = Pair { a: String::new(), b: String::new() };
+    whole(Box::new(String::new()));
+    partial(Box::new(pair));
+    nested(Box::new(Box::new(Pair { a: String::new(), b: String::new() })));
+}
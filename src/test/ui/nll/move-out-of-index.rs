@@ -0,0 +1,35 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check the suggestions for moves out of containers indexed through `Index`.
+
+#![feature(nll)]
+
+use std::collections::HashMap;
+
+fn take(_: String) {}
+
+fn mutable_vec(mut v: Vec<String>) {
+    take(v[0]);
+    //~^ ERROR cannot move out of borrowed content
+    v.clear();
+}
+
+fn immutable_vec(v: Vec<String>) {
+    take(v[0]);
+    //~^ ERROR cannot move out of borrowed content
+}
+
+fn map(m: HashMap<u32, String>) {
+    take(m[&0]);
+    //~^ ERROR cannot move out of borrowed content
+}
+
+fn main() {}
@@ -0,0 +1,37 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that moving a `Default` value out of a `&mut` suggests replacing it
+// with its default value instead.
+
+#![feature(nll)]
+
+fn take(_: String) {}
+
+struct S {
+    name: String,
+}
+
+fn deref(r: &mut String) {
+    take(*r);
+    //~^ ERROR cannot move out of borrowed content
+}
+
+fn field(s: &mut S) {
+    take(s.name);
+    //~^ ERROR cannot move out of borrowed content
+}
+
+fn shared(r: &String) {
+    take(*r);
+    //~^ ERROR cannot move out of borrowed content
+}
+
+fn main() {}
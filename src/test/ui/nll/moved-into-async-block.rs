@@ -0,0 +1,32 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// edition:2018
+
+// Check the notes for values used after they were moved into an async block
+// or into a spawned thread.
+
+#![feature(async_await, nll)]
+
+use std::thread;
+
+fn async_block(data: Vec<u8>) -> usize {
+    let _fut = async move { data.len() };
+    data.len()
+    //~^ ERROR borrow of moved value: `data`
+}
+
+fn spawned_thread(data: Vec<u8>) -> usize {
+    thread::spawn(move || data.len());
+    data.len()
+    //~^ ERROR borrow of moved value: `data`
+}
+
+fn main() {}
@@ -0,0 +1,63 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that `#![feature(partial_init)]` accepts values initialized field by
+// field, and that the fields still missing are named otherwise.
+
+#![feature(nll, partial_init)]
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+struct Line {
+    start: Point,
+    end: Point,
+}
+
+fn all_fields() -> Point {
+    let p: Point;
+    p.x = 1;
+    p.y = 2;
+    p
+}
+
+fn nested_fields() -> Line {
+    let l: Line;
+    l.start.x = 0;
+    l.start.y = 0;
+    l.end = Point { x: 1, y: 1 };
+    l
+}
+
+fn tuple_fields() -> (String, u32) {
+    let t: (String, u32);
+    t.0 = String::new();
+    t.1 = 0;
+    t
+}
+
+fn missing_field() -> Point {
+    let p: Point;
+    p.x = 1;
+    p
+    //~^ ERROR use of possibly uninitialized variable: `p`
+}
+
+fn missing_nested_fields() -> Line {
+    let l: Line;
+    l.start.x = 0;
+    l.end.y = 1;
+    l
+    //~^ ERROR use of possibly uninitialized variable: `l`
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that returning a borrow of a local from a function returning `&str`
+// or `&[T]` suggests returning the owned type instead.
+
+#![feature(nll)]
+
+fn name() -> &'static str {
+    let s = String::from("name");
+    &s
+    //~^ ERROR `s` does not live long enough
+}
+
+fn sorted<'a>(v: &'a [u32]) -> &'a [u32] {
+    let mut sorted = v.to_vec();
+    sorted.sort();
+    &sorted
+    //~^ ERROR `sorted` does not live long enough
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that storing a reference to one field of a value into another field
+// is explained as making the value self-referential.
+
+#![feature(nll)]
+
+struct Node<'a> {
+    value: u32,
+    pointer: &'a u32,
+}
+
+fn use_ref(_: &u32) {}
+
+fn main() {
+    let mut node = Node { value: 0, pointer: &0 };
+    node.pointer = &node.value;
+    node.value = 1;
+    //~^ ERROR cannot assign to `node.value` because it is borrowed
+    use_ref(node.pointer);
+}
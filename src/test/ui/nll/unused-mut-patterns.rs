@@ -0,0 +1,42 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the unused mut lint follows how MIR uses each binding: bindings
+// that are assigned, mutably borrowed or passed by `&mut` keep their `mut`,
+// whichever pattern they are bound by.
+
+#![feature(nll)]
+#![deny(unused_mut)]
+
+fn take_mut(_: &mut i32) {}
+
+fn used(pair: (i32, i32), opt: Option<Vec<i32>>) {
+    let (mut a, mut b) = pair;
+    a += 1;
+    take_mut(&mut b);
+    if let Some(mut v) = opt {
+        v.push(a);
+    }
+}
+
+fn unused(pair: (i32, i32)) {
+    let (mut a, b) = pair;
+    //~^ ERROR variable does not need to be mutable
+    match Some(a + b) {
+        Some(mut c) => drop(c),
+        //~^ ERROR variable does not need to be mutable
+        None => {}
+    }
+}
+
+fn main() {
+    used((0, 0), None);
+    unused((0, 0));
+}
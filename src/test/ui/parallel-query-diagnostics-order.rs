@@ -0,0 +1,28 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z query-threads=4
+
+// The diagnostics of the bodies are printed in the order of the bodies,
+// whatever the order in which the query threads check them.
+
+fn first() {
+    let x: &[u8] = "foo"; //~ ERROR mismatched types
+}
+
+fn second() {
+    let y: &[u8; 4] = "baaa"; //~ ERROR mismatched types
+}
+
+fn third() {
+    let z: &str = b"foo"; //~ ERROR mismatched types
+}
+
+fn main() {}
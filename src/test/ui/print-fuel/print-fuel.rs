@@ -11,7 +11,7 @@
 #![crate_name="foo"]
 #![allow(dead_code)]
 
-// compile-flags: -Z print-fuel=foo
+// compile-flags: -Z print-fuel=foo -Z mir-opt-level=0
 // compile-pass
 
 struct S1(u8, u16, u8);